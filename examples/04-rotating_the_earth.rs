@@ -61,7 +61,7 @@ impl GeodesicShift {
 
         if [lat_0, lon_0, lat_1, lon_1].iter().any(|&f| f.is_nan()) {
             return Err(GeodesyError::General(
-                "Missing lat_0, lon_0, lat_1 or lon_1",
+                "Missing lat_0, lon_0, lat_1 or lon_1".to_string(),
             ));
         }
 
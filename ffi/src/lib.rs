@@ -0,0 +1,4 @@
+//! The shared library of the C interface of *Rust Geodesy*, cf. the `ffi`
+//! module of geodesy, and `include/geodesy.h`
+
+pub use geodesy::ffi::*;
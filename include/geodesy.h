@@ -0,0 +1,38 @@
+/* The C interface of Rust Geodesy, cf. src/ffi.rs. The shared library,
+ * libgeodesy_ffi, is built by
+ *
+ *     cargo build --release -p geodesy-ffi
+ */
+#ifndef GEODESY_H
+#define GEODESY_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+#define GEODESY_FWD 1
+#define GEODESY_INV -1
+
+typedef struct GeodesyContext GeodesyContext;
+
+GeodesyContext *geodesy_create_context(void);
+void geodesy_destroy_context(GeodesyContext *ctx);
+const char *geodesy_last_error(const GeodesyContext *ctx);
+
+int geodesy_add_path(GeodesyContext *ctx, const char *path);
+int geodesy_register_resource(GeodesyContext *ctx, const char *name, const char *definition);
+
+int64_t geodesy_op_new(GeodesyContext *ctx, const char *definition);
+int64_t geodesy_apply(GeodesyContext *ctx, int64_t op, int direction,
+                      double *coordinates, size_t n, size_t dim);
+
+const char *geodesy_version(void);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* GEODESY_H */
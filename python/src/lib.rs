@@ -0,0 +1,129 @@
+//! Python bindings for *Rust Geodesy*: A [`Plain`] context, the operators
+//! instantiated through it, and their application to NumPy arrays of N×2,
+//! N×3, or N×4 coordinates. Built and installed by
+//!
+//! ```sh
+//! maturin develop --release
+//! ```
+//!
+//! In Python:
+//!
+//! ```python
+//! import geodesy, numpy
+//! ctx = geodesy.Context()
+//! utm32 = ctx.op("geo:in | utm zone=32")
+//! projected = ctx.apply(utm32, numpy.array([[55., 12.], [59., 10.]]))
+//! geographic = ctx.apply(utm32, projected, inverse=True)
+//! ```
+//!
+//! Coordinates failing to transform are set to NaN.
+
+use geodesy::preamble::*;
+use numpy::ndarray::Array2;
+use numpy::{IntoPyArray, PyArray2, PyReadonlyArray2};
+use pyo3::create_exception;
+use pyo3::exceptions::{PyException, PyValueError};
+use pyo3::prelude::*;
+
+create_exception!(geodesy, GeodesyError, PyException);
+
+// Errors are handed over to Python as GeodesyError, carrying the message
+fn py_error(error: Error) -> PyErr {
+    GeodesyError::new_err(error.to_string())
+}
+
+/// A context, with the built in adaptors and macros available, and resources
+/// looked up as described for the Plain context of Rust Geodesy
+#[pyclass(unsendable, name = "Context")]
+struct PyContext {
+    ctx: Plain,
+}
+
+/// An operator, instantiated by `Context.op`
+#[pyclass(frozen, name = "Op")]
+#[derive(Clone)]
+struct PyOp {
+    handle: OpHandle,
+    #[pyo3(get)]
+    definition: String,
+}
+
+#[pymethods]
+impl PyOp {
+    fn __repr__(&self) -> String {
+        format!("Op('{}')", self.definition)
+    }
+}
+
+#[pymethods]
+impl PyContext {
+    #[new]
+    fn new() -> PyContext {
+        PyContext { ctx: Plain::new() }
+    }
+
+    /// Add `path` to the directories searched for resources
+    fn add_path(&mut self, path: &str) {
+        self.ctx.add_path(path);
+    }
+
+    /// Register the macro `name`, given by `definition`
+    fn register_resource(&mut self, name: &str, definition: &str) {
+        self.ctx.register_resource(name, definition);
+    }
+
+    /// Instantiate the operator given by `definition`
+    fn op(&mut self, definition: &str) -> PyResult<PyOp> {
+        let handle = self.ctx.op(definition).map_err(py_error)?;
+        Ok(PyOp {
+            handle,
+            definition: definition.to_string(),
+        })
+    }
+
+    /// Apply `op` to the N×2, N×3, or N×4 array of `coordinates`, returning
+    /// the transformed coordinates as a new array of the same shape
+    #[pyo3(signature = (op, coordinates, inverse = false))]
+    fn apply<'py>(
+        &self,
+        py: Python<'py>,
+        op: &PyOp,
+        coordinates: PyReadonlyArray2<'py, f64>,
+        inverse: bool,
+    ) -> PyResult<Bound<'py, PyArray2<f64>>> {
+        let coordinates = coordinates.as_array();
+        let (n, dim) = coordinates.dim();
+        if !(2..=4).contains(&dim) {
+            let message = format!("Expected an N×2, N×3, or N×4 array, got N×{dim}");
+            return Err(PyValueError::new_err(message));
+        }
+
+        let mut data: Vec<Coord> = coordinates
+            .rows()
+            .into_iter()
+            .map(|row| {
+                let mut coord = Coord::default();
+                for (element, value) in coord.0.iter_mut().zip(row) {
+                    *element = *value;
+                }
+                coord
+            })
+            .collect();
+        let direction = if inverse { Inv } else { Fwd };
+        self.ctx
+            .apply(op.handle, direction, &mut data)
+            .map_err(py_error)?;
+
+        let result = Array2::from_shape_fn((n, dim), |(i, j)| data[i][j]);
+        Ok(result.into_pyarray_bound(py))
+    }
+}
+
+#[pymodule]
+#[pyo3(name = "geodesy")]
+fn geodesy_python(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<PyContext>()?;
+    m.add_class::<PyOp>()?;
+    m.add("GeodesyError", m.py().get_type_bound::<GeodesyError>())?;
+    Ok(())
+}
@@ -0,0 +1,26 @@
+import geodesy
+import numpy
+import pytest
+
+
+def test_roundtrip():
+    ctx = geodesy.Context()
+    utm32 = ctx.op("geo:in | utm zone=32")
+    data = numpy.array([[55.0, 12.0], [59.0, 10.0]])
+    projected = ctx.apply(utm32, data)
+    assert projected.shape == (2, 2)
+    assert abs(projected[0, 0] - 691875.632) < 1e-3
+    assert abs(projected[0, 1] - 6098907.825) < 1e-3
+    geographic = ctx.apply(utm32, projected, inverse=True)
+    assert numpy.allclose(geographic, data, atol=1e-12)
+
+
+def test_macros_and_errors():
+    ctx = geodesy.Context()
+    ctx.register_resource("my:utm", "geo:in | utm zone=32")
+    op = ctx.op("my:utm")
+    assert op.definition == "my:utm"
+    with pytest.raises(geodesy.GeodesyError):
+        ctx.op("foo | bar")
+    with pytest.raises(ValueError):
+        ctx.apply(op, numpy.zeros((2, 5)))
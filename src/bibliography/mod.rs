@@ -2,6 +2,12 @@
 
 /// Some literature, that has been useful in designing and implementing this library.
 pub enum Bibliography {
+    /// S. Baselga and J.C. Martínez-Llario, 2018:
+    /// *Intersection and point-to-line solutions for geodesics on the ellipsoid*.
+    /// Stud. Geophys. Geod. 62, pp. 353–363.
+    /// [DOI](https://doi.org/10.1007/s11200-017-1020-z).
+    Bas18,
+
     /// B.R. Bowring, 1976: *Transformation from spatial to geographical coordinates*.
     /// Survey Review 23(181), pp. 323–327.
     Bow76,
@@ -54,6 +60,15 @@ pub enum Bibliography {
     /// [DOI](https://doi.org/10.1007/s00190-006-0023-2)
     Fuk06,
 
+    /// W.A. Heiskanen and H. Moritz, 1967: *Physical Geodesy*.
+    /// W.H. Freeman and Company, San Francisco, 364 pp.
+    Hei67,
+
+    /// S.A. Holmes and W.E. Featherstone, 2002: *A unified approach to the Clenshaw summation and the recursive computation of very high degree and order normalised associated Legendre functions*.
+    /// Journal of Geodesy, 76(5), pp.279-299
+    /// [DOI](https://doi.org/10.1007/s00190-002-0216-2)
+    Hol02,
+
     /// IOGP, 2019: *Coordinate Conversions and Transformations including Formulas. Revised - September 2019*
     /// IOGP Geomatics Guidance Note Number 7, part 2. IOGP publication no. 373-7-2, 162 pp.
     /// [pdf](https://www.iogp.org/wp-content/uploads/2019/09/373-07-02.pdf)
@@ -96,6 +111,16 @@ pub enum Bibliography {
     /// [pdf](https://gfzpublic.gfz-potsdam.de/rest/items/item_8827_5/component/file_130038/content).
     Kru12,
 
+    /// H. Moritz, 2000: *Geodetic Reference System 1980*.
+    /// Journal of Geodesy 74, pp. 128–133.
+    /// [DOI](https://doi.org/10.1007/s001900050278).
+    Mor00,
+
+    /// NIMA, 2000: *Department of Defense World Geodetic System 1984:
+    /// Its Definition and Relationships with Local Geodetic Systems*.
+    /// Technical Report TR8350.2, 3rd ed., amendment 1.
+    Nima00,
+
     /// A. C. Ruffhead (2016):  The SMITSWAM method of datum transformations
     /// consisting of Standard Molodensky in two stages with applied misclosures,
     /// Survey Review, 48:350, pp. 376-384,
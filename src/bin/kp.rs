@@ -1,8 +1,10 @@
 use anyhow::bail;
-use clap::Parser;
+use clap::{Parser, Subcommand};
+use geodesy::inner_op_authoring::OpParameter;
 use geodesy::preamble::*;
 use simple_logger::SimpleLogger;
-use std::io::BufRead;
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, IsTerminal, Write};
 use std::path::PathBuf;
 use std::time;
 
@@ -18,11 +20,11 @@ use std::time;
 struct Cli {
     /// Inverse.
     /// Use of `inverse` mode excludes the use of `roundtrip` mode.
-    #[clap(short, long = "inv")]
+    #[clap(short, long = "inv", global = true)]
     inverse: bool,
 
     /// Activate debug mode
-    #[clap(short, long)]
+    #[clap(long)]
     debug: bool,
 
     /// Roundtrip mode - a signature feature of Knud Poder's programs:
@@ -33,23 +35,219 @@ struct Cli {
     #[clap(short, long)]
     roundtrip: bool,
 
+    /// Roundtrip tolerance, e.g. `1mm`, `0.5cm` or `0.001` (metres). With
+    /// a tolerance given, points exceeding it are flagged, and `kp` fails
+    /// if any do
+    #[clap(long, requires = "roundtrip", parse(try_from_str = parse_length))]
+    tolerance: Option<f64>,
+
     /// Echo input to output
     #[clap(short, long)]
     echo: bool,
 
+    /// Echo the effective definition to output, as comment lines: One step per
+    /// line, with macros expanded, all parameters given explicitly, and the
+    /// steps reversed and inverted in `inverse` mode
+    #[clap(long)]
+    echo_definition: bool,
+
+    /// Number of column header lines at the start of each file. Header lines,
+    /// and comment lines (starting with `#`), are passed through unchanged
+    #[clap(long, default_value_t = 0)]
+    header: usize,
+
+    /// Annotate the output of each file with the definition applied, as a
+    /// comment line following the header lines
+    #[clap(long)]
+    annotate: bool,
+
+    /// Trace the transformation: Before the output for each point, write the
+    /// intermediate results after every step of the pipeline, as comment lines
+    #[clap(long, conflicts_with = "roundtrip")]
+    trace: bool,
+
+    /// Number of decimals in the output. Default 10 for angular, and 5 for
+    /// linear coordinates
+    #[clap(short, long)]
+    decimals: Option<usize>,
+
+    /// Convert the first two output coordinates from radians to degrees
+    #[clap(long, conflicts_with = "radians")]
+    degrees: bool,
+
+    /// Convert the first two output coordinates from degrees to radians
+    #[clap(long)]
+    radians: bool,
+
+    /// Output in scientific notation, rather than fixed point
+    #[clap(long)]
+    scientific: bool,
+
+    /// Sexagesimal input: Latitude and longitude in degrees, minutes and
+    /// seconds, e.g. `55 30 36 N 12 45 36 E`, optionally followed by height
+    /// and time. Without hemisphere letters, separate the angles by a comma
+    #[clap(long)]
+    dms_in: bool,
+
+    /// Sexagesimal output: The first two output coordinates, taken as latitude
+    /// and longitude in degrees, in degrees, minutes and seconds, with
+    /// `decimals` (default 3) decimals on the seconds
+    #[clap(long, conflicts_with = "scientific")]
+    dms_out: bool,
+
+    /// Minimum width of each output field (right aligned)
+    #[clap(short, long, default_value_t = 0)]
+    width: usize,
+
+    /// Binary input and output: Packed little-endian f64 records, of `dimension`
+    /// coordinates each. Excludes the text oriented options
+    #[clap(
+        long,
+        conflicts_with_all = &["roundtrip", "echo", "echo-definition", "trace", "annotate", "header", "dms-in", "dms-out"]
+    )]
+    binary: bool,
+
+    /// Number of coordinates per record in `binary` mode (2, 3 or 4)
+    #[clap(long, default_value_t = 4, requires = "binary", parse(try_from_str = parse_dimension))]
+    dimension: usize,
+
+    /// Leave out the points failing to transform, rather than writing them as
+    /// NaNs. Either way, a warning is given for each
+    #[clap(long)]
+    skip_bad: bool,
+
+    /// Stop at the first point failing to transform
+    #[clap(long)]
+    fail_fast: bool,
+
+    /// Number of threads to use for the transformation, or 0 for one per
+    /// available core. With more than one, the input is handled in batches
+    #[clap(short, long, default_value_t = 1)]
+    jobs: usize,
+
     /// Verbose mode (-v, -vv, -vvv, etc.)
     #[clap(short, long, parse(from_occurrences))]
     verbose: u8,
 
+    /// Read named definitions from file, for use as the operation. The file
+    /// holds definitions introduced by `[name]` header lines, or, without
+    /// headers, a single definition named by the file stem. Names are also
+    /// looked up in the resource search path
+    #[clap(
+        short = 'f',
+        long = "file",
+        parse(from_os_str),
+        multiple_occurrences = true,
+        global = true
+    )]
+    definitions: Vec<PathBuf>,
+
     /// Output file, stdout if not present
-    #[clap(short, long, parse(from_os_str))]
-    _output: Option<PathBuf>,
+    #[clap(short, long, parse(from_os_str), global = true)]
+    output: Option<PathBuf>,
 
-    /// First argument is the operation to apply, the remaining the files to operate on
+    /// First argument is the operation to apply, the remaining the files to operate
+    /// on. Input from stdin if no files are given, or for the file name `-`
     args: Vec<String>,
+
+    #[clap(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Transform the positions of GeoJSON geometries, features and feature
+    /// collections, given as (longitude, latitude) in degrees
+    Geojson {
+        /// The operation to apply, typically starting with `gis:in`
+        operation: String,
+        /// The GeoJSON files to transform, stdin if not present
+        files: Vec<String>,
+    },
+    /// Transform the waypoints, track points and route points of GPX files,
+    /// given as (latitude, longitude, elevation) in degrees and metres
+    Gpx {
+        /// The operation to apply, typically starting with `geo:in`, and ending
+        /// with `geo:out`
+        operation: String,
+        /// The GPX files to transform, stdin if not present
+        files: Vec<String>,
+    },
+    /// Transform WKB and EWKB geometries, and GeoPackage geometry blobs, given
+    /// as one hex string per line, as in PostGIS dumps. Coordinates are given
+    /// as (x, y), i.e. (longitude, latitude) in degrees for geographical ones
+    Wkb {
+        /// The operation to apply, typically starting with `gis:in` for
+        /// geographical coordinates
+        operation: String,
+        /// The files to transform, stdin if not present
+        files: Vec<String>,
+    },
+    /// List the operators and macros available
+    List,
+    /// Show the documentation of an operator or macro: The parameters and
+    /// their defaults, or the docstring and the definition
+    Doc {
+        /// The name of the operator or macro
+        name: String,
+    },
+    /// Interactive mode: Define operations, and transform coordinates, one
+    /// line at a time. Type `help` for the commands available
+    Repl {
+        /// The initial operation
+        operation: Option<String>,
+    },
+    /// Run test suites in the style of PROJ's `gie` files: Operations, input
+    /// coordinates, and the output expected. Cf. the `run_gie` documentation
+    Test {
+        /// The test suites to run
+        files: Vec<String>,
+    },
+}
+
+/// The ways `kp` may fail, besides the general case, shown by distinct exit codes:
+/// 1 for errors in general (bad arguments, unknown operators, i/o errors), 2 for
+/// points failing to transform, 3 for points exceeding the roundtrip
+/// tolerance, and 4 for failing tests
+#[derive(Debug)]
+enum Exit {
+    Failed(usize),
+    ExceedingTolerance(usize),
+    FailedTests(usize),
+}
+
+impl Exit {
+    fn code(&self) -> i32 {
+        match self {
+            Exit::Failed(_) => 2,
+            Exit::ExceedingTolerance(_) => 3,
+            Exit::FailedTests(_) => 4,
+        }
+    }
+}
+
+impl std::fmt::Display for Exit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Exit::Failed(n) => write!(f, "{n} points failed to transform"),
+            Exit::ExceedingTolerance(n) => write!(f, "{n} points exceed the roundtrip tolerance"),
+            Exit::FailedTests(n) => write!(f, "{n} tests failed"),
+        }
+    }
 }
 
+impl std::error::Error for Exit {}
+
 fn main() -> Result<(), anyhow::Error> {
+    let result = kp();
+    if let Some(exit) = result.as_ref().err().and_then(|e| e.downcast_ref::<Exit>()) {
+        eprintln!("kp: {exit}");
+        std::process::exit(exit.code());
+    }
+    result
+}
+
+fn kp() -> Result<(), anyhow::Error> {
     SimpleLogger::new()
         .with_level(log::LevelFilter::Error)
         .env()
@@ -58,99 +256,981 @@ fn main() -> Result<(), anyhow::Error> {
     log::trace!("This is KP");
 
     let opt = Cli::parse();
-    println!("args: {:?}", opt.args);
-
-    let ctx = Minimal::new();
+    let mut ctx = Plain::new();
 
     if opt.inverse && opt.roundtrip {
         bail!("Options `inverse` and `roundtrip` are mutually exclusive");
     }
 
     if opt.debug {
-        if let Some(dir) = dirs::data_local_dir() {
-            eprintln!("data_local_dir: {}", dir.to_str().unwrap_or_default());
+        for dir in ctx.paths() {
+            eprintln!("resource path: {}", dir.to_str().unwrap_or_default());
         }
         eprintln!("opt: {:#?}", opt);
     }
 
+    for path in &opt.definitions {
+        let definitions = ctx.expand_includes(&std::fs::read_to_string(path)?)?;
+        if definitions.lines().any(|line| line.trim().starts_with('[')) {
+            ctx.register_definitions(&definitions)?;
+            continue;
+        }
+        let Some(name) = path.file_stem().and_then(|stem| stem.to_str()) else {
+            bail!("Cannot name the definition of {}", path.display());
+        };
+        ctx.register_resource(name, definitions.trim());
+    }
+
+    if let Some(Command::Geojson { operation, files }) = &opt.command {
+        let operation = definition(&ctx, operation)?;
+        return geojson(&opt, &mut ctx, &operation, files);
+    }
+    if let Some(Command::Gpx { operation, files }) = &opt.command {
+        let operation = definition(&ctx, operation)?;
+        return gpx(&opt, &mut ctx, &operation, files);
+    }
+    if let Some(Command::Wkb { operation, files }) = &opt.command {
+        let operation = definition(&ctx, operation)?;
+        return wkb(&opt, &mut ctx, &operation, files);
+    }
+    if let Some(Command::List) = &opt.command {
+        return list(&opt, &ctx);
+    }
+    if let Some(Command::Doc { name }) = &opt.command {
+        return doc(&opt, &ctx, name);
+    }
+    if let Some(Command::Repl { operation }) = &opt.command {
+        return repl(&opt, &mut ctx, operation.as_deref());
+    }
+    if let Some(Command::Test { files }) = &opt.command {
+        return test(&opt, &mut ctx, files);
+    }
+
     if opt.args.is_empty() {
         return Ok(());
     }
 
     let start = time::Instant::now();
-    let op = Op::new(&opt.args[0], &ctx)?;
+    let definition = definition(&ctx, &opt.args[0])?;
+    if opt.verbose > 0 && definition != opt.args[0] {
+        eprintln!("{}: {definition}", opt.args[0]);
+    }
+    let op = instantiate(&mut ctx, &definition)?;
     if opt.verbose > 2 {
         let duration = start.elapsed();
-        println!("Created operation in: {:?}", duration);
-        println!("{:#?}", op);
+        eprintln!("Created operation in: {:?}", duration);
+        eprintln!("{}", describe(&ctx.steps(op)?));
+    }
+
+    let mut output = output(&opt)?;
+    if opt.echo_definition {
+        let mut steps = ctx.steps(op)?;
+        if opt.inverse {
+            StepDescription::invert_sequence(&mut steps);
+        }
+        let steps: Vec<_> = steps.iter().map(|step| step.to_string()).collect();
+        writeln!(output, "#  {}", steps.join("\n#  | "))?;
     }
 
     let start = time::Instant::now();
-    for line in std::io::stdin().lock().lines() {
+    let files = if opt.args.len() > 1 {
+        opt.args[1..].to_vec()
+    } else {
+        vec!["-".to_string()]
+    };
+    let mut roundtrip = Roundtrip::default();
+    let mut failed = 0;
+    for file in files {
+        let input = input(&file)?;
+        if opt.binary {
+            failed += binary(&opt, &ctx, op, input, &mut output)?;
+            continue;
+        }
+        let operation = (&definition[..], op);
+        failed += process(
+            &opt,
+            &ctx,
+            operation,
+            opt.trace,
+            (&file, input),
+            &mut output,
+            &mut roundtrip,
+        )?;
+    }
+
+    if opt.roundtrip {
+        let (max, rms) = (1000. * roundtrip.max, 1000. * roundtrip.rms());
+        write!(
+            output,
+            "# {} points:  max d = {max:.2} mm,  rms = {rms:.2} mm",
+            roundtrip.count
+        )?;
+        if let Some(tolerance) = opt.tolerance {
+            write!(
+                output,
+                ",  {} exceeding {:.2} mm",
+                roundtrip.exceeding,
+                1000. * tolerance
+            )?;
+        }
+        writeln!(output)?;
+    }
+    output.flush()?;
+    if opt.verbose > 1 {
+        let duration = start.elapsed();
+        eprintln!("Transformed in: {:?}", duration);
+    }
+
+    if failed > 0 {
+        return Err(Exit::Failed(failed).into());
+    }
+    if roundtrip.exceeding > 0 {
+        return Err(Exit::ExceedingTolerance(roundtrip.exceeding).into());
+    }
+    Ok(())
+}
+
+/// Transform the coordinates read from `input`, one coordinate tuple per line, of
+/// 2, 3 or 4 whitespace separated columns (the missing ones taken as 0), and write
+/// the results to `output`. With `traced` set, the intermediate results of each
+/// step of `op` are written as well. Returns the number of points
+/// failing to transform
+fn process(
+    opt: &Cli,
+    ctx: &Plain,
+    (definition, op): (&str, OpHandle),
+    traced: bool,
+    (file, input): (&str, impl BufRead),
+    output: &mut dyn Write,
+    roundtrip: &mut Roundtrip,
+) -> Result<usize, anyhow::Error> {
+    // With multiple jobs, the input is transformed in batches. Otherwise, line by
+    // line, so interactive use gets an immediate response
+    let batch_size = if jobs(opt) > 1 && !traced {
+        BATCH_SIZE
+    } else {
+        1
+    };
+    let mut batch = Batch {
+        file: file.to_string(),
+        ..Batch::default()
+    };
+    if opt.annotate && opt.header == 0 {
+        batch.lines.push(Line::Text(annotation(opt, definition)));
+    }
+
+    for (number, line) in input.lines().enumerate() {
         let line = line?;
-        let line = line.trim();
 
-        let mut args: Vec<&str> = line.split_whitespace().collect();
-        let n = args.len();
+        // Header and comment lines pass through unchanged
+        if number < opt.header || line.trim_start().starts_with('#') {
+            batch.lines.push(Line::Text(line));
+            if opt.annotate && number + 1 == opt.header {
+                batch.lines.push(Line::Text(annotation(opt, definition)));
+            }
+            continue;
+        }
+        let line = line.trim();
 
         // Empty line
-        if n < 1 {
+        if line.is_empty() {
             continue;
         }
 
-        // Convert text to CoordinateTuple
-        args.extend(["0"; 4]);
-        let mut b: Vec<f64> = vec![];
-        for e in args {
-            b.push(e.parse().unwrap_or(std::f64::NAN))
+        // Convert text to Coord
+        let (coord, n) = if opt.dms_in {
+            parse_dms(line)
+        } else {
+            let mut args: Vec<&str> = line.split_whitespace().collect();
+            let n = args.len();
+            args.extend(["0"; 4]);
+            let mut b: Vec<f64> = vec![];
+            for e in args {
+                b.push(e.parse().unwrap_or(f64::NAN))
+            }
+            (Coord::raw(b[0], b[1], b[2], b[3]), n)
+        };
+        batch
+            .lines
+            .push(Line::Point(line.to_string(), n, number + 1));
+        batch.coords.push(coord);
+
+        if batch.coords.len() >= batch_size {
+            batch.flush(opt, ctx, (definition, op), traced, output, roundtrip)?;
         }
-        let coord = Coord::raw(b[0], b[1], b[2], b[3]);
-        let mut data = [coord];
+    }
+    batch.flush(opt, ctx, (definition, op), traced, output, roundtrip)?;
+    Ok(batch.failed)
+}
+
+// The number of coordinates transformed at a time, with multiple jobs
+const BATCH_SIZE: usize = 100_000;
+
+/// The number of threads to use: `jobs`, or, for 0, one per available core
+fn jobs(opt: &Cli) -> usize {
+    match opt.jobs {
+        0 => std::thread::available_parallelism().map_or(1, |n| n.get()),
+        jobs => jobs,
+    }
+}
+
+/// A line of input: Text to pass through, or a coordinate tuple, with the number
+/// of coordinates given, and the line number
+enum Line {
+    Text(String),
+    Point(String, usize, usize),
+}
+
+/// The input lines read from `file`, but not yet written, and their coordinates,
+/// along with the number of points failed so far
+#[derive(Default)]
+struct Batch {
+    file: String,
+    lines: Vec<Line>,
+    coords: Vec<Coord>,
+    failed: usize,
+}
+
+impl Batch {
+    /// Transform the coordinates of the batch, and write the results to `output`,
+    /// along with the text lines, in the order read
+    fn flush(
+        &mut self,
+        opt: &Cli,
+        ctx: &Plain,
+        (definition, op): (&str, OpHandle),
+        traced: bool,
+        output: &mut dyn Write,
+        roundtrip: &mut Roundtrip,
+    ) -> Result<(), anyhow::Error> {
+        let input = self.coords.clone();
+        let mut trace = Vec::new();
 
         // Transformation - this is the actual geodetic content
-        if opt.inverse {
-            op.apply(&ctx, &mut data, Inv)?;
-            if opt.roundtrip {
-                op.apply(&ctx, &mut data, Fwd)?;
+        let (first, second) = if opt.inverse { (Inv, Fwd) } else { (Fwd, Inv) };
+        let jobs = jobs(opt);
+        let mut apply = |direction| -> Result<usize, geodesy::Error> {
+            if traced {
+                let mut tracer = |step: &Op, direction: Direction, operands: &[Coord]| {
+                    let inv = if direction == Inv { " (inv)" } else { "" };
+                    let coords = operands.iter().map(|c| format_coord(opt, *c));
+                    let step = &step.descriptor.definition;
+                    trace.extend(coords.map(|coord| format!("#  {step}{inv}: {coord}")));
+                };
+                return ctx.apply_traced(op, direction, &mut self.coords, &mut tracer);
             }
-        } else {
-            op.apply(&ctx, &mut data, Fwd)?;
+            if jobs > 1 {
+                return ctx.apply_par_with(op, direction, &mut self.coords, jobs);
+            }
+            ctx.apply(op, direction, &mut self.coords)
+        };
+        apply(first)?;
+        if opt.roundtrip {
+            apply(second)?;
+        }
+
+        let mut points = input.iter().zip(&self.coords);
+        for line in self.lines.drain(..) {
+            let (line, n, number) = match line {
+                Line::Text(text) => {
+                    writeln!(output, "{text}")?;
+                    continue;
+                }
+                Line::Point(line, n, number) => (line, n, number),
+            };
+            let Some((input, result)) = points.next() else {
+                break;
+            };
+
+            let status = result.status();
+            if status != PointStatus::Ok {
+                self.failed += 1;
+                let file = if self.file == "-" {
+                    "<stdin>"
+                } else {
+                    &self.file
+                };
+                eprintln!("kp: warning: {file}:{number}: {status}: {line}");
+                if opt.fail_fast {
+                    return Err(Exit::Failed(self.failed).into());
+                }
+                if opt.skip_bad {
+                    continue;
+                }
+            }
+
             if opt.roundtrip {
-                op.apply(&ctx, &mut data, Inv)?;
+                let d = roundtrip_distance(definition, n, *input, *result);
+                let exceeding = roundtrip.add(d, opt.tolerance);
+                let flag = if exceeding {
+                    "  (exceeds tolerance)"
+                } else {
+                    ""
+                };
+                writeln!(output, "{}:  d = {:.2} mm{flag}", line, 1000. * d)?;
+                continue;
+            }
+            // Print output
+            if opt.echo {
+                writeln!(output, "#  {}", line)?;
+            }
+            for step in trace.drain(..) {
+                writeln!(output, "{step}")?;
             }
+            writeln!(output, "{}", format_coord(opt, *result))?;
         }
+        self.coords.clear();
+        Ok(())
+    }
+}
 
-        if opt.roundtrip {
-            let d = roundtrip_distance(&opt.args[0], n, coord, data[0]);
-            println!("{}:  d = {:.2} mm", line, 1000. * d);
-            continue;
+/// Transform the packed little-endian f64 records of `opt.dimension` coordinates
+/// read from `input`, and write the results to `output`, in the same format.
+/// Returns the number of points failing to transform
+fn binary(
+    opt: &Cli,
+    ctx: &Plain,
+    op: OpHandle,
+    mut input: impl BufRead,
+    output: &mut dyn Write,
+) -> Result<usize, anyhow::Error> {
+    const SIZE: usize = std::mem::size_of::<f64>();
+    let dimension = opt.dimension;
+    let direction = if opt.inverse { Inv } else { Fwd };
+    let jobs = jobs(opt);
+    let mut buffer = vec![0_u8; BATCH_SIZE * dimension * SIZE];
+    let mut coords = Vec::with_capacity(BATCH_SIZE);
+    let mut records = 0;
+    let mut failed = 0;
+
+    loop {
+        // Fill the buffer, as far as the input goes
+        let mut length = 0;
+        while length < buffer.len() {
+            match input.read(&mut buffer[length..])? {
+                0 => break,
+                n => length += n,
+            }
         }
-        // Print output
-        if opt.echo {
-            println!("#  {}", line);
+        if length % (dimension * SIZE) != 0 {
+            bail!("Binary input is not a whole number of {dimension}D records");
         }
-        if data[0][0] > 1000. {
-            // Projected or cartesian coordinates
-            println!(
-                "{:.5} {:.5} {:.5} {:.5}",
-                data[0][0], data[0][1], data[0][2], data[0][3]
-            );
+
+        coords.clear();
+        for record in buffer[..length].chunks_exact(dimension * SIZE) {
+            let mut coord = Coord::origin();
+            for (i, value) in record.chunks_exact(SIZE).enumerate() {
+                coord[i] = f64::from_le_bytes(value.try_into()?);
+            }
+            coords.push(coord);
+        }
+
+        if jobs > 1 {
+            ctx.apply_par_with(op, direction, &mut coords, jobs)?;
         } else {
-            // Angular coordinates
-            println!(
-                "{:.10} {:.10} {:.5} {:.5}",
-                data[0][0], data[0][1], data[0][2], data[0][3]
+            ctx.apply(op, direction, &mut coords)?;
+        }
+        for coord in &coords {
+            records += 1;
+            let status = coord.status();
+            if status != PointStatus::Ok {
+                failed += 1;
+                eprintln!("kp: warning: record {records}: {status}");
+                if opt.fail_fast {
+                    return Err(Exit::Failed(failed).into());
+                }
+                if opt.skip_bad {
+                    continue;
+                }
+            }
+            for i in 0..dimension {
+                output.write_all(&coord[i].to_le_bytes())?;
+            }
+        }
+
+        if length < buffer.len() {
+            return Ok(failed);
+        }
+    }
+}
+
+/// The number of coordinates per record: 2, 3 or 4
+fn parse_dimension(dimension: &str) -> Result<usize, anyhow::Error> {
+    let dimension = dimension.parse::<usize>()?;
+    if !(2..=4).contains(&dimension) {
+        bail!("Dimension must be 2, 3 or 4");
+    }
+    Ok(dimension)
+}
+
+/// The definition applied by `opt`, as a comment line
+fn annotation(opt: &Cli, definition: &str) -> String {
+    let mode = if opt.inverse { " --inv" } else { "" };
+    let definition = definition.split_whitespace().collect::<Vec<_>>().join(" ");
+    format!("# kp{mode}: {definition}")
+}
+
+/// Transform GeoJSON files. The files are handled one by one, giving one line of
+/// GeoJSON output for each
+#[cfg(feature = "serde")]
+fn geojson(
+    opt: &Cli,
+    ctx: &mut Plain,
+    operation: &str,
+    files: &[String],
+) -> Result<(), anyhow::Error> {
+    let op = instantiate(ctx, operation)?;
+    let direction = if opt.inverse { Inv } else { Fwd };
+    let mut output = output(opt)?;
+    let files = if files.is_empty() {
+        vec!["-".to_string()]
+    } else {
+        files.to_vec()
+    };
+    for file in files {
+        let mut geojson: serde_json::Value = serde_json::from_reader(input(&file)?)?;
+        geodesy::transform_geojson(ctx, op, direction, &mut geojson)?;
+        writeln!(output, "{geojson}")?;
+    }
+    output.flush()?;
+    Ok(())
+}
+
+#[cfg(not(feature = "serde"))]
+fn geojson(_: &Cli, _: &mut Plain, _: &str, _: &[String]) -> Result<(), anyhow::Error> {
+    bail!("GeoJSON support requires building kp with the `serde` feature");
+}
+
+const REPL_HELP: &str = "Commands:
+    op <definition>         Set the operation to apply
+    def <name> <definition> Define a named operation, for use in later definitions
+    show                    Show the current operation, with all steps expanded
+    inv                     Toggle inverse mode
+    trace                   Toggle tracing of the intermediate results
+    doc <name>              Show the documentation of an operator or macro
+    list                    List the operators and macros available
+    help                    Show this text
+    quit                    Leave
+Any other line is taken as a coordinate tuple, and transformed";
+
+/// The interactive mode: Read commands and coordinates from stdin, and write the
+/// results to stdout, keeping the context, and hence the definitions, alive
+fn repl(opt: &Cli, ctx: &mut Plain, operation: Option<&str>) -> Result<(), anyhow::Error> {
+    let mut inverse = opt.inverse;
+    let mut trace = opt.trace;
+    let mut current: Option<OpHandle> = None;
+    if let Some(operation) = operation {
+        let definition = definition(ctx, operation)?;
+        current = Some(instantiate(ctx, &definition)?);
+    }
+
+    // Prompt only when used interactively, not when reading a script
+    let interactive = std::io::stdin().is_terminal();
+    let mut stdout = std::io::stdout();
+    let mut lines = std::io::stdin().lock().lines();
+    loop {
+        if interactive {
+            write!(stdout, "kp> ")?;
+            stdout.flush()?;
+        }
+        let Some(line) = lines.next() else {
+            return Ok(());
+        };
+        let line = line?;
+        let line = line.trim();
+        let (command, argument) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+        let argument = argument.trim();
+
+        // Ok(false) for leaving
+        let result: Result<bool, anyhow::Error> = (|| {
+            match command {
+                "" => {}
+                "quit" | "exit" => return Ok(false),
+                "help" => writeln!(stdout, "{REPL_HELP}")?,
+                "op" => {
+                    let definition = definition(ctx, argument)?;
+                    current = Some(instantiate(ctx, &definition)?);
+                }
+                "def" => {
+                    let Some((name, definition)) = argument.split_once(char::is_whitespace) else {
+                        anyhow::bail!("Usage: def <name> <definition>");
+                    };
+                    ctx.register_resource(name, definition.trim());
+                }
+                "show" => match &current {
+                    Some(op) => writeln!(stdout, "{}", describe(&ctx.steps(*op)?))?,
+                    None => writeln!(stdout, "No operation given")?,
+                },
+                "inv" => {
+                    inverse = !inverse;
+                    writeln!(stdout, "inverse: {inverse}")?;
+                }
+                "trace" => {
+                    trace = !trace;
+                    writeln!(stdout, "trace: {trace}")?;
+                }
+                "doc" => doc(opt, ctx, argument)?,
+                "list" => list(opt, ctx)?,
+                _ => {
+                    let Some(op) = &current else {
+                        anyhow::bail!("No operation given. Use `op <definition>`");
+                    };
+                    let (coord, _) = if opt.dms_in {
+                        parse_dms(line)
+                    } else {
+                        let mut b: Vec<f64> = vec![0.; 4];
+                        for (i, e) in line.split_whitespace().take(4).enumerate() {
+                            let Ok(e) = e.parse() else {
+                                anyhow::bail!("Unknown command, or bad coordinate: {line}");
+                            };
+                            b[i] = e;
+                        }
+                        (Coord::raw(b[0], b[1], b[2], b[3]), 0)
+                    };
+                    let mut data = [coord];
+                    let direction = if inverse { Inv } else { Fwd };
+                    if trace {
+                        let mut tracer = |step: &Op, direction: Direction, operands: &[Coord]| {
+                            let inv = if direction == Inv { " (inv)" } else { "" };
+                            let coord = format_coord(opt, operands[0]);
+                            let step = &step.descriptor.definition;
+                            println!("#  {step}{inv}: {coord}");
+                        };
+                        ctx.apply_traced(*op, direction, &mut data, &mut tracer)?;
+                    } else {
+                        ctx.apply(*op, direction, &mut data)?;
+                    }
+                    writeln!(stdout, "{}", format_coord(opt, data[0]))?;
+                }
+            }
+            Ok(true)
+        })();
+
+        match result {
+            Ok(true) => {}
+            Ok(false) => return Ok(()),
+            Err(e) => writeln!(stdout, "Error: {e}")?,
+        }
+    }
+}
+
+/// List the operators and the macros available, with their short descriptions
+fn list(opt: &Cli, ctx: &Plain) -> Result<(), anyhow::Error> {
+    let mut output = output(opt)?;
+    let operators = ctx.operators();
+    let macros = ctx.resources();
+    let names = operators.iter().map(|op| &op.name).chain(&macros);
+    let width = names.map(|name| name.len()).max().unwrap_or_default() + 2;
+
+    writeln!(output, "Operators:")?;
+    for op in operators {
+        writeln!(output, "    {:<width$}{}", op.name, op.doc)?;
+    }
+    // Macros without a docstring are described by their definition
+    writeln!(output, "\nMacros:")?;
+    for name in macros {
+        let definition = ctx.get_resource(&name).unwrap_or_default();
+        let mut doc = docstring(&definition);
+        if doc.is_empty() {
+            doc = uncommented(&definition);
+        }
+        let doc = doc.lines().next().unwrap_or_default();
+        writeln!(output, "    {name:<width$}{doc}")?;
+    }
+    output.flush()?;
+    Ok(())
+}
+
+/// Document the operator or macro `name`
+fn doc(opt: &Cli, ctx: &Plain, name: &str) -> Result<(), anyhow::Error> {
+    let mut output = output(opt)?;
+    if let Some(op) = ctx.operators().into_iter().find(|op| op.name == name) {
+        writeln!(output, "{}: {}", op.name, op.doc)?;
+        if !op.gamut.is_empty() {
+            writeln!(output, "\nParameters:")?;
+        }
+        for parameter in op.gamut {
+            let (kind, default) = match *parameter {
+                OpParameter::Flag { .. } => ("flag", None),
+                OpParameter::Natural { default, .. } => ("natural", default.map(|d| d.to_string())),
+                OpParameter::Integer { default, .. } => ("integer", default.map(|d| d.to_string())),
+                OpParameter::Real { default, .. } => ("real", default.map(|d| d.to_string())),
+                OpParameter::Series { default, .. } => ("series", default.map(String::from)),
+                OpParameter::Text { default, .. } => ("text", default.map(String::from)),
+                OpParameter::Alias { canonical, .. } => ("alias", Some(canonical.to_string())),
+            };
+            let default = match default {
+                Some(canonical) if kind == "alias" => format!("of {canonical}"),
+                Some(default) => format!("default: {default}"),
+                None if kind == "flag" => String::new(),
+                None => "required".to_string(),
+            };
+            let line = format!("    {:<12}{kind:<10}{default}", parameter.key());
+            writeln!(output, "{}", line.trim_end())?;
+        }
+    } else if let Ok(definition) = ctx.get_resource(name) {
+        let doc = docstring(&definition);
+        if !doc.is_empty() {
+            writeln!(output, "{doc}\n")?;
+        }
+        // The placeholders declared, cf. the `#$` lines of the definition
+        let placeholders: Vec<_> = definition
+            .lines()
+            .filter_map(|line| line.trim().strip_prefix("#$"))
+            .flat_map(str::split_whitespace)
+            .collect();
+        if !placeholders.is_empty() {
+            writeln!(output, "Parameters:")?;
+            for placeholder in placeholders {
+                let line = match placeholder.split_once('=') {
+                    Some((key, default)) => format!("    {key:<12}default: {default}"),
+                    None => format!("    {placeholder:<12}required"),
+                };
+                writeln!(output, "{line}")?;
+            }
+            writeln!(output)?;
+        }
+        writeln!(output, "Definition:\n    {}", uncommented(&definition))?;
+    } else {
+        bail!("Unknown operator or macro: {name}");
+    }
+    output.flush()?;
+    Ok(())
+}
+
+/// Run the test suites in `files`, reporting each failing test by its file
+/// and line, followed by a summary
+fn test(opt: &Cli, ctx: &mut Plain, files: &[String]) -> Result<(), anyhow::Error> {
+    let mut output = output(opt)?;
+    let (mut tests, mut failures) = (0, 0);
+    for file in files {
+        let suite = std::fs::read_to_string(file)?;
+        let report = match geodesy::run_gie(ctx, &suite) {
+            Ok(report) => report,
+            Err(e) => bail!("{file}: {e}"),
+        };
+        for failure in &report.failures {
+            writeln!(output, "{file}:{}: {}", failure.line, failure.message)?;
+        }
+        if opt.verbose > 0 {
+            writeln!(
+                output,
+                "{file}: {} tests, {} failures",
+                report.tests,
+                report.failures.len()
+            )?;
+        }
+        tests += report.tests;
+        failures += report.failures.len();
+    }
+    writeln!(output, "{tests} tests, {failures} failures")?;
+    output.flush()?;
+    if failures > 0 {
+        return Err(Exit::FailedTests(failures).into());
+    }
+    Ok(())
+}
+
+/// The docstring of a macro: Its `##`-comment lines
+fn docstring(definition: &str) -> String {
+    let lines: Vec<_> = definition
+        .lines()
+        .filter_map(|line| line.trim_start().strip_prefix("##"))
+        .map(str::trim)
+        .collect();
+    lines.join("\n")
+}
+
+/// The definition of `operation`:
+/// - For a plain name, i.e. a single word without `:`, one of the definitions
+///   read, or found in the resource search path, if any, with comment lines
+///   removed, and its `#$` placeholders replaced by their defaults
+/// - For a pair of CRS, `EPSG:nnnn EPSG:mmmm`, the transformation from the first
+///   to the second, i.e. the inverse of the first followed by the second
+/// - For a PROJ string, `+proj=...`, its RG equivalent, taking longitude and
+///   latitude in degrees as input, as the `proj` program does
+///
+/// Otherwise `operation` itself
+fn definition(ctx: &Plain, operation: &str) -> Result<String, anyhow::Error> {
+    let operation = operation.trim();
+    if operation.starts_with('+') {
+        return Ok(format!("gis:in | {}", geodesy::parse_proj(operation)?));
+    }
+
+    let crs: Vec<_> = operation.split_whitespace().collect();
+    let is_epsg = |crs: &str| {
+        crs.split_once(':')
+            .is_some_and(|(authority, _)| authority.eq_ignore_ascii_case("epsg"))
+    };
+    if crs.len() == 2 && crs.iter().all(|crs| is_epsg(crs)) {
+        return Ok(format!("{} inv | {}", crs[0], crs[1]));
+    }
+
+    let plain = !operation.contains(|c: char| c == ':' || c == '|' || c.is_whitespace());
+    match ctx.get_resource(operation) {
+        Ok(definition) if plain => inlined(ctx, operation, &definition),
+        _ => Ok(operation.to_string()),
+    }
+}
+
+/// The definition of the plain named `name`, for inlining: Since it is not
+/// instantiated as a macro, the defaults of its `#$` placeholders are applied
+/// here. Placeholders without defaults must be given by the context globals
+fn inlined(ctx: &Plain, name: &str, definition: &str) -> Result<String, anyhow::Error> {
+    let mut inlined = uncommented(definition);
+    let placeholders = definition
+        .lines()
+        .filter_map(|line| line.trim().strip_prefix("#$"))
+        .flat_map(str::split_whitespace);
+    for placeholder in placeholders {
+        let Some((key, default)) = placeholder.split_once('=') else {
+            if !ctx.globals().contains_key(placeholder) {
+                bail!(
+                    "missing required parameter {placeholder} (required by the definition {name})"
+                );
+            }
+            continue;
+        };
+        inlined = replace_placeholder(&inlined, key, default);
+    }
+    Ok(inlined)
+}
+
+/// `text`, with the references `$key` replaced by `value`. References to other
+/// keys, starting with `key`, e.g. `$key_2`, are left as is
+fn replace_placeholder(text: &str, key: &str, value: &str) -> String {
+    let needle = format!("${key}");
+    let mut result = String::new();
+    let mut rest = text;
+    while let Some(start) = rest.find(&needle) {
+        let end = start + needle.len();
+        let whole = !rest[end..].starts_with(|c: char| c.is_alphanumeric() || c == '_');
+        result.push_str(&rest[..start]);
+        result.push_str(if whole { value } else { &needle });
+        rest = &rest[end..];
+    }
+    result + rest
+}
+
+/// Instantiate the operator given by `definition`, warning about anything
+/// questionable in the definition, e.g. ignored parameters
+fn instantiate(ctx: &mut Plain, definition: &str) -> Result<OpHandle, anyhow::Error> {
+    let op = ctx.op(definition)?;
+    for warning in ctx.warnings(op)? {
+        eprintln!("kp: warning: {warning}");
+    }
+    Ok(op)
+}
+
+/// A description of the operator given by `steps`, one step per line, cf.
+/// [`Op::describe`]
+fn describe(steps: &[StepDescription]) -> String {
+    let steps: Vec<_> = steps.iter().map(|step| step.to_string()).collect();
+    steps.join("\n| ")
+}
+
+/// `definition`, with comment lines removed
+fn uncommented(definition: &str) -> String {
+    let lines: Vec<_> = definition
+        .lines()
+        .filter(|line| !line.trim_start().starts_with('#'))
+        .collect();
+    lines.join("\n").trim().to_string()
+}
+
+/// Transform GPX files. The files are handled one by one, and written to the
+/// output in turn
+fn gpx(opt: &Cli, ctx: &mut Plain, operation: &str, files: &[String]) -> Result<(), anyhow::Error> {
+    let op = instantiate(ctx, operation)?;
+    let direction = if opt.inverse { Inv } else { Fwd };
+    let mut output = output(opt)?;
+    let files = if files.is_empty() {
+        vec!["-".to_string()]
+    } else {
+        files.to_vec()
+    };
+    let mut failed = 0;
+    for file in files {
+        let mut gpx = String::new();
+        input(&file)?.read_to_string(&mut gpx)?;
+        let (transformed, points, successes) = geodesy::transform_gpx(ctx, op, direction, &gpx)?;
+        if successes < points {
+            eprintln!(
+                "kp: warning: {file}: {} points failed to transform, and are left unchanged",
+                points - successes
             );
+            failed += points - successes;
         }
+        write!(output, "{transformed}")?;
     }
-    if opt.verbose > 1 {
-        let duration = start.elapsed();
-        println!("Transformed in: {:?}", duration);
+    output.flush()?;
+    if failed > 0 {
+        return Err(Exit::Failed(failed).into());
     }
+    Ok(())
+}
 
+/// Transform hex encoded WKB geometries, one per line, giving one line of hex
+/// encoded output for each. Blank lines and `#` comments pass through
+fn wkb(opt: &Cli, ctx: &mut Plain, operation: &str, files: &[String]) -> Result<(), anyhow::Error> {
+    let op = instantiate(ctx, operation)?;
+    let direction = if opt.inverse { Inv } else { Fwd };
+    let mut output = output(opt)?;
+    let files = if files.is_empty() {
+        vec!["-".to_string()]
+    } else {
+        files.to_vec()
+    };
+    for file in files {
+        for (number, line) in input(&file)?.lines().enumerate() {
+            let line = line?;
+            let text = line.trim();
+            if text.is_empty() || text.starts_with('#') {
+                writeln!(output, "{line}")?;
+                continue;
+            }
+            // PostgreSQL gives bytea values prefixed by `\x`
+            let text = text.strip_prefix("\\x").unwrap_or(text);
+            let Some(mut geometry) = hex_decode(text) else {
+                bail!("{file}:{}: Not a hex encoded geometry: {line}", number + 1);
+            };
+            if let Err(e) = geodesy::transform_wkb(ctx, op, direction, &mut geometry) {
+                bail!("{file}:{}: {e}", number + 1);
+            }
+            let hex: String = geometry.iter().map(|byte| format!("{byte:02X}")).collect();
+            writeln!(output, "{hex}")?;
+        }
+    }
+    output.flush()?;
     Ok(())
 }
 
+fn hex_decode(text: &str) -> Option<Vec<u8>> {
+    if text.len() % 2 != 0 || !text.bytes().all(|byte| byte.is_ascii_hexdigit()) {
+        return None;
+    }
+    (0..text.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&text[i..i + 2], 16).ok())
+        .collect()
+}
+
+/// The input file `file`, or stdin for the file name `-`
+fn input(file: &str) -> Result<Box<dyn BufRead>, anyhow::Error> {
+    if file == "-" {
+        return Ok(Box::new(std::io::stdin().lock()));
+    }
+    Ok(Box::new(BufReader::new(File::open(file)?)))
+}
+
+/// The output file given by `opt`, or stdout
+fn output(opt: &Cli) -> Result<Box<dyn Write>, anyhow::Error> {
+    Ok(match &opt.output {
+        Some(path) => Box::new(BufWriter::new(File::create(path)?)),
+        None => Box::new(BufWriter::new(std::io::stdout().lock())),
+    })
+}
+
+/// Format `coord` for output, as specified by the formatting options of `opt`
+fn format_coord(opt: &Cli, mut coord: Coord) -> String {
+    if opt.degrees {
+        coord = coord.to_degrees();
+    }
+    if opt.radians {
+        coord = coord.to_radians();
+    }
+
+    // Angular coordinates need more decimals than projected and cartesian
+    let decimals = |angular| opt.decimals.unwrap_or(if angular { 10 } else { 5 });
+    let angular = coord[0].abs() <= 1000.;
+    let width = opt.width;
+    let first = if opt.dms_out { 2 } else { 0 };
+    let mut fields: Vec<String> = (first..4)
+        .map(|i| {
+            let decimals = decimals(angular && i < 2);
+            if opt.scientific {
+                format!("{:>width$.decimals$e}", coord[i])
+            } else {
+                format!("{:>width$.decimals$}", coord[i])
+            }
+        })
+        .collect();
+    if opt.dms_out {
+        let geo = Coord::geo(coord[0], coord[1], 0., 0.);
+        let dms = geo.to_dms_string(opt.decimals.unwrap_or(3));
+        fields.insert(0, format!("{dms:>width$}"));
+    }
+    fields.join(" ")
+}
+
+/// Parse a line of sexagesimal input, cf. [`Coord::parse_geo`]: A pair of angles,
+/// followed by up to two further coordinates (height and time). Returns the
+/// coordinate tuple, as latitude and longitude in degrees, followed by height and
+/// time, and the number of coordinates given. Unparsable lines give `NaN`s
+fn parse_dms(line: &str) -> (Coord, usize) {
+    let fields: Vec<&str> = line.split_whitespace().collect();
+    // Try the fewest possible trailing coordinates first, since the last field
+    // of a pair of angles may itself look like a number
+    for extra in 0..=2.min(fields.len()) {
+        let (angles, rest) = fields.split_at(fields.len() - extra);
+        let Ok(rest) = rest
+            .iter()
+            .map(|f| f.parse::<f64>())
+            .collect::<Result<Vec<_>, _>>()
+        else {
+            continue;
+        };
+        let Ok(geo) = Coord::parse_geo(&angles.join(" ")) else {
+            continue;
+        };
+        let geo = geo.to_degrees();
+        let height = rest.first().copied().unwrap_or(0.);
+        let time = rest.get(1).copied().unwrap_or(0.);
+        return (Coord::raw(geo[1], geo[0], height, time), 2 + extra);
+    }
+    (Coord::nan(), 2)
+}
+
+/// Summary statistics of the roundtrip distances
+#[derive(Debug, Default)]
+struct Roundtrip {
+    count: usize,
+    max: f64,
+    sum_of_squares: f64,
+    exceeding: usize,
+}
+
+impl Roundtrip {
+    /// Register the distance `d`. Returns true if it exceeds `tolerance`
+    /// (or is NaN, with a tolerance given)
+    fn add(&mut self, d: f64, tolerance: Option<f64>) -> bool {
+        self.count += 1;
+        self.max = self.max.max(d);
+        if d.is_finite() {
+            self.sum_of_squares += d * d;
+        }
+        let exceeding = tolerance.is_some_and(|tolerance| d.is_nan() || d > tolerance);
+        self.exceeding += exceeding as usize;
+        exceeding
+    }
+
+    fn rms(&self) -> f64 {
+        (self.sum_of_squares / self.count.max(1) as f64).sqrt()
+    }
+}
+
+/// A length, given in metres, or with one of the unit suffixes `mm`, `cm`,
+/// `m`, `km`, e.g. `1mm`
+fn parse_length(length: &str) -> Result<f64, anyhow::Error> {
+    let length = length.trim();
+    for (unit, scale) in [("mm", 1e-3), ("cm", 1e-2), ("km", 1e3), ("m", 1.)] {
+        if let Some(value) = length.strip_suffix(unit) {
+            return Ok(value.trim().parse::<f64>()? * scale);
+        }
+    }
+    Ok(length.parse::<f64>()?)
+}
+
 /// Distance between input and output after a forward-inverse roundtrip
 fn roundtrip_distance(op: &str, dim: usize, mut input: Coord, mut result: Coord) -> f64 {
     // Try to figure out what kind of coordinates we're working with
@@ -53,6 +53,7 @@ fn main() -> Result<(), Error> {
     let mut pile = OpenOptions::new()
         .write(true)
         .create(true)
+        .truncate(false)
         .open(pile_path)?;
     // Seek to end to make later `stream_position()` calls work correctly
     pile.seek(SeekFrom::End(0))?;
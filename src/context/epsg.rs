@@ -0,0 +1,91 @@
+// ----- A N   E M B E D D E D   E P S G   S U B S E T ---------------------------------
+
+// Definitions for a small subset of the EPSG dataset: Geographic and projected CRS,
+// given as the conversion from their base geographic CRS, and a few common
+// transformations.
+// In both cases, the input is geographical coordinates in degrees, in the EPSG
+// axis order (latitude, longitude). The output follows the axis order of the
+// EPSG definition of the target CRS.
+#[rustfmt::skip]
+const EPSG_DEFINITIONS: [(u32, &str); 11] = [
+    // WGS 84, ETRS89, ED50: Geographic CRS, i.e. no conversion. Note that
+    // these are NOT mutually interchangeable, cf. `epsg_datum` below
+    (4326, "noop"),
+    (4258, "noop"),
+    (4230, "noop"),
+
+    // WGS 84 / Pseudo-Mercator
+    (3857, "geo:in | merc R=6378137"),
+    // ETRS89-extended / LCC Europe
+    (3034, "geo:in | lcc lat_1=35 lat_2=65 lat_0=52 lon_0=10 x_0=4000000 y_0=2800000 ellps=GRS80 | neu:out"),
+    // ETRS89-extended / LAEA Europe
+    (3035, "geo:in | laea lat_0=52 lon_0=10 x_0=4321000 y_0=3210000 ellps=GRS80 | neu:out"),
+    // OSGB36 / British National Grid
+    (27700, "geo:in | tmerc lat_0=49 lon_0=-2 k_0=0.9996012717 x_0=400000 y_0=-100000 ellps=airy"),
+
+    // ED50 to WGS 84 (1)
+    (1133, "#@ area_of_use=-16.1,25.71,48.61,84.73 accuracy=10
+            geo:in | cart ellps=intl | helmert x=-87 y=-98 z=-121 | cart inv ellps=WGS84 | geo:out"),
+    // ETRS89 to WGS 84 (1)
+    (1149, "noop"),
+    // NAD83 to WGS 84 (1)
+    (1188, "noop"),
+    // OSGB36 to WGS 84 (6)
+    (1314, "#@ area_of_use=-9.01,49.75,2.01,61.01 accuracy=2
+            geo:in | cart ellps=airy
+            | helmert x=446.448 y=-125.157 z=542.06 rx=0.15 ry=0.247 rz=0.842 s=-20.489
+              convention=position_vector
+            | cart inv ellps=WGS84 | geo:out"),
+];
+
+/// The definition of the operation corresponding to `name`, given as `EPSG:nnnn`,
+/// if `nnnn` is one of the codes covered by the embedded EPSG subset.
+pub(crate) fn epsg_definition(name: &str) -> Option<String> {
+    let (authority, code) = name.split_once(':')?;
+    if !authority.eq_ignore_ascii_case("epsg") {
+        return None;
+    }
+    let code = code.trim().parse::<u32>().ok()?;
+
+    // The UTM families
+    let utm = |zone: u32, ellps: &str| format!("geo:in | utm zone={zone} ellps={ellps}");
+    match code {
+        // ED50 / UTM zone 28N..38N
+        23028..=23038 => return Some(utm(code - 23000, "intl")),
+        // ETRS89 / UTM zone 28N..38N
+        25828..=25838 => return Some(utm(code - 25800, "GRS80")),
+        // WGS 84 / UTM zone 1N..60N
+        32601..=32660 => return Some(utm(code - 32600, "WGS84")),
+        // WGS 84 / UTM zone 1S..60S
+        32701..=32760 => {
+            let lon_0 = -183 + 6 * (code - 32700) as i32;
+            return Some(format!(
+                "geo:in | tmerc lon_0={lon_0} k_0=0.9996 x_0=500000 y_0=10000000 ellps=WGS84"
+            ));
+        }
+        _ => (),
+    }
+
+    EPSG_DEFINITIONS
+        .iter()
+        .find(|e| e.0 == code)
+        .map(|e| e.1.to_string())
+}
+
+/// The datum of the CRS given by `name`, as `EPSG:nnnn`, if `nnnn` is one of
+/// the CRS covered by the embedded EPSG subset. WGS 84 and ETRS89 are taken to
+/// be the same datum, in accordance with the null transformation EPSG:1149.
+pub(crate) fn epsg_datum(name: &str) -> Option<&'static str> {
+    let (authority, code) = name.split_once(':')?;
+    if !authority.eq_ignore_ascii_case("epsg") {
+        return None;
+    }
+    match code.trim().parse::<u32>().ok()? {
+        4326 | 4258 | 3857 | 3034 | 3035 | 25828..=25838 | 32601..=32660 | 32701..=32760 => {
+            Some("WGS 84")
+        }
+        4230 | 23028..=23038 => Some("ED50"),
+        27700 => Some("OSGB36"),
+        _ => None,
+    }
+}
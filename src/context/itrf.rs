@@ -0,0 +1,450 @@
+// The IERS transformation parameters between the ITRF realizations, as given
+// with the ITRF2020 and ITRF2014 solutions, for providers to make available by
+// name, as `itrfXXXX:itrfYYYY`. Like the other built in macros, they take, and
+// return, geographical coordinates in degrees, in the EPSG axis order, on GRS80.
+// The inverse transformations are given as inverted macros. The macros may
+// also be invoked by the aliases `itrfXXXX_to_itrfYYYY`, cf. `itrf_macro_name`.
+//
+// Following these, the EUREF boundary conditions for the ETRF realizations of
+// ETRS89, cf. EUREF TN-1: ITRFyy to ETRFyy is a rotation, growing at the rates
+// given from 1989.0, the defining epoch of ETRS89, preceded by a translation
+// for the earlier realizations. ITRF realizations without a boundary condition
+// of their own are taken to ETRF2000, the realization recommended by EUREF, by
+// way of the IERS parameters. (ITRF2014 to ETRF2014 is in `BUILTIN_MACROS`).
+//
+// Finally, the dynamic-to-plate-fixed transformations, following the NKG2020
+// model of the Nordic Geodetic Commission: ITRFyy at the observation epoch, to
+// ETRFyy by the boundary conditions, then to a fixed epoch by integrating the
+// intraplate velocities of a deformation model, e.g. NKG_RF17vel, given as a
+// Gravsoft velocity grid. Only the NKG_ETRF14 common frame, i.e. ETRF2014 at
+// 2000.0, is provided: The static transformations from it to the national
+// ETRS89 realizations are not included.
+//
+// The IERS units (mm, ppb, mas) are converted to those of `helmert` (m, ppm, ").
+#[rustfmt::skip]
+pub const ITRF_MACROS: [(&str, &str); 65] = [
+    ("itrf2020:itrf2014", "## ITRF2020 to ITRF2014: IERS 14 parameter set, reference epoch 2015.
+        ## The 4th coordinate element is the observation epoch, in decimal years
+        geo:in | cart ellps=GRS80
+        | helmert x=-0.0014 y=-0.0009 z=0.0014 s=-0.00042
+          dy=-0.0001 dz=0.0002 t_epoch=2015 convention=position_vector
+        | cart inv ellps=GRS80 | geo:out"),
+    ("itrf2020:itrf2008", "## ITRF2020 to ITRF2008: IERS 14 parameter set, reference epoch 2015.
+        ## The 4th coordinate element is the observation epoch, in decimal years
+        geo:in | cart ellps=GRS80
+        | helmert x=0.0002 y=0.001 z=0.0033 s=-0.00029
+          dy=-0.0001 dz=0.0001 ds=0.00003 t_epoch=2015 convention=position_vector
+        | cart inv ellps=GRS80 | geo:out"),
+    ("itrf2020:itrf2005", "## ITRF2020 to ITRF2005: IERS 14 parameter set, reference epoch 2015.
+        ## The 4th coordinate element is the observation epoch, in decimal years
+        geo:in | cart ellps=GRS80
+        | helmert x=0.0027 y=0.0001 z=-0.0014 s=0.00065
+          dx=0.0003 dy=-0.0001 dz=0.0001 ds=0.00003 t_epoch=2015 convention=position_vector
+        | cart inv ellps=GRS80 | geo:out"),
+    ("itrf2020:itrf2000", "## ITRF2020 to ITRF2000: IERS 14 parameter set, reference epoch 2015.
+        ## The 4th coordinate element is the observation epoch, in decimal years
+        geo:in | cart ellps=GRS80
+        | helmert x=-0.0002 y=0.0008 z=-0.0342 s=0.00225
+          dx=0.0001 dz=-0.0017 ds=0.00011 t_epoch=2015 convention=position_vector
+        | cart inv ellps=GRS80 | geo:out"),
+    ("itrf2020:itrf97", "## ITRF2020 to ITRF97: IERS 14 parameter set, reference epoch 2015.
+        ## The 4th coordinate element is the observation epoch, in decimal years
+        geo:in | cart ellps=GRS80
+        | helmert x=0.0065 y=-0.0039 z=-0.0779 s=0.00398 rz=0.00036
+          dx=0.0001 dy=-0.0006 dz=-0.0031 ds=0.00012 drz=0.00002 t_epoch=2015 convention=position_vector
+        | cart inv ellps=GRS80 | geo:out"),
+    ("itrf2020:itrf96", "## ITRF2020 to ITRF96: IERS 14 parameter set, reference epoch 2015.
+        ## The 4th coordinate element is the observation epoch, in decimal years
+        geo:in | cart ellps=GRS80
+        | helmert x=0.0065 y=-0.0039 z=-0.0779 s=0.00398 rz=0.00036
+          dx=0.0001 dy=-0.0006 dz=-0.0031 ds=0.00012 drz=0.00002 t_epoch=2015 convention=position_vector
+        | cart inv ellps=GRS80 | geo:out"),
+    ("itrf2020:itrf94", "## ITRF2020 to ITRF94: IERS 14 parameter set, reference epoch 2015.
+        ## The 4th coordinate element is the observation epoch, in decimal years
+        geo:in | cart ellps=GRS80
+        | helmert x=0.0065 y=-0.0039 z=-0.0779 s=0.00398 rz=0.00036
+          dx=0.0001 dy=-0.0006 dz=-0.0031 ds=0.00012 drz=0.00002 t_epoch=2015 convention=position_vector
+        | cart inv ellps=GRS80 | geo:out"),
+    ("itrf2020:itrf93", "## ITRF2020 to ITRF93: IERS 14 parameter set, reference epoch 2015.
+        ## The 4th coordinate element is the observation epoch, in decimal years
+        geo:in | cart ellps=GRS80
+        | helmert x=-0.0658 y=0.0019 z=-0.0713 s=0.00447 rx=-0.00336 ry=-0.00433 rz=0.00075
+          dx=-0.0028 dy=-0.0002 dz=-0.0023 ds=0.00012 drx=-0.00011 dry=-0.00019 drz=0.00007 t_epoch=2015 convention=position_vector
+        | cart inv ellps=GRS80 | geo:out"),
+    ("itrf2020:itrf92", "## ITRF2020 to ITRF92: IERS 14 parameter set, reference epoch 2015.
+        ## The 4th coordinate element is the observation epoch, in decimal years
+        geo:in | cart ellps=GRS80
+        | helmert x=0.0145 y=-0.0019 z=-0.0859 s=0.00327 rz=0.00036
+          dx=0.0001 dy=-0.0006 dz=-0.0031 ds=0.00012 drz=0.00002 t_epoch=2015 convention=position_vector
+        | cart inv ellps=GRS80 | geo:out"),
+    ("itrf2020:itrf91", "## ITRF2020 to ITRF91: IERS 14 parameter set, reference epoch 2015.
+        ## The 4th coordinate element is the observation epoch, in decimal years
+        geo:in | cart ellps=GRS80
+        | helmert x=0.0265 y=0.0121 z=-0.0919 s=0.00467 rz=0.00036
+          dx=0.0001 dy=-0.0006 dz=-0.0031 ds=0.00012 drz=0.00002 t_epoch=2015 convention=position_vector
+        | cart inv ellps=GRS80 | geo:out"),
+    ("itrf2020:itrf90", "## ITRF2020 to ITRF90: IERS 14 parameter set, reference epoch 2015.
+        ## The 4th coordinate element is the observation epoch, in decimal years
+        geo:in | cart ellps=GRS80
+        | helmert x=0.0245 y=0.0081 z=-0.1079 s=0.00492 rz=0.00036
+          dx=0.0001 dy=-0.0006 dz=-0.0031 ds=0.00012 drz=0.00002 t_epoch=2015 convention=position_vector
+        | cart inv ellps=GRS80 | geo:out"),
+    ("itrf2020:itrf89", "## ITRF2020 to ITRF89: IERS 14 parameter set, reference epoch 2015.
+        ## The 4th coordinate element is the observation epoch, in decimal years
+        geo:in | cart ellps=GRS80
+        | helmert x=0.0295 y=0.0321 z=-0.1459 s=0.00837 rz=0.00036
+          dx=0.0001 dy=-0.0006 dz=-0.0031 ds=0.00012 drz=0.00002 t_epoch=2015 convention=position_vector
+        | cart inv ellps=GRS80 | geo:out"),
+    ("itrf2020:itrf88", "## ITRF2020 to ITRF88: IERS 14 parameter set, reference epoch 2015.
+        ## The 4th coordinate element is the observation epoch, in decimal years
+        geo:in | cart ellps=GRS80
+        | helmert x=0.0245 y=-0.0039 z=-0.1699 s=0.01147 rx=0.0001 rz=0.00036
+          dx=0.0001 dy=-0.0006 dz=-0.0031 ds=0.00012 drz=0.00002 t_epoch=2015 convention=position_vector
+        | cart inv ellps=GRS80 | geo:out"),
+    ("itrf2014:itrf2008", "## ITRF2014 to ITRF2008: IERS 14 parameter set, reference epoch 2010.
+        ## The 4th coordinate element is the observation epoch, in decimal years
+        geo:in | cart ellps=GRS80
+        | helmert x=0.0016 y=0.0019 z=0.0024 s=-0.00002
+          dz=-0.0001 ds=0.00003 t_epoch=2010 convention=position_vector
+        | cart inv ellps=GRS80 | geo:out"),
+    ("itrf2014:itrf2005", "## ITRF2014 to ITRF2005: IERS 14 parameter set, reference epoch 2010.
+        ## The 4th coordinate element is the observation epoch, in decimal years
+        geo:in | cart ellps=GRS80
+        | helmert x=0.0026 y=0.001 z=-0.0023 s=0.00092
+          dx=0.0003 dz=-0.0001 ds=0.00003 t_epoch=2010 convention=position_vector
+        | cart inv ellps=GRS80 | geo:out"),
+    ("itrf2014:itrf2000", "## ITRF2014 to ITRF2000: IERS 14 parameter set, reference epoch 2010.
+        ## The 4th coordinate element is the observation epoch, in decimal years
+        geo:in | cart ellps=GRS80
+        | helmert x=0.0007 y=0.0012 z=-0.0261 s=0.00212
+          dx=0.0001 dy=0.0001 dz=-0.0019 ds=0.00011 t_epoch=2010 convention=position_vector
+        | cart inv ellps=GRS80 | geo:out"),
+    ("itrf2014:itrf97", "## ITRF2014 to ITRF97: IERS 14 parameter set, reference epoch 2010.
+        ## The 4th coordinate element is the observation epoch, in decimal years
+        geo:in | cart ellps=GRS80
+        | helmert x=0.0074 y=-0.0005 z=-0.0628 s=0.0038 rz=0.00026
+          dx=0.0001 dy=-0.0005 dz=-0.0033 ds=0.00012 drz=0.00002 t_epoch=2010 convention=position_vector
+        | cart inv ellps=GRS80 | geo:out"),
+    ("itrf2014:itrf96", "## ITRF2014 to ITRF96: IERS 14 parameter set, reference epoch 2010.
+        ## The 4th coordinate element is the observation epoch, in decimal years
+        geo:in | cart ellps=GRS80
+        | helmert x=0.0074 y=-0.0005 z=-0.0628 s=0.0038 rz=0.00026
+          dx=0.0001 dy=-0.0005 dz=-0.0033 ds=0.00012 drz=0.00002 t_epoch=2010 convention=position_vector
+        | cart inv ellps=GRS80 | geo:out"),
+    ("itrf2014:itrf94", "## ITRF2014 to ITRF94: IERS 14 parameter set, reference epoch 2010.
+        ## The 4th coordinate element is the observation epoch, in decimal years
+        geo:in | cart ellps=GRS80
+        | helmert x=0.0074 y=-0.0005 z=-0.0628 s=0.0038 rz=0.00026
+          dx=0.0001 dy=-0.0005 dz=-0.0033 ds=0.00012 drz=0.00002 t_epoch=2010 convention=position_vector
+        | cart inv ellps=GRS80 | geo:out"),
+    ("itrf2014:itrf93", "## ITRF2014 to ITRF93: IERS 14 parameter set, reference epoch 2010.
+        ## The 4th coordinate element is the observation epoch, in decimal years
+        geo:in | cart ellps=GRS80
+        | helmert x=-0.0504 y=0.0033 z=-0.0602 s=0.00429 rx=-0.00281 ry=-0.00338 rz=0.0004
+          dx=-0.0028 dy=-0.0001 dz=-0.0025 ds=0.00012 drx=-0.00011 dry=-0.00019 drz=0.00007 t_epoch=2010 convention=position_vector
+        | cart inv ellps=GRS80 | geo:out"),
+    ("itrf2014:itrf92", "## ITRF2014 to ITRF92: IERS 14 parameter set, reference epoch 2010.
+        ## The 4th coordinate element is the observation epoch, in decimal years
+        geo:in | cart ellps=GRS80
+        | helmert x=0.0154 y=0.0015 z=-0.0708 s=0.00309 rz=0.00026
+          dx=0.0001 dy=-0.0005 dz=-0.0033 ds=0.00012 drz=0.00002 t_epoch=2010 convention=position_vector
+        | cart inv ellps=GRS80 | geo:out"),
+    ("itrf2014:itrf91", "## ITRF2014 to ITRF91: IERS 14 parameter set, reference epoch 2010.
+        ## The 4th coordinate element is the observation epoch, in decimal years
+        geo:in | cart ellps=GRS80
+        | helmert x=0.0274 y=0.0155 z=-0.0768 s=0.00449 rz=0.00026
+          dx=0.0001 dy=-0.0005 dz=-0.0033 ds=0.00012 drz=0.00002 t_epoch=2010 convention=position_vector
+        | cart inv ellps=GRS80 | geo:out"),
+    ("itrf2014:itrf90", "## ITRF2014 to ITRF90: IERS 14 parameter set, reference epoch 2010.
+        ## The 4th coordinate element is the observation epoch, in decimal years
+        geo:in | cart ellps=GRS80
+        | helmert x=0.0254 y=0.0115 z=-0.0928 s=0.00479 rz=0.00026
+          dx=0.0001 dy=-0.0005 dz=-0.0033 ds=0.00012 drz=0.00002 t_epoch=2010 convention=position_vector
+        | cart inv ellps=GRS80 | geo:out"),
+    ("itrf2014:itrf89", "## ITRF2014 to ITRF89: IERS 14 parameter set, reference epoch 2010.
+        ## The 4th coordinate element is the observation epoch, in decimal years
+        geo:in | cart ellps=GRS80
+        | helmert x=0.0304 y=0.0355 z=-0.1308 s=0.00819 rz=0.00026
+          dx=0.0001 dy=-0.0005 dz=-0.0033 ds=0.00012 drz=0.00002 t_epoch=2010 convention=position_vector
+        | cart inv ellps=GRS80 | geo:out"),
+    ("itrf2014:itrf88", "## ITRF2014 to ITRF88: IERS 14 parameter set, reference epoch 2010.
+        ## The 4th coordinate element is the observation epoch, in decimal years
+        geo:in | cart ellps=GRS80
+        | helmert x=0.0254 y=-0.0005 z=-0.1548 s=0.01129 rx=0.0001 rz=0.00026
+          dx=0.0001 dy=-0.0005 dz=-0.0033 ds=0.00012 drz=0.00002 t_epoch=2010 convention=position_vector
+        | cart inv ellps=GRS80 | geo:out"),
+    ("itrf2014:itrf2020", "## ITRF2014 to ITRF2020: The inverse of itrf2020:itrf2014
+        itrf2020:itrf2014 inv"),
+    ("itrf2008:itrf2020", "## ITRF2008 to ITRF2020: The inverse of itrf2020:itrf2008
+        itrf2020:itrf2008 inv"),
+    ("itrf2005:itrf2020", "## ITRF2005 to ITRF2020: The inverse of itrf2020:itrf2005
+        itrf2020:itrf2005 inv"),
+    ("itrf2000:itrf2020", "## ITRF2000 to ITRF2020: The inverse of itrf2020:itrf2000
+        itrf2020:itrf2000 inv"),
+    ("itrf97:itrf2020", "## ITRF97 to ITRF2020: The inverse of itrf2020:itrf97
+        itrf2020:itrf97 inv"),
+    ("itrf96:itrf2020", "## ITRF96 to ITRF2020: The inverse of itrf2020:itrf96
+        itrf2020:itrf96 inv"),
+    ("itrf94:itrf2020", "## ITRF94 to ITRF2020: The inverse of itrf2020:itrf94
+        itrf2020:itrf94 inv"),
+    ("itrf93:itrf2020", "## ITRF93 to ITRF2020: The inverse of itrf2020:itrf93
+        itrf2020:itrf93 inv"),
+    ("itrf92:itrf2020", "## ITRF92 to ITRF2020: The inverse of itrf2020:itrf92
+        itrf2020:itrf92 inv"),
+    ("itrf91:itrf2020", "## ITRF91 to ITRF2020: The inverse of itrf2020:itrf91
+        itrf2020:itrf91 inv"),
+    ("itrf90:itrf2020", "## ITRF90 to ITRF2020: The inverse of itrf2020:itrf90
+        itrf2020:itrf90 inv"),
+    ("itrf89:itrf2020", "## ITRF89 to ITRF2020: The inverse of itrf2020:itrf89
+        itrf2020:itrf89 inv"),
+    ("itrf88:itrf2020", "## ITRF88 to ITRF2020: The inverse of itrf2020:itrf88
+        itrf2020:itrf88 inv"),
+    ("itrf2008:itrf2014", "## ITRF2008 to ITRF2014: The inverse of itrf2014:itrf2008
+        itrf2014:itrf2008 inv"),
+    ("itrf2005:itrf2014", "## ITRF2005 to ITRF2014: The inverse of itrf2014:itrf2005
+        itrf2014:itrf2005 inv"),
+    ("itrf2000:itrf2014", "## ITRF2000 to ITRF2014: The inverse of itrf2014:itrf2000
+        itrf2014:itrf2000 inv"),
+    ("itrf97:itrf2014", "## ITRF97 to ITRF2014: The inverse of itrf2014:itrf97
+        itrf2014:itrf97 inv"),
+    ("itrf96:itrf2014", "## ITRF96 to ITRF2014: The inverse of itrf2014:itrf96
+        itrf2014:itrf96 inv"),
+    ("itrf94:itrf2014", "## ITRF94 to ITRF2014: The inverse of itrf2014:itrf94
+        itrf2014:itrf94 inv"),
+    ("itrf93:itrf2014", "## ITRF93 to ITRF2014: The inverse of itrf2014:itrf93
+        itrf2014:itrf93 inv"),
+    ("itrf92:itrf2014", "## ITRF92 to ITRF2014: The inverse of itrf2014:itrf92
+        itrf2014:itrf92 inv"),
+    ("itrf91:itrf2014", "## ITRF91 to ITRF2014: The inverse of itrf2014:itrf91
+        itrf2014:itrf91 inv"),
+    ("itrf90:itrf2014", "## ITRF90 to ITRF2014: The inverse of itrf2014:itrf90
+        itrf2014:itrf90 inv"),
+    ("itrf89:itrf2014", "## ITRF89 to ITRF2014: The inverse of itrf2014:itrf89
+        itrf2014:itrf89 inv"),
+    ("itrf88:itrf2014", "## ITRF88 to ITRF2014: The inverse of itrf2014:itrf88
+        itrf2014:itrf88 inv"),
+    // ----- ETRF ---------------------------------------------------------------------
+    ("itrf2020:etrf2020", "## ITRF2020 to ETRF2020, following EUREF TN-1. The 4th coordinate
+        ## element is the observation epoch, in decimal years
+        geo:in | cart ellps=GRS80
+        | helmert drx=0.000086 dry=0.000519 drz=-0.000753 t_epoch=1989
+          convention=position_vector
+        | cart inv ellps=GRS80 | geo:out"),
+    ("itrf2000:etrf2000", "## ITRF2000 to ETRF2000, following EUREF TN-1. The 4th coordinate
+        ## element is the observation epoch, in decimal years
+        geo:in | cart ellps=GRS80
+        | helmert x=0.054 y=0.051 z=-0.048
+          drx=0.000081 dry=0.00049 drz=-0.000792 t_epoch=1989
+          convention=position_vector
+        | cart inv ellps=GRS80 | geo:out"),
+    ("itrf2020:etrf2000", "## ITRF2020 to ETRF2000, by way of ITRF2000
+        itrf2020:itrf2000 | itrf2000:etrf2000"),
+    ("itrf2014:etrf2000", "## ITRF2014 to ETRF2000, by way of ITRF2000
+        itrf2014:itrf2000 | itrf2000:etrf2000"),
+    ("itrf2008:etrf2000", "## ITRF2008 to ETRF2000, by way of ITRF2014 and ITRF2000
+        itrf2008:itrf2014 | itrf2014:itrf2000 | itrf2000:etrf2000"),
+    ("itrf2005:etrf2000", "## ITRF2005 to ETRF2000, by way of ITRF2014 and ITRF2000
+        itrf2005:itrf2014 | itrf2014:itrf2000 | itrf2000:etrf2000"),
+    ("etrf2020:itrf2020", "## ETRF2020 to ITRF2020: The inverse of itrf2020:etrf2020
+        itrf2020:etrf2020 inv"),
+    ("etrf2014:itrf2014", "## ETRF2014 to ITRF2014: The inverse of itrf2014:etrf2014
+        itrf2014:etrf2014 inv"),
+    ("etrf2000:itrf2000", "## ETRF2000 to ITRF2000: The inverse of itrf2000:etrf2000
+        itrf2000:etrf2000 inv"),
+    // ----- Plate fixed, by deformation models ---------------------------------------
+    ("itrf2020:etrf2020_fixed", "## ITRF2020 at the observation epoch to ETRF2020 at t_epoch, by
+        ## the velocities of the deformation model given by grids
+        #$ grids t_epoch
+        geo:in | cart ellps=GRS80
+        | helmert drx=0.000086 dry=0.000519 drz=-0.000753 t_epoch=1989
+          convention=position_vector
+        | deformation grids=$grids t_epoch=$t_epoch ellps=GRS80
+        | cart inv ellps=GRS80 | geo:out"),
+    ("itrf2014:etrf2014_fixed", "## ITRF2014 at the observation epoch to ETRF2014 at t_epoch, by
+        ## the velocities of the deformation model given by grids
+        #$ grids t_epoch
+        geo:in | cart ellps=GRS80
+        | helmert drx=0.000085 dry=0.000531 drz=-0.00077 t_epoch=1989
+          convention=position_vector
+        | deformation grids=$grids t_epoch=$t_epoch ellps=GRS80
+        | cart inv ellps=GRS80 | geo:out"),
+    ("itrf2014:nkg_etrf14", "## ITRF2014 at the observation epoch to NKG_ETRF14 (ETRF2014 at
+        ## 2000.0), by the NKG_RF17vel deformation model, as a Gravsoft velocity grid
+        #$ grids=nkgrf17vel.deformation
+        itrf2014:etrf2014_fixed t_epoch=2000"),
+    ("etrf2020_fixed:itrf2020", "## ETRF2020 at t_epoch to ITRF2020: The inverse of itrf2020:etrf2020_fixed
+        #$ grids t_epoch
+        itrf2020:etrf2020_fixed inv"),
+    ("etrf2014_fixed:itrf2014", "## ETRF2014 at t_epoch to ITRF2014: The inverse of itrf2014:etrf2014_fixed
+        #$ grids t_epoch
+        itrf2014:etrf2014_fixed inv"),
+    ("nkg_etrf14:itrf2014", "## NKG_ETRF14 to ITRF2014: The inverse of itrf2014:nkg_etrf14
+        #$ grids=nkgrf17vel.deformation
+        itrf2014:nkg_etrf14 inv"),
+];
+
+/// The name of the macro invoked by `alias`, of the form `itrfXXXX_to_itrfYYYY`,
+/// i.e. `itrfXXXX:itrfYYYY`, if among the `ITRF_MACROS`
+pub(crate) fn itrf_macro_name(alias: &str) -> Option<&'static str> {
+    let (from, to) = alias.split_once("_to_")?;
+    ITRF_MACROS
+        .iter()
+        .map(|(name, _)| *name)
+        .find(|name| name.split_once(':') == Some((from, to)))
+}
+
+// ----- T E S T S ------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::internal::*;
+
+    #[test]
+    fn itrf() -> Result<(), Error> {
+        let mut ctx = Minimal::new();
+        for (name, _) in ITRF_MACROS {
+            assert!(["itrf", "etrf", "nkg"].iter().any(|p| name.starts_with(p)));
+            // The deformation model placeholders are superfluous for the rest
+            ctx.op(&format!("{name} grids=test.deformation t_epoch=2000"))?;
+        }
+
+        // ITRF2014 to ITRF2008: Translation, scale, and their rates, with the
+        // epochs given per point
+        let op = ctx.op("itrf2014:itrf2008")?;
+        let mut data = [
+            Coord::raw(55., 12., 100., 2010.),
+            Coord::raw(55., 12., 100., 2020.),
+        ];
+        let input = data;
+        ctx.apply(op, Fwd, &mut data)?;
+
+        let grs80 = Ellipsoid::default();
+        let cart = |c: &Coord| grs80.cartesian(&Coord::geo(c[0], c[1], c[2], c[3]));
+        for (i, dt) in [0., 10.].into_iter().enumerate() {
+            let (a, b) = (cart(&input[i]), cart(&data[i]));
+            let t = [0.0016, 0.0019, 0.0024 - 0.0001 * dt];
+            let d = (-0.02 + 0.03 * dt) * 1e-9;
+            for j in 0..3 {
+                assert!((b[j] - a[j] - t[j] - d * a[j]).abs() < 1e-6);
+            }
+        }
+
+        // The inverse
+        let inv = ctx.op("itrf2008:itrf2014")?;
+        ctx.apply(inv, Fwd, &mut data)?;
+        for i in 0..2 {
+            assert!(input[i].hypot3(&data[i]) < 1e-8);
+        }
+
+        // The same, by the aliases
+        let mut aliased = input;
+        let op = ctx.op("itrf2014_to_itrf2008")?;
+        ctx.apply(op, Fwd, &mut aliased)?;
+        let expected = aliased;
+        for back in ["itrf2008_to_itrf2014", "itrf2014_to_itrf2008 inv"] {
+            let mut data = expected;
+            let op = ctx.op(back)?;
+            ctx.apply(op, Fwd, &mut data)?;
+            for i in 0..2 {
+                assert!(input[i].hypot3(&data[i]) < 1e-8, "{back}");
+            }
+        }
+        let mut direct = input;
+        let op = ctx.op("itrf2014:itrf2008")?;
+        ctx.apply(op, Fwd, &mut direct)?;
+        assert_eq!(direct, expected);
+        let name = itrf_macro_name("itrf2014_to_nkg_etrf14");
+        assert_eq!(name, Some("itrf2014:nkg_etrf14"));
+        assert!(ctx.op("itrf2014_to_itrf1066").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn etrf() -> Result<(), Error> {
+        let mut ctx = Minimal::new();
+
+        // ITRF2000 to ETRF2000: The translation alone at 1989.0, then the
+        // rotation rates accumulate. At 1999.0 rotations of 10 years
+        let op = ctx.op("itrf2000:etrf2000")?;
+        let mut data = [
+            Coord::raw(55., 12., 0., 1989.),
+            Coord::raw(55., 12., 0., 1999.),
+        ];
+        let input = data;
+        ctx.apply(op, Fwd, &mut data)?;
+
+        let grs80 = Ellipsoid::default();
+        let cart = |c: &Coord| grs80.cartesian(&Coord::geo(c[0], c[1], c[2], c[3]));
+        let mas = (1f64 / 3_600_000.).to_radians();
+        for (i, dt) in [0., 10.].into_iter().enumerate() {
+            let (a, b) = (cart(&input[i]), cart(&data[i]));
+            let r = [0.081 * mas * dt, 0.490 * mas * dt, -0.792 * mas * dt];
+            let expected = [
+                a[0] + 0.054 - r[2] * a[1] + r[1] * a[2],
+                a[1] + 0.051 + r[2] * a[0] - r[0] * a[2],
+                a[2] - 0.048 - r[1] * a[0] + r[0] * a[1],
+            ];
+            for j in 0..3 {
+                assert!((b[j] - expected[j]).abs() < 1e-6);
+            }
+        }
+
+        // The chains by way of the IERS parameters, and the inverses
+        let chain = ctx.op("itrf2014:etrf2000")?;
+        let mut direct = [Coord::raw(55., 12., 0., 2020.)];
+        let mut stepwise = direct;
+        ctx.apply(chain, Fwd, &mut direct)?;
+        let iers = ctx.op("itrf2014:itrf2000")?;
+        ctx.apply(iers, Fwd, &mut stepwise)?;
+        ctx.apply(op, Fwd, &mut stepwise)?;
+        assert!(direct[0].hypot3(&stepwise[0]) < 1e-12);
+
+        let inv = ctx.op("etrf2000:itrf2000")?;
+        ctx.apply(op, Fwd, &mut stepwise)?;
+        ctx.apply(inv, Fwd, &mut stepwise)?;
+        // Inverting the small angle rotation matrix by transposition is exact only
+        // to second order in the rotations grown since 1989.0, hence micrometres
+        assert!(direct[0].hypot3(&stepwise[0]) < 1e-6);
+        Ok(())
+    }
+
+    #[test]
+    fn plate_fixed() -> Result<(), Error> {
+        let mut ctx = Minimal::new();
+
+        // ITRF2014 at 2010.0 to ETRF2014 at 2000.0: The boundary conditions at
+        // 2010.0, followed by 10 years of (negative) deformation at 1, 2, and
+        // 3 mm/year north, east and up
+        let op = ctx.op("itrf2014:etrf2014_fixed grids=test.deformation t_epoch=2000")?;
+        let mut direct = [Coord::raw(55., 12., 100., 2010.)];
+        let input = direct;
+        ctx.apply(op, Fwd, &mut direct)?;
+
+        let mut stepwise = input;
+        let etrf = ctx.op("itrf2014:etrf2014")?;
+        ctx.apply(etrf, Fwd, &mut stepwise)?;
+        let deformation = ctx
+            .op("geo:in | cart | deformation grids=test.deformation dt=-10 | cart inv | geo:out")?;
+        ctx.apply(deformation, Fwd, &mut stepwise)?;
+        assert!(direct[0].hypot3(&stepwise[0]) < 1e-9);
+        // The height is dominated by the deformation: The rotations of the
+        // boundary conditions alter it by about a millimetre only
+        assert!((direct[0][2] - input[0][2] + 0.03).abs() < 2e-3);
+
+        // NKG_ETRF14 is the special case of epoch 2000.0 and the NKG_RF17vel grid
+        let nkg = ctx.op("itrf2014:nkg_etrf14 grids=test.deformation")?;
+        let mut data = input;
+        ctx.apply(nkg, Fwd, &mut data)?;
+        assert!(direct[0].hypot3(&data[0]) < 1e-12);
+
+        // ...and back again
+        let inv = ctx.op("nkg_etrf14:itrf2014 grids=test.deformation")?;
+        ctx.apply(inv, Fwd, &mut data)?;
+        assert!(input[0].hypot3(&data[0]) < 1e-6);
+
+        // The deformation model must be given
+        assert!(ctx.op("itrf2014:etrf2014_fixed t_epoch=2000").is_err());
+        Ok(())
+    }
+}
@@ -4,6 +4,11 @@ use super::*;
 
 /// A minimalistic context provider, supporting only built in and run-time defined operators.
 /// Usually sufficient for cartographic uses, and for internal test authoring.
+///
+/// Grids and other binary resources may be held in memory, cf. [`Minimal::register_blob`],
+/// which is the only way of providing them on targets without a file system, e.g.
+/// `wasm32-unknown-unknown`. Elsewhere, resources not held in memory are read from
+/// `./geodesy/<extension>/<name>`.
 #[derive(Debug, Default)]
 pub struct Minimal {
     /// Constructors for user defined operators
@@ -12,6 +17,23 @@ pub struct Minimal {
     resources: BTreeMap<String, String>,
     /// Instantiations of operators
     operators: BTreeMap<OpHandle, Op>,
+    /// Handles of the operators, by normalized definition
+    definitions: BTreeMap<String, OpHandle>,
+    /// User defined global default values
+    globals: BTreeMap<String, String>,
+    /// Binary resources (grids etc.) held in memory
+    blobs: BTreeMap<String, Vec<u8>>,
+    /// Precomputations shared by the operators
+    precomputed: Precomputed,
+}
+
+impl Minimal {
+    /// Make the binary resource `name`, e.g. a grid, available from memory, in
+    /// preference to the file system
+    pub fn register_blob(&mut self, name: &str, contents: Vec<u8>) {
+        self.definitions.clear();
+        self.blobs.insert(name.to_string(), contents);
+    }
 }
 
 impl Context for Minimal {
@@ -20,33 +42,100 @@ impl Context for Minimal {
         for item in BUILTIN_ADAPTORS {
             ctx.register_resource(item.0, item.1);
         }
+        for item in BUILTIN_MACROS {
+            ctx.register_resource(item.0, item.1);
+        }
+        for item in ITRF_MACROS {
+            ctx.register_resource(item.0, item.1);
+        }
         ctx
     }
 
     fn op(&mut self, definition: &str) -> Result<OpHandle, Error> {
+        // Reuse the operator, if already instantiated
+        let key = normalized_definition(definition);
+        if let Some(id) = self.definitions.get(&key) {
+            return Ok(*id);
+        }
+
         let op = Op::new(definition, self)?;
         let id = op.id;
         self.operators.insert(id, op);
+        self.definitions.insert(key, id);
         assert!(self.operators.contains_key(&id));
         Ok(id)
     }
 
-    fn apply(
+    fn provenance(&self, op: OpHandle) -> Result<Vec<Provenance>, Error> {
+        let bad_id = || Error::General("Minimal: Unknown operator id".to_string());
+        let op = self.operators.get(&op).ok_or_else(bad_id)?;
+        Ok(op.provenance())
+    }
+
+    fn metadata(&self, op: OpHandle) -> Result<Metadata, Error> {
+        let bad_id = || Error::General("Minimal: Unknown operator id".to_string());
+        let op = self.operators.get(&op).ok_or_else(bad_id)?;
+        Ok(op.metadata())
+    }
+
+    fn warnings(&self, op: OpHandle) -> Result<Vec<String>, Error> {
+        let bad_id = || Error::General("Minimal: Unknown operator id".to_string());
+        let op = self.operators.get(&op).ok_or_else(bad_id)?;
+        Ok(op.warnings())
+    }
+
+    fn steps(&self, op: OpHandle) -> Result<Vec<StepDescription>, Error> {
+        let bad_id = || Error::General("Minimal: Unknown operator id".to_string());
+        let op = self.operators.get(&op).ok_or_else(bad_id)?;
+        Ok(op.steps())
+    }
+
+    fn apply_traced(
         &self,
         op: OpHandle,
         direction: Direction,
         operands: &mut [Coord],
+        tracer: &mut Tracer,
     ) -> Result<usize, Error> {
-        const BAD_ID_MESSAGE: Error = Error::General("Minimal: Unknown operator id");
-        let op = self.operators.get(&op).ok_or(BAD_ID_MESSAGE)?;
+        let bad_id = || Error::General("Minimal: Unknown operator id".to_string());
+        let op = self.operators.get(&op).ok_or_else(bad_id)?;
+        op.apply_traced(self, operands, direction, tracer)
+    }
+
+    fn apply(
+        &self,
+        op: OpHandle,
+        direction: Direction,
+        operands: &mut dyn CoordinateSet,
+    ) -> Result<usize, Error> {
+        let bad_id = || Error::General("Minimal: Unknown operator id".to_string());
+        let op = self.operators.get(&op).ok_or_else(bad_id)?;
         op.apply(self, operands, direction)
     }
 
+    fn operators(&self) -> Vec<OperatorInfo> {
+        operator_inventory(self.constructors.keys().map(String::as_str))
+    }
+
+    fn resources(&self) -> Vec<String> {
+        self.resources.keys().cloned().collect()
+    }
+
     fn globals(&self) -> BTreeMap<String, String> {
-        BTreeMap::from([("ellps".to_string(), "GRS80".to_string())])
+        let mut globals = BTreeMap::from([("ellps".to_string(), "GRS80".to_string())]);
+        globals.extend(self.globals.clone());
+        globals
+    }
+
+    fn register_global(&mut self, key: &str, value: &str) -> Result<(), Error> {
+        self.definitions.clear();
+        self.globals.insert(String::from(key), String::from(value));
+        Ok(())
     }
 
     fn register_op(&mut self, name: &str, constructor: OpConstructor) {
+        // Previously instantiated definitions may refer to what is now redefined
+        self.definitions.clear();
         self.constructors.insert(String::from(name), constructor);
     }
 
@@ -62,6 +151,7 @@ impl Context for Minimal {
     }
 
     fn register_resource(&mut self, name: &str, definition: &str) {
+        self.definitions.clear();
         self.resources
             .insert(String::from(name), String::from(definition));
     }
@@ -78,6 +168,20 @@ impl Context for Minimal {
     }
 
     fn get_blob(&self, name: &str) -> Result<Vec<u8>, Error> {
+        if let Some(blob) = self.blobs.get(name) {
+            return Ok(blob.clone());
+        }
+        if cfg!(target_arch = "wasm32") {
+            return Err(Error::NotFound(
+                name.to_string(),
+                ": No file system, and not registered as a blob".to_string(),
+            ));
+        }
+        if let Some(directory) = self.globals().get("grid_dir") {
+            if let Ok(blob) = std::fs::read(PathBuf::from(directory).join(name)) {
+                return Ok(blob);
+            }
+        }
         let n = PathBuf::from(name);
         let ext = n
             .extension()
@@ -91,7 +195,11 @@ impl Context for Minimal {
     /// Access grid resources by identifier
     fn get_grid(&self, _name: &str) -> Result<Grid, Error> {
         Err(Error::General(
-            "Grid access by identifier not supported by the Minimal context provider",
+            "Grid access by identifier not supported by the Minimal context provider".to_string(),
         ))
     }
+
+    fn precomputed(&self) -> Option<&Precomputed> {
+        Some(&self.precomputed)
+    }
 }
@@ -1,9 +1,24 @@
 use crate::internal::*;
+mod epsg;
+mod itrf;
 mod minimal;
+#[cfg(not(target_arch = "wasm32"))]
 mod plain;
+mod precomputed;
+#[cfg(feature = "async")]
+mod remote;
+mod shared;
 
+pub(crate) use epsg::{epsg_datum, epsg_definition};
+pub(crate) use itrf::itrf_macro_name;
+pub use itrf::ITRF_MACROS;
 pub use minimal::Minimal;
+#[cfg(not(target_arch = "wasm32"))]
 pub use plain::Plain;
+pub use precomputed::Precomputed;
+#[cfg(feature = "async")]
+pub use remote::{AsyncProvider, Remote};
+pub use shared::Shared;
 
 // ----- T H E   C O N T E X T   T R A I T ---------------------------------------------
 
@@ -19,21 +34,366 @@ pub trait Context {
 
     /// Instantiate the operation given by `definition`
     fn op(&mut self, definition: &str) -> Result<OpHandle, Error>;
+    /// Instantiate the operation given by the EPSG code `code`. For a projected CRS,
+    /// this is the conversion from its base geographic CRS, for a transformation,
+    /// the transformation itself. Input coordinates are in degrees, in the EPSG axis
+    /// order (latitude, longitude). Equivalent to `op("EPSG:nnnn")`, and like
+    /// that, only supports a small embedded subset of the EPSG dataset.
+    fn op_from_epsg(&mut self, code: u32) -> Result<OpHandle, Error> {
+        self.op(&format!("EPSG:{code}"))
+    }
+
+    /// The macros and grids used by the operation `op`, cf. [`Op::provenance`].
+    /// Context providers not keeping the instantiated operators accessible
+    /// should leave this unimplemented.
+    fn provenance(&self, _op: OpHandle) -> Result<Vec<Provenance>, Error> {
+        Err(Error::General(
+            "Provenance not supported by this context provider".to_string(),
+        ))
+    }
+
+    /// The area of use and accuracy of the operation `op`, cf. [`Op::area_of_use`]
+    /// and [`Op::accuracy`]. Context providers not keeping the instantiated
+    /// operators accessible should leave this unimplemented.
+    fn metadata(&self, _op: OpHandle) -> Result<Metadata, Error> {
+        Err(Error::General(
+            "Metadata not supported by this context provider".to_string(),
+        ))
+    }
+
+    /// The warnings from the instantiation of the operation `op`, cf.
+    /// [`Op::warnings`]. Context providers not keeping the instantiated
+    /// operators accessible should leave this unimplemented.
+    fn warnings(&self, _op: OpHandle) -> Result<Vec<String>, Error> {
+        Err(Error::General(
+            "Warnings not supported by this context provider".to_string(),
+        ))
+    }
+
+    /// The steps of the operation `op`, as actually instantiated, cf.
+    /// [`Op::steps`]. Context providers not keeping the instantiated
+    /// operators accessible should leave this unimplemented.
+    fn steps(&self, _op: OpHandle) -> Result<Vec<StepDescription>, Error> {
+        Err(Error::General(
+            "Steps not supported by this context provider".to_string(),
+        ))
+    }
+
+    /// Apply operation `op` to `operands`, handing the intermediate results of
+    /// each step to `tracer`, cf. [`Op::apply_traced`]. Context providers not
+    /// keeping the instantiated operators accessible should leave this
+    /// unimplemented.
+    fn apply_traced(
+        &self,
+        _op: OpHandle,
+        _direction: Direction,
+        _operands: &mut [Coord],
+        _tracer: &mut Tracer,
+    ) -> Result<usize, Error> {
+        Err(Error::General(
+            "Tracing not supported by this context provider".to_string(),
+        ))
+    }
+
     /// Apply operation `op` to `operands`
     fn apply(
         &self,
         op: OpHandle,
         direction: Direction,
-        operands: &mut [Coord],
+        operands: &mut dyn CoordinateSet,
     ) -> Result<usize, Error>;
 
+    /// Apply operation `op` to the slice `operands`, as [`Context::apply`], i.e.
+    /// `ctx.apply_slice(op, Fwd, &mut data[1..])`, rather than the otherwise
+    /// needed `ctx.apply(op, Fwd, &mut &mut data[1..])`, since slices are unsized
+    fn apply_slice<T>(
+        &self,
+        op: OpHandle,
+        direction: Direction,
+        operands: &mut [T],
+    ) -> Result<usize, Error>
+    where
+        Self: Sized,
+        [T]: CoordinateSet,
+    {
+        self.apply(op, direction, &mut &mut *operands)
+    }
+
+    /// Apply operation `op` to `operands`, as [`Context::apply`], but return the
+    /// status of each coordinate, i.e. which ones failed, and why, if reported
+    /// by the operators, cf. [`PointStatus`]
+    fn apply_with_status(
+        &self,
+        op: OpHandle,
+        direction: Direction,
+        operands: &mut dyn CoordinateSet,
+    ) -> Result<Vec<PointStatus>, Error> {
+        self.apply(op, direction, &mut *operands)?;
+        Ok((0..operands.len())
+            .map(|i| operands.get_coord(i).status())
+            .collect())
+    }
+
+    /// Apply operation `op` to `operands`, as [`Context::apply`], but with the
+    /// time coordinate of every point set to `epoch` (in decimal years), for
+    /// time dependent operators, e.g. 14-parameter Helmert transformations, on
+    /// data without per-point time. The operands are handled as `Coord`s, so the
+    /// epoch also applies to 2D and 3D coordinate sets, which cannot store it. For
+    /// 4D sets, the epoch given replaces the original time coordinate.
+    fn apply_at_epoch(
+        &self,
+        op: OpHandle,
+        direction: Direction,
+        operands: &mut dyn CoordinateSet,
+        epoch: f64,
+    ) -> Result<usize, Error> {
+        let mut coords: Vec<Coord> = (0..operands.len())
+            .map(|i| {
+                let mut coord = operands.get_coord(i);
+                coord[3] = epoch;
+                coord
+            })
+            .collect();
+        let successes = self.apply(op, direction, &mut coords)?;
+        for (i, coord) in coords.iter().enumerate() {
+            operands.set_coord(i, coord);
+        }
+        Ok(successes)
+    }
+
+    /// Transform `operands` from the CRS given by `from`, to the one given by `to`,
+    /// e.g. `transform("EPSG:4326", "EPSG:3857", ...)`. Both definitions are taken
+    /// as conversions from the same geographical coordinates (as is the case for
+    /// CRS sharing a datum in the embedded EPSG subset), so the transformation is
+    /// the inverse of `from`, followed by `to`. The instantiations are cached,
+    /// like any other. Returns the number of successfully transformed coordinates.
+    /// Fails, without touching `operands`, if `from` and `to` are CRS from the
+    /// embedded EPSG subset, referring to different datums.
+    fn transform(
+        &mut self,
+        from: &str,
+        to: &str,
+        operands: &mut dyn CoordinateSet,
+    ) -> Result<usize, Error> {
+        if let (Some(from_datum), Some(to_datum)) = (epsg_datum(from), epsg_datum(to)) {
+            if from_datum != to_datum {
+                return Err(Error::Unexpected {
+                    message: format!("Datum shift needed for {from} to {to}"),
+                    expected: from_datum.to_string(),
+                    found: to_datum.to_string(),
+                });
+            }
+        }
+        let from = self.op(from)?;
+        let to = self.op(to)?;
+        let hub = self.apply(from, Inv, &mut *operands)?;
+        Ok(hub.min(self.apply(to, Fwd, operands)?))
+    }
+
+    /// Apply operation `op` to `operands`, splitting the work into chunks, handled
+    /// by one thread per available core. Returns the total number of successfully
+    /// transformed coordinates, i.e. the sum of the success counts of the chunks,
+    /// or the first error encountered.
+    fn apply_par(
+        &self,
+        op: OpHandle,
+        direction: Direction,
+        operands: &mut [Coord],
+    ) -> Result<usize, Error>
+    where
+        Self: Sized + Sync,
+    {
+        let threads = std::thread::available_parallelism().map_or(1, |n| n.get());
+        self.apply_par_with(op, direction, operands, threads)
+    }
+
+    /// As [`Context::apply_par`], but using at most `threads` threads
+    fn apply_par_with(
+        &self,
+        op: OpHandle,
+        direction: Direction,
+        operands: &mut [Coord],
+        threads: usize,
+    ) -> Result<usize, Error>
+    where
+        Self: Sized + Sync,
+    {
+        // Not worth the cost of spawning threads
+        const MINIMUM_CHUNK_SIZE: usize = 1000;
+
+        let threads = threads.max(1);
+        let chunk_size = operands.len().div_ceil(threads).max(MINIMUM_CHUNK_SIZE);
+        if threads == 1 || operands.len() <= chunk_size {
+            return self.apply_slice(op, direction, operands);
+        }
+
+        std::thread::scope(|s| {
+            let chunks: Vec<_> = operands
+                .chunks_mut(chunk_size)
+                .map(|mut chunk| s.spawn(move || self.apply(op, direction, &mut chunk)))
+                .collect();
+            chunks
+                .into_iter()
+                .map(|chunk| {
+                    chunk
+                        .join()
+                        .unwrap_or_else(|e| std::panic::resume_unwind(e))
+                })
+                .sum()
+        })
+    }
+
+    /// Apply operation `op` lazily to the coordinates of `operands`, e.g. as read
+    /// from a file, without materializing them in a slice. The coordinates are
+    /// transformed in chunks, as they are consumed from the returned iterator.
+    /// Failures of single coordinates are marked by `NaN`s, as for `apply`.
+    /// If `apply` fails for a chunk as a whole (e.g. on an area of use check,
+    /// or a grid i/o error), the error is yielded in place of the chunk, and
+    /// the iteration stops. Fails up front, if `op` is unknown to the context.
+    fn apply_iter<'a, I>(
+        &'a self,
+        op: OpHandle,
+        direction: Direction,
+        operands: I,
+    ) -> Result<impl Iterator<Item = Result<Coord, Error>> + 'a, Error>
+    where
+        Self: Sized,
+        I: IntoIterator<Item = Coord>,
+        I::IntoIter: 'a,
+    {
+        const CHUNK_SIZE: usize = 64;
+
+        let mut chunk: Vec<Coord> = Vec::with_capacity(CHUNK_SIZE);
+        self.apply(op, direction, &mut chunk)?;
+        let mut operands = operands.into_iter();
+        let mut next = 0;
+        let mut failed = false;
+        Ok(std::iter::from_fn(move || {
+            if failed {
+                return None;
+            }
+            if next == chunk.len() {
+                chunk.clear();
+                chunk.extend(operands.by_ref().take(CHUNK_SIZE));
+                next = 0;
+                if chunk.is_empty() {
+                    return None;
+                }
+                if let Err(e) = self.apply(op, direction, &mut chunk) {
+                    failed = true;
+                    return Some(Err(e));
+                }
+            }
+            next += 1;
+            Some(Ok(chunk[next - 1]))
+        }))
+    }
+
+    /// The operators available for instantiation: Built-ins and user defined
+    /// ones, sorted by name, with parameter gamut and short description
+    fn operators(&self) -> Vec<OperatorInfo> {
+        operator_inventory([])
+    }
+
+    /// The names of the user defined resources (macros) available, sorted
+    fn resources(&self) -> Vec<String> {
+        Vec::new()
+    }
+
+    /// The documentation of the macro or operator `name`: The docstring of a macro,
+    /// or the short description of an operator, cf. [`Op::documentation`]
+    fn documentation(&self, name: &str) -> Result<String, Error> {
+        if crate::op::is_resource_name(name) {
+            let definition = self.get_resource(name)?;
+            return Ok(crate::inner_op::pipeline::split_into_steps(&definition).1);
+        }
+        self.operators()
+            .into_iter()
+            .find(|op| op.name == name)
+            .map(|op| op.doc.to_string())
+            .ok_or_else(|| Error::NotFound(name.to_string(), String::default()))
+    }
+
     /// Globally defined default values (typically just `ellps=GRS80`)
     fn globals(&self) -> BTreeMap<String, String>;
+    /// Set the global default value of the parameter `key`, used by operators
+    /// instantiated hereafter, for parameters not given explicitly. Fails for
+    /// context providers without support for user defined globals.
+    fn register_global(&mut self, key: &str, value: &str) -> Result<(), Error> {
+        Err(Error::General(format!(
+            "Global defaults ({key}={value}) not supported by this context provider"
+        )))
+    }
+    /// Set the default ellipsoid, used by operators instantiated hereafter,
+    /// unless given explicitly. Fails for unknown ellipsoid names.
+    fn set_default_ellipsoid(&mut self, name: &str) -> Result<(), Error> {
+        Ellipsoid::named(name)?;
+        self.register_global("ellps", name)
+    }
+    /// Set the default angular unit, `deg` or `rad`, at the boundaries of the
+    /// operators instantiated hereafter, as if given by an `angular_units`
+    /// pipeline directive, unless given explicitly.
+    fn set_default_angular_unit(&mut self, unit: &str) -> Result<(), Error> {
+        if unit != "deg" && unit != "rad" {
+            return Err(Error::BadParam(
+                "angular_units".to_string(),
+                unit.to_string(),
+            ));
+        }
+        self.register_global("angular_units", unit)
+    }
+    /// Set the directory searched first for grids and other blobs, by the
+    /// operators instantiated hereafter. Fails for non-existing directories.
+    fn set_default_grid_directory(&mut self, directory: &str) -> Result<(), Error> {
+        if !std::path::Path::new(directory).is_dir() {
+            return Err(Error::NotFound(
+                directory.to_string(),
+                ": Grid directory".to_string(),
+            ));
+        }
+        self.register_global("grid_dir", directory)
+    }
 
     /// Register a new user-defined operator
     fn register_op(&mut self, name: &str, constructor: OpConstructor);
     /// Register a new user-defined resource (macro, ellipsoid parameter set...)
     fn register_resource(&mut self, name: &str, definition: &str);
+    /// Register a collection of named definitions as resources. Each definition
+    /// is introduced by a header line, `[name]`, and extends to the next header.
+    /// Blank lines and `#`-comments may precede the first header. Returns the
+    /// names registered, in order of appearance.
+    fn register_definitions(&mut self, definitions: &str) -> Result<Vec<String>, Error> {
+        let mut entries: Vec<(String, String)> = Vec::new();
+        for line in definitions.lines() {
+            let trimmed = line.trim();
+            if let Some(name) = trimmed.strip_prefix('[').and_then(|n| n.strip_suffix(']')) {
+                entries.push((name.trim().to_string(), String::new()));
+                continue;
+            }
+            match entries.last_mut() {
+                Some((_, definition)) => {
+                    definition.push_str(line);
+                    definition.push('\n');
+                }
+                None if trimmed.is_empty() || trimmed.starts_with('#') => {}
+                None => {
+                    return Err(Error::Syntax(format!(
+                        "Definition without a [name] header: {trimmed}"
+                    )));
+                }
+            }
+        }
+
+        let mut names = Vec::new();
+        for (name, definition) in entries {
+            if name.is_empty() || name.contains(char::is_whitespace) {
+                return Err(Error::Syntax(format!("Bad definition name: [{name}]")));
+            }
+            self.register_resource(&name, definition.trim());
+            names.push(name);
+        }
+        Ok(names)
+    }
 
     /// Helper for the `Op` instantiation logic in `Op::op(...)`
     fn get_op(&self, name: &str) -> Result<OpConstructor, Error>;
@@ -45,6 +405,28 @@ pub trait Context {
 
     /// Access grid resources by identifier
     fn get_grid(&self, name: &str) -> Result<Grid, Error>;
+
+    /// The cache of precomputations shared by the operators instantiated
+    /// through the context, cf. [`Precomputed`]. Context providers without
+    /// one leave this unimplemented, in which case everything is computed
+    /// afresh for each operator.
+    fn precomputed(&self) -> Option<&Precomputed> {
+        None
+    }
+
+    /// Helper for the operator constructors: The Fourier coefficients `name`,
+    /// for `inputs`, from the cache if available, otherwise computed by `compute`
+    fn fourier_coefficients(
+        &self,
+        name: &'static str,
+        inputs: &[f64; 2],
+        compute: &dyn Fn() -> FourierCoefficients,
+    ) -> FourierCoefficients {
+        match self.precomputed() {
+            Some(cache) => cache.fourier_coefficients(name, inputs, compute),
+            None => compute(),
+        }
+    }
 }
 
 // Help context providers provide canonically named, built in coordinate adaptors
@@ -59,3 +441,470 @@ pub const BUILTIN_ADAPTORS: [(&str, &str); 8] = [
     ("enu:in",  "adapt from=enuf"    ),
     ("enu:out", "adapt to=enuf"      ),
 ];
+
+// A small library of common regional transformations, and of height system
+// conversions, for providers to make available by name. All take geographical
+// coordinates in degrees, in the EPSG axis order (latitude, longitude), and
+// return the same. Where known, the area of use and accuracy are given by the
+// `#@` metadata lines.
+#[rustfmt::skip]
+pub const BUILTIN_MACROS: [(&str, &str); 14] = [
+    ("ed50:etrs89", "## ED50 to ETRS89: 3 parameter shift, cf. EPSG:1133. Accuracy: 10 m
+        #@ area_of_use=-16.1,25.71,48.61,84.73 accuracy=10
+        geo:in | cart ellps=intl | helmert x=-87 y=-98 z=-121 | cart inv ellps=GRS80 | geo:out"),
+    ("nad27:nad83", "## NAD27 to NAD83, conterminous US: 3 parameter shift, cf. EPSG:1173. Accuracy: 10 m
+        #@ area_of_use=-124.79,24.41,-66.91,49.38 accuracy=10
+        geo:in | cart ellps=clrk66 | helmert x=-8 y=160 z=176 | cart inv ellps=GRS80 | geo:out"),
+    ("osgb36:etrs89", "## OSGB36 to ETRS89: 7 parameter shift, cf. EPSG:1314. Accuracy: 2 m
+        #@ area_of_use=-9.01,49.75,2.01,61.01 accuracy=2
+        geo:in | cart ellps=airy
+        | helmert x=446.448 y=-125.157 z=542.06 rx=0.15 ry=0.247 rz=0.842 s=-20.489
+          convention=position_vector
+        | cart inv ellps=GRS80 | geo:out"),
+    ("itrf2014:etrf2014", "## ITRF2014 to ETRF2014, following EUREF TN-1 (2018). The 4th
+        ## coordinate element is the observation epoch, in decimal years
+        geo:in | cart ellps=GRS80
+        | helmert drx=0.000085 dry=0.000531 drz=-0.00077 t_epoch=1989
+          convention=position_vector
+        | cart inv ellps=GRS80 | geo:out"),
+    // ----- Height systems -------------------------------------------------------------
+    ("ellipsoidal:orthometric", "## Ellipsoidal to orthometric heights, by the geoid grid given
+        #$ geoid
+        geo:in | gridshift grids=$geoid | geo:out"),
+    ("ellipsoidal:normal", "## Ellipsoidal to normal heights, by the quasigeoid grid given
+        #$ quasigeoid
+        geo:in | gridshift grids=$quasigeoid | geo:out"),
+    ("ellipsoidal:egm96", "## WGS84 ellipsoidal heights to EGM96 heights, by the coefficients of the model
+        #$ model=EGM96
+        geo:in | geoid model=$model ellps=WGS84 zero_degree=-0.53 | geo:out"),
+    ("ellipsoidal:egm2008", "## WGS84 ellipsoidal heights to EGM2008 heights, by the coefficients of the model
+        #$ model=EGM2008_to2190_TideFree
+        geo:in | geoid model=$model ellps=WGS84 zero_degree=-0.41 | geo:out"),
+    ("orthometric:normal", "## Orthometric to normal heights, by the (constant) Bouguer anomaly given, in mGal
+        #$ anomaly=0
+        geo:in | normal_height anomaly=$anomaly | geo:out"),
+    ("orthometric:ellipsoidal", "## The inverse of ellipsoidal:orthometric
+        ellipsoidal:orthometric inv"),
+    ("normal:ellipsoidal", "## The inverse of ellipsoidal:normal
+        ellipsoidal:normal inv"),
+    ("egm96:ellipsoidal", "## The inverse of ellipsoidal:egm96
+        ellipsoidal:egm96 inv"),
+    ("egm2008:ellipsoidal", "## The inverse of ellipsoidal:egm2008
+        ellipsoidal:egm2008 inv"),
+    ("normal:orthometric", "## The inverse of orthometric:normal
+        orthometric:normal inv"),
+];
+
+// ----- T E S T S ------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn operators() -> Result<(), Error> {
+        let mut ctx = Minimal::new();
+        let builtins = ctx.operators();
+        assert!(builtins.windows(2).all(|w| w[0].name < w[1].name));
+
+        let utm = builtins.iter().find(|op| op.name == "utm").unwrap();
+        assert!(utm.doc.contains("Mercator"));
+        assert!(matches!(
+            utm.gamut[2],
+            OpParameter::Text { key: "zone", .. }
+        ));
+
+        // User defined operators are included, and shadow built-ins
+        fn frobnicate(parameters: &RawParameters, ctx: &dyn Context) -> Result<Op, Error> {
+            Op::plain(parameters, InnerOp::default(), InnerOp::default(), &[], ctx)
+        }
+        ctx.register_op("utm", OpConstructor(frobnicate));
+        ctx.register_op("frobnicate", OpConstructor(frobnicate));
+        let all = ctx.operators();
+        assert_eq!(all.len(), builtins.len() + 1);
+        let utm = all.iter().find(|op| op.name == "utm").unwrap();
+        assert!(utm.gamut.is_empty());
+        assert!(all.iter().any(|op| op.name == "frobnicate"));
+        Ok(())
+    }
+
+    #[test]
+    fn apply_at_epoch() -> Result<(), Error> {
+        let mut ctx = Minimal::new();
+        let op = ctx.op("helmert x=1 dx=0.1 t_epoch=2000")?;
+
+        // A 3D set, which cannot carry the epoch itself
+        let mut data = vec![Coor3D::raw(0., 0., 0.); 2];
+        assert_eq!(ctx.apply_at_epoch(op, Fwd, &mut data, 2010.)?, 2);
+        assert!((data[0][0] - 2.).abs() < 1e-12);
+        ctx.apply_at_epoch(op, Inv, &mut data, 2010.)?;
+        assert!(data[0][0].abs() < 1e-12);
+
+        // Time coordinates of 4D sets are replaced
+        let mut data = [Coord::raw(0., 0., 0., 1900.)];
+        ctx.apply_at_epoch(op, Fwd, &mut data, 2020.)?;
+        assert!((data[0][0] - 3.).abs() < 1e-12);
+        assert_eq!(data[0][3], 2020.);
+        Ok(())
+    }
+
+    #[test]
+    fn globals() -> Result<(), Error> {
+        let mut ctx = Minimal::new();
+        let grs80 = ctx.op("cart")?;
+        assert!(ctx.set_default_ellipsoid("cucumber").is_err());
+        ctx.set_default_ellipsoid("intl")?;
+        assert_eq!(ctx.globals()["ellps"], "intl");
+
+        // Not handed back from the instantiation cache
+        let intl = ctx.op("cart")?;
+        assert_ne!(grs80, intl);
+        let mut data = [Coord::geo(55., 12., 0., 0.)];
+        let mut expected = data;
+        ctx.apply(intl, Fwd, &mut data)?;
+        Op::new("cart ellps=intl", &ctx)?.apply(&ctx, &mut expected, Fwd)?;
+        assert_eq!(data, expected);
+
+        // Any parameter can be given a default
+        ctx.register_global("zone", "33")?;
+        let op = ctx.op("utm")?;
+        let mut data = [Coord::geo(55., 15., 0., 0.)];
+        ctx.apply(op, Fwd, &mut data)?;
+        assert!((data[0][0] - 500000.).abs() < 1e-6);
+
+        // The default angular unit applies at the boundaries of the operator
+        assert!(ctx.set_default_angular_unit("gon").is_err());
+        ctx.set_default_angular_unit("deg")?;
+        let op = ctx.op("utm")?;
+        let mut degrees = [Coord::raw(15., 55., 0., 0.)];
+        ctx.apply(op, Fwd, &mut degrees)?;
+        assert_eq!(degrees, data);
+
+        // ...leaving the definition, and operators without angular i/o, as is
+        let op = Op::new("utm", &ctx)?;
+        assert_eq!(op.descriptor.definition, "utm");
+        assert!(Op::new("helmert x=1", &ctx)?.steps.is_empty());
+
+        // ...and is overridden by an explicit directive, but not by a comment
+        let op = ctx.op("utm | angular_units=rad")?;
+        let mut radians = [Coord::geo(55., 15., 0., 0.)];
+        ctx.apply(op, Fwd, &mut radians)?;
+        assert_eq!(radians, data);
+        let op = ctx.op("# angular_units=rad\nutm")?;
+        let mut degrees = [Coord::raw(15., 55., 0., 0.)];
+        ctx.apply(op, Fwd, &mut degrees)?;
+        assert_eq!(degrees, data);
+        ctx.set_default_angular_unit("rad")?;
+
+        // Grids are looked up in the default grid directory first
+        let mut dir = std::env::temp_dir();
+        dir.push(format!("geodesy-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir)?;
+        std::fs::copy("geodesy/datum/test.datum", dir.join("moved.datum"))?;
+        let before = ctx.op("gridshift grids=moved.datum");
+        assert!(ctx.set_default_grid_directory("nowhere").is_err());
+        ctx.set_default_grid_directory(&dir.to_string_lossy())?;
+        let after = ctx.op("gridshift grids=moved.datum");
+        std::fs::remove_dir_all(&dir)?;
+        assert!(before.is_err());
+        assert!(after.is_ok());
+        Ok(())
+    }
+
+    #[test]
+    fn builtin_macros() -> Result<(), Error> {
+        let mut ctx = Minimal::new();
+        let ed50 = Coord::raw(55., 12., 0., 0.);
+
+        // Roughly 100 m in Denmark...
+        let op = ctx.op("ed50:etrs89")?;
+        let mut data = [ed50];
+        ctx.apply(op, Fwd, &mut data)?;
+        let shift = Coord::geo(data[0][0], data[0][1], 0., 0.)
+            .default_ellps_dist(&Coord::geo(55., 12., 0., 0.));
+        assert!((90.0..120.0).contains(&shift));
+        ctx.apply(op, Inv, &mut data)?;
+        assert!(data[0].hypot2(&ed50) < 1e-12);
+
+        // ...and, but for the GRS80/WGS84 difference, the same as the EPSG
+        // definition for OSGB36
+        let op = ctx.op("osgb36:etrs89")?;
+        let epsg = ctx.op_from_epsg(1314)?;
+        let mut data = [Coord::raw(52., -1., 0., 0.)];
+        let mut expected = data;
+        ctx.apply(op, Fwd, &mut data)?;
+        ctx.apply(epsg, Fwd, &mut expected)?;
+        assert!(data[0].hypot2(&expected[0]) < 1e-8);
+
+        // ITRF2014 and ETRF2014 coincide at 1989.0, and then drift apart
+        // by approximately 2.5 cm per year in Europe
+        let op = ctx.op("itrf2014:etrf2014")?;
+        let mut data = [
+            Coord::raw(55., 12., 0., 1989.),
+            Coord::raw(55., 12., 0., 2019.),
+        ];
+        ctx.apply(op, Fwd, &mut data)?;
+        let drift = |c: Coord| {
+            Coord::geo(c[0], c[1], 0., 0.).default_ellps_dist(&Coord::geo(55., 12., 0., 0.))
+        };
+        assert!(drift(data[0]) < 1e-6);
+        assert!((0.6..0.9).contains(&drift(data[1])));
+
+        assert!(ctx
+            .documentation("nad27:nad83")?
+            .starts_with("NAD27 to NAD83"));
+        Ok(())
+    }
+
+    #[test]
+    fn height_systems() -> Result<(), Error> {
+        let mut ctx = Minimal::new();
+        let gps = [Coord::raw(55., 12., 100., 0.)];
+
+        // GPS heights to heights above the geoid, by grid, and back
+        let op = ctx.op("ellipsoidal:orthometric geoid=test.geoid")?;
+        let mut data = gps;
+        ctx.apply(op, Fwd, &mut data)?;
+        assert!((data[0][2] - (100. - 55.12)).abs() < 1e-5); // f32 grid values
+        let inv = ctx.op("orthometric:ellipsoidal geoid=test.geoid")?;
+        ctx.apply(inv, Fwd, &mut data)?;
+        assert!((data[0][2] - 100.).abs() < 1e-9);
+
+        // ...and by spherical harmonics, here with EGM96 truncated at degree 3
+        let op = ctx.op("ellipsoidal:egm96 model=test.egm")?;
+        let direct = ctx.op("geo:in | geoid model=test.egm ellps=WGS84 zero_degree=-0.53")?;
+        let (mut data, mut expected) = (gps, gps);
+        ctx.apply(op, Fwd, &mut data)?;
+        ctx.apply(direct, Fwd, &mut expected)?;
+        assert_eq!(data[0][2], expected[0][2]);
+
+        // Orthometric to normal heights, and back: Centimetres in the mountains
+        let op = ctx.op("orthometric:normal anomaly=-100")?;
+        let mut data = [Coord::raw(46., 8., 2000., 0.)];
+        ctx.apply(op, Fwd, &mut data)?;
+        assert!((-0.25..-0.15).contains(&(data[0][2] - 2000.)));
+        let inv = ctx.op("normal:orthometric anomaly=-100")?;
+        ctx.apply(inv, Fwd, &mut data)?;
+        assert!((data[0][2] - 2000.).abs() < 1e-9);
+
+        // The geoid must be given
+        assert!(ctx.op("ellipsoidal:orthometric").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn register_blob() -> Result<(), Error> {
+        // Grids held in memory, rather than read from ./geodesy/datum
+        let mut ctx = Minimal::default();
+        let grid = ctx.get_blob("test.datum")?;
+        ctx.register_blob("in_memory.datum", grid);
+        let op = ctx.op("gridshift grids=in_memory.datum")?;
+        let reference = ctx.op("gridshift grids=test.datum")?;
+        let mut data = [Coord::geo(55., 12., 0., 0.)];
+        let mut expected = data;
+        ctx.apply(op, Fwd, &mut data)?;
+        ctx.apply(reference, Fwd, &mut expected)?;
+        assert_eq!(data, expected);
+        assert!(ctx.op("gridshift grids=in_memory.geoid").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn register_definitions() -> Result<(), Error> {
+        let mut ctx = Minimal::new();
+        let definitions = "# Pipelines for testing
+            [copenhagen:stockholm]
+            ## UTM zone 32 to UTM zone 33
+            utm inv zone=32
+            | utm zone=33
+
+            [add:two]
+            addone | addone
+        ";
+        let names = ctx.register_definitions(definitions)?;
+        assert_eq!(names, ["copenhagen:stockholm", "add:two"]);
+        assert_eq!(ctx.get_resource("add:two")?, "addone | addone");
+        assert!(ctx
+            .documentation("copenhagen:stockholm")?
+            .starts_with("UTM zone 32"));
+
+        let op = ctx.op("add:two")?;
+        let mut data = some_basic_coordinates();
+        ctx.apply(op, Fwd, &mut data)?;
+        assert_eq!(data[0][0], 57.);
+
+        let op = ctx.op("copenhagen:stockholm")?;
+        let expected = ctx.op("utm inv zone=32 | utm zone=33")?;
+        let mut data = [Coord::raw(691875.63214, 6098907.82501, 0., 0.)];
+        let mut result = data;
+        ctx.apply(op, Fwd, &mut result)?;
+        ctx.apply(expected, Fwd, &mut data)?;
+        assert_eq!(result, data);
+
+        assert!(ctx
+            .register_definitions("addone\n[add:one]\naddone")
+            .is_err());
+        assert!(ctx.register_definitions("[add one]\naddone").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn transform() -> Result<(), Error> {
+        let mut ctx = Minimal::new();
+
+        // Geographic to Web Mercator
+        let mut data = [Coord::raw(55., 12., 0., 0.)];
+        assert_eq!(ctx.transform("EPSG:4326", "EPSG:3857", &mut data)?, 1);
+        let r = 6378137_f64;
+        assert!((data[0][0] - r * 12_f64.to_radians()).abs() < 1e-6);
+        assert!((data[0][1] - r * 72.5_f64.to_radians().tan().ln()).abs() < 1e-6);
+
+        // Between UTM zones, and back
+        let mut data = [Coord::raw(55., 12., 0., 0.)];
+        let mut expected = data;
+        let utm33 = ctx.op("geo:in | utm zone=33")?;
+        ctx.apply(utm33, Fwd, &mut expected)?;
+        ctx.transform("EPSG:4258", "EPSG:25832", &mut data)?;
+        ctx.transform("EPSG:25832", "geo:in | utm zone=33", &mut data)?;
+        assert!(data[0].hypot2(&expected[0]) < 1e-6);
+        ctx.transform("EPSG:25833", "EPSG:4258", &mut data)?;
+        assert!(data[0].hypot2(&Coord::raw(55., 12., 0., 0.)) < 1e-9);
+
+        // Different datums require an actual transformation, not just conversions
+        let mut data = [Coord::raw(55., 12., 0., 0.)];
+        assert!(ctx.transform("EPSG:4230", "EPSG:4326", &mut data).is_err());
+        assert!(ctx
+            .transform("EPSG:23032", "EPSG:25832", &mut data)
+            .is_err());
+        assert_eq!(data[0], Coord::raw(55., 12., 0., 0.));
+        assert_eq!(ctx.transform("EPSG:4230", "EPSG:23032", &mut data)?, 1);
+        Ok(())
+    }
+
+    #[test]
+    fn op_from_epsg() -> Result<(), Error> {
+        let mut ctx = Minimal::new();
+
+        // ETRS89 / UTM zone 32N vs. its explicit definition
+        let epsg = ctx.op_from_epsg(25832)?;
+        assert_eq!(epsg, ctx.op("EPSG:25832")?);
+        let utm = ctx.op("geo:in | utm zone=32 ellps=GRS80")?;
+        let mut data = [Coord::raw(55., 12., 0., 0.)];
+        let mut expected = data;
+        ctx.apply(epsg, Fwd, &mut data)?;
+        ctx.apply(utm, Fwd, &mut expected)?;
+        assert_eq!(data, expected);
+
+        // OSGB36 / British National Grid: The worked example from "A guide to
+        // coordinate systems in Great Britain", Ordnance Survey (2020), p. 47
+        let bng = ctx.op_from_epsg(27700)?;
+        let lat = 52. + 39. / 60. + 27.2531 / 3600.;
+        let lon = 1. + 43. / 60. + 4.5177 / 3600.;
+        let mut data = [Coord::raw(lat, lon, 0., 0.)];
+        ctx.apply(bng, Fwd, &mut data)?;
+        assert!((data[0][0] - 651409.903).abs() < 1e-3);
+        assert!((data[0][1] - 313177.270).abs() < 1e-3);
+        ctx.apply(bng, Inv, &mut data)?;
+        assert!((data[0][0] - lat).abs() < 1e-10);
+        assert!((data[0][1] - lon).abs() < 1e-10);
+
+        // LAEA Europe has northing first. The center of the projection...
+        let laea = ctx.op("EPSG:3035")?;
+        let mut data = [Coord::raw(52., 10., 0., 0.)];
+        ctx.apply(laea, Fwd, &mut data)?;
+        assert!((data[0][0] - 3210000.).abs() < 1e-6);
+        assert!((data[0][1] - 4321000.).abs() < 1e-6);
+
+        // ...and the southern hemisphere UTM zones have a false northing
+        let utm = ctx.op_from_epsg(32733)?;
+        let mut data = [Coord::raw(-0.000001, 15., 0., 0.)];
+        ctx.apply(utm, Fwd, &mut data)?;
+        assert!((data[0][0] - 500000.).abs() < 1e-6);
+        assert!((data[0][1] - 10000000.).abs() < 0.2);
+
+        // ED50 to WGS 84 (1): A 3 parameter shift of roughly 100 m
+        let ed50 = ctx.op_from_epsg(1133)?;
+        let mut data = [Coord::raw(55., 12., 0., 0.)];
+        ctx.apply(ed50, Fwd, &mut data)?;
+        let shift = Coord::geo(data[0][0], data[0][1], 0., 0.)
+            .default_ellps_dist(&Coord::geo(55., 12., 0., 0.));
+        assert!((90.0..120.0).contains(&shift));
+
+        // Outside of the embedded subset
+        assert!(matches!(ctx.op_from_epsg(4242), Err(Error::NotFound(_, _))));
+        Ok(())
+    }
+
+    #[test]
+    fn apply_par() -> Result<(), Error> {
+        let mut ctx = Minimal::new();
+        let op = ctx.op("addone")?;
+
+        // Large enough to be split into chunks on any multi core machine
+        let n = 10_000;
+        let mut data = vec![Coord::raw(1., 2., 3., 4.); n];
+        assert_eq!(ctx.apply_par(op, Fwd, &mut data)?, n);
+        assert!(data.iter().all(|c| c[0] == 2.));
+        assert_eq!(ctx.apply_par(op, Inv, &mut data)?, n);
+        assert!(data.iter().all(|c| c[0] == 1.));
+
+        // With a given number of threads, fewer than the chunks otherwise made
+        assert_eq!(ctx.apply_par_with(op, Fwd, &mut data, 3)?, n);
+        assert_eq!(ctx.apply_par_with(op, Inv, &mut data, 0)?, n);
+        assert!(data.iter().all(|c| c[0] == 1.));
+
+        // Small enough to be handled by the calling thread
+        let mut data = some_basic_coordinates();
+        assert_eq!(ctx.apply_par(op, Fwd, &mut data)?, 2);
+        assert_eq!(data[0][0], 56.);
+
+        // Per-chunk failures are accounted for
+        let op = ctx.op("utm zone=auto")?;
+        let mut data = vec![Coord::raw(33_500_000., 6e6, 0., 0.); n];
+        data[0] = Coord::raw(500_000., 6e6, 0., 0.);
+        data[n - 1] = Coord::raw(500_000., 6e6, 0., 0.);
+        assert_eq!(ctx.apply_par(op, Inv, &mut data)?, n - 2);
+        assert!(data[n - 1][0].is_nan());
+        Ok(())
+    }
+
+    #[test]
+    fn apply_iter() -> Result<(), Error> {
+        let mut ctx = Minimal::new();
+        let op = ctx.op("addone")?;
+
+        // Spanning more than one chunk, and never materialized
+        let operands = (0..100).map(|i| Coord::raw(i as f64, 0., 0., 0.));
+        let result: Vec<Coord> = ctx
+            .apply_iter(op, Fwd, operands)?
+            .collect::<Result<_, _>>()?;
+        assert_eq!(result.len(), 100);
+        assert!(result
+            .iter()
+            .enumerate()
+            .all(|(i, c)| c[0] == i as f64 + 1.));
+
+        let mut iter = ctx.apply_iter(op, Inv, some_basic_coordinates())?;
+        assert_eq!(iter.next().unwrap()?[0], 54.);
+        assert_eq!(iter.count(), 1);
+
+        // Unknown operators fail up front
+        let other = Minimal::new().op("addone")?;
+        assert!(ctx.apply_iter(other, Fwd, Vec::new()).is_err());
+
+        // A chunk failing as a whole yields the error, after the coordinates
+        // of the chunks before it, and ends the iteration
+        let op = ctx.op("ed50:etrs89 area_check=error")?;
+        let copenhagen = Coord::raw(55., 12., 0., 0.);
+        let new_york = Coord::raw(40.7, -74., 0., 0.);
+        let operands = (0..100).map(|i| if i == 70 { new_york } else { copenhagen });
+        let result: Vec<_> = ctx.apply_iter(op, Fwd, operands)?.collect();
+        assert_eq!(result.len(), 65);
+        assert!(result[..64].iter().all(|c| c.is_ok()));
+        let Err(Error::Operator(name, _)) = &result[64] else {
+            panic!("Expected an area of use error");
+        };
+        assert_eq!(name, "ed50:etrs89");
+        Ok(())
+    }
+}
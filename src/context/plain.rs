@@ -8,71 +8,318 @@ use crate::context::Context;
 /// Sufficient for most uses, especially geodetic grid development.
 /// May get somewhat clunky when working with large numbers of grids and macros, as each grid
 /// and macro resides in individual files.
+///
+/// Resources are looked up in a list of directories, searched in order:
+///
+/// 1. The directories given in the `GEODESY_DATA` environment variable (deployment
+///    level, analogous to `PROJ_DATA`)
+/// 2. `./geodesy` (project level)
+/// 3. `~/.geodesy` and the `geodesy` subdirectory of the platform specific local
+///    data directory (user level)
+/// 4. The directories given in the `GEODESY_RESOURCES` environment variable (site
+///    level)
+///
+/// The environment variables use the platform specific `PATH` separator. Further
+/// directories may be added using [`Plain::add_path`], or the list replaced
+/// entirely, using [`Plain::set_paths`].
+/// Macros are read from the `macro` subdirectory of each search directory, with
+/// the extension `.macro` or `.gys`, and any `:` in the macro name replaced by `_`.
+/// Hence, `ctx.op("my:macro")` will look for `macro/my_macro.macro`. Macro files
+/// may include other files, cf. [`Plain::expand_includes`].
 #[derive(Debug)]
 pub struct Plain {
     constructors: BTreeMap<String, OpConstructor>,
     resources: BTreeMap<String, String>,
     operators: BTreeMap<OpHandle, Op>,
+    definitions: BTreeMap<String, OpHandle>,
+    globals: BTreeMap<String, String>,
     paths: Vec<std::path::PathBuf>,
+    precomputed: Precomputed,
 }
 
+// The environment variables holding the deployment and site level resource
+// directories
+const DATA_PATH_VARIABLE: &str = "GEODESY_DATA";
+const RESOURCE_PATH_VARIABLE: &str = "GEODESY_RESOURCES";
+
+// The file extensions recognized for macro definitions
+const MACRO_EXTENSIONS: [&str; 2] = ["macro", "gys"];
+
+// The line prefixes marking the inclusion of another resource file, and the
+// bound on the nesting of inclusions, catching circular ones
+const INCLUDE_PREFIXES: [&str; 2] = ["include:", "@"];
+const MAX_INCLUDE_DEPTH: usize = 16;
+
 impl Default for Plain {
     fn default() -> Plain {
         let constructors = BTreeMap::new();
         let resources = BTreeMap::new();
         let operators = BTreeMap::new();
-        let mut paths = Vec::new();
-
-        let localpath: PathBuf = [".", "geodesy"].iter().collect();
-        paths.push(localpath);
-
-        if let Some(mut userpath) = dirs::data_local_dir() {
-            userpath.push("geodesy");
-            paths.push(userpath);
-        }
+        let definitions = BTreeMap::new();
+        let globals = BTreeMap::new();
+        let paths = default_paths(
+            std::env::var_os(DATA_PATH_VARIABLE),
+            std::env::var_os(RESOURCE_PATH_VARIABLE),
+        );
 
         Plain {
             constructors,
             resources,
             operators,
+            definitions,
+            globals,
             paths,
+            precomputed: Precomputed::default(),
+        }
+    }
+}
+
+impl Plain {
+    /// Replace the list of directories searched for resources
+    pub fn set_paths<P: Into<PathBuf>>(&mut self, paths: impl IntoIterator<Item = P>) {
+        self.definitions.clear();
+        self.paths = paths.into_iter().map(Into::into).collect();
+    }
+
+    /// Append `path` to the list of directories searched for resources
+    pub fn add_path(&mut self, path: impl Into<PathBuf>) {
+        self.paths.push(path.into());
+    }
+
+    /// The directories searched for resources, in search order
+    pub fn paths(&self) -> &[PathBuf] {
+        &self.paths
+    }
+
+    /// Expand the include directives of the resource text `text`: Lines of the
+    /// form `include: file`, or `@file`, are replaced by the contents of `file`,
+    /// which may include further files. Unless given by an absolute path, `file`
+    /// is looked up in the `macro` subdirectories of the search path, trying
+    /// each of the macro extensions, if none is given.
+    ///
+    /// Macros read from the search path have their includes expanded
+    /// automatically. So can large macro libraries (cf.
+    /// [`Context::register_definitions`]) be split across files, and shared.
+    pub fn expand_includes(&self, text: &str) -> Result<String, Error> {
+        self.expand(text, 0)
+    }
+
+    fn expand(&self, text: &str, depth: usize) -> Result<String, Error> {
+        if !text.lines().any(|line| include(line).is_some()) {
+            return Ok(text.to_string());
+        }
+
+        let mut expanded = String::with_capacity(text.len());
+        for line in text.lines() {
+            let Some(file) = include(line) else {
+                expanded.push_str(line);
+                expanded.push('\n');
+                continue;
+            };
+            if depth >= MAX_INCLUDE_DEPTH {
+                return Err(Error::Recursion(file.to_string(), "include".to_string()));
+            }
+            let included = self.expand(&self.read_include(file)?, depth + 1)?;
+            expanded.push_str(included.trim_end());
+            expanded.push('\n');
+        }
+        Ok(expanded)
+    }
+
+    fn read_include(&self, file: &str) -> Result<String, Error> {
+        let path = PathBuf::from(file);
+        if path.is_absolute() {
+            return Ok(std::fs::read_to_string(path)?);
+        }
+        // Names may contain dots, so unless the name ends in one of the macro
+        // extensions, we try both the name as given, and with them appended
+        let mut extensions = vec![None];
+        let given = path.extension().and_then(|e| e.to_str());
+        if !given.is_some_and(|e| MACRO_EXTENSIONS.contains(&e)) {
+            extensions.extend(MACRO_EXTENSIONS.iter().copied().map(Some));
+        }
+        for dir in &self.paths {
+            for extension in &extensions {
+                let candidate = match extension {
+                    Some(extension) => dir.join("macro").join(format!("{file}.{extension}")),
+                    None => dir.join("macro").join(&path),
+                };
+                if let Ok(text) = std::fs::read_to_string(candidate) {
+                    return Ok(text);
+                }
+            }
         }
+        Err(Error::Unexpected {
+            message: "Included resource file not found".to_string(),
+            expected: "a file in the macro directories of the search path".to_string(),
+            found: file.to_string(),
+        })
     }
 }
 
+// The file included by `line`, if it is an include directive
+fn include(line: &str) -> Option<&str> {
+    let line = line.trim();
+    INCLUDE_PREFIXES
+        .iter()
+        .find_map(|prefix| line.strip_prefix(prefix))
+        .map(str::trim)
+}
+
+// The resource search directories: deployment, project, user, and site level,
+// in that order
+fn default_paths(
+    data: Option<std::ffi::OsString>,
+    site: Option<std::ffi::OsString>,
+) -> Vec<PathBuf> {
+    let split = |paths: std::ffi::OsString| -> Vec<PathBuf> {
+        std::env::split_paths(&paths)
+            .filter(|p| !p.as_os_str().is_empty())
+            .collect()
+    };
+    let mut paths = data.map(split).unwrap_or_default();
+
+    let localpath: PathBuf = [".", "geodesy"].iter().collect();
+    paths.push(localpath);
+
+    if let Some(mut userpath) = dirs::home_dir() {
+        userpath.push(".geodesy");
+        paths.push(userpath);
+    }
+
+    if let Some(mut userpath) = dirs::data_local_dir() {
+        userpath.push("geodesy");
+        paths.push(userpath);
+    }
+
+    paths.extend(site.map(split).unwrap_or_default());
+
+    paths
+}
+
 impl Context for Plain {
     fn new() -> Plain {
         let mut ctx = Plain::default();
         for item in BUILTIN_ADAPTORS {
             ctx.register_resource(item.0, item.1);
         }
+        for item in BUILTIN_MACROS {
+            ctx.register_resource(item.0, item.1);
+        }
+        for item in ITRF_MACROS {
+            ctx.register_resource(item.0, item.1);
+        }
         ctx
     }
 
     fn op(&mut self, definition: &str) -> Result<OpHandle, Error> {
+        // Reuse the operator, if already instantiated
+        let key = normalized_definition(definition);
+        if let Some(id) = self.definitions.get(&key) {
+            return Ok(*id);
+        }
+
         let op = Op::new(definition, self)?;
         let id = op.id;
         self.operators.insert(id, op);
+        self.definitions.insert(key, id);
         assert!(self.operators.contains_key(&id));
         Ok(id)
     }
 
-    fn apply(
+    fn provenance(&self, op: OpHandle) -> Result<Vec<Provenance>, Error> {
+        let bad_id = || Error::General("Plain: Unknown operator id".to_string());
+        let op = self.operators.get(&op).ok_or_else(bad_id)?;
+        Ok(op.provenance())
+    }
+
+    fn metadata(&self, op: OpHandle) -> Result<Metadata, Error> {
+        let bad_id = || Error::General("Plain: Unknown operator id".to_string());
+        let op = self.operators.get(&op).ok_or_else(bad_id)?;
+        Ok(op.metadata())
+    }
+
+    fn warnings(&self, op: OpHandle) -> Result<Vec<String>, Error> {
+        let bad_id = || Error::General("Plain: Unknown operator id".to_string());
+        let op = self.operators.get(&op).ok_or_else(bad_id)?;
+        Ok(op.warnings())
+    }
+
+    fn steps(&self, op: OpHandle) -> Result<Vec<StepDescription>, Error> {
+        let bad_id = || Error::General("Plain: Unknown operator id".to_string());
+        let op = self.operators.get(&op).ok_or_else(bad_id)?;
+        Ok(op.steps())
+    }
+
+    fn apply_traced(
         &self,
         op: OpHandle,
         direction: Direction,
         operands: &mut [Coord],
+        tracer: &mut Tracer,
     ) -> Result<usize, Error> {
-        const BAD_ID_MESSAGE: Error = Error::General("Local: Unknown operator id");
-        let op = self.operators.get(&op).ok_or(BAD_ID_MESSAGE)?;
+        let bad_id = || Error::General("Plain: Unknown operator id".to_string());
+        let op = self.operators.get(&op).ok_or_else(bad_id)?;
+        op.apply_traced(self, operands, direction, tracer)
+    }
+
+    fn apply(
+        &self,
+        op: OpHandle,
+        direction: Direction,
+        operands: &mut dyn CoordinateSet,
+    ) -> Result<usize, Error> {
+        let bad_id = || Error::General("Local: Unknown operator id".to_string());
+        let op = self.operators.get(&op).ok_or_else(bad_id)?;
         op.apply(self, operands, direction)
     }
 
+    fn operators(&self) -> Vec<OperatorInfo> {
+        operator_inventory(self.constructors.keys().map(String::as_str))
+    }
+
+    /// The resources registered, and the macros found in the resource search
+    /// path, named by their file stems (with `_` for any `:` in the name)
+    fn resources(&self) -> Vec<String> {
+        let mut names: BTreeSet<String> = self.resources.keys().cloned().collect();
+        for path in &self.paths {
+            let Ok(entries) = std::fs::read_dir(path.join("macro")) else {
+                continue;
+            };
+            for path in entries
+                .filter_map(|entry| entry.ok())
+                .map(|entry| entry.path())
+            {
+                let extension = path
+                    .extension()
+                    .and_then(|e| e.to_str())
+                    .unwrap_or_default();
+                if !MACRO_EXTENSIONS.contains(&extension) {
+                    continue;
+                }
+                if let Some(stem) = path.file_stem().and_then(|s| s.to_str()) {
+                    names.insert(stem.to_string());
+                }
+            }
+        }
+        names.into_iter().collect()
+    }
+
     fn globals(&self) -> BTreeMap<String, String> {
-        BTreeMap::from([("ellps".to_string(), "GRS80".to_string())])
+        let mut globals = BTreeMap::from([("ellps".to_string(), "GRS80".to_string())]);
+        globals.extend(self.globals.clone());
+        globals
+    }
+
+    fn register_global(&mut self, key: &str, value: &str) -> Result<(), Error> {
+        self.definitions.clear();
+        self.globals.insert(String::from(key), String::from(value));
+        Ok(())
     }
 
     fn register_op(&mut self, name: &str, constructor: OpConstructor) {
+        // Previously instantiated definitions may refer to what is now redefined
+        self.definitions.clear();
         self.constructors.insert(String::from(name), constructor);
     }
 
@@ -88,6 +335,7 @@ impl Context for Plain {
     }
 
     fn register_resource(&mut self, name: &str, definition: &str) {
+        self.definitions.clear();
         self.resources
             .insert(String::from(name), String::from(definition));
     }
@@ -100,17 +348,20 @@ impl Context for Plain {
         // TODO: Check for "known prefixes": 'ellps:', 'datum:', etc.
 
         // We cannot have ':' in filenames on Windows, so we swap them for '_',
-        // and add the ".macro" extension
+        // and try each of the macro extensions
         #[allow(clippy::single_char_pattern)]
-        let name = name.replace(":", "_") + ".macro";
+        let name = name.replace(":", "_");
 
         let section = "macro";
         for path in &self.paths {
-            let mut path = path.clone();
-            path.push(section);
-            path.push(&name);
-            if let Ok(result) = std::fs::read_to_string(path) {
-                return Ok(result);
+            for extension in MACRO_EXTENSIONS {
+                // Appended, rather than set, since names may contain dots
+                let mut path = path.clone();
+                path.push(section);
+                path.push(format!("{name}.{extension}"));
+                if let Ok(result) = std::fs::read_to_string(path) {
+                    return self.expand_includes(&result);
+                }
             }
         }
 
@@ -118,6 +369,11 @@ impl Context for Plain {
     }
 
     fn get_blob(&self, name: &str) -> Result<Vec<u8>, Error> {
+        if let Some(directory) = self.globals().get("grid_dir") {
+            if let Ok(blob) = std::fs::read(PathBuf::from(directory).join(name)) {
+                return Ok(blob);
+            }
+        }
         let n = PathBuf::from(name);
         let ext = n
             .extension()
@@ -138,9 +394,13 @@ impl Context for Plain {
     /// Access grid resources by identifier
     fn get_grid(&self, _name: &str) -> Result<Grid, Error> {
         Err(Error::General(
-            "Grid access by identifier not supported by the Plain context provider",
+            "Grid access by identifier not supported by the Plain context provider".to_string(),
         ))
     }
+
+    fn precomputed(&self) -> Option<&Precomputed> {
+        Some(&self.precomputed)
+    }
 }
 
 // ----- T E S T S ------------------------------------------------------------------
@@ -170,4 +430,120 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn search_paths() -> Result<(), Error> {
+        // Site level directories are searched after the project and user level ones,
+        // and deployment level directories before
+        let site = std::env::join_paths(["site/a", "site/b"]).unwrap();
+        let data = std::env::join_paths(["data"]).unwrap();
+        let paths = default_paths(None, Some(site.clone()));
+        assert_eq!(paths[0], PathBuf::from("./geodesy"));
+        assert_eq!(paths[paths.len() - 2], PathBuf::from("site/a"));
+        assert_eq!(paths[paths.len() - 1], PathBuf::from("site/b"));
+        let paths = default_paths(Some(data), Some(site));
+        assert_eq!(paths[0], PathBuf::from("data"));
+        assert_eq!(paths[1], PathBuf::from("./geodesy"));
+        assert_eq!(paths[paths.len() - 1], PathBuf::from("site/b"));
+
+        // A user defined macro, in a directory added at run time
+        let mut dir = std::env::temp_dir();
+        dir.push(format!("geodesy-{}", uuid::Uuid::new_v4()));
+        let macro_dir = dir.join("macro");
+        std::fs::create_dir_all(&macro_dir)?;
+        std::fs::write(macro_dir.join("add_two.gys"), "addone | addone")?;
+        // Dots in the name are not mistaken for an extension
+        std::fs::write(macro_dir.join("add_two.v2.gys"), "addone | addone | addone")?;
+
+        let mut ctx = Plain::new();
+        assert!(ctx.op("add:two").is_err());
+        ctx.add_path(&dir);
+        assert_eq!(ctx.paths().last(), Some(&dir));
+        let op = ctx.op("add:two");
+        let dotted = ctx.op("add:two.v2");
+        let resources = ctx.resources();
+        std::fs::remove_dir_all(&dir)?;
+        assert!(resources.contains(&"add_two".to_string()));
+        assert!(resources.contains(&"gis:in".to_string()));
+
+        let mut data = some_basic_coordinates();
+        ctx.apply(op?, Fwd, &mut data)?;
+        assert_eq!(data[0][0], 57.);
+        assert_eq!(data[1][0], 61.);
+        let mut data = some_basic_coordinates();
+        ctx.apply(dotted?, Fwd, &mut data)?;
+        assert_eq!(data[0][0], 58.);
+
+        // Overriding the search paths entirely
+        ctx.set_paths(["nowhere"]);
+        assert_eq!(ctx.paths(), [PathBuf::from("nowhere")]);
+        assert!(ctx.op("stupid:way").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn includes() -> Result<(), Error> {
+        let mut dir = std::env::temp_dir();
+        dir.push(format!("geodesy-{}", uuid::Uuid::new_v4()));
+        let macro_dir = dir.join("macro");
+        std::fs::create_dir_all(macro_dir.join("lib"))?;
+        let files = [
+            ("add_two.macro", "addone | addone"),
+            ("add_three.gys", "## Add three\ninclude: add_two\n| addone"),
+            (
+                "lib/common.gys",
+                "[add:one]\naddone\n\n[add:four]\n@add_two.macro\n| add:one | add:one",
+            ),
+            (
+                "library.gys",
+                "# A library\n@lib/common\n[sub:one]\naddone inv",
+            ),
+            ("add_one.v2.gys", "@lib/add.v2"),
+            ("lib/add.v2.macro", "addone"),
+            ("foo_circular.macro", "addone\n@foo_circular"),
+            ("foo_missing.macro", "@nowhere"),
+        ];
+        for (name, text) in files {
+            std::fs::write(macro_dir.join(name), text)?;
+        }
+
+        let mut ctx = Plain::new();
+        ctx.add_path(&dir);
+
+        // Includes in macros
+        let add_three = ctx.op("add:three");
+        let documentation = ctx.documentation("add:three");
+        let circular = ctx.op("foo:circular");
+        let missing = ctx.op("foo:missing");
+        let dotted = ctx.op("add:one.v2");
+
+        // Includes in definition libraries, and absolute paths
+        let library = std::fs::read_to_string(macro_dir.join("library.gys"))?;
+        let library = ctx.expand_includes(&library);
+        let absolute = format!("@{}", macro_dir.join("add_two.macro").display());
+        let absolute = ctx.expand_includes(&absolute);
+        std::fs::remove_dir_all(&dir)?;
+
+        let mut data = some_basic_coordinates();
+        ctx.apply(add_three?, Fwd, &mut data)?;
+        assert_eq!(data[0][0], 58.);
+        assert!(documentation?.starts_with("Add three"));
+        assert!(matches!(circular, Err(Error::Recursion(_, _))));
+        assert!(matches!(missing, Err(Error::Unexpected { .. })));
+        let mut data = some_basic_coordinates();
+        ctx.apply(dotted?, Fwd, &mut data)?;
+        assert_eq!(data[0][0], 56.);
+
+        let names = ctx.register_definitions(&library?)?;
+        assert_eq!(names, ["add:one", "add:four", "sub:one"]);
+        let mut data = some_basic_coordinates();
+        let add_four = ctx.op("add:four")?;
+        ctx.apply(add_four, Fwd, &mut data)?;
+        assert_eq!(data[0][0], 59.);
+        assert_eq!(absolute?.trim(), "addone | addone");
+
+        // Text without includes is left as is
+        assert_eq!(ctx.expand_includes("addone\n")?, "addone\n");
+        Ok(())
+    }
 }
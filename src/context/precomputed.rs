@@ -0,0 +1,121 @@
+use super::*;
+use std::sync::{PoisonError, RwLock};
+
+// ----- T H E   P R E C O M P U T A T I O N   C A C H E -------------------------------
+
+/// A cache of the computationally heavy prerequisites of operators, e.g. the
+/// Fourier coefficients for the auxiliary latitudes, keyed by the name of
+/// the computation, and its two inputs (typically the semimajor axis and the
+/// flattening of the ellipsoid).
+///
+/// Context providers holding a `Precomputed` make it available through
+/// [`Context::precomputed`], so instantiating e.g. all 60 UTM zones, or
+/// thousands of per-feature operators, computes the coefficients once per
+/// ellipsoid, rather than once per operator.
+#[derive(Debug, Default)]
+pub struct Precomputed {
+    // The inputs are keyed by their bit patterns, so only identical inputs
+    // share an entry. The key is built without allocating, so a lookup is
+    // cheap compared to the computations cached
+    fourier: RwLock<BTreeMap<(&'static str, [u64; 2]), FourierCoefficients>>,
+}
+
+// Neither of the operations on the map can leave it in an inconsistent state,
+// so we just ignore lock poisoning
+impl Precomputed {
+    pub fn new() -> Precomputed {
+        Precomputed::default()
+    }
+
+    /// The Fourier coefficients `name`, for `inputs`: Computed by `compute`,
+    /// unless already cached
+    pub fn fourier_coefficients(
+        &self,
+        name: &'static str,
+        inputs: &[f64; 2],
+        compute: &dyn Fn() -> FourierCoefficients,
+    ) -> FourierCoefficients {
+        let key = (name, inputs.map(f64::to_bits));
+        let cache = self.fourier.read().unwrap_or_else(PoisonError::into_inner);
+        if let Some(coefficients) = cache.get(&key) {
+            return *coefficients;
+        }
+        drop(cache);
+
+        let coefficients = compute();
+        self.fourier
+            .write()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(key, coefficients);
+        coefficients
+    }
+
+    /// The number of cached items
+    pub fn len(&self) -> usize {
+        self.fourier
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn clear(&self) {
+        self.fourier
+            .write()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clear();
+    }
+}
+
+// ----- T E S T S ---------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn precomputed() -> Result<(), Error> {
+        let cache = Precomputed::new();
+        let ellps = Ellipsoid::named("GRS80")?;
+        let inputs = [ellps.semimajor_axis(), ellps.flattening()];
+        let conformal = &|| ellps.coefficients_for_conformal_latitude_computations();
+
+        let first = cache.fourier_coefficients("conformal", &inputs, conformal);
+        assert_eq!(cache.len(), 1);
+        let again = cache.fourier_coefficients("conformal", &inputs, &|| unreachable!());
+        assert_eq!(first.fwd, again.fwd);
+        assert_eq!(first.inv, again.inv);
+
+        // Other names, or other inputs, are other entries
+        cache.fourier_coefficients("authalic", &inputs, &|| {
+            ellps.coefficients_for_authalic_latitude_computations()
+        });
+        cache.fourier_coefficients("conformal", &[6378388., 1. / 297.], conformal);
+        assert_eq!(cache.len(), 3);
+        cache.clear();
+        assert!(cache.is_empty());
+
+        // Operators instantiated through the same context share the entries
+        let mut ctx = Minimal::new();
+        for zone in 1..=60 {
+            ctx.op(&format!("utm zone={zone}"))?;
+        }
+        ctx.op("tmerc ellps=intl")?;
+        let cache = ctx.precomputed().unwrap();
+        assert_eq!(cache.len(), 4);
+
+        // ...and give the same results as when computed afresh
+        let mut fresh = Minimal::new();
+        let op = fresh.op("utm zone=32")?;
+        let cached = ctx.op("utm zone=32")?;
+        let mut data = [Coord::geo(55., 12., 0., 0.)];
+        let mut expected = data;
+        ctx.apply(cached, Fwd, &mut data)?;
+        fresh.apply(op, Fwd, &mut expected)?;
+        assert_eq!(data, expected);
+        Ok(())
+    }
+}
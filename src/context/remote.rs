@@ -0,0 +1,299 @@
+use super::*;
+use std::future::Future;
+
+// ----- T H E   A S Y N C   R E S O U R C E   P R O V I D E R   T R A I T -----------
+
+/// Asynchronous access to remote resources, e.g. macro definitions and grids
+/// kept in object storage, or behind an HTTP API.
+///
+/// Implementations can use `async fn` for both methods. The returned futures
+/// must be `Send`, so they can be awaited from multithreaded async runtimes.
+pub trait AsyncProvider {
+    /// Fetch the definition of the macro `name`
+    fn fetch_resource(&self, name: &str) -> impl Future<Output = Result<String, Error>> + Send;
+    /// Fetch the `blob`-like resource (typically a grid file) `name`
+    fn fetch_blob(&self, name: &str) -> impl Future<Output = Result<Vec<u8>, Error>> + Send;
+}
+
+// ----- T H E   R E M O T E   P R O V I D E R -----------------------------------------
+
+/// A context provider, backed by an [`AsyncProvider`] for resources not available
+/// from the wrapped provider, `C`.
+///
+/// Since operator instantiation is synchronous, the resources needed are fetched
+/// up front by [`Remote::op_async`], which then instantiates the operator from
+/// what was fetched. Fetched resources are kept for later instantiations. All
+/// other functionality is provided by the wrapped provider.
+///
+/// A `Remote` is set up by [`Remote::new`], or by [`Remote::with_context`] for
+/// wrapping a configured provider. One set up by `Context::new` has no
+/// `AsyncProvider`, so it fails to fetch anything.
+#[derive(Debug)]
+pub struct Remote<P: AsyncProvider, C: Context = Minimal> {
+    provider: Option<P>,
+    context: C,
+    /// Blobs fetched from the provider
+    blobs: BTreeMap<String, Vec<u8>>,
+    /// Instantiations of operators
+    operators: BTreeMap<OpHandle, Op>,
+    /// Handles of the operators, by normalized definition
+    definitions: BTreeMap<String, OpHandle>,
+}
+
+impl<P: AsyncProvider> Remote<P> {
+    /// A `Remote` provider, fetching from `provider` what a `Minimal` cannot provide
+    pub fn new(provider: P) -> Remote<P> {
+        Remote::with_context(provider, Minimal::new())
+    }
+}
+
+impl<P: AsyncProvider, C: Context> Remote<P, C> {
+    /// A `Remote` provider, fetching from `provider` what `context` cannot provide
+    pub fn with_context(provider: P, context: C) -> Remote<P, C> {
+        Remote {
+            provider: Some(provider),
+            context,
+            blobs: BTreeMap::new(),
+            operators: BTreeMap::new(),
+            definitions: BTreeMap::new(),
+        }
+    }
+
+    fn provider(&self) -> Result<&P, Error> {
+        self.provider
+            .as_ref()
+            .ok_or_else(|| Error::General("Remote: No AsyncProvider".to_string()))
+    }
+
+    /// Fetch the resources needed for the operation given by `definition`, then
+    /// instantiate it.
+    pub async fn op_async(&mut self, definition: &str) -> Result<OpHandle, Error> {
+        self.prefetch(definition).await?;
+        self.op(definition)
+    }
+
+    /// Fetch the macros (recursively) and grids referred to by `definition`,
+    /// unless already available
+    pub async fn prefetch(&mut self, definition: &str) -> Result<(), Error> {
+        let mut pending = vec![definition.to_string()];
+        let mut seen = BTreeSet::new();
+        while let Some(definition) = pending.pop() {
+            for step in crate::inner_op::pipeline::split_into_steps(&definition).0 {
+                if let Ok(Some((inner, _))) = crate::op::sub_pipeline(&step) {
+                    pending.push(inner);
+                    continue;
+                }
+                let mut params = crate::op::split_into_parameters(&step);
+                let name = params.remove("name").unwrap_or_default();
+
+                if crate::op::is_resource_name(&name)
+                    && seen.insert(name.clone())
+                    && self.get_resource(&name).is_err()
+                    && epsg_definition(&name).is_none()
+                {
+                    let resource = self.provider()?.fetch_resource(&name).await?;
+                    self.register_resource(&name, &resource);
+                    pending.push(resource);
+                }
+
+                if let Some(grid) = params.get("grids") {
+                    if self.get_blob(grid).is_err() {
+                        let blob = self.provider()?.fetch_blob(grid).await?;
+                        self.blobs.insert(grid.to_string(), blob);
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<P: AsyncProvider, C: Context> Context for Remote<P, C> {
+    fn new() -> Remote<P, C> {
+        Remote {
+            provider: None,
+            context: C::new(),
+            blobs: BTreeMap::new(),
+            operators: BTreeMap::new(),
+            definitions: BTreeMap::new(),
+        }
+    }
+
+    fn op(&mut self, definition: &str) -> Result<OpHandle, Error> {
+        // Reuse the operator, if already instantiated
+        let key = normalized_definition(definition);
+        if let Some(id) = self.definitions.get(&key) {
+            return Ok(*id);
+        }
+
+        let op = Op::new(definition, self)?;
+        let id = op.id;
+        self.operators.insert(id, op);
+        self.definitions.insert(key, id);
+        Ok(id)
+    }
+
+    fn provenance(&self, op: OpHandle) -> Result<Vec<Provenance>, Error> {
+        let bad_id = || Error::General("Remote: Unknown operator id".to_string());
+        let op = self.operators.get(&op).ok_or_else(bad_id)?;
+        Ok(op.provenance())
+    }
+
+    fn metadata(&self, op: OpHandle) -> Result<Metadata, Error> {
+        let bad_id = || Error::General("Remote: Unknown operator id".to_string());
+        let op = self.operators.get(&op).ok_or_else(bad_id)?;
+        Ok(op.metadata())
+    }
+
+    fn warnings(&self, op: OpHandle) -> Result<Vec<String>, Error> {
+        let bad_id = || Error::General("Remote: Unknown operator id".to_string());
+        let op = self.operators.get(&op).ok_or_else(bad_id)?;
+        Ok(op.warnings())
+    }
+
+    fn steps(&self, op: OpHandle) -> Result<Vec<StepDescription>, Error> {
+        let bad_id = || Error::General("Remote: Unknown operator id".to_string());
+        let op = self.operators.get(&op).ok_or_else(bad_id)?;
+        Ok(op.steps())
+    }
+
+    fn apply_traced(
+        &self,
+        op: OpHandle,
+        direction: Direction,
+        operands: &mut [Coord],
+        tracer: &mut Tracer,
+    ) -> Result<usize, Error> {
+        let bad_id = || Error::General("Remote: Unknown operator id".to_string());
+        let op = self.operators.get(&op).ok_or_else(bad_id)?;
+        op.apply_traced(self, operands, direction, tracer)
+    }
+
+    fn apply(
+        &self,
+        op: OpHandle,
+        direction: Direction,
+        operands: &mut dyn CoordinateSet,
+    ) -> Result<usize, Error> {
+        let bad_id = || Error::General("Remote: Unknown operator id".to_string());
+        let op = self.operators.get(&op).ok_or_else(bad_id)?;
+        op.apply(self, operands, direction)
+    }
+
+    fn operators(&self) -> Vec<OperatorInfo> {
+        self.context.operators()
+    }
+
+    fn resources(&self) -> Vec<String> {
+        self.context.resources()
+    }
+
+    fn globals(&self) -> BTreeMap<String, String> {
+        self.context.globals()
+    }
+
+    fn register_global(&mut self, key: &str, value: &str) -> Result<(), Error> {
+        self.definitions.clear();
+        self.context.register_global(key, value)
+    }
+
+    fn register_op(&mut self, name: &str, constructor: OpConstructor) {
+        self.definitions.clear();
+        self.context.register_op(name, constructor);
+    }
+
+    fn register_resource(&mut self, name: &str, definition: &str) {
+        self.definitions.clear();
+        self.context.register_resource(name, definition);
+    }
+
+    fn get_op(&self, name: &str) -> Result<OpConstructor, Error> {
+        self.context.get_op(name)
+    }
+
+    fn get_resource(&self, name: &str) -> Result<String, Error> {
+        self.context.get_resource(name)
+    }
+
+    fn get_blob(&self, name: &str) -> Result<Vec<u8>, Error> {
+        if let Some(blob) = self.blobs.get(name) {
+            return Ok(blob.clone());
+        }
+        self.context.get_blob(name)
+    }
+
+    fn get_grid(&self, name: &str) -> Result<Grid, Error> {
+        self.context.get_grid(name)
+    }
+
+    fn precomputed(&self) -> Option<&Precomputed> {
+        self.context.precomputed()
+    }
+}
+
+// ----- T E S T S ------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::task::{Context as TaskContext, Poll, Waker};
+
+    // Resources "on the other side of the network"
+    #[derive(Debug)]
+    struct Server;
+    impl AsyncProvider for Server {
+        async fn fetch_resource(&self, name: &str) -> Result<String, Error> {
+            match name {
+                "remote:shift" => Ok("gridshift grids=remote.datum | remote:add".to_string()),
+                "remote:add" => Ok("addone".to_string()),
+                _ => Err(Error::NotFound(name.to_string(), ": Server".to_string())),
+            }
+        }
+        async fn fetch_blob(&self, name: &str) -> Result<Vec<u8>, Error> {
+            match name {
+                "remote.datum" => Ok(Minimal::new().get_blob("test.datum")?),
+                _ => Err(Error::NotFound(name.to_string(), ": Server".to_string())),
+            }
+        }
+    }
+
+    // A minimal executor: The futures of `Server` are always ready
+    fn block_on<F: Future>(future: F) -> F::Output {
+        let mut future = std::pin::pin!(future);
+        let mut cx = TaskContext::from_waker(Waker::noop());
+        loop {
+            if let Poll::Ready(result) = future.as_mut().poll(&mut cx) {
+                return result;
+            }
+        }
+    }
+
+    #[test]
+    fn remote() -> Result<(), Error> {
+        let mut ctx = Remote::new(Server);
+
+        // Not available before fetching
+        assert!(ctx.op("remote:shift").is_err());
+        let op = block_on(ctx.op_async("geo:in | remote:shift"))?;
+        assert_eq!(ctx.get_resource("remote:add")?, "addone");
+
+        let mut data = [Coord::raw(55., 12., 0., 0.)];
+        ctx.apply(op, Fwd, &mut data)?;
+        assert!((data[0][0] - (1. + 12.003333_f64.to_radians())).abs() < 1e-6);
+        ctx.apply(op, Inv, &mut data)?;
+        assert!(data[0].hypot2(&Coord::raw(55., 12., 0., 0.)) < 1e-8);
+
+        // Now available without fetching
+        ctx.op("remote:shift inv")?;
+        assert!(matches!(
+            block_on(ctx.op_async("remote:nothing")),
+            Err(Error::NotFound(_, _))
+        ));
+
+        // Without a provider, nothing can be fetched
+        let mut ctx = <Remote<Server> as Context>::new();
+        assert!(block_on(ctx.op_async("remote:add")).is_err());
+        assert!(block_on(ctx.op_async("addone")).is_ok());
+        Ok(())
+    }
+}
@@ -0,0 +1,329 @@
+use super::*;
+use std::sync::{Arc, PoisonError, RwLock};
+
+// ----- T H E   S H A R E D   P R O V I D E R -----------------------------------------
+
+/// A thread safe context provider, supporting built in and run-time defined operators.
+///
+/// `Shared` is `Send + Sync`, and all state lives behind `RwLock`s, so a single
+/// instance (typically wrapped in an `Arc`) can be used for instantiating and
+/// applying operators concurrently from any number of threads, e.g. from a
+/// thread pool, or the request handlers of a web service.
+///
+/// The `Context` trait methods taking `&mut self` are mirrored by the inherent
+/// methods [`Shared::instantiate`], [`Shared::add_op`], [`Shared::add_resource`]
+/// and [`Shared::add_global`], which take `&self`.
+#[derive(Debug, Default)]
+pub struct Shared {
+    /// Constructors for user defined operators
+    constructors: RwLock<BTreeMap<String, OpConstructor>>,
+    /// User defined resources (macros)
+    resources: RwLock<BTreeMap<String, String>>,
+    /// Instantiations of operators
+    operators: RwLock<BTreeMap<OpHandle, Arc<Op>>>,
+    /// Handles of the operators, by normalized definition
+    definitions: RwLock<BTreeMap<String, OpHandle>>,
+    /// User defined global default values
+    globals: RwLock<BTreeMap<String, String>>,
+    /// Precomputations shared by the operators, internally synchronized
+    precomputed: Precomputed,
+}
+
+// None of the operations on the maps can leave them in an inconsistent state,
+// so we just ignore lock poisoning
+impl Shared {
+    /// Instantiate the operation given by `definition`
+    pub fn instantiate(&self, definition: &str) -> Result<OpHandle, Error> {
+        // Reuse the operator, if already instantiated
+        let key = normalized_definition(definition);
+        let definitions = self
+            .definitions
+            .read()
+            .unwrap_or_else(PoisonError::into_inner);
+        if let Some(id) = definitions.get(&key) {
+            return Ok(*id);
+        }
+        drop(definitions);
+
+        let op = Op::new(definition, self)?;
+        let mut definitions = self
+            .definitions
+            .write()
+            .unwrap_or_else(PoisonError::into_inner);
+
+        // Another thread may have instantiated the same definition meanwhile
+        if let Some(id) = definitions.get(&key) {
+            return Ok(*id);
+        }
+        let id = op.id;
+        let mut operators = self
+            .operators
+            .write()
+            .unwrap_or_else(PoisonError::into_inner);
+        operators.insert(id, Arc::new(op));
+        definitions.insert(key, id);
+        Ok(id)
+    }
+
+    /// Register a new user-defined operator
+    pub fn add_op(&self, name: &str, constructor: OpConstructor) {
+        let mut constructors = self
+            .constructors
+            .write()
+            .unwrap_or_else(PoisonError::into_inner);
+        constructors.insert(String::from(name), constructor);
+        self.forget_definitions();
+    }
+
+    /// Register a new user-defined resource (macro, ellipsoid parameter set...)
+    pub fn add_resource(&self, name: &str, definition: &str) {
+        let mut resources = self
+            .resources
+            .write()
+            .unwrap_or_else(PoisonError::into_inner);
+        resources.insert(String::from(name), String::from(definition));
+        self.forget_definitions();
+    }
+
+    /// Set the global default value of the parameter `key`
+    pub fn add_global(&self, key: &str, value: &str) {
+        let mut globals = self.globals.write().unwrap_or_else(PoisonError::into_inner);
+        globals.insert(String::from(key), String::from(value));
+        self.forget_definitions();
+    }
+
+    // Previously instantiated definitions may refer to what is now redefined
+    fn forget_definitions(&self) {
+        self.definitions
+            .write()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clear();
+    }
+}
+
+impl Context for Shared {
+    fn new() -> Shared {
+        let ctx = Shared::default();
+        for item in BUILTIN_ADAPTORS {
+            ctx.add_resource(item.0, item.1);
+        }
+        for item in BUILTIN_MACROS {
+            ctx.add_resource(item.0, item.1);
+        }
+        for item in ITRF_MACROS {
+            ctx.add_resource(item.0, item.1);
+        }
+        ctx
+    }
+
+    fn op(&mut self, definition: &str) -> Result<OpHandle, Error> {
+        self.instantiate(definition)
+    }
+
+    fn provenance(&self, op: OpHandle) -> Result<Vec<Provenance>, Error> {
+        let bad_id = || Error::General("Shared: Unknown operator id".to_string());
+        let operators = self
+            .operators
+            .read()
+            .unwrap_or_else(PoisonError::into_inner);
+        let op = operators.get(&op).ok_or_else(bad_id)?;
+        Ok(op.provenance())
+    }
+
+    fn metadata(&self, op: OpHandle) -> Result<Metadata, Error> {
+        let bad_id = || Error::General("Shared: Unknown operator id".to_string());
+        let operators = self
+            .operators
+            .read()
+            .unwrap_or_else(PoisonError::into_inner);
+        let op = operators.get(&op).ok_or_else(bad_id)?;
+        Ok(op.metadata())
+    }
+
+    fn warnings(&self, op: OpHandle) -> Result<Vec<String>, Error> {
+        let bad_id = || Error::General("Shared: Unknown operator id".to_string());
+        let operators = self
+            .operators
+            .read()
+            .unwrap_or_else(PoisonError::into_inner);
+        let op = operators.get(&op).ok_or_else(bad_id)?;
+        Ok(op.warnings())
+    }
+
+    fn steps(&self, op: OpHandle) -> Result<Vec<StepDescription>, Error> {
+        let bad_id = || Error::General("Shared: Unknown operator id".to_string());
+        let operators = self
+            .operators
+            .read()
+            .unwrap_or_else(PoisonError::into_inner);
+        let op = operators.get(&op).ok_or_else(bad_id)?;
+        Ok(op.steps())
+    }
+
+    fn apply_traced(
+        &self,
+        op: OpHandle,
+        direction: Direction,
+        operands: &mut [Coord],
+        tracer: &mut Tracer,
+    ) -> Result<usize, Error> {
+        let bad_id = || Error::General("Shared: Unknown operator id".to_string());
+        // Release the lock before applying, so instantiations are not blocked
+        let op = self
+            .operators
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get(&op)
+            .cloned()
+            .ok_or_else(bad_id)?;
+        op.apply_traced(self, operands, direction, tracer)
+    }
+
+    fn apply(
+        &self,
+        op: OpHandle,
+        direction: Direction,
+        operands: &mut dyn CoordinateSet,
+    ) -> Result<usize, Error> {
+        let bad_id = || Error::General("Shared: Unknown operator id".to_string());
+        // Release the lock before applying, so instantiations are not blocked
+        let op = self
+            .operators
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get(&op)
+            .cloned()
+            .ok_or_else(bad_id)?;
+        op.apply(self, operands, direction)
+    }
+
+    fn operators(&self) -> Vec<OperatorInfo> {
+        let constructors = self
+            .constructors
+            .read()
+            .unwrap_or_else(PoisonError::into_inner);
+        operator_inventory(constructors.keys().map(String::as_str))
+    }
+
+    fn resources(&self) -> Vec<String> {
+        let resources = self
+            .resources
+            .read()
+            .unwrap_or_else(PoisonError::into_inner);
+        resources.keys().cloned().collect()
+    }
+
+    fn globals(&self) -> BTreeMap<String, String> {
+        let mut globals = BTreeMap::from([("ellps".to_string(), "GRS80".to_string())]);
+        let user_defined = self.globals.read().unwrap_or_else(PoisonError::into_inner);
+        globals.extend(user_defined.clone());
+        globals
+    }
+
+    fn register_global(&mut self, key: &str, value: &str) -> Result<(), Error> {
+        self.add_global(key, value);
+        Ok(())
+    }
+
+    fn register_op(&mut self, name: &str, constructor: OpConstructor) {
+        self.add_op(name, constructor);
+    }
+
+    fn get_op(&self, name: &str) -> Result<OpConstructor, Error> {
+        let constructors = self
+            .constructors
+            .read()
+            .unwrap_or_else(PoisonError::into_inner);
+        if let Some(result) = constructors.get(name) {
+            return Ok(OpConstructor(result.0));
+        }
+
+        Err(Error::NotFound(
+            name.to_string(),
+            ": User defined constructor".to_string(),
+        ))
+    }
+
+    fn register_resource(&mut self, name: &str, definition: &str) {
+        self.add_resource(name, definition);
+    }
+
+    fn get_resource(&self, name: &str) -> Result<String, Error> {
+        let resources = self
+            .resources
+            .read()
+            .unwrap_or_else(PoisonError::into_inner);
+        if let Some(result) = resources.get(name) {
+            return Ok(result.to_string());
+        }
+
+        Err(Error::NotFound(
+            name.to_string(),
+            ": User defined resource".to_string(),
+        ))
+    }
+
+    fn get_blob(&self, name: &str) -> Result<Vec<u8>, Error> {
+        if let Some(directory) = self.globals().get("grid_dir") {
+            if let Ok(blob) = std::fs::read(PathBuf::from(directory).join(name)) {
+                return Ok(blob);
+            }
+        }
+        let n = PathBuf::from(name);
+        let ext = n
+            .extension()
+            .unwrap_or_default()
+            .to_str()
+            .unwrap_or_default();
+        let path: PathBuf = [".", "geodesy", ext, name].iter().collect();
+        Ok(std::fs::read(path)?)
+    }
+
+    /// Access grid resources by identifier
+    fn get_grid(&self, _name: &str) -> Result<Grid, Error> {
+        Err(Error::General(
+            "Grid access by identifier not supported by the Shared context provider".to_string(),
+        ))
+    }
+
+    fn precomputed(&self) -> Option<&Precomputed> {
+        Some(&self.precomputed)
+    }
+}
+
+// ----- T E S T S ------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn concurrent() -> Result<(), Error> {
+        fn send_and_sync<T: Send + Sync>() {}
+        send_and_sync::<Shared>();
+
+        let ctx = Shared::new();
+        ctx.add_resource("add:two", "addone | addone");
+        let op = ctx.instantiate("add:two")?;
+
+        std::thread::scope(|s| {
+            for _ in 0..4 {
+                let ctx = &ctx;
+                s.spawn(move || {
+                    // Instantiate while others apply
+                    let own = ctx.instantiate("addone").unwrap();
+
+                    let mut data = some_basic_coordinates();
+                    assert_eq!(ctx.apply(op, Fwd, &mut data).unwrap(), 2);
+                    assert_eq!(ctx.apply_slice(own, Fwd, &mut data[0..1]).unwrap(), 1);
+                    assert_eq!(data[0][0], 58.);
+                    assert_eq!(data[1][0], 61.);
+                });
+            }
+        });
+
+        // Repeated instantiations of "addone" are deduplicated
+        assert_eq!(ctx.operators.read().unwrap().len(), 2);
+        Ok(())
+    }
+}
@@ -1,4 +1,4 @@
-use super::Coord;
+use super::*;
 
 impl Coord {
     /// Transform the first two elements of a `Coord` from degrees to radians
@@ -31,6 +31,22 @@ impl Coord {
         Coord([self[1].to_degrees(), self[0].to_degrees(), self[2], self[3]])
     }
 
+    /// Normalize the first element of a `Coord` (the longitude, in radians) to
+    /// [-π, π), i.e. [-180°, 180°)
+    #[must_use]
+    pub fn normalize_longitude(self) -> Coord {
+        let longitude = crate::math::normalize_angle_symmetric(self[0]);
+        Coord([longitude, self[1], self[2], self[3]])
+    }
+
+    /// Normalize the first element of a `Coord` (the longitude, in radians) to
+    /// [0, 2π), i.e. [0°, 360°)
+    #[must_use]
+    pub fn normalize_longitude_positive(self) -> Coord {
+        let longitude = crate::math::normalize_angle_positive(self[0]);
+        Coord([longitude, self[1], self[2], self[3]])
+    }
+
     /// For an entire data set: Transform the internal lon/lat/h/t-in-radians to lat/lon/h/t-in-degrees
     pub fn geo_all(operands: &mut [Coord]) {
         for coord in operands {
@@ -131,6 +147,12 @@ mod tests {
         let e = d.to_degrees();
         assert_eq!(e[0], c.to_degrees()[0]);
 
+        let c = Coord::gis(190., 55., 100., 0.).normalize_longitude();
+        assert!((c.to_degrees()[0] + 170.).abs() < 1e-12);
+        let c = c.normalize_longitude_positive();
+        assert!((c.to_degrees()[0] - 190.).abs() < 1e-12);
+        assert_eq!(c[1], 55_f64.to_radians());
+
         assert_eq!(Coord::dms_to_dd(55, 30, 36.), 55.51);
         assert_eq!(Coord::dm_to_dd(55, 30.60), 55.51);
 
@@ -0,0 +1,143 @@
+use super::*;
+
+// ----- L I G H T W E I G H T   C O O R D I N A T E   T Y P E S -----------------------
+
+/// Generic 2D coordinate tuple, with no fixed interpretation of the elements.
+/// Half the size of a `Coord`, for the common case of 2D data
+#[derive(Debug, Default, PartialEq, Copy, Clone)]
+pub struct Coor2D(pub [f64; 2]);
+
+/// Generic 3D coordinate tuple, with no fixed interpretation of the elements
+#[derive(Debug, Default, PartialEq, Copy, Clone)]
+pub struct Coor3D(pub [f64; 3]);
+
+impl Index<usize> for Coor2D {
+    type Output = f64;
+    fn index(&self, i: usize) -> &Self::Output {
+        &self.0[i]
+    }
+}
+
+impl IndexMut<usize> for Coor2D {
+    fn index_mut(&mut self, i: usize) -> &mut Self::Output {
+        &mut self.0[i]
+    }
+}
+
+impl Index<usize> for Coor3D {
+    type Output = f64;
+    fn index(&self, i: usize) -> &Self::Output {
+        &self.0[i]
+    }
+}
+
+impl IndexMut<usize> for Coor3D {
+    fn index_mut(&mut self, i: usize) -> &mut Self::Output {
+        &mut self.0[i]
+    }
+}
+
+impl Coor2D {
+    /// A `Coor2D` from latitude/longitude, with the angular input in degrees
+    #[must_use]
+    pub fn geo(latitude: f64, longitude: f64) -> Coor2D {
+        Coor2D([longitude.to_radians(), latitude.to_radians()])
+    }
+
+    /// A `Coor2D` from longitude/latitude, with the angular input in degrees
+    #[must_use]
+    pub fn gis(longitude: f64, latitude: f64) -> Coor2D {
+        Coor2D([longitude.to_radians(), latitude.to_radians()])
+    }
+
+    /// A `Coor2D` from longitude/latitude, with the angular input in radians
+    #[must_use]
+    pub fn raw(first: f64, second: f64) -> Coor2D {
+        Coor2D([first, second])
+    }
+}
+
+impl Coor3D {
+    /// A `Coor3D` from latitude/longitude/height, with the angular input in degrees
+    #[must_use]
+    pub fn geo(latitude: f64, longitude: f64, height: f64) -> Coor3D {
+        Coor3D([longitude.to_radians(), latitude.to_radians(), height])
+    }
+
+    /// A `Coor3D` from longitude/latitude/height, with the angular input in degrees
+    #[must_use]
+    pub fn gis(longitude: f64, latitude: f64, height: f64) -> Coor3D {
+        Coor3D([longitude.to_radians(), latitude.to_radians(), height])
+    }
+
+    /// A `Coor3D` from longitude/latitude/height, with the angular input in radians
+    #[must_use]
+    pub fn raw(first: f64, second: f64, third: f64) -> Coor3D {
+        Coor3D([first, second, third])
+    }
+}
+
+// ----- C O N V E R S I O N S ---------------------------------------------------------
+
+// The missing elements are zeroed on conversion to `Coord`, and dropped on
+// conversion from `Coord`
+
+impl From<Coor2D> for Coord {
+    fn from(c: Coor2D) -> Coord {
+        Coord([c[0], c[1], 0., 0.])
+    }
+}
+
+impl From<Coord> for Coor2D {
+    fn from(c: Coord) -> Coor2D {
+        Coor2D([c[0], c[1]])
+    }
+}
+
+impl From<Coor3D> for Coord {
+    fn from(c: Coor3D) -> Coord {
+        Coord([c[0], c[1], c[2], 0.])
+    }
+}
+
+impl From<Coord> for Coor3D {
+    fn from(c: Coord) -> Coor3D {
+        Coor3D([c[0], c[1], c[2]])
+    }
+}
+
+// ----- T E S T S ------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lite() -> Result<(), Error> {
+        assert_eq!(Coor2D::geo(55., 12.), Coor2D::gis(12., 55.));
+        assert_eq!(
+            Coord::from(Coor2D::gis(12., 55.)),
+            Coord::gis(12., 55., 0., 0.)
+        );
+        assert_eq!(
+            Coor3D::from(Coord::raw(1., 2., 3., 4.)),
+            Coor3D::raw(1., 2., 3.)
+        );
+
+        let mut ctx = Minimal::new();
+        let op = ctx.op("utm zone=32")?;
+        let mut expected = [Coord::geo(55., 12., 100., 0.)];
+        ctx.apply(op, Fwd, &mut expected)?;
+
+        let mut data = vec![Coor2D::geo(55., 12.); 2];
+        assert_eq!(ctx.apply(op, Fwd, &mut data)?, 2);
+        assert_eq!(data[1], Coor2D::from(expected[0]));
+
+        let mut data = [Coor3D::geo(55., 12., 100.)];
+        ctx.apply(op, Fwd, &mut data)?;
+        assert_eq!(data[0], Coor3D::from(expected[0]));
+        ctx.apply_slice(op, Inv, &mut data[..])?;
+        assert!((data[0][1] - 55_f64.to_radians()).abs() < 1e-12);
+        Ok(())
+    }
+}
@@ -1,8 +1,20 @@
 use super::internal::*;
-use std::ops::{Add, Div, Index, IndexMut, Mul, Sub};
+use core::ops::{Add, Div, Index, IndexMut, Mul, Sub};
 
 pub mod conversions;
 pub mod distances;
+mod lite;
+mod set;
+mod sexagesimal;
+mod status;
+
+pub use lite::{Coor2D, Coor3D};
+pub use set::CoordinateSet;
+pub use set::CoordinateSoA;
+#[cfg(any(feature = "ffi", feature = "wasm", feature = "arrow"))]
+pub(crate) use set::Interleaved;
+pub use sexagesimal::DmsFormat;
+pub use status::PointStatus;
 
 /// Generic 4D coordinate tuple, with no fixed interpretation of the elements
 #[derive(Debug, Default, PartialEq, Copy, Clone)]
@@ -84,7 +96,7 @@ impl Div for Coord {
 }
 
 impl Coord {
-    /// Constructors
+    // Constructors
 
     /// A `Coord` from latitude/longitude/height/time, with the angular input in degrees
     #[must_use]
@@ -147,7 +159,7 @@ impl Coord {
         Coord([1., 1., 1., 1.])
     }
 
-    /// Accessors
+    // Accessors
 
     /// First coordinate of the `Coord`
     #[must_use]
@@ -173,7 +185,7 @@ impl Coord {
         self[3]
     }
 
-    /// Arithmetic (also see the operator trait implementations `add, sub, mul, div`)
+    // Arithmetic (also see the operator trait implementations `add, sub, mul, div`)
 
     /// Multiply by a scalar
     #[must_use]
@@ -0,0 +1,401 @@
+use super::*;
+
+// ----- T H E   C O O R D I N A T E S E T   T R A I T ---------------------------------
+
+/// Any indexable collection of coordinates, to which operators can be applied.
+///
+/// Implemented for vectors, arrays and slices of `Coord`, `Coor2D` and `Coor3D`,
+/// and of `[f64; 2]`, `[f64; 3]`, `[f32; 2]` and `[f32; 3]` (with the missing
+/// elements read as 0, and ignored on writing, and `f32` promoted to `f64` for
+/// the computations). For coordinates stored as one slice per element, use
+/// [`CoordinateSoA`].
+/// Implement it for your own data structures, to transform them in place, without
+/// copying them into slices of `Coord`.
+pub trait CoordinateSet {
+    /// The number of coordinates in the set
+    fn len(&self) -> usize;
+    /// The coordinate at `index`
+    fn get_coord(&self, index: usize) -> Coord;
+    /// Overwrite the coordinate at `index` with `value`
+    fn set_coord(&mut self, index: usize, value: &Coord);
+
+    /// True if the set holds no coordinates
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Access to the coordinates as a slice of `Coord`, for sets stored that
+    /// way, enabling operators to work directly on the data. Otherwise `None`, in
+    /// which case the coordinates are handled through `get_coord`/`set_coord`
+    fn as_mut_slice(&mut self) -> Option<&mut [Coord]> {
+        None
+    }
+
+    /// Copy the coordinates from `start` and on into `buffer`, filling it.
+    /// Override for sets offering a faster way than coordinate by coordinate
+    fn get_coords(&self, start: usize, buffer: &mut [Coord]) {
+        for (i, coord) in buffer.iter_mut().enumerate() {
+            *coord = self.get_coord(start + i);
+        }
+    }
+
+    /// Overwrite the coordinates from `start` and on with those of `buffer`
+    fn set_coords(&mut self, start: usize, buffer: &[Coord]) {
+        for (i, coord) in buffer.iter().enumerate() {
+            self.set_coord(start + i, coord);
+        }
+    }
+}
+
+impl CoordinateSet for [Coord] {
+    fn len(&self) -> usize {
+        <[Coord]>::len(self)
+    }
+    fn get_coord(&self, index: usize) -> Coord {
+        self[index]
+    }
+    fn set_coord(&mut self, index: usize, value: &Coord) {
+        self[index] = *value;
+    }
+    fn as_mut_slice(&mut self) -> Option<&mut [Coord]> {
+        Some(self)
+    }
+}
+
+impl<const N: usize> CoordinateSet for [Coord; N] {
+    fn len(&self) -> usize {
+        N
+    }
+    fn get_coord(&self, index: usize) -> Coord {
+        self[index]
+    }
+    fn set_coord(&mut self, index: usize, value: &Coord) {
+        self[index] = *value;
+    }
+    fn as_mut_slice(&mut self) -> Option<&mut [Coord]> {
+        Some(self)
+    }
+}
+
+impl CoordinateSet for Vec<Coord> {
+    fn len(&self) -> usize {
+        Vec::len(self)
+    }
+    fn get_coord(&self, index: usize) -> Coord {
+        self[index]
+    }
+    fn set_coord(&mut self, index: usize, value: &Coord) {
+        self[index] = *value;
+    }
+    fn as_mut_slice(&mut self) -> Option<&mut [Coord]> {
+        Some(self)
+    }
+}
+
+// Coordinates stored as x0, y0, x1, y1, ... (for dim = 2), in a buffer owned
+// by somebody else, e.g. the caller of a foreign function interface
+#[cfg(any(feature = "ffi", feature = "wasm", feature = "arrow"))]
+pub(crate) struct Interleaved<'a> {
+    pub values: &'a mut [f64],
+    pub dim: usize,
+}
+
+#[cfg(any(feature = "ffi", feature = "wasm", feature = "arrow"))]
+impl CoordinateSet for Interleaved<'_> {
+    fn len(&self) -> usize {
+        self.values.len() / self.dim
+    }
+    fn get_coord(&self, index: usize) -> Coord {
+        let mut coord = Coord::default();
+        let start = index * self.dim;
+        coord.0[..self.dim].copy_from_slice(&self.values[start..start + self.dim]);
+        coord
+    }
+    fn set_coord(&mut self, index: usize, value: &Coord) {
+        let start = index * self.dim;
+        self.values[start..start + self.dim].copy_from_slice(&value.0[..self.dim]);
+    }
+}
+
+// Mutable references to coordinate sets are coordinate sets, so slices can be
+// handed to `Context::apply` as `&mut &mut data[a..b]` (cf. `Context::apply_slice`)
+impl<T: CoordinateSet + ?Sized> CoordinateSet for &mut T {
+    fn len(&self) -> usize {
+        (**self).len()
+    }
+    fn get_coord(&self, index: usize) -> Coord {
+        (**self).get_coord(index)
+    }
+    fn set_coord(&mut self, index: usize, value: &Coord) {
+        (**self).set_coord(index, value)
+    }
+    fn as_mut_slice(&mut self) -> Option<&mut [Coord]> {
+        (**self).as_mut_slice()
+    }
+    fn get_coords(&self, start: usize, buffer: &mut [Coord]) {
+        (**self).get_coords(start, buffer)
+    }
+    fn set_coords(&mut self, start: usize, buffer: &[Coord]) {
+        (**self).set_coords(start, buffer)
+    }
+}
+
+// 2D and 3D coordinates, in slices, arrays and vectors. Single precision
+// coordinates are promoted to double precision for the computations, and
+// rounded back on writing
+macro_rules! coordinate_set {
+    ($t:ty, $dim:literal, [$($generics:tt)*] $set:ty) => {
+        impl<$($generics)*> CoordinateSet for $set {
+            fn len(&self) -> usize {
+                AsRef::<[[$t; $dim]]>::as_ref(self).len()
+            }
+            fn get_coord(&self, index: usize) -> Coord {
+                let mut coord = Coord::default();
+                let element = &AsRef::<[[$t; $dim]]>::as_ref(self)[index];
+                for i in 0..$dim {
+                    coord[i] = element[i] as f64;
+                }
+                coord
+            }
+            fn set_coord(&mut self, index: usize, value: &Coord) {
+                let element = &mut AsMut::<[[$t; $dim]]>::as_mut(self)[index];
+                for i in 0..$dim {
+                    element[i] = value[i] as $t;
+                }
+            }
+        }
+    };
+}
+
+coordinate_set!(f64, 2, [][[f64; 2]]);
+coordinate_set!(f64, 2, [const N: usize] [[f64; 2]; N]);
+coordinate_set!(f64, 2, [] Vec<[f64; 2]>);
+coordinate_set!(f64, 3, [][[f64; 3]]);
+coordinate_set!(f64, 3, [const N: usize] [[f64; 3]; N]);
+coordinate_set!(f64, 3, [] Vec<[f64; 3]>);
+coordinate_set!(f32, 2, [][[f32; 2]]);
+coordinate_set!(f32, 2, [const N: usize] [[f32; 2]; N]);
+coordinate_set!(f32, 2, [] Vec<[f32; 2]>);
+coordinate_set!(f32, 3, [][[f32; 3]]);
+coordinate_set!(f32, 3, [const N: usize] [[f32; 3]; N]);
+coordinate_set!(f32, 3, [] Vec<[f32; 3]>);
+
+// The lightweight coordinate types, in slices, arrays and vectors
+macro_rules! lite_coordinate_set {
+    ($t:ty, [$($generics:tt)*] $set:ty) => {
+        impl<$($generics)*> CoordinateSet for $set {
+            fn len(&self) -> usize {
+                AsRef::<[$t]>::as_ref(self).len()
+            }
+            fn get_coord(&self, index: usize) -> Coord {
+                AsRef::<[$t]>::as_ref(self)[index].into()
+            }
+            fn set_coord(&mut self, index: usize, value: &Coord) {
+                AsMut::<[$t]>::as_mut(self)[index] = (*value).into();
+            }
+        }
+    };
+}
+
+lite_coordinate_set!(Coor2D, [][Coor2D]);
+lite_coordinate_set!(Coor2D, [const N: usize] [Coor2D; N]);
+lite_coordinate_set!(Coor2D, [] Vec<Coor2D>);
+lite_coordinate_set!(Coor3D, [][Coor3D]);
+lite_coordinate_set!(Coor3D, [const N: usize] [Coor3D; N]);
+lite_coordinate_set!(Coor3D, [] Vec<Coor3D>);
+
+// ----- S T R U C T U R E   O F   A R R A Y S --------------------------------------
+
+/// Coordinates stored as one slice per coordinate element, e.g. the columns of
+/// a table: `CoordinateSoA::new(&mut x, &mut y).z(&mut z)`. The elements not
+/// given are read as 0, and ignored on writing. The coordinates are copied in
+/// and out of the internal buffer of [`Context::apply`](crate::Context::apply)
+/// element by element, i.e. by contiguous runs of each slice
+#[derive(Debug)]
+pub struct CoordinateSoA<'a> {
+    x: &'a mut [f64],
+    y: &'a mut [f64],
+    z: Option<&'a mut [f64]>,
+    t: Option<&'a mut [f64]>,
+}
+
+impl<'a> CoordinateSoA<'a> {
+    /// # Panics
+    /// If the slices differ in length
+    pub fn new(x: &'a mut [f64], y: &'a mut [f64]) -> CoordinateSoA<'a> {
+        assert_eq!(x.len(), y.len(), "CoordinateSoA: Slices differ in length");
+        CoordinateSoA {
+            x,
+            y,
+            z: None,
+            t: None,
+        }
+    }
+
+    /// # Panics
+    /// If `z` differs in length from `x` and `y`
+    pub fn z(mut self, z: &'a mut [f64]) -> CoordinateSoA<'a> {
+        assert_eq!(
+            z.len(),
+            self.x.len(),
+            "CoordinateSoA: Slices differ in length"
+        );
+        self.z = Some(z);
+        self
+    }
+
+    /// # Panics
+    /// If `t` differs in length from `x` and `y`
+    pub fn t(mut self, t: &'a mut [f64]) -> CoordinateSoA<'a> {
+        assert_eq!(
+            t.len(),
+            self.x.len(),
+            "CoordinateSoA: Slices differ in length"
+        );
+        self.t = Some(t);
+        self
+    }
+
+    // The slices, in element order, with None for the missing ones
+    fn elements(&self) -> [Option<&[f64]>; 4] {
+        [
+            Some(self.x),
+            Some(self.y),
+            self.z.as_deref(),
+            self.t.as_deref(),
+        ]
+    }
+}
+
+impl CoordinateSet for CoordinateSoA<'_> {
+    fn len(&self) -> usize {
+        self.x.len()
+    }
+    fn get_coord(&self, index: usize) -> Coord {
+        let mut coord = Coord::default();
+        for (element, values) in self.elements().iter().enumerate() {
+            if let Some(values) = values {
+                coord[element] = values[index];
+            }
+        }
+        coord
+    }
+    fn set_coord(&mut self, index: usize, value: &Coord) {
+        self.x[index] = value[0];
+        self.y[index] = value[1];
+        if let Some(z) = &mut self.z {
+            z[index] = value[2];
+        }
+        if let Some(t) = &mut self.t {
+            t[index] = value[3];
+        }
+    }
+    fn get_coords(&self, start: usize, buffer: &mut [Coord]) {
+        for (element, values) in self.elements().iter().enumerate() {
+            let values = values.map(|values| &values[start..start + buffer.len()]);
+            for (i, coord) in buffer.iter_mut().enumerate() {
+                coord[element] = values.map_or(0., |values| values[i]);
+            }
+        }
+    }
+    fn set_coords(&mut self, start: usize, buffer: &[Coord]) {
+        let range = start..start + buffer.len();
+        let elements = [
+            Some(&mut self.x[range.clone()]),
+            Some(&mut self.y[range.clone()]),
+            self.z.as_deref_mut().map(|z| &mut z[range.clone()]),
+            self.t.as_deref_mut().map(|t| &mut t[range]),
+        ];
+        for (element, values) in elements.into_iter().enumerate() {
+            let Some(values) = values else { continue };
+            for (value, coord) in values.iter_mut().zip(buffer) {
+                *value = coord[element];
+            }
+        }
+    }
+}
+
+// ----- T E S T S ------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A user defined, columnar, coordinate set
+    struct Columns {
+        x: Vec<f64>,
+        y: Vec<f64>,
+    }
+
+    impl CoordinateSet for Columns {
+        fn len(&self) -> usize {
+            self.x.len()
+        }
+        fn get_coord(&self, index: usize) -> Coord {
+            Coord::raw(self.x[index], self.y[index], 0., 0.)
+        }
+        fn set_coord(&mut self, index: usize, value: &Coord) {
+            self.x[index] = value[0];
+            self.y[index] = value[1];
+        }
+    }
+
+    #[test]
+    fn coordinate_set() -> Result<(), Error> {
+        let mut ctx = Minimal::new();
+        let op = ctx.op("geo:in | utm zone=32")?;
+        let mut expected = [Coord::raw(55., 12., 0., 0.)];
+        ctx.apply(op, Fwd, &mut expected)?;
+
+        // Spanning more than one chunk of the internal buffer
+        let mut columns = Columns {
+            x: vec![55.; 100],
+            y: vec![12.; 100],
+        };
+        assert_eq!(ctx.apply(op, Fwd, &mut columns)?, 100);
+        assert_eq!(
+            (columns.x[99], columns.y[99]),
+            (expected[0][0], expected[0][1])
+        );
+        ctx.apply(op, Inv, &mut columns)?;
+        assert!((columns.x[0] - 55.).abs() < 1e-10);
+
+        // 2D and 3D arrays
+        let mut data = [[55., 12.], [55., 12.]];
+        ctx.apply(op, Fwd, &mut data)?;
+        assert_eq!(data[1], [expected[0][0], expected[0][1]]);
+        let mut data = vec![[55., 12., 100.]];
+        ctx.apply(op, Fwd, &mut data)?;
+        assert_eq!(data[0], [expected[0][0], expected[0][1], 100.]);
+
+        // Single precision, e.g. from graphics pipelines
+        let mut data = vec![[55_f32, 12.]; 2];
+        ctx.apply(op, Fwd, &mut data)?;
+        assert_eq!(data[1], [expected[0][0] as f32, expected[0][1] as f32]);
+        ctx.apply(op, Inv, &mut data)?;
+        assert!((data[0][0] - 55.).abs() < 1e-5);
+
+        // Structure of arrays, with and without the third element
+        let mut x = vec![55.; 100];
+        let mut y = vec![12.; 100];
+        let mut z = vec![100.; 100];
+        let mut soa = CoordinateSoA::new(&mut x, &mut y).z(&mut z);
+        assert_eq!(ctx.apply(op, Fwd, &mut soa)?, 100);
+        assert_eq!(
+            (x[99], y[99], z[99]),
+            (expected[0][0], expected[0][1], 100.)
+        );
+        let mut soa = CoordinateSoA::new(&mut x[..3], &mut y[..3]);
+        ctx.apply(op, Inv, &mut soa)?;
+        assert_eq!(soa.get_coord(2), Coord::raw(x[2], y[2], 0., 0.));
+        assert!((x[0] - 55.).abs() < 1e-10);
+        assert_eq!(x[3], expected[0][0]);
+
+        // Slices, by reference
+        let mut data = [Coord::raw(55., 12., 0., 0.); 3];
+        ctx.apply_slice(op, Fwd, &mut data[1..])?;
+        assert_eq!(data[0][0], 55.);
+        assert_eq!(data[2], expected[0]);
+        Ok(())
+    }
+}
@@ -0,0 +1,332 @@
+use super::*;
+
+// ----- P A R S I N G   O F   S E X A G E S I M A L   A N G L E S ---------------------
+
+impl Coord {
+    /// Parse a human readable angle, in degrees, minutes and seconds, into
+    /// degrees-with-decimals. Handles the usual degree, minute and second marks
+    /// (`°`, `'`, `"`, and their typographic variants `′`, `″`, `’`, `”`), `d`,
+    /// colons, or blanks as separators, a leading sign, and a leading or trailing
+    /// hemisphere letter (`N`, `S`, `E`, `W`). Each component may have decimals.
+    ///
+    /// ```
+    /// # use geodesy::Coord;
+    /// assert_eq!(Coord::parse_angle("55°30'36\"S").unwrap(), -55.51);
+    /// assert_eq!(Coord::parse_angle("-55:30:36").unwrap(), -55.51);
+    /// assert_eq!(Coord::parse_angle("W 12d 45.6'").unwrap(), -12.76);
+    /// ```
+    pub fn parse_angle(angle: &str) -> Result<f64, Error> {
+        let (value, hemisphere) = parse_angle_and_hemisphere(angle)?;
+        Ok(match hemisphere {
+            Some('S') | Some('W') => -value,
+            _ => value,
+        })
+    }
+
+    /// Parse a human readable latitude/longitude pair, e.g. `55°30'36"N 12°45'36"E`,
+    /// into a `Coord` (with the longitude and latitude in radians, as from
+    /// [`Coord::geo`]). The angles are parsed as by [`Coord::parse_angle`], and
+    /// separated by a comma, a semicolon, or (when written without internal blanks)
+    /// by blanks. With hemisphere letters, the angles may be given in any order,
+    /// otherwise the latitude goes first.
+    ///
+    /// ```
+    /// # use geodesy::Coord;
+    /// let expected = Coord::geo(55.51, 12.76, 0., 0.);
+    /// assert_eq!(Coord::parse_geo("55°30'36\"N 12°45'36\"E").unwrap(), expected);
+    /// assert_eq!(Coord::parse_geo("E 12 45 36, N 55 30 36").unwrap(), expected);
+    /// assert_eq!(Coord::parse_geo("55.51 12.76").unwrap(), expected);
+    /// ```
+    pub fn parse_geo(text: &str) -> Result<Coord, Error> {
+        let (first, second) = split_pair(text.trim())
+            .ok_or_else(|| Error::Syntax(format!("Expected two angles in '{text}'")))?;
+        let first = parse_angle_and_hemisphere(first)?;
+        let second = parse_angle_and_hemisphere(second)?;
+
+        let (latitude, longitude) = match (first.1, second.1) {
+            (Some('E') | Some('W'), None | Some('N') | Some('S')) => (second, first),
+            (None | Some('N') | Some('S'), None | Some('E') | Some('W')) => (first, second),
+            _ => {
+                return Err(Error::Syntax(format!(
+                    "Inconsistent hemispheres in '{text}'"
+                )))
+            }
+        };
+
+        let sign = |(value, hemisphere): (f64, Option<char>)| match hemisphere {
+            Some('S') | Some('W') => -value,
+            _ => value,
+        };
+        let (latitude, longitude) = (sign(latitude), sign(longitude));
+        if latitude.abs() > 90. {
+            return Err(Error::Syntax(format!("Latitude out of range in '{text}'")));
+        }
+        Ok(Coord::geo(latitude, longitude, 0., 0.))
+    }
+}
+
+// The (unsigned, unless given with a sign) value of an angle in degrees, and its
+// hemisphere letter (in upper case), if any
+fn parse_angle_and_hemisphere(angle: &str) -> Result<(f64, Option<char>), Error> {
+    let bad = || Error::Syntax(format!("Bad angle '{angle}'"));
+    let mut components: Vec<String> = Vec::new();
+    let mut current = String::new();
+    let mut hemisphere = None;
+    let mut trailing = false;
+    let mut negative = false;
+
+    for (i, c) in angle.trim().chars().enumerate() {
+        match c {
+            '0'..='9' | '.' => {
+                // No hemisphere letters inside the numbers
+                if trailing {
+                    return Err(bad());
+                }
+                current.push(c)
+            }
+            '+' | '-' if i == 0 => negative = c == '-',
+            'N' | 'S' | 'E' | 'W' | 'n' | 's' | 'e' | 'w' => {
+                if hemisphere.is_some() || negative {
+                    return Err(bad());
+                }
+                hemisphere = Some(c.to_ascii_uppercase());
+                trailing = !(components.is_empty() && current.is_empty());
+            }
+            '°' | 'd' | 'D' | '\'' | '′' | '’' | '"' | '″' | '”' | ':' | ' ' | '\t' => {}
+            _ => return Err(bad()),
+        }
+        if !c.is_ascii_digit() && c != '.' && !current.is_empty() {
+            components.push(core::mem::take(&mut current));
+        }
+    }
+    if !current.is_empty() {
+        components.push(current);
+    }
+    if components.is_empty() || components.len() > 3 {
+        return Err(bad());
+    }
+
+    // Accumulate from the seconds and up, as in `Coord::dms_to_dd`
+    let mut value = 0.;
+    for (i, component) in components.iter().enumerate().rev() {
+        let v = component.parse::<f64>().map_err(|_| bad())?;
+        if i > 0 && v >= 60. {
+            return Err(bad());
+        }
+        value = if i > 0 { (v + value) / 60. } else { v + value };
+    }
+    Ok((if negative { -value } else { value }, hemisphere))
+}
+
+// Split a lat/lon pair into its two angles
+fn split_pair(text: &str) -> Option<(&str, &str)> {
+    // Explicit separators
+    if let Some((first, second)) = text.split_once([',', ';']) {
+        return Some((first, second));
+    }
+
+    // Hemisphere letters: Split after the first one, when trailing, or before
+    // the second one, when leading
+    let is_hemisphere = |c: char| "NSEWnsew".contains(c);
+    let letters: Vec<usize> = text
+        .char_indices()
+        .filter(|(_, c)| is_hemisphere(*c))
+        .map(|(i, _)| i)
+        .collect();
+    if letters.len() == 2 {
+        let at = if letters[0] == 0 {
+            letters[1]
+        } else {
+            letters[0] + 1
+        };
+        return Some(text.split_at(at));
+    }
+
+    // Otherwise, two blank separated angles
+    let mut parts = text.split_whitespace();
+    match (parts.next(), parts.next(), parts.next()) {
+        (Some(first), Some(second), None) => Some((first, second)),
+        _ => None,
+    }
+}
+
+// ----- F O R M A T T I N G   O F   S E X A G E S I M A L   A N G L E S ---------------
+
+/// The marks used by [`Coord::to_dms_string_with`] and [`Coord::to_dm_string_with`]
+/// when formatting coordinates as human readable strings. The default is
+/// `55°30'36.000"N 12°45'36.000"E`
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct DmsFormat {
+    /// Following the degrees
+    pub degree: &'static str,
+    /// Following the minutes
+    pub minute: &'static str,
+    /// Following the seconds
+    pub second: &'static str,
+    /// Between the latitude and the longitude
+    pub separator: &'static str,
+}
+
+impl Default for DmsFormat {
+    fn default() -> DmsFormat {
+        DmsFormat {
+            degree: "°",
+            minute: "'",
+            second: "\"",
+            separator: " ",
+        }
+    }
+}
+
+impl DmsFormat {
+    /// Colon separated components, e.g. `55:30:36.000N, 12:45:36.000E`
+    pub const COLON: DmsFormat = DmsFormat {
+        degree: ":",
+        minute: ":",
+        second: "",
+        separator: ", ",
+    };
+}
+
+impl Coord {
+    /// The latitude and longitude of `self` (in radians, as from [`Coord::geo`]),
+    /// as a human readable string of degrees, minutes and seconds with
+    /// `precision` decimals, and hemisphere suffixes, e.g. `55°30'36.000"N 12°45'36.000"E`.
+    /// The inverse of [`Coord::parse_geo`].
+    #[must_use]
+    pub fn to_dms_string(&self, precision: usize) -> String {
+        self.to_dms_string_with(precision, &DmsFormat::default())
+    }
+
+    /// As [`Coord::to_dms_string`], but with degrees and minutes only,
+    /// e.g. `55°30.600'N 12°45.600'E`
+    #[must_use]
+    pub fn to_dm_string(&self, precision: usize) -> String {
+        self.to_dm_string_with(precision, &DmsFormat::default())
+    }
+
+    /// As [`Coord::to_dms_string`], with the marks and separator given by `format`
+    #[must_use]
+    pub fn to_dms_string_with(&self, precision: usize, format: &DmsFormat) -> String {
+        self.format_geo(true, precision, format)
+    }
+
+    /// As [`Coord::to_dm_string`], with the marks and separator given by `format`
+    #[must_use]
+    pub fn to_dm_string_with(&self, precision: usize, format: &DmsFormat) -> String {
+        self.format_geo(false, precision, format)
+    }
+
+    fn format_geo(&self, seconds: bool, precision: usize, format: &DmsFormat) -> String {
+        let latitude = format_angle(self[1].to_degrees(), "NS", seconds, precision, format);
+        let longitude = format_angle(self[0].to_degrees(), "EW", seconds, precision, format);
+        format!("{latitude}{}{longitude}", format.separator)
+    }
+}
+
+// Format `angle` (in degrees) as degrees, minutes and (optionally) seconds, with
+// `precision` decimals in the last component
+fn format_angle(
+    angle: f64,
+    hemispheres: &str,
+    seconds: bool,
+    precision: usize,
+    format: &DmsFormat,
+) -> String {
+    let mut hemisphere = hemispheres.chars();
+    let hemisphere = if angle < 0. {
+        hemisphere.nth(1)
+    } else {
+        hemisphere.next()
+    }
+    .unwrap_or_default();
+
+    // Round once, in units of the last decimal, to avoid e.g. 60 seconds
+    let units_per_minute = if seconds { 60. } else { 1. } * 10_f64.powi(precision as i32);
+    let units = (angle.abs() * 60. * units_per_minute).round();
+    let degrees = (units / (60. * units_per_minute)).floor();
+    let units = units - degrees * 60. * units_per_minute;
+    let width = if precision == 0 { 2 } else { precision + 3 };
+    let (degree, minute, second) = (format.degree, format.minute, format.second);
+
+    if !seconds {
+        let minutes = units / units_per_minute;
+        return format!("{degrees}{degree}{minutes:0width$.precision$}{minute}{hemisphere}");
+    }
+    let units_per_second = units_per_minute / 60.;
+    let minutes = (units / units_per_minute).floor();
+    let secs = (units - minutes * units_per_minute) / units_per_second;
+    format!("{degrees}{degree}{minutes:02}{minute}{secs:0width$.precision$}{second}{hemisphere}")
+}
+
+// ----- T E S T S ------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_angle() -> Result<(), Error> {
+        assert_eq!(Coord::parse_angle("55")?, 55.);
+        assert_eq!(Coord::parse_angle("55.51")?, 55.51);
+        assert_eq!(Coord::parse_angle("55°30'36\"")?, 55.51);
+        assert_eq!(Coord::parse_angle("55° 30′ 36″ N")?, 55.51);
+        assert_eq!(Coord::parse_angle("s55 30 36")?, -55.51);
+        assert_eq!(Coord::parse_angle("+55d30.6")?, 55.51);
+        assert_eq!(Coord::parse_angle("-55:30:36")?, -55.51);
+        assert!((Coord::parse_angle("12°45'36.36\"W")? + 12.7601).abs() < 1e-12);
+
+        assert!(Coord::parse_angle("").is_err());
+        assert!(Coord::parse_angle("55 70").is_err());
+        assert!(Coord::parse_angle("55 30 36 10").is_err());
+        assert!(Coord::parse_angle("-55 S").is_err());
+        assert!(Coord::parse_angle("N 55 N").is_err());
+        assert!(Coord::parse_angle("55x").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn parse_geo() -> Result<(), Error> {
+        let expected = Coord::geo(55.51, 12.76, 0., 0.);
+        assert_eq!(Coord::parse_geo("55°30'36\"N 12°45'36\"E")?, expected);
+        assert_eq!(Coord::parse_geo("55 30 36 N 12 45 36 E")?, expected);
+        assert_eq!(Coord::parse_geo("N55 30 36 E12 45 36")?, expected);
+        assert_eq!(Coord::parse_geo("12°45'36\"E 55°30'36\"N")?, expected);
+        assert_eq!(Coord::parse_geo("55:30:36, 12:45:36")?, expected);
+        assert_eq!(Coord::parse_geo("55.51;12.76")?, expected);
+        assert_eq!(
+            Coord::parse_geo("55.51S 12.76W")?,
+            Coord::geo(-55.51, -12.76, 0., 0.)
+        );
+
+        assert!(Coord::parse_geo("55.51").is_err());
+        assert!(Coord::parse_geo("55 30 36 12 45 36").is_err());
+        assert!(Coord::parse_geo("55N 12N").is_err());
+        assert!(Coord::parse_geo("95 12").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn format() -> Result<(), Error> {
+        let c = Coord::geo(55.51, 12.76, 0., 0.);
+        assert_eq!(c.to_dms_string(3), "55°30'36.000\"N 12°45'36.000\"E");
+        assert_eq!(c.to_dm_string(2), "55°30.60'N 12°45.60'E");
+        assert_eq!(
+            Coord::parse_geo(&c.to_dms_string(5))?.to_dms_string(5),
+            c.to_dms_string(5)
+        );
+
+        let c = Coord::geo(-0.5, -179.9999999, 0., 0.);
+        assert_eq!(c.to_dms_string(0), "0°30'00\"S 180°00'00\"W");
+        assert_eq!(
+            c.to_dm_string_with(1, &DmsFormat::COLON),
+            "0:30.0:S, 180:00.0:W"
+        );
+        let c = Coord::geo(1., 2., 0., 0.);
+        assert_eq!(
+            c.to_dms_string_with(1, &DmsFormat::COLON),
+            "1:00:00.0N, 2:00:00.0E"
+        );
+        Ok(())
+    }
+}
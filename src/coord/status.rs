@@ -0,0 +1,106 @@
+use super::*;
+
+// ----- P E R - P O I N T   S T A T U S -----------------------------------------------
+
+/// The outcome of the transformation of a single coordinate, cf.
+/// [`Context::apply_with_status`](crate::Context::apply_with_status).
+///
+/// Operators mark failed coordinates by `NaN`s. Those given by
+/// [`PointStatus::nan`] carry the reason for the failure along, as the payload
+/// of the `NaN`, so it can be recovered by [`Coord::status`].
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum PointStatus {
+    /// Successfully transformed
+    Ok,
+    /// Failed, for reasons not reported by the operator
+    Failed,
+    /// Outside of the domain of the operator, e.g. too far from the central
+    /// meridian of a transverse Mercator projection
+    OutOfDomain,
+    /// Outside of the extent of the grid used by the operator
+    OutOfGrid,
+    /// An iterative computation (typically a numerical inverse) did not converge
+    NoConvergence,
+}
+
+// Quiet NaN with a recognizable tag in the upper part of the payload
+const TAG: u64 = 0x7ff8_5247_0000_0000;
+const TAG_MASK: u64 = 0x7fff_ffff_0000_0000;
+
+impl PointStatus {
+    /// A `NaN` carrying `self` as its payload, for marking failed coordinates
+    #[must_use]
+    pub fn nan(self) -> f64 {
+        let code = match self {
+            PointStatus::Ok | PointStatus::Failed => return f64::NAN,
+            PointStatus::OutOfDomain => 1,
+            PointStatus::OutOfGrid => 2,
+            PointStatus::NoConvergence => 3,
+        };
+        f64::from_bits(TAG | code)
+    }
+}
+
+impl core::fmt::Display for PointStatus {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let text = match self {
+            PointStatus::Ok => "ok",
+            PointStatus::Failed => "failed",
+            PointStatus::OutOfDomain => "out of domain",
+            PointStatus::OutOfGrid => "out of grid",
+            PointStatus::NoConvergence => "no convergence",
+        };
+        write!(f, "{text}")
+    }
+}
+
+impl Coord {
+    /// A `Coord` consisting of 4 `NaN`s, carrying `status` along
+    #[must_use]
+    pub fn failed(status: PointStatus) -> Coord {
+        Coord([status.nan(); 4])
+    }
+
+    /// The status of `self`, as marked by the operators applied: `Ok` unless
+    /// any of the elements is `NaN`
+    #[must_use]
+    pub fn status(&self) -> PointStatus {
+        let Some(nan) = self.0.iter().find(|c| c.is_nan()) else {
+            return PointStatus::Ok;
+        };
+        let bits = nan.to_bits();
+        if bits & TAG_MASK != TAG {
+            return PointStatus::Failed;
+        }
+        match bits & 0xffff_ffff {
+            1 => PointStatus::OutOfDomain,
+            2 => PointStatus::OutOfGrid,
+            3 => PointStatus::NoConvergence,
+            _ => PointStatus::Failed,
+        }
+    }
+}
+
+// ----- T E S T S ------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn status() {
+        assert_eq!(Coord::origin().status(), PointStatus::Ok);
+        assert_eq!(Coord::nan().status(), PointStatus::Failed);
+        for status in [
+            PointStatus::OutOfDomain,
+            PointStatus::OutOfGrid,
+            PointStatus::NoConvergence,
+        ] {
+            assert_eq!(Coord::failed(status).status(), status);
+            let mut c = Coord::origin();
+            c[1] = status.nan();
+            assert_eq!(c.status(), status);
+        }
+        assert_eq!(PointStatus::OutOfGrid.to_string(), "out of grid");
+    }
+}
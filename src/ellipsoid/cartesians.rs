@@ -1,6 +1,6 @@
 use super::*;
 
-use std::f64::consts::FRAC_PI_2;
+use core::f64::consts::FRAC_PI_2;
 
 impl Ellipsoid {
     // ----- Cartesian <--> Geographic conversion ----------------------------------
@@ -12,7 +12,7 @@ pub(super) const ELLIPSOID_LIST: [(&str, &str, &str, &str, &str); 47] = [
     ("APL4.9",    "6378137",       "6378137.0",    "298.25",             "Appl. Physics. 1965"),
     ("NWL9D",     "6378145",       "6378145.0",    "298.25",             "Naval Weapons Lab., 1965"),
     ("mod_airy",  "6377340.189",   "6377340.189",  "299.3249373654824",  "Modified Airy"),
-    ("andrae",    "6377104.43 ",   "6377104.43",   "300.0",              "Andrae 1876 (Denmark, Iceland)"),
+    ("andrae",    "6377104.43",    "6377104.43",   "300.0",              "Andrae 1876 (Denmark, Iceland)"),
     ("danish",    "6377019.2563",  "6377019.2563", "300.0",              "Andrae 1876 (Denmark, Iceland)"),
     ("aust_SA",   "6378160",       "6378160",      "298.25",             "Australian Natl & S. Amer. 1969"),
     ("GRS67",     "6378160",       "6378160",      "298.2471674270",     "GRS 67(IUGG 1967)"),
@@ -50,7 +50,31 @@ pub(super) const ELLIPSOID_LIST: [(&str, &str, &str, &str, &str); 47] = [
     ("WGS72",     "6378135",       "6378135",      "298.26",             "WGS 72"),
     ("WGS84",     "6378137",       "6378137",      "298.257223563",      "WGS 84"),
     ("sphere",    "6370997",       "6370997",      "0.",                 "Normal Sphere (r=6370997)"),
-    ("unitsphere",      "1",             "1",      "0.",                 "Unit Sphere (r=1)"),
+    ("unitsphere","1",             "1",            "0.",                 "Unit Sphere (r=1)"),
+];
+
+// EPSG ellipsoid codes, for the entries in ELLIPSOID_LIST having an EPSG
+// equivalent with identical defining parameters
+#[rustfmt::skip]
+pub(super) const EPSG_ELLIPSOIDS: [(u32, &str); 18] = [
+    (1025, "GSK2011"),
+    (7001, "airy"),
+    (7003, "aust_SA"),
+    (7004, "bessel"),
+    (7008, "clrk66"),
+    (7011, "clrk80ign"),
+    (7015, "evrst30"),
+    (7016, "evrstSS"),
+    (7018, "evrst48"),
+    (7019, "GRS80"),
+    (7020, "helmert"),
+    (7022, "intl"),
+    (7024, "krass"),
+    (7025, "NWL9D"),
+    (7030, "WGS84"),
+    (7036, "GRS67"),
+    (7043, "WGS72"),
+    (7044, "evrst56"),
 ];
 
 #[rustfmt::skip]
@@ -47,6 +47,10 @@ impl Ellipsoid {
         theta + 63. / 4. * C * r.powf(8. / 155.) * (8. / 155. * v).sin()
     }
 
+    /// The direct geodesic problem: The destination reached by travelling `distance`
+    /// metres from `from`, in the direction of `azimuth`. Returns a `Coord` of
+    /// (longitude, latitude, return azimuth, number of iterations).
+    ///
     /// For general geodesics, we use the algorithm by Vincenty
     /// ([1975](crate::Bibliography::Vin75)), with updates by the same author
     /// ([1976](crate::Bibliography::Vin76)).
@@ -57,9 +61,32 @@ impl Ellipsoid {
     /// presented an algorithm which is exact to machine precision, and converges everywhere.
     /// The crate [geographiclib-rs](https://crates.io/crates/geographiclib-rs), by
     /// Federico Dolce and Michael Kirk, provides a Rust implementation of Karney's algorithm.
+    ///
+    /// See [`vincenty_fwd`](crate::Ellipsoid::vincenty_fwd) for an explicit
+    /// invocation of Vincenty's algorithm, including a convergence flag
     #[must_use]
-    #[allow(non_snake_case)]
     pub fn geodesic_fwd(&self, from: &Coord, azimuth: f64, distance: f64) -> Coord {
+        self.vincenty_fwd(from, azimuth, distance).0
+    }
+
+    /// The inverse geodesic problem: The distance and azimuths between `from` and
+    /// `to`. Returns a `Coord` of (forward azimuth, return azimuth, distance,
+    /// number of iterations).
+    ///
+    /// See [`geodesic_fwd`](crate::Ellipsoid::geodesic_fwd) for the algorithm used, and
+    /// [`vincenty_inv`](crate::Ellipsoid::vincenty_inv) for an explicit invocation
+    /// of Vincenty's algorithm, including a convergence flag.
+    #[must_use]
+    pub fn geodesic_inv(&self, from: &Coord, to: &Coord) -> Coord {
+        self.vincenty_inv(from, to).0
+    }
+
+    /// The direct geodesic problem, by Vincenty's algorithm, as described for
+    /// [`geodesic_fwd`](crate::Ellipsoid::geodesic_fwd). The second element of the
+    /// return tuple is `false` if the iteration failed to converge.
+    #[must_use]
+    #[allow(non_snake_case)]
+    pub fn vincenty_fwd(&self, from: &Coord, azimuth: f64, distance: f64) -> (Coord, bool) {
         // Coordinates of the point of origin, P1
         let B1 = from[1];
         let L1 = from[0];
@@ -91,6 +118,7 @@ impl Ellipsoid {
         let mut i: i32 = 0;
         let mut t1 = 0.;
         let mut ssmx2cos = 0.;
+        let mut converged = false;
 
         while i < 1000 {
             i += 1;
@@ -112,6 +140,7 @@ impl Ellipsoid {
 
             // Stop criterion: Last update of σ made little difference
             if (prevss - ss).abs() < 1e-13 {
+                converged = true;
                 break;
             }
         }
@@ -132,13 +161,16 @@ impl Ellipsoid {
         // Return azimuth
         let aa2 = aasin.atan2(U1cos * sscos * azicos - U1sin * sssin);
 
-        Coord::raw(L2, B2, aa2, f64::from(i))
+        (Coord::raw(L2, B2, aa2, f64::from(i)), converged)
     }
 
-    /// See [`geodesic_fwd`](crate::Ellipsoid::geodesic_fwd)
+    /// The inverse geodesic problem, by Vincenty's algorithm, as described for
+    /// [`geodesic_inv`](crate::Ellipsoid::geodesic_inv). The second element of the
+    /// return tuple is `false` if the iteration failed to converge, which is
+    /// typically the case for nearly antipodal points.
     #[must_use]
     #[allow(non_snake_case)] // allow math-like notation
-    pub fn geodesic_inv(&self, from: &Coord, to: &Coord) -> Coord {
+    pub fn vincenty_inv(&self, from: &Coord, to: &Coord) -> (Coord, bool) {
         let B1 = from[1];
         let B2 = to[1];
         let B = B2 - B1;
@@ -149,7 +181,7 @@ impl Ellipsoid {
 
         // Below the micrometer level, we don't care about directions
         if L.hypot(B) < 1e-15 {
-            return Coord::geo(0., 0., 0., 0.);
+            return (Coord::geo(0., 0., 0., 0.), true);
         }
 
         let U1 = self.latitude_geographic_to_reduced(B1);
@@ -171,6 +203,7 @@ impl Ellipsoid {
         let mut llcos = 1.;
 
         let mut i: i32 = 0;
+        let mut converged = false;
 
         while i < 1000 {
             i += 1;
@@ -198,6 +231,7 @@ impl Ellipsoid {
             let dl = (ll - ll_next).abs();
             ll = ll_next;
             if dl < 1e-12 {
+                converged = true;
                 break;
             }
         }
@@ -219,7 +253,10 @@ impl Ellipsoid {
         let s = self.semiminor_axis() * A * (ss - dss);
         let a1 = (U2cos * llsin).atan2(U1cos * U2sin - U1sin * U2cos * llcos);
         let a2 = (U1cos * llsin).atan2(-U1sin * U2cos + U1cos * U2sin * llcos);
-        Coord::raw(a1, a2, s, f64::from(i))
+        (
+            Coord::raw(a1, a2, s, f64::from(i)),
+            converged && s.is_finite(),
+        )
     }
 
     /// Geodesic distance between two points. Assumes the first coordinate
@@ -247,6 +284,215 @@ impl Ellipsoid {
     pub fn distance(&self, from: &Coord, to: &Coord) -> f64 {
         self.geodesic_inv(from, to)[2]
     }
+
+    /// Points along the geodesic from `from` to `to`: The two end points, with `n`
+    /// equidistant intermediate points between them, i.e. `n + 2` points in total.
+    /// The third and fourth coordinates are interpolated linearly along the line.
+    ///
+    /// Mostly for densification of long lines before projection
+    #[must_use]
+    pub fn geodesic_line(&self, from: &Coord, to: &Coord, n: usize) -> Vec<Coord> {
+        let d = self.geodesic_inv(from, to);
+
+        // A line of zero length has no direction: All points are at `from`,
+        // with the third and fourth coordinates interpolated as usual
+        if d[2] == 0. {
+            let mut points: Vec<Coord> = (0..=n)
+                .map(|i| {
+                    let t = i as f64 / (n + 1) as f64;
+                    let height = from[2] + t * (to[2] - from[2]);
+                    let time = from[3] + t * (to[3] - from[3]);
+                    Coord::raw(from[0], from[1], height, time)
+                })
+                .collect();
+            points.push(*to);
+            return points;
+        }
+
+        let step = d[2] / (n + 1) as f64;
+        let distances: Vec<f64> = (1..=n).map(|i| i as f64 * step).collect();
+        self.geodesic_points(from, to, d[0], d[2], &distances)
+    }
+
+    /// Points along the geodesic from `from` to `to`, spaced `interval` metres apart,
+    /// starting at `from`. The last step, ending at `to`, may be shorter than `interval`.
+    /// For non-positive `interval`s, only the end points are returned.
+    ///
+    /// See also [`geodesic_line`](crate::Ellipsoid::geodesic_line)
+    #[must_use]
+    pub fn geodesic_line_interval(&self, from: &Coord, to: &Coord, interval: f64) -> Vec<Coord> {
+        let d = self.geodesic_inv(from, to);
+        let mut distances = Vec::new();
+        if interval > 0. {
+            let mut distance = interval;
+            while distance < d[2] {
+                distances.push(distance);
+                distance += interval;
+            }
+        }
+        self.geodesic_points(from, to, d[0], d[2], &distances)
+    }
+
+    // The end points, and the points at the given distances along the line between them
+    fn geodesic_points(
+        &self,
+        from: &Coord,
+        to: &Coord,
+        azimuth: f64,
+        length: f64,
+        distances: &[f64],
+    ) -> Vec<Coord> {
+        let mut points = Vec::with_capacity(distances.len() + 2);
+        points.push(*from);
+        for &distance in distances {
+            let b = self.geodesic_fwd(from, azimuth, distance);
+            let t = distance / length;
+            let height = from[2] + t * (to[2] - from[2]);
+            let time = from[3] + t * (to[3] - from[3]);
+            points.push(Coord::raw(b[0], b[1], height, time));
+        }
+        points.push(*to);
+        points
+    }
+
+    /// The intersection of the geodesic through `a1` and `a2` with the geodesic
+    /// through `b1` and `b2`, following the iterative approach of
+    /// [Baselga and Martínez-Llario (2018)](crate::Bibliography::Bas18): Repeatedly
+    /// move the starting points along their geodesics, to the intersection
+    /// found by a spherical approximation, until the moves become negligible.
+    ///
+    /// Returns the longitude and latitude of the intersection nearest to `a1`,
+    /// followed by its signed distances along the geodesics from `a1` and `b1`,
+    /// respectively. Hence the *segments* intersect if the distances are positive,
+    /// and smaller than the lengths of the segments. `NaN`s in case of parallel
+    /// geodesics, or lack of convergence.
+    #[must_use]
+    pub fn geodesic_intersection(&self, a1: &Coord, a2: &Coord, b1: &Coord, b2: &Coord) -> Coord {
+        let radius = self.semimajor_axis();
+        let (mut a, mut b) = (*a1, *b1);
+        let mut azimuth_a = self.geodesic_inv(a1, a2)[0];
+        let mut azimuth_b = self.geodesic_inv(b1, b2)[0];
+        let (mut distance_a, mut distance_b) = (0., 0.);
+
+        for _ in 0..50 {
+            // Intersection of the corresponding great circles on the unit sphere
+            let (pa, ta) = unit_vector_and_tangent(&a, azimuth_a);
+            let (pb, tb) = unit_vector_and_tangent(&b, azimuth_b);
+            let x = cross(&cross(&pa, &ta), &cross(&pb, &tb));
+            let norm = dot(&x, &x).sqrt();
+            if norm < 1e-15 {
+                break;
+            }
+
+            // Of the two antipodal intersections, select the one nearest to `a`
+            let sign = if dot(&x, &pa) < 0. { -1. } else { 1. };
+            let x = [sign * x[0] / norm, sign * x[1] / norm, sign * x[2] / norm];
+
+            // Signed angular distances to the intersection, converted to moves
+            let angle = |p: &[f64; 3], t: &[f64; 3]| {
+                let c = cross(p, &x);
+                dot(&c, &c).sqrt().copysign(dot(&x, t)).atan2(dot(p, &x))
+            };
+            let move_a = radius * angle(&pa, &ta);
+            let move_b = radius * angle(&pb, &tb);
+
+            let next = self.geodesic_fwd(&a, azimuth_a, move_a);
+            (a, azimuth_a) = (Coord::raw(next[0], next[1], 0., 0.), next[2]);
+            let next = self.geodesic_fwd(&b, azimuth_b, move_b);
+            (b, azimuth_b) = (Coord::raw(next[0], next[1], 0., 0.), next[2]);
+            distance_a += move_a;
+            distance_b += move_b;
+
+            if move_a.hypot(move_b) < 1e-8 {
+                return Coord::raw(a[0], a[1], distance_a, distance_b);
+            }
+        }
+        Coord::nan()
+    }
+
+    /// The point on the geodesic segment from `from` to `to`, nearest to `point`,
+    /// again following [Baselga and Martínez-Llario (2018)](crate::Bibliography::Bas18).
+    ///
+    /// Returns the longitude and latitude of the nearest point, followed by its
+    /// distance to `point`, and its distance along the segment from `from`.
+    /// `NaN`s in case of lack of convergence.
+    ///
+    /// See also [`distance_to_geodesic`](crate::Ellipsoid::distance_to_geodesic)
+    #[must_use]
+    pub fn nearest_on_geodesic(&self, point: &Coord, from: &Coord, to: &Coord) -> Coord {
+        let radius = self.semimajor_axis();
+        let d = self.geodesic_inv(from, to);
+        let (length, mut azimuth) = (d[2], d[0]);
+        let mut foot = *from;
+        let mut along = 0.;
+        let mut converged = false;
+
+        for _ in 0..50 {
+            // The foot of the perpendicular in the spherical right triangle
+            let d = self.geodesic_inv(&foot, point);
+            let (sigma, angle) = (d[2] / radius, d[0] - azimuth);
+            let step = radius * (sigma.sin() * angle.cos()).atan2(sigma.cos());
+
+            let next = self.geodesic_fwd(&foot, azimuth, step);
+            (foot, azimuth) = (Coord::raw(next[0], next[1], 0., 0.), next[2]);
+            along += step;
+
+            if step.abs() < 1e-8 {
+                converged = true;
+                break;
+            }
+        }
+        if !converged {
+            return Coord::nan();
+        }
+
+        // Feet outside of the segment: The nearest end point is the nearest point
+        if along < 0. {
+            (foot, along) = (*from, 0.);
+        } else if along > length {
+            (foot, along) = (*to, length);
+        }
+        Coord::raw(foot[0], foot[1], self.distance(&foot, point), along)
+    }
+
+    /// The minimum distance from `point` to the geodesic segment from `from` to `to`.
+    ///
+    /// See also [`nearest_on_geodesic`](crate::Ellipsoid::nearest_on_geodesic)
+    #[must_use]
+    pub fn distance_to_geodesic(&self, point: &Coord, from: &Coord, to: &Coord) -> f64 {
+        self.nearest_on_geodesic(point, from, to)[2]
+    }
+}
+
+// ----- Ancillary functions -------------------------------------------------------
+
+// Unit vector of a point (lon, lat) on the sphere, and the
+// unit tangent vector pointing in the direction of `azimuth`
+fn unit_vector_and_tangent(point: &Coord, azimuth: f64) -> ([f64; 3], [f64; 3]) {
+    let (slon, clon) = point[0].sin_cos();
+    let (slat, clat) = point[1].sin_cos();
+    let (saz, caz) = azimuth.sin_cos();
+    let east = [-slon, clon, 0.];
+    let north = [-slat * clon, -slat * slon, clat];
+    let p = [clat * clon, clat * slon, slat];
+    let t = [
+        caz * north[0] + saz * east[0],
+        caz * north[1] + saz * east[1],
+        caz * north[2] + saz * east[2],
+    ];
+    (p, t)
+}
+
+fn cross(u: &[f64; 3], v: &[f64; 3]) -> [f64; 3] {
+    [
+        u[1] * v[2] - u[2] * v[1],
+        u[2] * v[0] - u[0] * v[2],
+        u[0] * v[1] - u[1] * v[0],
+    ]
+}
+
+fn dot(u: &[f64; 3], v: &[f64; 3]) -> f64 {
+    u[0] * v[0] + u[1] * v[1] + u[2] * v[2]
 }
 
 // ----- Tests ---------------------------------------------------------------------
@@ -292,4 +538,132 @@ mod tests {
         assert!((b[1] - p2[1].to_degrees()).abs() < 1e-9);
         Ok(())
     }
+
+    #[test]
+    fn vincenty() -> Result<(), Error> {
+        let ellps = Ellipsoid::named("GRS80")?;
+        let p1 = Coord::gis(12., 55., 0., 0.);
+        let p2 = Coord::gis(2., 49., 0., 0.);
+
+        // The general geodesic functions are bit-for-bit identical to Vincenty
+        let (d, converged) = ellps.vincenty_inv(&p1, &p2);
+        assert!(converged);
+        assert_eq!(d, ellps.geodesic_inv(&p1, &p2));
+        let (b, converged) = ellps.vincenty_fwd(&p1, d[0], d[2]);
+        assert!(converged);
+        assert_eq!(b, ellps.geodesic_fwd(&p1, d[0], d[2]));
+
+        // Nearly antipodal points: Vincenty fails to converge
+        let p1 = Coord::gis(0., 0., 0., 0.);
+        let p2 = Coord::gis(179.8, 0.1, 0., 0.);
+        let (_, converged) = ellps.vincenty_inv(&p1, &p2);
+        assert!(!converged);
+        Ok(())
+    }
+
+    #[test]
+    fn geodesic_line() -> Result<(), Error> {
+        let ellps = Ellipsoid::named("GRS80")?;
+
+        // Copenhagen (Denmark)--Paris (France)
+        let p1 = Coord::gis(12., 55., 0., 0.);
+        let p2 = Coord::gis(2., 49., 100., 0.);
+        let total = 956066.231959;
+
+        // End points included, and the intermediate points equidistant
+        let line = ellps.geodesic_line(&p1, &p2, 3);
+        assert_eq!(line.len(), 5);
+        assert_eq!(line[0], p1);
+        assert_eq!(line[4], p2);
+        for i in 0..4 {
+            let d = ellps.distance(&line[i], &line[i + 1]);
+            assert!((d - total / 4.).abs() < 1e-4);
+        }
+        assert!((line[2][2] - 50.).abs() < 1e-9);
+
+        // The points are on the geodesic, hence sharing its azimuth at the origin
+        let azimuth = ellps.geodesic_inv(&p1, &p2)[0];
+        assert!((ellps.geodesic_inv(&p1, &line[2])[0] - azimuth).abs() < 1e-10);
+
+        // No intermediate points
+        assert_eq!(ellps.geodesic_line(&p1, &p2, 0), vec![p1, p2]);
+
+        // A zero length line: No NaNs, but the heights still interpolated
+        let p3 = Coord::gis(12., 55., 100., 0.);
+        let line = ellps.geodesic_line(&p1, &p3, 3);
+        assert_eq!(line.len(), 5);
+        assert!(line.iter().all(|p| p[0] == p1[0] && p[1] == p1[1]));
+        assert_eq!(line[2][2], 50.);
+        assert_eq!(line[4], p3);
+
+        // 100 km intervals: 9 intermediate points, and a shorter final step
+        let line = ellps.geodesic_line_interval(&p1, &p2, 100_000.);
+        assert_eq!(line.len(), 11);
+        assert!((ellps.distance(&p1, &line[1]) - 100_000.).abs() < 1e-4);
+        assert!((ellps.distance(&p1, &line[9]) - 900_000.).abs() < 1e-4);
+        assert!((ellps.distance(&line[9], &line[10]) - (total - 900_000.)).abs() < 1e-4);
+        assert_eq!(ellps.geodesic_line_interval(&p1, &p2, 0.).len(), 2);
+        Ok(())
+    }
+
+    #[test]
+    fn intersection() -> Result<(), Error> {
+        let ellps = Ellipsoid::named("GRS80")?;
+
+        // Construct two geodesics crossing at a known point
+        let x = Coord::gis(5., 52., 0., 0.);
+        let a1 = Coord::gis(1., 50., 0., 0.);
+        let b1 = Coord::gis(10., 50., 0., 0.);
+        let a = ellps.geodesic_inv(&a1, &x);
+        let b = ellps.geodesic_inv(&b1, &x);
+        let a2 = ellps.geodesic_fwd(&a1, a[0], 2. * a[2]);
+        let b2 = ellps.geodesic_fwd(&b1, b[0], 0.5 * b[2]);
+
+        // The segment a1-a2 passes x halfway, while the segment b1-b2 ends halfway to x
+        let i = ellps.geodesic_intersection(&a1, &a2, &b1, &b2);
+        assert!(ellps.distance(&i, &x) < 1e-4);
+        assert!((i[2] - a[2]).abs() < 1e-4);
+        assert!((i[3] - b[2]).abs() < 1e-4);
+
+        // Same intersection, when starting from the other end of a
+        let i = ellps.geodesic_intersection(&a2, &a1, &b1, &b2);
+        assert!(ellps.distance(&i, &x) < 1e-4);
+        assert!((i[2] - a[2]).abs() < 1e-4);
+
+        // Intersection behind the starting point of a: negative distance along a
+        let a3 = ellps.geodesic_fwd(&a1, a[0], 3. * a[2]);
+        let i = ellps.geodesic_intersection(&a2, &a3, &b1, &b2);
+        assert!(ellps.distance(&i, &x) < 1e-4);
+        assert!((i[2] + a[2]).abs() < 1e-4);
+
+        // Parallel geodesics: No intersection
+        assert!(ellps.geodesic_intersection(&a1, &a2, &a1, &a2)[0].is_nan());
+        Ok(())
+    }
+
+    #[test]
+    fn nearest() -> Result<(), Error> {
+        let ellps = Ellipsoid::named("GRS80")?;
+
+        // A point 10 km off the midpoint of a long geodesic segment
+        let from = Coord::gis(12., 55., 0., 0.);
+        let to = Coord::gis(2., 49., 0., 0.);
+        let d = ellps.geodesic_inv(&from, &to);
+        let mid = ellps.geodesic_fwd(&from, d[0], d[2] / 2.);
+        let p = ellps.geodesic_fwd(&mid, mid[2] + core::f64::consts::FRAC_PI_2, 10_000.);
+
+        let n = ellps.nearest_on_geodesic(&p, &from, &to);
+        assert!(ellps.distance(&n, &mid) < 1e-4);
+        assert!((n[2] - 10_000.).abs() < 1e-4);
+        assert!((n[3] - d[2] / 2.).abs() < 1e-4);
+        assert!((ellps.distance_to_geodesic(&p, &from, &to) - 10_000.).abs() < 1e-4);
+
+        // Beyond the end of the segment, the nearest point is the end point
+        let beyond = ellps.geodesic_fwd(&from, d[0], d[2] + 5000.);
+        let n = ellps.nearest_on_geodesic(&beyond, &from, &to);
+        assert_eq!((n[0], n[1]), (to[0], to[1]));
+        assert!((n[2] - 5000.).abs() < 1e-4);
+        assert_eq!(n[3], d[2]);
+        Ok(())
+    }
 }
@@ -0,0 +1,59 @@
+use super::*;
+
+// ----- Normal gravity --------------------------------------------------------
+impl Ellipsoid {
+    /// Equatorial and polar normal gravity, *γₑ* and *γₚ*, in m/s², for the GRS80
+    /// level ellipsoid, according to [Moritz (2000)](crate::Bibliography::Mor00)
+    pub const GRS80_NORMAL_GRAVITY: [f64; 2] = [9.780_326_771_5, 9.832_186_368_5];
+
+    /// Equatorial and polar normal gravity, *γₑ* and *γₚ*, in m/s², for the WGS84
+    /// ellipsoid, according to [NIMA (2000)](crate::Bibliography::Nima00)
+    pub const WGS84_NORMAL_GRAVITY: [f64; 2] = [9.780_325_335_9, 9.832_184_937_8];
+
+    /// Normal gravity on the surface of the ellipsoid, at the geographic `latitude`,
+    /// by Somigliana's closed formula
+    ///
+    /// *γ = (a·γₑ·cos²𝜙 + b·γₚ·sin²𝜙) / sqrt(a²cos²𝜙 + b²sin²𝜙)*
+    ///
+    /// where `gravity` holds the equatorial and polar normal gravity, *[γₑ, γₚ]*,
+    /// of the level ellipsoid, e.g.
+    /// [`GRS80_NORMAL_GRAVITY`](crate::Ellipsoid::GRS80_NORMAL_GRAVITY)
+    #[must_use]
+    pub fn normal_gravity(&self, latitude: f64, gravity: [f64; 2]) -> f64 {
+        let [ge, gp] = gravity;
+        let a = self.semimajor_axis();
+        let b = self.semiminor_axis();
+        let (s, c) = latitude.sin_cos();
+        let (s2, c2) = (s * s, c * c);
+        (a * ge * c2 + b * gp * s2) / (a * a * c2 + b * b * s2).sqrt()
+    }
+}
+
+// ----- Tests ---------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normal_gravity() -> Result<(), Error> {
+        let ellps = Ellipsoid::named("GRS80")?;
+        let gravity = Ellipsoid::GRS80_NORMAL_GRAVITY;
+
+        // At the equator and the poles, we get what we put in
+        assert!((ellps.normal_gravity(0., gravity) - gravity[0]).abs() < 1e-12);
+        assert!((ellps.normal_gravity(90_f64.to_radians(), gravity) - gravity[1]).abs() < 1e-12);
+        assert!((ellps.normal_gravity(-90_f64.to_radians(), gravity) - gravity[1]).abs() < 1e-12);
+
+        // γ at 45°, according to Moritz (2000)
+        let g45 = ellps.normal_gravity(45_f64.to_radians(), gravity);
+        assert!((g45 - 9.806_199_203).abs() < 1e-9);
+
+        // WGS84 normal gravity differs from GRS80 at the 0.1 mGal level
+        let ellps = Ellipsoid::named("WGS84")?;
+        let w45 = ellps.normal_gravity(45_f64.to_radians(), Ellipsoid::WGS84_NORMAL_GRAVITY);
+        assert!((w45 - 9.806_197_769_343_78).abs() < 1e-12);
+        assert!((w45 - g45).abs() < 2e-6);
+        Ok(())
+    }
+}
@@ -16,7 +16,8 @@ impl Ellipsoid {
     /// See also [latitude_geographic_to_geocentric](Ellipsoid::latitude_geographic_to_geocentric)
     #[must_use]
     pub fn latitude_geocentric_to_geographic(&self, geocentric: f64) -> f64 {
-        (geocentric.tan() / (1.0 - self.eccentricity_squared())).atan()
+        let (sin, cos) = geocentric.sin_cos();
+        sin.atan2(cos * (1.0 - self.eccentricity_squared()))
     }
 
     /// Geographic latitude, 𝜙 to reduced latitude, 𝛽.
@@ -66,8 +67,7 @@ impl Ellipsoid {
         geographic_latitude: f64,
         coefficients: &FourierCoefficients,
     ) -> f64 {
-        coefficients.etc[0]
-            * (geographic_latitude + clenshaw_sin(2. * geographic_latitude, &coefficients.fwd))
+        geographic_latitude + clenshaw_sin(2. * geographic_latitude, &coefficients.fwd)
     }
 
     /// Rectifying latitude, 𝜇, to geographic, 𝜙
@@ -76,8 +76,7 @@ impl Ellipsoid {
         rectifying_latitude: f64,
         coefficients: &FourierCoefficients,
     ) -> f64 {
-        let rlat = rectifying_latitude / coefficients.etc[0];
-        rlat + clenshaw_sin(2. * rlat, &coefficients.inv)
+        rectifying_latitude + clenshaw_sin(2. * rectifying_latitude, &coefficients.inv)
     }
 
     // --- Conformal latitude ---
@@ -148,15 +147,15 @@ impl Ellipsoid {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::f64::consts::FRAC_PI_2;
+    use core::f64::consts::FRAC_PI_2;
 
     // Geocentric latitude, 𝜃
     #[test]
     fn geocentric() -> Result<(), Error> {
         let ellps = Ellipsoid::named("GRS80")?;
-        let lats = Vec::from([35., 45., 55.]);
+        let lats = Vec::from([35_f64, 45., 55.]);
         for lat in &lats {
-            let lat = *lat as f64;
+            let lat = *lat;
             let theta = ellps.latitude_geographic_to_geocentric(lat.to_radians());
             let roundtrip = ellps.latitude_geocentric_to_geographic(theta).to_degrees();
             assert!((lat - roundtrip).abs() < 1e-15);
@@ -166,6 +165,26 @@ mod tests {
         Ok(())
     }
 
+    // The atan2 formulation of the inverse keeps the quadrant of its
+    // argument, so it neither folds over at the poles, nor loses the sign
+    #[test]
+    fn geocentric_quadrant() -> Result<(), Error> {
+        let ellps = Ellipsoid::named("GRS80")?;
+        for i in -900..=900 {
+            let lat = (i as f64 / 10.).to_radians();
+            let theta = ellps.latitude_geographic_to_geocentric(lat);
+            let roundtrip = ellps.latitude_geocentric_to_geographic(theta);
+            assert!((lat - roundtrip).abs() < 1e-15);
+        }
+        let north = ellps.latitude_geocentric_to_geographic(FRAC_PI_2);
+        let south = ellps.latitude_geocentric_to_geographic(-FRAC_PI_2);
+        assert!((north - FRAC_PI_2).abs() < 1e-15);
+        assert!((south + FRAC_PI_2).abs() < 1e-15);
+        let beyond = ellps.latitude_geocentric_to_geographic(100_f64.to_radians());
+        assert!(beyond > FRAC_PI_2);
+        Ok(())
+    }
+
     // Reduced latitude, 𝛽
     #[test]
     fn reduced() -> Result<(), Error> {
@@ -195,11 +214,19 @@ mod tests {
     #[test]
     fn rectifying() -> Result<(), Error> {
         let ellps = Ellipsoid::named("GRS80")?;
-        let latitudes = vec![35., 45., 55., -35., -45., -55., 0., 90.];
+        let latitudes = [35_f64, 45., 55., -35., -45., -55., 0., 90.];
         let coefficients = ellps.coefficients_for_rectifying_latitude_computations();
+
+        // The rectifying latitude is the geographic latitude on a sphere with
+        // the same meridian length, hence identical at the equator and the poles
+        let mu = ellps.latitude_geographic_to_rectifying(FRAC_PI_2, &coefficients);
+        assert!((mu - FRAC_PI_2).abs() < 1e-15);
+        let mu = ellps.latitude_geographic_to_rectifying(55_f64.to_radians(), &coefficients);
+        assert!((mu.to_degrees() - 54.864_287_983_539_557).abs() < 1e-12);
+
         // Roundtrip 𝜙 -> 𝜇 -> 𝜙
         for phi in latitudes {
-            let lat = (phi as f64).to_radians();
+            let lat = phi.to_radians();
             let mu = ellps.latitude_geographic_to_rectifying(lat, &coefficients);
             let phi = ellps.latitude_rectifying_to_geographic(mu, &coefficients);
             let ihp = ellps.latitude_rectifying_to_geographic(-mu, &coefficients);
@@ -213,9 +240,9 @@ mod tests {
     #[test]
     fn conformal() -> Result<(), Error> {
         let ellps = Ellipsoid::named("GRS80")?;
-        let latitudes = vec![35., 45., 55., -35., -45., -55., 0., 90.];
+        let latitudes = [35_f64, 45., 55., -35., -45., -55., 0., 90.];
         #[rustfmt::skip]
-        let conformal_latitudes = vec![
+        let conformal_latitudes: [f64; 8] = [
             34.819454814955349775,  44.807684055145067248,  54.819109023689023275, // Northern hemisphere
            -34.819454814955349775, -44.807684055145067248, -54.819109023689023275, // Symmetry wrt. the Equator
             0., 90., // Extreme values are invariant
@@ -225,9 +252,8 @@ mod tests {
         let pairs = latitudes.iter().zip(conformal_latitudes.iter());
 
         for pair in pairs {
-            // The casts are necessary, at least as of Rust 1.66
-            let phi = (*(pair.0) as f64).to_radians();
-            let chi = (*(pair.1) as f64).to_radians();
+            let phi = pair.0.to_radians();
+            let chi = pair.1.to_radians();
             assert!((chi - ellps.latitude_geographic_to_conformal(phi, &chi_coefs)).abs() < 1e-14);
             assert!((phi - ellps.latitude_conformal_to_geographic(chi, &chi_coefs)).abs() < 1e-14);
         }
@@ -245,7 +271,7 @@ mod tests {
     fn authalic() -> Result<(), Error> {
         let ellps = Ellipsoid::named("GRS80")?;
         let authalic = ellps.coefficients_for_authalic_latitude_computations();
-        let geographic_latitudes = [35., 45., 50., 55., -35., -45., -50., -55., -90., 0., 90.];
+        let geographic_latitudes = [35_f64, 45., 50., 55., -35., -45., -50., -55., -90., 0., 90.];
 
         // The IOGP Geomatics Guidance Note Number 7, part 2, p.79, provides this *independent*
         // test value for 𝜙 = 50°N:  𝜉 = 0.870_458_708 rad = 49.87361020881051°N
@@ -256,7 +282,7 @@ mod tests {
         // The additional test values below are computed directly from Karney's expansion,
         // so they provide regression testing, rather than further validation.
         #[rustfmt::skip]
-        let authalic_latitudes = [
+        let authalic_latitudes: [f64; 11] = [
              34.879518549145985,  44.87170287280388,  49.87361022014683,  54.879361594517796,  // Northern hemisphere
             -34.879518549145985, -44.87170287280388, -49.87361022014683, -54.879361594517796,  // Symmetry wrt. the Equator
             -90.,  0.,  90.   // Extreme values are invariant
@@ -265,11 +291,8 @@ mod tests {
         let pairs = geographic_latitudes.iter().zip(authalic_latitudes.iter());
 
         for pair in pairs.clone() {
-            // These casts to f64 are necessary, at least as of Rust 1.66. It appears that the 'Chalk'
-            // trait checker used in Rust-Analyzer has this correctly, so perhaps the need for these
-            // casts may be eliminated in a later Rust version
-            let phi = (*(pair.0) as f64).to_radians();
-            let xi = (*(pair.1) as f64).to_radians();
+            let phi = pair.0.to_radians();
+            let xi = pair.1.to_radians();
             // Forward
             let xi_karney = ellps.latitude_geographic_to_authalic(phi, &authalic);
             assert!((xi - xi_karney).abs() < 1e-14);
@@ -288,8 +311,8 @@ mod tests {
         // authalic to geographic latitudes.
         let proj_coefs = authset(ellps.eccentricity_squared());
         for pair in pairs {
-            let phi = (*(pair.0) as f64).to_radians();
-            let xi = (*(pair.1) as f64).to_radians();
+            let phi = pair.0.to_radians();
+            let xi = pair.1.to_radians();
             let phi_evenden = authlat(xi, &proj_coefs);
             assert!((phi - phi_evenden).abs() < 1e-9);
         }
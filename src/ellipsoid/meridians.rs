@@ -1,5 +1,5 @@
 use super::*;
-use std::f64::consts::FRAC_PI_2;
+use core::f64::consts::FRAC_PI_2;
 
 // ----- Meridian geometry -----------------------------------------------------
 impl Ellipsoid {
@@ -25,8 +25,7 @@ impl Ellipsoid {
     #[must_use]
     pub fn rectifying_radius(&self) -> f64 {
         let n = self.third_flattening();
-        self.a * crate::math::horner(n * n, &constants::MERIDIAN_ARC_COEFFICIENTS)
-            / ((1. + n) * (1. + n))
+        self.a * crate::math::horner(n * n, &constants::MERIDIAN_ARC_COEFFICIENTS) / (1. + n)
     }
 
     /// The Meridian Quadrant, *Qm*, is the distance from the equator to one of the poles.
@@ -36,6 +35,22 @@ impl Ellipsoid {
     pub fn meridian_quadrant(&self) -> f64 {
         self.a * FRAC_PI_2 * self.normalized_meridian_arc_unit()
     }
+
+    /// The meridian arc length from latitude `lat1` to latitude `lat2`, i.e.
+    /// *A · (μ₂ - μ₁)*, where *A* is the [rectifying radius](Ellipsoid::rectifying_radius),
+    /// and *μ* the [rectifying latitude](Ellipsoid::latitude_geographic_to_rectifying).
+    /// Negative if `lat2` is south of `lat1`.
+    ///
+    /// Using the 6th order series from [Karney (2022)](crate::Bibliography::Kar22),
+    /// this is more accurate than the [Bowring](Ellipsoid::meridional_distance)
+    /// algorithm, at the cost of a few more operations.
+    #[must_use]
+    pub fn meridian_arc(&self, lat1: f64, lat2: f64) -> f64 {
+        let coefficients = self.coefficients_for_rectifying_latitude_computations();
+        let mu1 = self.latitude_geographic_to_rectifying(lat1, &coefficients);
+        let mu2 = self.latitude_geographic_to_rectifying(lat2, &coefficients);
+        self.rectifying_radius() * (mu2 - mu1)
+    }
 }
 
 // ----- Tests ---------------------------------------------------------------------
@@ -51,7 +66,7 @@ mod tests {
         let ellps = Ellipsoid::named("GRS80")?;
 
         // Rectifying radius, A
-        assert!((ellps.rectifying_radius() - 6356774.720017125).abs() < 1e-9);
+        assert!((ellps.rectifying_radius() - 6367449.145771043).abs() < 1e-9);
 
         // --------------------------------------------------------------------
         // Meridional distance, M
@@ -101,10 +116,10 @@ mod tests {
             10_001_965.729230457,
         ];
 
-        for i in 0..s.len() {
+        for (i, distance) in s.iter().enumerate() {
             let angle = (10.0 * i as f64).to_radians();
-            assert!((ellps.meridional_distance(angle, Fwd) - s[i]).abs() < 6e-6);
-            assert!((ellps.meridional_distance(s[i], Inv) - angle).abs() < 6e-11);
+            assert!((ellps.meridional_distance(angle, Fwd) - distance).abs() < 6e-6);
+            assert!((ellps.meridional_distance(*distance, Inv) - angle).abs() < 6e-11);
         }
 
         // Since we suspect the deviation might be worst at 45°, we check that as well
@@ -114,4 +129,20 @@ mod tests {
         assert!((ellps.meridional_distance(length, Inv) - angle).abs() < 4e-6);
         Ok(())
     }
+
+    #[test]
+    fn meridian_arc() -> Result<(), Error> {
+        let ellps = Ellipsoid::named("GRS80")?;
+        let lat = |degrees: f64| degrees.to_radians();
+
+        // Reference values by numerical integration of the meridian radius of curvature
+        assert!((ellps.meridian_arc(lat(10.), lat(55.)) - 4991375.479801476).abs() < 1e-6);
+        assert!((ellps.meridian_arc(lat(55.), lat(10.)) + 4991375.479801476).abs() < 1e-6);
+        assert!((ellps.meridian_arc(0., lat(55.)) - 6097230.312999923).abs() < 1e-6);
+
+        // From the equator to the pole, the arc is the meridian quadrant
+        assert!((ellps.meridian_arc(0., FRAC_PI_2) - ellps.meridian_quadrant()).abs() < 1e-8);
+        assert!((ellps.meridian_arc(-FRAC_PI_2, 0.) - ellps.meridian_quadrant()).abs() < 1e-8);
+        Ok(())
+    }
 }
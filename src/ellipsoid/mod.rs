@@ -1,19 +1,53 @@
 mod cartesians;
 mod constants;
 mod geodesics;
+mod gravity;
 mod latitudes;
 mod meridians;
 
 use super::internal::*;
 
 /// Representation of a (potentially triaxial) ellipsoid.
+///
+/// With the `serde` feature enabled, ellipsoids are (de)serialized by their
+/// defining parameters: The semimajor axis, `a`, and the reciprocal flattening,
+/// `rf` (0 for spheres) - plus the semimedian axis, `ay`, for triaxial ellipsoids.
 #[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(from = "Definition", into = "Definition"))]
 pub struct Ellipsoid {
     a: f64,
     ay: f64,
     f: f64,
 }
 
+// The serialized form of an Ellipsoid
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct Definition {
+    a: f64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    ay: Option<f64>,
+    rf: f64,
+}
+
+#[cfg(feature = "serde")]
+impl From<Definition> for Ellipsoid {
+    fn from(d: Definition) -> Ellipsoid {
+        let f = if d.rf != 0.0 { 1.0 / d.rf } else { 0.0 };
+        Ellipsoid::triaxial(d.a, d.ay.unwrap_or(d.a), f)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl From<Ellipsoid> for Definition {
+    fn from(e: Ellipsoid) -> Definition {
+        let rf = if e.f != 0.0 { 1.0 / e.f } else { 0.0 };
+        let ay = if e.ay != e.a { Some(e.ay) } else { None };
+        Definition { a: e.a, ay, rf }
+    }
+}
+
 /// GRS80 is the default ellipsoid.
 impl Default for Ellipsoid {
     fn default() -> Ellipsoid {
@@ -43,16 +77,11 @@ impl Ellipsoid {
     /// Predefined ellipsoid; built-in or defined in asset collections
     pub fn named(name: &str) -> Result<Ellipsoid, Error> {
         // Is it one of the few builtins?
-        if let Some(index) = constants::ELLIPSOID_LIST
+        if let Some(e) = constants::ELLIPSOID_LIST
             .iter()
-            .position(|&ellps| ellps.0 == name)
+            .find(|&ellps| ellps.0 == name)
         {
-            let e = constants::ELLIPSOID_LIST[index];
-            let ax: f64 = e.1.parse().unwrap();
-            let ay: f64 = e.2.parse().unwrap();
-            let rf: f64 = e.3.parse().unwrap();
-            let f = if rf != 0.0 { 1.0 / rf } else { rf };
-            return Ok(Ellipsoid::triaxial(ax, ay, f));
+            return Ok(Ellipsoid::builtin(e));
         }
 
         // The "semiminor, reciproque-flattening" form, e.g. "6378137, 298.3"
@@ -72,6 +101,38 @@ impl Ellipsoid {
         ))
     }
 
+    /// Predefined ellipsoid, given by its EPSG code, e.g. 7019 for GRS80.
+    /// Only the EPSG ellipsoids corresponding to one of the built-ins
+    /// (cf. [`list`](crate::Ellipsoid::list)) are supported
+    pub fn from_epsg(code: u32) -> Result<Ellipsoid, Error> {
+        if let Some(e) = constants::EPSG_ELLIPSOIDS.iter().find(|e| e.0 == code) {
+            return Ellipsoid::named(e.1);
+        }
+        Err(Error::NotFound(
+            code.to_string(),
+            String::from("Ellipsoid::from_epsg()"),
+        ))
+    }
+
+    /// The built-in ellipsoids: Name, ellipsoid, and description, e.g.
+    /// `("intl", Ellipsoid::new(6378388., 1./297.), "International 1909 (Hayford)")`
+    #[must_use]
+    pub fn list() -> Vec<(&'static str, Ellipsoid, &'static str)> {
+        constants::ELLIPSOID_LIST
+            .iter()
+            .map(|e| (e.0, Ellipsoid::builtin(e), e.4))
+            .collect()
+    }
+
+    // Instantiate an entry from the ELLIPSOID_LIST
+    fn builtin(e: &(&str, &str, &str, &str, &str)) -> Ellipsoid {
+        let ax: f64 = e.1.parse().unwrap();
+        let ay: f64 = e.2.parse().unwrap();
+        let rf: f64 = e.3.parse().unwrap();
+        let f = if rf != 0.0 { 1.0 / rf } else { rf };
+        Ellipsoid::triaxial(ax, ay, f)
+    }
+
     // ----- Eccentricities --------------------------------------------------------
 
     /// The linear eccentricity *E* = sqrt(a² - b²). Negative if b > a.
@@ -177,11 +238,48 @@ impl Ellipsoid {
         num / denom
     }
 
+    /// The geocentric radius, i.e. the distance from the centre of the ellipsoid
+    /// to the point on its surface at the geographic `latitude`
+    #[must_use]
+    pub fn geocentric_radius(&self, latitude: f64) -> f64 {
+        let (a, b) = (self.a, self.semiminor_axis());
+        let (s, c) = latitude.sin_cos();
+        let (ac, bs) = (a * c, b * s);
+        ((a * ac).powi(2) + (b * bs).powi(2)).sqrt() / ac.hypot(bs)
+    }
+
     /// The polar radius of curvature, *c*
     #[must_use]
     pub fn polar_radius_of_curvature(&self) -> f64 {
         self.a * self.a / self.semiminor_axis()
     }
+
+    // ----- Spheres ---------------------------------------------------------------
+
+    /// The authalic sphere, i.e. the sphere having the same surface area as the
+    /// ellipsoid. Combined with the [authalic latitude](Ellipsoid::latitude_geographic_to_authalic),
+    /// this supports equal area mappings through the sphere.
+    #[must_use]
+    pub fn authalic_sphere(&self) -> Ellipsoid {
+        if self.f == 0.0 {
+            return Ellipsoid::new(self.a, 0.);
+        }
+        let b = self.semiminor_axis();
+        let e = self.eccentricity();
+        let radius = ((self.a * self.a + b * b * e.atanh() / e) / 2.).sqrt();
+        Ellipsoid::new(radius, 0.)
+    }
+
+    /// The conformal (Gaussian) sphere at `latitude`, i.e. the sphere having the
+    /// radius *sqrt(M·N)*, the geometric mean of the principal radii of curvature.
+    /// Combined with the [conformal latitude](Ellipsoid::latitude_geographic_to_conformal),
+    /// this supports double projections, e.g. for the oblique stereographic.
+    #[must_use]
+    pub fn conformal_sphere(&self, latitude: f64) -> Ellipsoid {
+        let m = self.meridian_radius_of_curvature(latitude);
+        let n = self.prime_vertical_radius_of_curvature(latitude);
+        Ellipsoid::new((m * n).sqrt(), 0.)
+    }
 }
 
 // ----- Tests ---------------------------------------------------------------------
@@ -209,6 +307,95 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn list() -> Result<(), Error> {
+        let list = Ellipsoid::list();
+        assert_eq!(list.len(), 47);
+
+        // All built-ins are well formed, and available by name
+        for (name, ellps, description) in &list {
+            assert!(!description.is_empty());
+            assert!(ellps.semimajor_axis() > 0.);
+            assert!(ellps.flattening() >= 0. && ellps.flattening() < 0.01);
+            assert_eq!(Ellipsoid::named(name)?, *ellps);
+        }
+
+        let (_, andrae, _) = list.iter().find(|e| e.0 == "andrae").unwrap();
+        assert_eq!(andrae.semimajor_axis(), 6377104.43);
+        assert_eq!(andrae.flattening(), 1. / 300.);
+        Ok(())
+    }
+
+    #[test]
+    fn from_epsg() -> Result<(), Error> {
+        assert_eq!(Ellipsoid::from_epsg(7019)?, Ellipsoid::named("GRS80")?);
+        assert_eq!(Ellipsoid::from_epsg(7030)?, Ellipsoid::named("WGS84")?);
+        assert_eq!(Ellipsoid::from_epsg(7022)?.flattening(), 1. / 297.);
+        assert!(matches!(
+            Ellipsoid::from_epsg(4326),
+            Err(Error::NotFound(_, _))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn geocentric_radius() -> Result<(), Error> {
+        let ellps = Ellipsoid::named("GRS80")?;
+        let b = ellps.semiminor_axis();
+        assert!((ellps.geocentric_radius(0.) - 6_378_137.).abs() < 1e-9);
+        assert!((ellps.geocentric_radius(90_f64.to_radians()) - b).abs() < 1e-9);
+        let r = ellps.geocentric_radius(45_f64.to_radians());
+        assert!((r - 6_367_489.543_811_49).abs() < 1e-7);
+
+        // Consistent with the geocentric latitude and the cartesian coordinates
+        let p = Coord::geo(45., 0., 0., 0.);
+        let c = ellps.cartesian(&p);
+        assert!((c[0].hypot(c[2]) - r).abs() < 1e-7);
+        Ok(())
+    }
+
+    #[test]
+    fn spheres() -> Result<(), Error> {
+        let ellps = Ellipsoid::named("GRS80")?;
+
+        // The GRS80 authalic radius (6371007.1810 m according to Moritz (1980):
+        // Geodetic Reference System 1980), here given to higher precision
+        let sphere = ellps.authalic_sphere();
+        assert_eq!(sphere.flattening(), 0.);
+        assert!((sphere.semimajor_axis() - 6_371_007.180_883_5).abs() < 1e-6);
+
+        // The conformal sphere at 45°N, i.e. a / (1 - e² sin² 45°) · sqrt(1 - e²)
+        let sphere = ellps.conformal_sphere(45_f64.to_radians());
+        let es = ellps.eccentricity_squared();
+        let r = ellps.semimajor_axis() * (1. - es).sqrt() / (1. - es / 2.);
+        assert_eq!(sphere.flattening(), 0.);
+        assert!((sphere.semimajor_axis() - r).abs() < 1e-8);
+
+        // A sphere is its own authalic and conformal sphere
+        assert_eq!(sphere.authalic_sphere(), sphere);
+        assert_eq!(sphere.conformal_sphere(1.), sphere);
+        Ok(())
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde() -> Result<(), Error> {
+        let ellps = Ellipsoid::named("intl")?;
+        let json = serde_json::to_string(&ellps).unwrap();
+        assert_eq!(json, r#"{"a":6378388.0,"rf":297.0}"#);
+        let e: Ellipsoid = serde_json::from_str(&json).unwrap();
+        assert_eq!(e, ellps);
+
+        // Spheres and triaxial ellipsoids
+        let e: Ellipsoid = serde_json::from_str(r#"{"a":1737400,"rf":0}"#).unwrap();
+        assert_eq!(e, Ellipsoid::new(1737400., 0.));
+        let ellps = Ellipsoid::triaxial(6378173., 6378103., 1. / 297.8);
+        let json = serde_json::to_string(&ellps).unwrap();
+        assert_eq!(json, r#"{"a":6378173.0,"ay":6378103.0,"rf":297.8}"#);
+        assert_eq!(serde_json::from_str::<Ellipsoid>(&json).unwrap(), ellps);
+        Ok(())
+    }
+
     #[test]
     fn shape_and_size() -> Result<(), Error> {
         let ellps = Ellipsoid::named("GRS80")?;
@@ -245,10 +432,11 @@ mod tests {
         let ellps = Ellipsoid::named("GRS80")?;
         // The curvatures at the North Pole
         assert!(
-            (ellps.meridian_radius_of_curvature(90_f64.to_radians()) - 6_399_593.6259).abs() < 1e-4
+            (ellps.meridian_radius_of_curvature(90_f64.to_radians()) - 6_399_593.625_9).abs()
+                < 1e-4
         );
         assert!(
-            (ellps.prime_vertical_radius_of_curvature(90_f64.to_radians()) - 6_399_593.6259).abs()
+            (ellps.prime_vertical_radius_of_curvature(90_f64.to_radians()) - 6_399_593.625_9).abs()
                 < 1e-4
         );
         assert!(
@@ -265,7 +453,7 @@ mod tests {
         );
 
         // The curvatures at the Equator
-        assert!((ellps.meridian_radius_of_curvature(0.0) - 6_335_439.3271).abs() < 1.0e-4);
+        assert!((ellps.meridian_radius_of_curvature(0.0) - 6_335_439.327_1).abs() < 1.0e-4);
         assert!(
             (ellps.prime_vertical_radius_of_curvature(0.0) - ellps.semimajor_axis()).abs() < 1.0e-4
         );
@@ -0,0 +1,281 @@
+//! A C interface, for embedding *Rust Geodesy* in C, C++, Fortran etc.
+//! applications, and for language bindings. Enabled by the `ffi` feature. The
+//! declarations are in `include/geodesy.h`, and a shared library, e.g.
+//! `libgeodesy_ffi.so`, is built by the `geodesy-ffi` package of the workspace:
+//!
+//! ```sh
+//! cargo build --release -p geodesy-ffi
+//! ```
+//!
+//! All functions take a context, created by [`geodesy_create_context`], and
+//! released by [`geodesy_destroy_context`]. Operators are identified by small,
+//! non-negative integers. Functions failing return a negative value (or a null
+//! pointer), in which case the reason is given by [`geodesy_last_error`]. The
+//! error is cleared by each call using the context, and panics are caught, and
+//! reported as errors, rather than unwinding into the caller.
+
+use crate::coord::Interleaved;
+use crate::internal::*;
+use std::ffi::{c_char, c_int, CStr, CString};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+/// Direction selectors for [`geodesy_apply`]
+pub const GEODESY_FWD: c_int = 1;
+pub const GEODESY_INV: c_int = -1;
+
+/// The context handed over to C: A [`Plain`] context, the operators
+/// instantiated through it, and the message of the latest error
+pub struct GeodesyContext {
+    ctx: Plain,
+    operators: Vec<OpHandle>,
+    error: CString,
+}
+
+impl GeodesyContext {
+    // Record the error, for `geodesy_last_error`, and return `failure`
+    fn fail<T>(&mut self, error: Error, failure: T) -> T {
+        let message = error.to_string().replace('\0', " ");
+        self.error = CString::new(message).unwrap_or_default();
+        failure
+    }
+}
+
+// Run `body` on the context behind `ctx`, after clearing its latest error.
+// Errors, and panics, are recorded for `geodesy_last_error`, and give `failure`
+unsafe fn with_context<T>(
+    ctx: *mut GeodesyContext,
+    failure: T,
+    body: impl FnOnce(&mut GeodesyContext) -> Result<T, Error>,
+) -> T {
+    let Some(context) = ctx.as_mut() else {
+        return failure;
+    };
+    context.error = CString::default();
+    match catch_unwind(AssertUnwindSafe(|| body(context))) {
+        Ok(Ok(value)) => value,
+        Ok(Err(e)) => context.fail(e, failure),
+        Err(panic) => {
+            let message = panic
+                .downcast_ref::<&str>()
+                .map(|m| m.to_string())
+                .or_else(|| panic.downcast_ref::<String>().cloned())
+                .unwrap_or_default();
+            context.fail(Error::General(format!("FFI: Panic: {message}")), failure)
+        }
+    }
+}
+
+// A Rust string from a C string, which must be valid UTF-8
+unsafe fn text<'a>(s: *const c_char) -> Result<&'a str, Error> {
+    if s.is_null() {
+        return Err(Error::General("FFI: Null string".to_string()));
+    }
+    CStr::from_ptr(s)
+        .to_str()
+        .map_err(|_| Error::General("FFI: String not valid UTF-8".to_string()))
+}
+
+// ----- C O N T E X T S ------------------------------------------------------------
+
+/// A new context, with the built in adaptors and macros available, and
+/// resources looked up as described for [`Plain`]. To be released by
+/// [`geodesy_destroy_context`]
+#[no_mangle]
+pub extern "C" fn geodesy_create_context() -> *mut GeodesyContext {
+    let context = catch_unwind(|| GeodesyContext {
+        ctx: Plain::new(),
+        operators: Vec::new(),
+        error: CString::default(),
+    });
+    match context {
+        Ok(context) => Box::into_raw(Box::new(context)),
+        Err(_) => std::ptr::null_mut(),
+    }
+}
+
+/// Release the context, and the operators instantiated through it
+///
+/// # Safety
+/// `ctx` must be null, or a context given by [`geodesy_create_context`], not
+/// already released
+#[no_mangle]
+pub unsafe extern "C" fn geodesy_destroy_context(ctx: *mut GeodesyContext) {
+    if !ctx.is_null() {
+        let context = Box::from_raw(ctx);
+        _ = catch_unwind(AssertUnwindSafe(|| drop(context)));
+    }
+}
+
+/// The message of the latest error in the context, or an empty string. Valid
+/// until the next call using the context
+///
+/// # Safety
+/// `ctx` must be a live context given by [`geodesy_create_context`]
+#[no_mangle]
+pub unsafe extern "C" fn geodesy_last_error(ctx: *const GeodesyContext) -> *const c_char {
+    let error = catch_unwind(AssertUnwindSafe(|| {
+        ctx.as_ref().map(|context| context.error.as_ptr())
+    }));
+    error.ok().flatten().unwrap_or(std::ptr::null())
+}
+
+/// Add `path` to the directories searched for resources. Returns 0 on success,
+/// -1 on failure
+///
+/// # Safety
+/// `ctx` must be a live context given by [`geodesy_create_context`], and `path`
+/// a null terminated string
+#[no_mangle]
+pub unsafe extern "C" fn geodesy_add_path(ctx: *mut GeodesyContext, path: *const c_char) -> c_int {
+    with_context(ctx, -1, |context| {
+        context.ctx.add_path(text(path)?);
+        Ok(0)
+    })
+}
+
+/// Register the macro `name`, given by `definition`. Returns 0 on success, -1
+/// on failure
+///
+/// # Safety
+/// `ctx` must be a live context given by [`geodesy_create_context`], and `name`
+/// and `definition` null terminated strings
+#[no_mangle]
+pub unsafe extern "C" fn geodesy_register_resource(
+    ctx: *mut GeodesyContext,
+    name: *const c_char,
+    definition: *const c_char,
+) -> c_int {
+    with_context(ctx, -1, |context| {
+        context
+            .ctx
+            .register_resource(text(name)?, text(definition)?);
+        Ok(0)
+    })
+}
+
+// ----- O P E R A T O R S ----------------------------------------------------------
+
+/// Instantiate the operator given by `definition`. Returns its identifier, or
+/// -1 on failure
+///
+/// # Safety
+/// `ctx` must be a live context given by [`geodesy_create_context`], and
+/// `definition` a null terminated string
+#[no_mangle]
+pub unsafe extern "C" fn geodesy_op_new(
+    ctx: *mut GeodesyContext,
+    definition: *const c_char,
+) -> i64 {
+    with_context(ctx, -1, |context| {
+        let op = context.ctx.op(text(definition)?)?;
+        context.operators.push(op);
+        Ok(context.operators.len() as i64 - 1)
+    })
+}
+
+/// Apply the operator `op` to the `n` coordinate tuples of `dim` (2, 3, or 4)
+/// elements each, stored consecutively in `coordinates`, in the direction
+/// given by [`GEODESY_FWD`] or [`GEODESY_INV`]. Returns the number of
+/// successfully transformed coordinates, or -1 on failure. Coordinates failing
+/// to transform are set to NaN
+///
+/// # Safety
+/// `ctx` must be a live context given by [`geodesy_create_context`], and
+/// `coordinates` point to `n * dim` values
+#[no_mangle]
+pub unsafe extern "C" fn geodesy_apply(
+    ctx: *mut GeodesyContext,
+    op: i64,
+    direction: c_int,
+    coordinates: *mut f64,
+    n: usize,
+    dim: usize,
+) -> i64 {
+    with_context(ctx, -1, |context| {
+        let Some(&handle) = usize::try_from(op)
+            .ok()
+            .and_then(|i| context.operators.get(i))
+        else {
+            return Err(Error::General(format!("FFI: Unknown operator {op}")));
+        };
+        let direction = match direction {
+            GEODESY_FWD => Direction::Fwd,
+            GEODESY_INV => Direction::Inv,
+            _ => {
+                let value = direction.to_string();
+                return Err(Error::BadParam("direction".to_string(), value));
+            }
+        };
+        if !(2..=4).contains(&dim) {
+            return Err(Error::BadParam("dim".to_string(), dim.to_string()));
+        }
+        if n == 0 {
+            return Ok(0);
+        }
+        if coordinates.is_null() {
+            return Err(Error::General("FFI: Null coordinates".to_string()));
+        }
+
+        let values = std::slice::from_raw_parts_mut(coordinates, n * dim);
+        let mut operands = Interleaved { values, dim };
+        Ok(context.ctx.apply(handle, direction, &mut operands)? as i64)
+    })
+}
+
+/// The version of the library, e.g. "0.9.2"
+#[no_mangle]
+pub extern "C" fn geodesy_version() -> *const c_char {
+    concat!(env!("CARGO_PKG_VERSION"), "\0").as_ptr().cast()
+}
+
+// ----- T E S T S ------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ffi() {
+        let ctx = geodesy_create_context();
+        unsafe {
+            let definition = CString::new("geo:in | utm zone=32").unwrap();
+            let op = geodesy_op_new(ctx, definition.as_ptr());
+            assert_eq!(op, 0);
+
+            let mut coordinates = [55., 12., 59., 10.];
+            let n = geodesy_apply(ctx, op, GEODESY_FWD, coordinates.as_mut_ptr(), 2, 2);
+            assert_eq!(n, 2);
+            assert!((coordinates[0] - 691875.632).abs() < 1e-3);
+            assert!((coordinates[1] - 6098907.825).abs() < 1e-3);
+            geodesy_apply(ctx, op, GEODESY_INV, coordinates.as_mut_ptr(), 2, 2);
+            assert!((coordinates[2] - 59.).abs() < 1e-12);
+
+            // Macros registered through the interface, and errors
+            let name = CString::new("my:utm").unwrap();
+            geodesy_register_resource(ctx, name.as_ptr(), definition.as_ptr());
+            assert_eq!(geodesy_op_new(ctx, name.as_ptr()), 1);
+            let definition = CString::new("foo | bar").unwrap();
+            assert_eq!(geodesy_op_new(ctx, definition.as_ptr()), -1);
+            let message = CStr::from_ptr(geodesy_last_error(ctx)).to_str().unwrap();
+            assert!(message.contains("foo"));
+            let data = coordinates.as_mut_ptr();
+            assert_eq!(geodesy_apply(ctx, 7, GEODESY_FWD, data, 2, 2), -1);
+            assert_eq!(geodesy_apply(ctx, op, 0, data, 2, 2), -1);
+            assert_eq!(geodesy_apply(ctx, op, GEODESY_FWD, data, 2, 5), -1);
+
+            // The error is cleared by the next call
+            assert_eq!(geodesy_apply(ctx, op, GEODESY_FWD, data, 2, 2), 2);
+            assert_eq!(*geodesy_last_error(ctx), 0);
+
+            // Panics are reported as errors
+            let panicking = |_: &mut GeodesyContext| -> Result<i64, Error> { panic!("oops") };
+            assert_eq!(with_context(ctx, -1, panicking), -1);
+            let message = CStr::from_ptr(geodesy_last_error(ctx)).to_str().unwrap();
+            assert_eq!(message, "error: FFI: Panic: oops");
+
+            let version = CStr::from_ptr(geodesy_version()).to_str().unwrap();
+            assert_eq!(version, env!("CARGO_PKG_VERSION"));
+            geodesy_destroy_context(ctx);
+        }
+    }
+}
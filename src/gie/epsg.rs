@@ -0,0 +1,161 @@
+//! The worked examples of the EPSG Guidance Note 7-2 (IOGP Publication 373-7-2,
+//! "Coordinate Conversions and Transformations including Formulas"), for the
+//! methods implemented, as test suites for [`run_gie`](super::run_gie).
+//!
+//! The examples are given to the precision of the guidance note: Centimetres
+//! for projected coordinates, millimetres for geocentric ones, and thousandths
+//! of an arc second for geographical ones. Lengths in US survey feet are
+//! converted to metres.
+
+/// The operators implementing the method of another operator, for a special
+/// case, and hence covered by the examples of that operator
+pub(crate) const EPSG_ALIASES: [(&str, &str); 2] = [("utm", "tmerc"), ("butm", "btmerc")];
+
+/// The examples, by the name of the operator implementing the method, the EPSG
+/// method code and name, and the test suite
+#[rustfmt::skip]
+pub(crate) const EPSG_EXAMPLES: [(&str, &str, &str); 13] = [
+    ("tmerc", "EPSG:9807 Transverse Mercator", "
+        # OSGB36 / British National Grid
+        operation geo:in | tmerc lat_0=49 lon_0=-2 k_0=0.9996012717
+                           x_0=400000 y_0=-100000 ellps=airy
+        tolerance 1 cm
+        accept    50.5 0.5
+        expect    577274.99 69740.50
+        roundtrip
+    "),
+
+    ("btmerc", "EPSG:9807 Transverse Mercator", "
+        # OSGB36 / British National Grid
+        operation geo:in | btmerc lat_0=49 lon_0=-2 k_0=0.9996012717
+                           x_0=400000 y_0=-100000 ellps=airy
+        tolerance 1 cm
+        accept    50.5 0.5
+        expect    577274.99 69740.50
+        roundtrip
+    "),
+
+    ("lcc", "EPSG:9801 Lambert Conic Conformal (1SP)", "
+        # JAD69 / Jamaica National Grid
+        operation geo:in | lcc lat_1=18 lat_0=18 lon_0=-77 k_0=1
+                           x_0=250000 y_0=150000 ellps=clrk66
+        tolerance 1 cm
+        accept    17.932166666666667 -76.94368333333334
+        expect    255966.58 142493.51
+        roundtrip
+    "),
+
+    ("lcc", "EPSG:9802 Lambert Conic Conformal (2SP)", "
+        # NAD27 / Texas South Central: E 2963503.91, N 254759.80 US survey feet
+        operation geo:in | lcc lat_1=28.383333333333333 lat_2=30.283333333333335
+                           lat_0=27.833333333333332 lon_0=-99
+                           x_0=609601.2192024384 ellps=clrk66
+        tolerance 1 cm
+        accept    28.5 -96
+        expect    903277.7983235966 77650.94234188467
+        roundtrip
+    "),
+
+    ("merc", "EPSG:9804 Mercator (variant A)", "
+        # Makassar / NEIEZ
+        operation geo:in | merc lon_0=110 k_0=0.997 x_0=3900000 y_0=900000 ellps=bessel
+        tolerance 1 cm
+        accept    -3 120
+        expect    5009726.58 569150.82
+        roundtrip
+    "),
+
+    ("merc", "EPSG:9805 Mercator (variant B)", "
+        # Pulkovo 1942 / Mercator Caspian Sea
+        operation geo:in | merc lat_ts=42 lon_0=51 ellps=krass
+        tolerance 1 cm
+        accept    53 53
+        expect    165704.29 5171848.07
+        roundtrip
+    "),
+
+    ("laea", "EPSG:9820 Lambert Azimuthal Equal Area", "
+        # ETRS89 / LAEA Europe
+        operation geo:in | laea lat_0=52 lon_0=10 x_0=4321000 y_0=3210000 ellps=GRS80
+        tolerance 1 cm
+        accept    50 5
+        expect    3962799.45 2999718.85
+        roundtrip
+    "),
+
+    ("cart", "EPSG:9602 Geographic/geocentric conversions", "
+        # WGS 84
+        operation geo:in | cart ellps=WGS84
+        tolerance 1 mm
+        accept    53.80939444444444 2.12955 73
+        expect    3771793.968 140253.342 5124304.349
+        roundtrip
+    "),
+
+    ("helmert", "EPSG:9603 Geocentric translations", "
+        # WGS 72 to WGS 84
+        operation helmert z=4.5
+        tolerance 1 cm
+        accept    3657660.66 255768.55 5201382.11
+        expect    3657660.66 255768.55 5201386.61
+        roundtrip
+    "),
+
+    ("helmert", "EPSG:9606 Position Vector transformation", "
+        # WGS 72 to WGS 84
+        operation helmert z=4.5 rz=0.554 s=0.219 convention=position_vector
+        tolerance 1 cm
+        accept    3657660.66 255768.55 5201382.11
+        expect    3657660.78 255778.43 5201387.75
+        roundtrip
+    "),
+
+    ("helmert", "EPSG:9607 Coordinate Frame rotation", "
+        # WGS 72 to WGS 84
+        operation helmert z=4.5 rz=-0.554 s=0.219 convention=coordinate_frame
+        tolerance 1 cm
+        accept    3657660.66 255768.55 5201382.11
+        expect    3657660.78 255778.43 5201387.75
+        roundtrip
+    "),
+
+    // Molodensky is not exactly invertible, so no roundtrips here. The tolerance
+    // corresponds to the 1e-6 degree precision of the examples, and the heights
+    // (28.091 m and 28.02 m) are not checked
+    ("molodensky", "EPSG:9604 Molodensky", "
+        # WGS 84 to ED50
+        operation geo:in | molodensky ellps_0=WGS84 ellps_1=intl
+                           dx=84.87 dy=96.49 dz=116.95 | geo:out
+        tolerance 10 cm
+        accept    53.80939444444444 2.12955 73
+        expect    53.810156388888885 2.1309658333333332
+    "),
+
+    ("molodensky", "EPSG:9605 Abridged Molodensky", "
+        # WGS 84 to ED50
+        operation geo:in | molodensky ellps_0=WGS84 ellps_1=intl
+                           dx=84.87 dy=96.49 dz=116.95 abridged | geo:out
+        tolerance 10 cm
+        accept    53.80939444444444 2.12955 73
+        expect    53.810156944444444 2.1309658333333332
+    "),
+];
+
+// ----- T E S T S ------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::internal::*;
+
+    #[test]
+    fn epsg_examples() -> Result<(), Error> {
+        let mut ctx = Minimal::new();
+        for (operator, method, suite) in EPSG_EXAMPLES {
+            let report = crate::run_gie(&mut ctx, suite)?;
+            assert!(report.tests > 0, "{operator}: {method}");
+            assert_eq!(report.failures, [], "{operator}: {method}");
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,465 @@
+//! Test suites in the style of the PROJ `gie` files: Operations, input
+//! coordinates, and the expected output, for validation of operators and
+//! their combinations.
+
+use crate::internal::*;
+
+#[cfg(feature = "validation")]
+pub(crate) mod epsg;
+
+// ----- T H E   T E S T   S U I T E   R U N N E R -------------------------------------
+
+/// The outcome of running a test suite, cf. [`run_gie`]
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct GieReport {
+    /// The number of tests run, i.e. the number of `expect` and `roundtrip`
+    /// commands
+    pub tests: usize,
+    /// The failing tests
+    pub failures: Vec<GieFailure>,
+}
+
+/// A failing test, cf. [`GieReport`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct GieFailure {
+    /// The line number of the test in the suite, counted from 1
+    pub line: usize,
+    /// What went wrong
+    pub message: String,
+}
+
+// The default tolerance, as in PROJ's `gie`: Half a millimetre. For linear
+// output, this presumes the output is in metres
+const DEFAULT_TOLERANCE: f64 = 0.0005;
+
+// The radius used for converting angular deviations to metres
+const RADIUS: f64 = 6_378_137.;
+
+// The state of the runner, as set by the commands preceding a test
+struct Runner {
+    operation: Option<String>,
+    // The operation, instantiated at its first test
+    op: Option<Result<Op, Error>>,
+    direction: Direction,
+    tolerance: f64,
+    accepted: Option<Coord>,
+}
+
+/// Run the test suite `suite`, written in a simple test language, modelled on
+/// the one of PROJ's `gie` program: A sequence of commands, one per line, with
+/// `#` starting a comment:
+///
+/// - `operation <definition>`: The operation to test. Following lines, which
+///   are indented, or start with `|`, continue the definition
+/// - `direction fwd|inv`: The direction of the subsequent tests (default `fwd`)
+/// - `tolerance <value>`: The largest acceptable deviation, in metres. Unit
+///   suffixes are accepted, e.g. `1mm`. Default 0.5 mm
+/// - `accept <coordinates>`: The input coordinate tuple, 2 to 4 numbers
+/// - `expect <coordinates>`: The output expected for the accepted input,
+///   or `expect failure` if the operation, or the transformation, should fail
+/// - `roundtrip [n] [tolerance]`: Check that `n` (default 100) roundtrips of
+///   the accepted input, in the current direction followed by its opposite,
+///   stay within the tolerance given (default the current tolerance)
+///
+/// The deviation is the Euclidean distance over the coordinates expected. As in
+/// PROJ's `gie`, it is measured in metres also for angular coordinates: The
+/// first two coordinates are taken as angular when given in radians (cf.
+/// [`Op::angular_io`]), or in degrees or gon, by an adaptor as the final step
+/// (e.g. `geo:out`). Their deviations are converted to metres on a sphere of
+/// radius 6378137 m, without the cos(latitude) reduction of the longitude, i.e.
+/// erring on the safe side. Unlike in PROJ, linear output is not converted, so
+/// tolerances are in the units of the output, when these are not metres.
+///
+/// If the suite contains a `<gie>` line, only the text between `<gie>` and
+/// `</gie>` lines is read, as in PROJ's `.gie` files.
+///
+/// Malformed suites give an [`Error::SyntaxAt`], while failing tests, including
+/// operations failing to instantiate, are reported in the [`GieReport`].
+pub fn run_gie(ctx: &mut dyn Context, suite: &str) -> Result<GieReport, Error> {
+    let mut report = GieReport::default();
+    let mut runner = Runner {
+        operation: None,
+        op: None,
+        direction: Fwd,
+        tolerance: DEFAULT_TOLERANCE,
+        accepted: None,
+    };
+    let delimited = suite.lines().any(|line| line.trim() == "<gie>");
+    let mut inside = !delimited;
+
+    for (index, raw) in suite.lines().enumerate() {
+        let number = index + 1;
+        match raw.trim() {
+            "<gie>" if delimited => inside = true,
+            "</gie>" if delimited => inside = false,
+            _ => {}
+        }
+        let line = raw.split('#').next().unwrap_or_default().trim();
+        if !inside || line.is_empty() || line == "<gie>" || line == "</gie>" {
+            continue;
+        }
+
+        let (command, argument) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+        let argument = argument.trim();
+        let syntax = |message: &str| Error::SyntaxAt {
+            message: message.to_string(),
+            line: number,
+            column: 1,
+            token: command.to_string(),
+        };
+
+        match command {
+            "operation" => {
+                runner.operation = Some(argument.to_string());
+                runner.op = None;
+                runner.accepted = None;
+            }
+            "direction" => {
+                runner.direction = match argument {
+                    "fwd" | "forward" => Fwd,
+                    "inv" | "inverse" => Inv,
+                    _ => return Err(syntax("Expected 'fwd' or 'inv'")),
+                }
+            }
+            "tolerance" => {
+                runner.tolerance = tolerance(argument).ok_or_else(|| syntax("Bad tolerance"))?;
+            }
+            "accept" => {
+                let coord = coordinates(argument).ok_or_else(|| syntax("Bad coordinates"))?;
+                runner.accepted = Some(coord);
+            }
+            "expect" => {
+                let expected = match argument {
+                    "failure" => None,
+                    _ => Some(coordinates(argument).ok_or_else(|| syntax("Bad coordinates"))?),
+                };
+                let dimension = argument.split_whitespace().count();
+                let input = runner
+                    .accepted
+                    .filter(|_| runner.operation.is_some())
+                    .ok_or_else(|| syntax("Expected 'operation' and 'accept' before 'expect'"))?;
+                report.tests += 1;
+                let outcome = expect(&*ctx, &mut runner, input, expected, dimension);
+                if let Err(message) = outcome {
+                    report.failures.push(GieFailure {
+                        line: number,
+                        message,
+                    });
+                }
+            }
+            "roundtrip" => {
+                let mut arguments = argument.split_whitespace();
+                let n = match arguments.next() {
+                    Some(n) => n.parse::<usize>().map_err(|_| syntax("Bad count"))?,
+                    None => 100,
+                };
+                let tolerance = match arguments.next() {
+                    Some(t) => tolerance(t).ok_or_else(|| syntax("Bad tolerance"))?,
+                    None => runner.tolerance,
+                };
+                let input = runner
+                    .accepted
+                    .filter(|_| runner.operation.is_some())
+                    .ok_or_else(|| {
+                        syntax("Expected 'operation' and 'accept' before 'roundtrip'")
+                    })?;
+                report.tests += 1;
+                if let Err(message) = roundtrip(&*ctx, &mut runner, input, n, tolerance) {
+                    report.failures.push(GieFailure {
+                        line: number,
+                        message,
+                    });
+                }
+            }
+            // Continuation of a multi-line operation: Indented, or starting
+            // with a pipeline step separator. Anything else is a misspelled
+            // command, rather than a part of the definition
+            _ => match &mut runner.operation {
+                Some(definition)
+                    if runner.accepted.is_none()
+                        && (raw.starts_with(char::is_whitespace) || line.starts_with('|')) =>
+                {
+                    definition.push('\n');
+                    definition.push_str(line);
+                }
+                _ => return Err(syntax("Unknown command")),
+            },
+        }
+    }
+    Ok(report)
+}
+
+impl Runner {
+    // The operation of the test at hand, instantiated at its first use
+    fn op(&mut self, ctx: &dyn Context) -> Result<&Op, String> {
+        let definition = self.operation.clone().unwrap_or_default();
+        self.op
+            .get_or_insert_with(|| Op::new(&definition, ctx))
+            .as_ref()
+            .map_err(|e| format!("Cannot instantiate '{definition}': {e}"))
+    }
+}
+
+// Run a single `expect` test
+fn expect(
+    ctx: &dyn Context,
+    runner: &mut Runner,
+    input: Coord,
+    expected: Option<Coord>,
+    dimension: usize,
+) -> Result<(), String> {
+    let (direction, tolerance) = (runner.direction, runner.tolerance);
+    let op = match (runner.op(ctx), expected) {
+        (Ok(op), _) => op,
+        (Err(_), None) => return Ok(()),
+        (Err(e), Some(_)) => return Err(e),
+    };
+    let mut data = [input];
+    let successes = op.apply(ctx, &mut data, direction);
+    let failed = !matches!(successes, Ok(1)) || data[0].status() != PointStatus::Ok;
+    let Some(expected) = expected else {
+        if failed {
+            return Ok(());
+        }
+        return Err(format!("Expected failure, got {}", show(&data[0], 4)));
+    };
+    if failed {
+        return Err("Failed to transform".to_string());
+    }
+
+    let angular = angular_unit(op, direction);
+    let deviation = distance(&data[0], &expected, dimension, angular);
+    if deviation > tolerance {
+        return Err(format!(
+            "Expected {}, got {} (deviation {deviation:e} m, tolerance {tolerance:e} m)",
+            show(&expected, dimension),
+            show(&data[0], dimension),
+        ));
+    }
+    Ok(())
+}
+
+// Run a single `roundtrip` test, starting in the current direction
+fn roundtrip(
+    ctx: &dyn Context,
+    runner: &mut Runner,
+    input: Coord,
+    n: usize,
+    tolerance: f64,
+) -> Result<(), String> {
+    let (there, back) = match runner.direction {
+        Fwd => (Fwd, Inv),
+        Inv => (Inv, Fwd),
+    };
+    let op = runner.op(ctx)?;
+    let mut data = [input];
+    for _ in 0..n {
+        let there = op.apply(ctx, &mut data, there);
+        let back = op.apply(ctx, &mut data, back);
+        if !matches!((there, back), (Ok(1), Ok(1))) {
+            return Err("Failed to transform".to_string());
+        }
+    }
+    // The roundtrip ends on the input side of the current direction
+    let deviation = distance(&data[0], &input, 4, angular_unit(op, back));
+    if deviation > tolerance {
+        return Err(format!(
+            "Roundtrip deviation {deviation:e} exceeds the tolerance {tolerance:e}"
+        ));
+    }
+    Ok(())
+}
+
+// ----- H E L P E R S -----------------------------------------------------------------
+
+// A coordinate tuple of 2 to 4 numbers, with the missing ones taken as 0
+fn coordinates(text: &str) -> Option<Coord> {
+    let values: Vec<f64> = text
+        .split_whitespace()
+        .map(|v| v.parse::<f64>().ok())
+        .collect::<Option<_>>()?;
+    if !(2..=4).contains(&values.len()) {
+        return None;
+    }
+    let mut coord = Coord::origin();
+    for (i, value) in values.into_iter().enumerate() {
+        coord[i] = value;
+    }
+    Some(coord)
+}
+
+// A tolerance: A plain number, or a length with a unit suffix, e.g. `1mm` or
+// `1 mm`, converted to metres
+fn tolerance(text: &str) -> Option<f64> {
+    let text: String = text.split_whitespace().collect();
+    crate::op::parse_real("x", &text).filter(|t| *t >= 0.)
+}
+
+// The angular unit of the first two output coordinates of `op`, applied in
+// `direction`, in radians, if angular: Radians, by the angular i/o of the
+// operator, or otherwise the output unit of a final `adapt` step. An adaptor
+// output without a unit is in radians, when its input is angular
+fn angular_unit(op: &Op, direction: Direction) -> Option<f64> {
+    if op.angular_io(direction).1 {
+        return Some(1.);
+    }
+    let mut steps = op.steps();
+    if direction == Inv {
+        StepDescription::invert_sequence(&mut steps);
+    }
+    let last = steps.last().filter(|step| step.name == "adapt")?;
+    let (output, input) = match last.inverted {
+        true => ("from", "to"),
+        false => ("to", "from"),
+    };
+    let unit = |side| {
+        let descriptor = last.parameters.get(side)?;
+        descriptor.split_once('_').map(|(_, unit)| unit)
+    };
+    let unit = match (unit(output), unit(input)) {
+        (Some(unit), _) => unit,
+        (None, Some("deg" | "gon" | "rad")) => "rad",
+        _ => return None,
+    };
+    match unit {
+        "rad" => Some(1.),
+        "deg" => Some(core::f64::consts::PI / 180.),
+        "gon" => Some(core::f64::consts::PI / 200.),
+        _ => None,
+    }
+}
+
+// The Euclidean distance over the first `dimension` coordinates, with the
+// first two converted from `angular` units to metres, if given
+fn distance(a: &Coord, b: &Coord, dimension: usize, angular: Option<f64>) -> f64 {
+    (0..dimension.min(4))
+        .map(|i| match (i, angular) {
+            (0 | 1, Some(unit)) => (a[i] - b[i]) * unit * RADIUS,
+            _ => a[i] - b[i],
+        })
+        .map(|d| d.powi(2))
+        .sum::<f64>()
+        .sqrt()
+}
+
+fn show(coord: &Coord, dimension: usize) -> String {
+    let values: Vec<_> = (0..dimension.min(4))
+        .map(|i| coord[i].to_string())
+        .collect();
+    values.join(" ")
+}
+
+// ----- T E S T S ------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SUITE: &str = r#"
+Commentary outside the gie markers is ignored
+
+<gie>
+# UTM zone 32, with multi-line definition
+operation geo:in
+          | utm zone=32
+tolerance 0.1 mm
+accept  55 12
+expect  691875.6321 6098907.8250
+roundtrip 10
+
+direction inv
+accept  691875.6321 6098907.8250
+expect  55 12 0 0
+tolerance 1e-3
+expect  55.1 12   # Outside tolerance
+
+operation nonexistent
+accept 1 2
+expect failure
+
+operation addone
+direction fwd
+accept 1 2 3 4
+expect 2 2 3 4
+</gie>
+
+Neither is commentary after the end marker
+"#;
+
+    #[test]
+    fn gie() -> Result<(), Error> {
+        let mut ctx = Minimal::new();
+        let report = run_gie(&mut ctx, SUITE)?;
+        assert_eq!(report.tests, 6);
+        assert_eq!(report.failures.len(), 1);
+        assert_eq!(report.failures[0].line, 17);
+        assert!(report.failures[0].message.contains("tolerance"));
+
+        // Malformed suites
+        for suite in [
+            "accept 1 2\nexpect 1 2",
+            "operation addone\naccept 1\nexpect 2 0",
+            "operation addone\ndirection sideways",
+            "operation addone\naccept 1 2\nfoo bar",
+            "tolerance 1 parsec",
+        ] {
+            assert!(
+                matches!(run_gie(&mut ctx, suite), Err(Error::SyntaxAt { .. })),
+                "{suite}"
+            );
+        }
+        let suite = "operation addone\naccept 1 2\nexpect 2 2\nexpect 3 2";
+        let report = run_gie(&mut ctx, suite)?;
+        assert_eq!((report.tests, report.failures.len()), (2, 1));
+        assert_eq!(report.failures[0].line, 4);
+
+        // Misspelled commands are not taken as continuation lines
+        let suite = "operation addone\nacept 1 2\nexpect 2 2";
+        assert!(matches!(
+            run_gie(&mut ctx, suite),
+            Err(Error::SyntaxAt { line: 2, .. })
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn angular_tolerance() -> Result<(), Error> {
+        // Angular deviations are measured in metres: 1e-6 degrees is about 0.1 m
+        let mut ctx = Minimal::new();
+        let suite = "operation geo:in | geo:out
+            accept 55 12
+            expect 55.000000001 12
+            expect 55.000001 12
+            tolerance 0.2 m
+            expect 55.000001 12
+            direction inv
+            expect 55.000001 12";
+        let report = run_gie(&mut ctx, suite)?;
+        assert_eq!(report.tests, 4);
+        assert_eq!(report.failures.len(), 1);
+        assert_eq!(report.failures[0].line, 4);
+
+        // ...also for output in radians
+        let suite = "operation geo:in
+            accept 55 12
+            expect 0.20943951024 0.95993108860
+            expect 0.20943952 0.95993108860";
+        let report = run_gie(&mut ctx, suite)?;
+        assert_eq!(report.failures.len(), 1);
+        assert_eq!(report.failures[0].line, 4);
+        Ok(())
+    }
+
+    #[test]
+    fn roundtrip_direction() -> Result<(), Error> {
+        // Roundtrips start in the current direction
+        let mut ctx = Minimal::new();
+        let suite = "operation utm zone=32
+            direction inv
+            accept 691875.6321 6098907.8250
+            roundtrip 10";
+        let report = run_gie(&mut ctx, suite)?;
+        assert_eq!((report.tests, report.failures.len()), (1, 0));
+        Ok(())
+    }
+}
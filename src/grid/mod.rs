@@ -32,7 +32,7 @@ impl Grid {
         offset: Option<usize>,
     ) -> Result<Self, Error> {
         if header.len() < 7 {
-            return Err(Error::General("Incomplete grid"));
+            return Err(Error::General("Incomplete grid".to_string()));
         }
 
         let lat_0 = header[1];
@@ -52,7 +52,7 @@ impl Grid {
         let grid = Vec::from(grid.unwrap_or(&[]));
 
         if elements == 0 || (offset == 0 && elements > grid.len()) || bands < 1 {
-            return Err(Error::General("Malformed grid"));
+            return Err(Error::General("Malformed grid".to_string()));
         }
 
         Ok(Grid {
@@ -74,6 +74,12 @@ impl Grid {
     /// Determine whether a given coordinate falls within the grid borders.
     /// "On the border" qualifies as within.
     pub fn contains(&self, position: Coord) -> bool {
+        self.contains_with_margin(position, 0.)
+    }
+
+    /// Determine whether a given coordinate falls within the grid borders,
+    /// extended by `margin` grid cells in all directions.
+    pub fn contains_with_margin(&self, position: Coord, margin: f64) -> bool {
         // We start by assuming that the last row (latitude) is the southernmost
         let mut min = self.lat_1;
         let mut max = self.lat_0;
@@ -81,7 +87,8 @@ impl Grid {
         if self.dlat > 0. {
             (min, max) = (max, min)
         }
-        if position[1] != position[1].clamp(min, max) {
+        let margin_lat = margin * self.dlat.abs();
+        if position[1] != position[1].clamp(min - margin_lat, max + margin_lat) {
             return false;
         }
 
@@ -92,7 +99,8 @@ impl Grid {
         if self.dlon < 0. {
             (min, max) = (max, min)
         }
-        if position[0] != position[0].clamp(min, max) {
+        let margin_lon = margin * self.dlon.abs();
+        if position[0] != position[0].clamp(min - margin_lon, max + margin_lon) {
             return false;
         }
 
@@ -179,9 +187,17 @@ fn normalize_gravsoft_grid_values(header: &mut [f64], grid: &mut [f32]) {
         return;
     }
 
+    // Velocity grids have 3 bands, in mm/year and north/east/up order. Swap
+    // the horizontal components into east/north/up order, and keep the units
+    if h.bands == 3 {
+        for i in (0..grid.len()).step_by(3) {
+            grid.swap(i, i + 1);
+        }
+        return;
+    }
+
     // The grid values are in minutes-of-arc and in latitude/longitude order.
     // Swap them and convert into radians.
-    // TODO: handle 3-D data with 3rd coordinate in meters
     for i in 0..grid.len() {
         grid[i] = (grid[i] / 3600.0).to_radians();
         if i % 2 == 1 {
@@ -213,7 +229,7 @@ fn gravsoft_grid_reader(buf: &[u8]) -> Result<(Vec<f64>, Vec<f32>), Error> {
     }
 
     if header.len() < 6 {
-        return Err(Error::General("Incomplete Gravsoft header"));
+        return Err(Error::General("Incomplete Gravsoft header".to_string()));
     }
 
     // Count the number of bands
@@ -227,18 +243,18 @@ fn gravsoft_grid_reader(buf: &[u8]) -> Result<(Vec<f64>, Vec<f32>), Error> {
     let cols = ((lon_1 - lon_0) / dlon + 1.5).floor() as usize;
     let bands = grid.len() / (rows * cols);
     if (rows * cols * bands) > grid.len() || bands < 1 {
-        return Err(Error::General("Incomplete Gravsoft grid"));
+        return Err(Error::General("Incomplete Gravsoft grid".to_string()));
     }
 
     if (rows * cols * bands) != grid.len() {
         return Err(Error::General(
-            "Unrecognized material at end of Gravsoft grid",
+            "Unrecognized material at end of Gravsoft grid".to_string(),
         ));
     }
 
-    if bands > 2 {
+    if bands > 3 {
         return Err(Error::General(
-            "Unsupported number of bands in Gravsoft grid",
+            "Unsupported number of bands in Gravsoft grid".to_string(),
         ));
     }
 
@@ -292,7 +308,7 @@ mod tests {
         let geoid = Grid::plain(&geoid_header, Some(&geoid_grid), None)?;
 
         let c = Coord::geo(58.75, 08.25, 0., 0.);
-        assert_eq!(geoid.contains(c), false);
+        assert!(!geoid.contains(c));
 
         let n = geoid.interpolation(&c, None);
         assert!((n[0] - 58.83).abs() < 0.1);
@@ -316,7 +332,7 @@ mod tests {
         // Interpolation
         let c = Coord::geo(55.06, 12.03, 0., 0.);
         // Check that we're not extrapolating
-        assert_eq!(datum.contains(c), true);
+        assert!(datum.contains(c));
         // ...with output converted back to arcsec
         let d = datum.interpolation(&c, None).to_arcsec();
         // We can do slightly better for interpolation than for extrapolation,
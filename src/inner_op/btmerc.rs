@@ -12,15 +12,22 @@ fn fwd(op: &Op, _ctx: &dyn Context, operands: &mut [Coord]) -> Result<usize, Err
     let x_0 = op.params.x[0];
     let y_0 = op.params.y[0];
     let k_0 = op.params.k[0];
+    let wrap = op.params.boolean("wrap");
+
+    // The meridional distance of the latitude of origin
+    let m_0 = ellps.meridional_distance(lat_0, Fwd);
 
     let mut successes = 0_usize;
     for coord in operands {
-        let lat = coord[1] + lat_0;
+        let lat = coord[1];
         let (s, c) = lat.sin_cos();
         let cc = c * c;
         let ss = s * s;
 
-        let dlon = coord[0] - lon_0;
+        let mut dlon = coord[0] - lon_0;
+        if wrap {
+            dlon = normalize_angle_symmetric(dlon);
+        }
         let oo = dlon * dlon;
 
         #[allow(non_snake_case)]
@@ -38,7 +45,7 @@ fn fwd(op: &Op, _ctx: &dyn Context, operands: &mut [Coord]) -> Result<usize, Err
         let m = ellps.meridional_distance(lat, Fwd);
         let znos4 = z * N * dlon * s / 4.;
         let ecc = 4. * eps * cc;
-        coord[1] = y_0 + k_0 * (m + N * theta_2 + znos4 * (9. + ecc + oo * (20. * cc - 11.)));
+        coord[1] = y_0 + k_0 * (m - m_0 + N * theta_2 + znos4 * (9. + ecc + oo * (20. * cc - 11.)));
         successes += 1;
     }
 
@@ -57,11 +64,13 @@ fn inv(op: &Op, _ctx: &dyn Context, operands: &mut [Coord]) -> Result<usize, Err
     let y_0 = op.params.y[0];
     let k_0 = op.params.k[0];
 
+    let m_0 = ellps.meridional_distance(lat_0, Fwd);
+
     let mut successes = 0_usize;
     for coord in operands {
         // Footpoint latitude, i.e. the latitude of a point on the central meridian
         // having the same northing as the point of interest
-        let lat = ellps.meridional_distance((coord[1] - y_0) / k_0, Inv);
+        let lat = ellps.meridional_distance((coord[1] - y_0) / k_0 + m_0, Inv);
         let t = lat.tan();
         let c = lat.cos();
         let cc = c * c;
@@ -74,7 +83,7 @@ fn inv(op: &Op, _ctx: &dyn Context, operands: &mut [Coord]) -> Result<usize, Err
 
         // Latitude
         let xet = xx * xx * eps * t / 24.;
-        coord[1] = lat_0 + (1. + cc * eps) * (theta_5 - xet * (9. - 10. * cc)) - eps * cc * lat;
+        coord[1] = (1. + cc * eps) * (theta_5 - xet * (9. - 10. * cc)) - eps * cc * lat;
 
         // Longitude
         let approx = lon_0 + theta_4;
@@ -90,7 +99,7 @@ fn inv(op: &Op, _ctx: &dyn Context, operands: &mut [Coord]) -> Result<usize, Err
 // ----- C O N S T R U C T O R ---------------------------------------------------------
 
 #[rustfmt::skip]
-pub const GAMUT: [OpParameter; 7] = [
+pub const GAMUT: [OpParameter; 8] = [
     OpParameter::Flag { key: "inv" },
     OpParameter::Text { key: "ellps", default: Some("GRS80") },
 
@@ -100,17 +109,21 @@ pub const GAMUT: [OpParameter; 7] = [
     OpParameter::Real { key: "y_0",   default: Some(0_f64) },
 
     OpParameter::Real { key: "k_0",   default: Some(1_f64) },
+    OpParameter::Flag { key: "wrap" },
 ];
 
 pub fn new(parameters: &RawParameters, ctx: &dyn Context) -> Result<Op, Error> {
-    Op::plain(parameters, InnerOp(fwd), InnerOp(inv), &GAMUT, ctx)
+    let mut op = Op::plain(parameters, InnerOp(fwd), InnerOp(inv), &GAMUT, ctx)?;
+    op.descriptor.angular_io = (true, false);
+    Ok(op)
 }
 
 #[rustfmt::skip]
-pub const UTM_GAMUT: [OpParameter; 3] = [
+pub const UTM_GAMUT: [OpParameter; 4] = [
     OpParameter::Flag { key: "inv" },
     OpParameter::Text { key: "ellps", default: Some("GRS80") },
     OpParameter::Natural { key: "zone", default: None },
+    OpParameter::Flag { key: "wrap" },
 ];
 
 pub fn utm(parameters: &RawParameters, _ctx: &dyn Context) -> Result<Op, Error> {
@@ -121,7 +134,7 @@ pub fn utm(parameters: &RawParameters, _ctx: &dyn Context) -> Result<Op, Error>
     let zone = params.natural("zone")?;
     if !(1..61).contains(&zone) {
         return Err(Error::General(
-            "UTM: 'zone' must be an integer in the interval 1..60",
+            "UTM: 'zone' must be an integer in the interval 1..60".to_string(),
         ));
     }
 
@@ -140,7 +153,8 @@ pub fn utm(parameters: &RawParameters, _ctx: &dyn Context) -> Result<Op, Error>
     // The false northing is 0 m by definition of UTM
     params.x[0] = 500000.0;
 
-    let descriptor = OpDescriptor::new(def, InnerOp(fwd), Some(InnerOp(inv)));
+    let mut descriptor = OpDescriptor::new(def, InnerOp(fwd), Some(InnerOp(inv)));
+    descriptor.angular_io = (true, false);
     let steps = Vec::<Op>::new();
     let id = OpHandle::new();
 
@@ -182,7 +196,7 @@ mod tests {
             Coord::raw(-455_673.814_189_040,-6_198_246.671_090_279, 0., 0.)
         ];
 
-        let mut operands = geo.clone();
+        let mut operands = geo;
         op.apply(&ctx, &mut operands, Fwd)?;
         for i in 0..operands.len() {
             assert!(operands[i].hypot2(&projected[i]) < 5e-3);
@@ -219,7 +233,7 @@ mod tests {
             Coord::raw(-455_673.814_189_040,-6_198_246.671_090_279, 0., 0.)
         ];
 
-        let mut operands = geo.clone();
+        let mut operands = geo;
         op.apply(&ctx, &mut operands, Fwd)?;
         for i in 0..operands.len() {
             assert!(operands[i].hypot2(&projected[i]) < 5e-3);
@@ -231,4 +245,33 @@ mod tests {
         }
         Ok(())
     }
+
+    #[test]
+    fn btmerc_lat_0() -> Result<(), Error> {
+        let ctx = Minimal::default();
+        let definition = "lat_0=55 lon_0=9 x_0=500000 y_0=100000";
+        let op = Op::new(&format!("btmerc {definition}"), &ctx)?;
+        let reference = Op::new(&format!("tmerc {definition}"), &ctx)?;
+
+        // The latitude of origin is mapped to the false northing
+        let mut origin = [Coord::geo(55., 9., 0., 0.)];
+        op.apply(&ctx, &mut origin, Fwd)?;
+        assert!(origin[0].hypot2(&Coord::raw(500000., 100000., 0., 0.)) < 1e-6);
+
+        // ...and elsewhere, btmerc agrees with tmerc
+        let geo = [Coord::geo(57., 12., 0., 0.), Coord::geo(52., 6., 0., 0.)];
+        let mut operands = geo;
+        let mut expected = geo;
+        op.apply(&ctx, &mut operands, Fwd)?;
+        reference.apply(&ctx, &mut expected, Fwd)?;
+        for i in 0..operands.len() {
+            assert!(operands[i].hypot2(&expected[i]) < 5e-3);
+        }
+
+        op.apply(&ctx, &mut operands, Inv)?;
+        for i in 0..operands.len() {
+            assert!(operands[i].hypot2(&geo[i]) < 10e-8);
+        }
+        Ok(())
+    }
 }
@@ -0,0 +1,213 @@
+//! Kinematic datum shift, by integration of the velocities of a deformation model
+use super::*;
+
+// The parameters, as prepared by the constructor
+struct Prepared {
+    dt: f64,
+    t_epoch: f64,
+    padding: f64,
+}
+
+// ...and the velocity grid and the ellipsoid, as used at run time
+struct Deformation<'a> {
+    grid: &'a Grid,
+    ellps: &'a Ellipsoid,
+    params: &'a Prepared,
+}
+
+impl Deformation<'_> {
+    fn new(op: &Op) -> Result<Deformation<'_>, Error> {
+        Ok(Deformation {
+            grid: &op.params.grids["grid"],
+            ellps: &op.params.ellps[0],
+            params: op.params.prepared()?,
+        })
+    }
+}
+
+// The time span, in years, over which to integrate the velocities: Either the
+// fixed `dt`, or from the observation epoch of the coordinate, to `t_epoch`
+fn time_span(model: &Deformation, coord: &Coord) -> f64 {
+    let Prepared { dt, t_epoch, .. } = *model.params;
+    if dt.is_nan() {
+        t_epoch - coord[3]
+    } else {
+        dt
+    }
+}
+
+// The displacement of the cartesian coordinate, `coord`, over `dt` years,
+// i.e. the velocities interpolated at its geographical position, rotated from
+// the local (east, north, up) system into the cartesian one. None if the
+// position is outside of the grid
+fn displacement(model: &Deformation, coord: &Coord, dt: f64) -> Option<Coord> {
+    let grid = model.grid;
+    let geo = model.ellps.geographic(coord);
+    if !grid.contains_with_margin(geo, model.params.padding) {
+        return None;
+    }
+
+    // The grid velocities are in mm/year
+    let v = grid.interpolation(&geo, None).scale(dt / 1000.);
+    let (e, n, u) = (v[0], v[1], v[2]);
+    let (slam, clam) = geo[0].sin_cos();
+    let (sphi, cphi) = geo[1].sin_cos();
+    Some(Coord::raw(
+        -slam * e - sphi * clam * n + cphi * clam * u,
+        clam * e - sphi * slam * n + cphi * slam * u,
+        cphi * n + sphi * u,
+        0.,
+    ))
+}
+
+// ----- F O R W A R D --------------------------------------------------------------
+
+fn fwd(op: &Op, _ctx: &dyn Context, operands: &mut [Coord]) -> Result<usize, Error> {
+    let model = Deformation::new(op)?;
+    let mut successes = 0_usize;
+    for coord in operands {
+        let dt = time_span(&model, coord);
+        let Some(d) = displacement(&model, coord, dt) else {
+            *coord = Coord::failed(PointStatus::OutOfGrid);
+            continue;
+        };
+        *coord = *coord + d;
+        successes += 1;
+    }
+    Ok(successes)
+}
+
+// ----- I N V E R S E --------------------------------------------------------------
+
+fn inv(op: &Op, _ctx: &dyn Context, operands: &mut [Coord]) -> Result<usize, Error> {
+    let model = Deformation::new(op)?;
+    let mut successes = 0_usize;
+    for coord in operands {
+        let dt = time_span(&model, coord);
+        let Some(d) = displacement(&model, coord, dt) else {
+            *coord = Coord::failed(PointStatus::OutOfGrid);
+            continue;
+        };
+
+        // The velocities at the original position differ insignificantly from
+        // those at the displaced one, but we refine once, to be on the safe side
+        let t = *coord - d;
+        let d = displacement(&model, &t, dt).unwrap_or(d);
+        *coord = *coord - d;
+        successes += 1;
+    }
+    Ok(successes)
+}
+
+// ----- C O N S T R U C T O R ------------------------------------------------------
+
+#[rustfmt::skip]
+pub const GAMUT: [OpParameter; 6] = [
+    OpParameter::Flag { key: "inv" },
+    OpParameter::Text { key: "grids", default: None },
+    OpParameter::Text { key: "ellps", default: Some("GRS80") },
+    OpParameter::Real { key: "dt", default: Some(f64::NAN) },
+    OpParameter::Real { key: "t_epoch", default: Some(f64::NAN) },
+    OpParameter::Real { key: "padding", default: Some(0.5) },
+];
+
+pub fn new(parameters: &RawParameters, ctx: &dyn Context) -> Result<Op, Error> {
+    let def = &parameters.definition;
+    let mut params = ParsedParameters::new(parameters, &GAMUT)?;
+
+    // One of `dt` and `t_epoch` is needed for determining the time span
+    if params.real("dt")?.is_nan() && params.real("t_epoch")?.is_nan() {
+        return Err(Error::MissingParam("dt or t_epoch".to_string()));
+    }
+
+    let grid_file_name = params.text("grids")?;
+    let buf = ctx.get_blob(&grid_file_name)?;
+
+    let grid = Grid::gravsoft(&buf).map_err(|e| e.context(format!("grid {grid_file_name}")))?;
+    if grid.bands != 3 {
+        return Err(Error::BadParam(
+            "grids".to_string(),
+            format!("{grid_file_name} is not a velocity grid"),
+        ));
+    }
+    params.grids.insert("grid", grid);
+    params.prepare(Prepared {
+        dt: params.real("dt")?,
+        t_epoch: params.real("t_epoch")?,
+        padding: params.real("padding")?,
+    });
+
+    let fwd = InnerOp(fwd);
+    let inv = InnerOp(inv);
+    let mut descriptor = OpDescriptor::new(def, fwd, Some(inv));
+    let provenance = Provenance::new(ResourceKind::Grid, &grid_file_name, &buf);
+    descriptor.provenance.push(provenance);
+    let steps = Vec::<Op>::new();
+    let id = OpHandle::new();
+
+    Ok(Op {
+        descriptor,
+        params,
+        steps,
+        id,
+    })
+}
+
+// ----- T E S T S ------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deformation() -> Result<(), Error> {
+        let mut ctx = Minimal::default();
+        let ellps = Ellipsoid::named("GRS80")?;
+
+        // The test grid has velocities of 2 mm/year east, 1 mm/year north,
+        // and 3 mm/year up
+        let cph = ellps.cartesian(&Coord::geo(55., 12., 100., 2010.));
+        let op = ctx.op("deformation grids=test.deformation dt=10")?;
+        let mut data = [cph];
+        ctx.apply(op, Fwd, &mut data)?;
+
+        // The displacement, in the local system of Copenhagen
+        let (from, to) = (ellps.geographic(&cph), ellps.geographic(&data[0]));
+        let lat = from[1];
+        let n = (to[1] - lat) * ellps.meridian_radius_of_curvature(lat);
+        let e = (to[0] - from[0]) * ellps.prime_vertical_radius_of_curvature(lat) * lat.cos();
+        assert!((e - 0.02).abs() < 1e-6);
+        assert!((n - 0.01).abs() < 1e-6);
+        assert!((to[2] - 100.03).abs() < 1e-6);
+        assert_eq!(data[0][3], 2010.);
+
+        ctx.apply(op, Inv, &mut data)?;
+        assert!(data[0].hypot3(&cph) < 1e-9);
+
+        // The time span from the observation epoch to the reference epoch
+        let op = ctx.op("deformation grids=test.deformation t_epoch=2020")?;
+        let mut observed = [cph];
+        ctx.apply(op, Fwd, &mut observed)?;
+        let op = ctx.op("deformation grids=test.deformation dt=10")?;
+        ctx.apply(op, Fwd, &mut data)?;
+        assert!(observed[0].hypot3(&data[0]) < 1e-9);
+
+        // Outside of the grid
+        let mut data = [ellps.cartesian(&Coord::geo(45., 12., 0., 0.))];
+        assert_eq!(
+            ctx.apply_with_status(op, Fwd, &mut data)?,
+            [PointStatus::OutOfGrid]
+        );
+
+        // No time span, and not a velocity grid
+        assert!(matches!(
+            ctx.op("deformation grids=test.deformation"),
+            Err(Error::MissingParam(_))
+        ));
+        assert!(matches!(
+            ctx.op("deformation grids=test.datum dt=1"),
+            Err(Error::BadParam(..))
+        ));
+        Ok(())
+    }
+}
@@ -0,0 +1,148 @@
+/// Geodesics: The forward and inverse geodesic problems as pipeline operators
+use super::*;
+
+// ----- F O R W A R D -----------------------------------------------------------------
+
+fn fwd(op: &Op, _ctx: &dyn Context, operands: &mut [Coord]) -> Result<usize, Error> {
+    let ellps = op.params.ellps[0];
+    let mut successes = 0_usize;
+
+    // The inverse geodesic problem for pairs of points, given as (lon1, lat1, lon2, lat2)
+    if op.params.boolean("pairs") {
+        for coord in operands {
+            let from = Coord::raw(coord[0], coord[1], 0., 0.);
+            let to = Coord::raw(coord[2], coord[3], 0., 0.);
+            let d = ellps.geodesic_inv(&from, &to);
+            *coord = Coord::raw(d[0], d[1], d[2], 0.);
+            if !d[2].is_nan() {
+                successes += 1;
+            }
+        }
+        return Ok(successes);
+    }
+
+    // The forward geodesic problem from the fixed origin: (azimuth, distance) -> (lon, lat)
+    let origin = Coord::raw(op.params.lon[0], op.params.lat[0], 0., 0.);
+    for coord in operands {
+        let b = ellps.geodesic_fwd(&origin, coord[0], coord[1]);
+        coord[0] = b[0];
+        coord[1] = b[1];
+        coord[2] = b[2];
+        if !b[1].is_nan() {
+            successes += 1;
+        }
+    }
+    Ok(successes)
+}
+
+// ----- I N V E R S E -----------------------------------------------------------------
+
+fn inv(op: &Op, _ctx: &dyn Context, operands: &mut [Coord]) -> Result<usize, Error> {
+    let ellps = op.params.ellps[0];
+    let mut successes = 0_usize;
+
+    // The inverse geodesic problem from the fixed origin: (lon, lat) -> (azimuth, distance)
+    let origin = Coord::raw(op.params.lon[0], op.params.lat[0], 0., 0.);
+    for coord in operands {
+        let d = ellps.geodesic_inv(&origin, coord);
+        coord[0] = d[0];
+        coord[1] = d[2];
+        coord[2] = d[1];
+        if !d[2].is_nan() {
+            successes += 1;
+        }
+    }
+    Ok(successes)
+}
+
+// ----- C O N S T R U C T O R ---------------------------------------------------------
+
+#[rustfmt::skip]
+pub const GAMUT: [OpParameter; 5] = [
+    OpParameter::Flag { key: "inv" },
+    OpParameter::Flag { key: "pairs" },
+    OpParameter::Text { key: "ellps", default: Some("GRS80") },
+    OpParameter::Real { key: "lat_0", default: Some(0_f64) },
+    OpParameter::Real { key: "lon_0", default: Some(0_f64) },
+];
+
+pub fn new(parameters: &RawParameters, _ctx: &dyn Context) -> Result<Op, Error> {
+    let def = &parameters.definition;
+    let params = ParsedParameters::new(parameters, &GAMUT)?;
+
+    // The pairwise case has no origin to return to, hence no inverse
+    let inv = if params.boolean("pairs") {
+        None
+    } else {
+        Some(InnerOp(inv))
+    };
+
+    // The output, a position or a pair of azimuths, is angular. The input, an
+    // (azimuth, distance) pair or a pair of positions, is not just two angles
+    let mut descriptor = OpDescriptor::new(def, InnerOp(fwd), inv);
+    descriptor.angular_io = (false, true);
+    let steps = Vec::<Op>::new();
+    let id = OpHandle::new();
+
+    Ok(Op {
+        descriptor,
+        params,
+        steps,
+        id,
+    })
+}
+
+// ----- T E S T S ---------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Expected values from Karney: https://geographiclib.sourceforge.io/cgi-bin/GeodSolve
+    // cf. the test in `ellipsoid/geodesics.rs`
+    const AZI1: f64 = -130.15406042072;
+    const AZI2: f64 = -138.05257941874;
+    const DIST: f64 = 956066.231959;
+
+    #[test]
+    fn from_origin() -> Result<(), Error> {
+        let mut ctx = Minimal::default();
+
+        // Copenhagen (Denmark)--Paris (France)
+        let op = ctx.op("geodesic lat_0=55 lon_0=12")?;
+        let mut operands = [Coord::raw(AZI1.to_radians(), DIST, 0., 0.)];
+
+        ctx.apply(op, Fwd, &mut operands)?;
+        assert!((operands[0][0].to_degrees() - 2.).abs() < 1e-9);
+        assert!((operands[0][1].to_degrees() - 49.).abs() < 1e-9);
+        assert!((operands[0][2].to_degrees() - AZI2).abs() < 1e-8);
+
+        ctx.apply(op, Inv, &mut operands)?;
+        assert!((operands[0][0].to_degrees() - AZI1).abs() < 1e-9);
+        assert!((operands[0][1] - DIST).abs() < 1e-5);
+        assert!((operands[0][2].to_degrees() - AZI2).abs() < 1e-9);
+        Ok(())
+    }
+
+    #[test]
+    fn pairs() -> Result<(), Error> {
+        let mut ctx = Minimal::default();
+        let op = ctx.op("geodesic pairs")?;
+
+        let cph = Coord::gis(12., 55., 0., 0.);
+        let par = Coord::gis(2., 49., 0., 0.);
+        let mut operands = [Coord::raw(cph[0], cph[1], par[0], par[1])];
+
+        assert_eq!(1, ctx.apply(op, Fwd, &mut operands)?);
+        assert!((operands[0][0].to_degrees() - AZI1).abs() < 1e-9);
+        assert!((operands[0][1].to_degrees() - AZI2).abs() < 1e-9);
+        assert!((operands[0][2] - DIST).abs() < 1e-5);
+
+        // There is no way back from a pair of azimuths and a distance alone
+        assert!(matches!(
+            ctx.op("geodesic pairs inv"),
+            Err(Error::NonInvertible(_))
+        ));
+        Ok(())
+    }
+}
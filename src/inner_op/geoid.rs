@@ -0,0 +1,465 @@
+//! Geoid undulations from spherical harmonic models of the gravity potential,
+//! e.g. EGM96 and EGM2008
+use super::*;
+
+// The geocentric gravitational constant, and the angular velocity, of the
+// normal gravity fields of GRS80 ([Moritz, 2000](crate::Bibliography::Mor00))
+// and WGS84 ([NIMA, 2000](crate::Bibliography::Nima00)). Those of WGS84 are
+// used with other ellipsoids
+#[rustfmt::skip]
+const NORMAL_FIELDS: [(&str, f64, f64); 2] = [
+    ("GRS80", 3.986005e14,    7.292115e-5),
+    ("WGS84", 3.986004418e14, 7.292115e-5),
+];
+
+// The normal field constants of `ellps`, cf. `NORMAL_FIELDS`
+fn normal_constants(ellps: &Ellipsoid) -> (f64, f64) {
+    let (_, gm, omega) = NORMAL_FIELDS
+        .into_iter()
+        .find(|field| Ellipsoid::named(field.0).is_ok_and(|e| e == *ellps))
+        .unwrap_or(NORMAL_FIELDS[1]);
+    (gm, omega)
+}
+
+// The Legendre functions are scaled by this factor while recursing, to keep
+// them within range at high degrees, cf. `harmonic_sum`
+const SCALE: f64 = 1e-280;
+
+// The index of the coefficient of degree n and order m, in the triangular
+// arrays of coefficients
+fn index(n: usize, m: usize) -> usize {
+    n * (n + 1) / 2 + m
+}
+
+// The model, less the normal potential, and the normal gravity field, as
+// prepared by the constructor
+struct Model {
+    degree: usize,
+    c: Vec<f64>,
+    s: Vec<f64>,
+    gamma: [f64; 2],
+    radius: f64,
+    gm: f64,
+    zero_degree: f64,
+}
+
+// The geoid undulation at the geographical position of `coord`, following
+// Bruns' formula, N = T/γ, with the disturbing potential, T, given by the
+// model minus the normal potential, and evaluated on the ellipsoid
+// and `qn` and `sums` are scratch space for `harmonic_sum`
+fn undulation(
+    model: &Model,
+    ellps: &Ellipsoid,
+    coord: &Coord,
+    qn: &mut [f64],
+    sums: &mut [f64],
+) -> f64 {
+    let (c, s) = (&model.c, &model.s);
+    let degree = model.degree;
+    let (lam, phi) = (coord[0], coord[1]);
+
+    // The geocentric radius and latitude of the point on the ellipsoid
+    let p = ellps.cartesian(&Coord::raw(lam, phi, 0., 0.));
+    let r = p[0].hypot(p[1]).hypot(p[2]);
+    let t = p[2] / r; // The sine of the geocentric latitude
+    let u = p[0].hypot(p[1]) / r; // ...and its cosine
+
+    let gamma = ellps.normal_gravity(phi, model.gamma);
+
+    // The powers of the ratio between the reference radius of the model and r
+    let q = model.radius / r;
+    qn[0] = 1.;
+    for n in 1..=degree {
+        qn[n] = qn[n - 1] * q;
+    }
+
+    let sum = harmonic_sum(c, s, degree, (t, u, lam), qn, sums);
+    model.gm / (r * gamma) * sum + model.zero_degree
+}
+
+// The sum of the spherical harmonics of degree 2 and above, with the
+// coefficients `c` and `s`, weighted by `qn`, at the point given by the sine,
+// and cosine, of its geocentric latitude, and its longitude.
+//
+// Following [Holmes & Featherstone, 2002](crate::Bibliography::Hol02), the
+// fully normalized associated Legendre functions, divided by u^m, are computed
+// order by order, by the forward column recursion from the sectorials, and
+// scaled by `SCALE`. The sums for each order, m, are kept in `sums`, and
+// summed by Horner's scheme in u, so u^m, which underflows for the orders of
+// e.g. EGM2008 (2190) at moderate latitudes, is never computed
+fn harmonic_sum(
+    c: &[f64],
+    s: &[f64],
+    degree: usize,
+    (t, u, lam): (f64, f64, f64),
+    qn: &[f64],
+    sums: &mut [f64],
+) -> f64 {
+    let mut pmm = SCALE;
+    for (m, order_sum) in sums.iter_mut().enumerate().take(degree + 1) {
+        if m == 1 {
+            pmm *= 3_f64.sqrt();
+        } else if m > 1 {
+            pmm *= ((2 * m + 1) as f64 / (2 * m) as f64).sqrt();
+        }
+        let (sin_ml, cos_ml) = (m as f64 * lam).sin_cos();
+
+        let mut sum = 0.;
+        let (mut p2, mut p1) = (0., pmm);
+        for (n, qn) in qn.iter().enumerate().take(degree + 1).skip(m) {
+            let pnm = if n == m {
+                pmm
+            } else {
+                let (nf, mf) = (n as f64, m as f64);
+                let anm = ((2. * nf - 1.) * (2. * nf + 1.) / ((nf - mf) * (nf + mf))).sqrt();
+                let bnm = if n == m + 1 {
+                    0.
+                } else {
+                    ((2. * nf + 1.) * (nf + mf - 1.) * (nf - mf - 1.)
+                        / ((nf - mf) * (nf + mf) * (2. * nf - 3.)))
+                        .sqrt()
+                };
+                let pnm = anm * t * p1 - bnm * p2;
+                (p2, p1) = (p1, pnm);
+                pnm
+            };
+            if n >= 2 {
+                let i = index(n, m);
+                sum += qn * (c[i] * cos_ml + s[i] * sin_ml) * pnm;
+            }
+        }
+        *order_sum = sum;
+    }
+
+    let sum = sums[..=degree].iter().rev().fold(0., |sum, s| sum * u + s);
+    sum / SCALE
+}
+
+// ----- F O R W A R D --------------------------------------------------------------
+
+// Ellipsoidal heights to heights above the geoid, as for `gridshift` with a geoid
+fn fwd(op: &Op, _ctx: &dyn Context, operands: &mut [Coord]) -> Result<usize, Error> {
+    let (model, ellps) = (op.params.prepared::<Model>()?, &op.params.ellps[0]);
+    let (mut qn, mut sums) = (vec![1.; model.degree + 1], vec![0.; model.degree + 1]);
+    for coord in operands.iter_mut() {
+        coord[2] -= undulation(model, ellps, coord, &mut qn, &mut sums);
+    }
+    Ok(operands.len())
+}
+
+// ----- I N V E R S E --------------------------------------------------------------
+
+fn inv(op: &Op, _ctx: &dyn Context, operands: &mut [Coord]) -> Result<usize, Error> {
+    let (model, ellps) = (op.params.prepared::<Model>()?, &op.params.ellps[0]);
+    let (mut qn, mut sums) = (vec![1.; model.degree + 1], vec![0.; model.degree + 1]);
+    for coord in operands.iter_mut() {
+        coord[2] += undulation(model, ellps, coord, &mut qn, &mut sums);
+    }
+    Ok(operands.len())
+}
+
+// ----- M O D E L   F I L E S ------------------------------------------------------
+
+// Read the coefficients of a spherical harmonic model, in the format of the
+// EGM96 and EGM2008 coefficient files, i.e. one line per coefficient pair,
+// `n m C S [sigma_C sigma_S]`, possibly with Fortran style exponents (`D`).
+// The ICGEM format is also supported, since its `gfc` lines only differ by
+// the prefix, and its header lines are ignored, as are '#'-style comments.
+// Returns the degree, and the C and S coefficients in triangular arrays
+fn read_model(buf: &[u8]) -> Result<(usize, Vec<f64>, Vec<f64>), Error> {
+    let text = String::from_utf8_lossy(buf);
+    let mut coefficients = Vec::new();
+    for line in text.lines() {
+        let line = line.split('#').next().unwrap_or_default();
+        let mut items: Vec<&str> = line.split_whitespace().collect();
+        if items.first().is_some_and(|item| item.starts_with("gfc")) {
+            items.remove(0);
+        }
+        if items.len() < 4 {
+            continue;
+        }
+        let (Ok(n), Ok(m)) = (items[0].parse::<usize>(), items[1].parse::<usize>()) else {
+            continue;
+        };
+        let value = |item: &str| {
+            item.replace(['D', 'd'], "E")
+                .parse::<f64>()
+                .map_err(|_| Error::General(format!("Bad coefficient: {item}")))
+        };
+        if m > n {
+            return Err(Error::General(format!("Bad coefficient order: {n} {m}")));
+        }
+        coefficients.push((n, m, value(items[2])?, value(items[3])?));
+    }
+
+    let Some(degree) = coefficients.iter().map(|c| c.0).max() else {
+        return Err(Error::General(
+            "No spherical harmonic coefficients".to_string(),
+        ));
+    };
+    let mut c = vec![0.; index(degree, degree) + 1];
+    let mut s = c.clone();
+    for (n, m, cnm, snm) in coefficients {
+        c[index(n, m)] = cnm;
+        s[index(n, m)] = snm;
+    }
+    Ok((degree, c, s))
+}
+
+// The normal gravity at the equator and the poles, and the fully normalized
+// even zonal coefficients of the normal potential of `ellps`, following
+// [Heiskanen & Moritz, 1967](crate::Bibliography::Hei67), eqs. 2-73, 2-92
+// and 2-95, with the normal field constants `gm` and `omega`. For WGS84, and
+// GRS80, this reproduces `Ellipsoid::WGS84_NORMAL_GRAVITY`, resp.
+// `Ellipsoid::GRS80_NORMAL_GRAVITY`
+fn normal_field(ellps: &Ellipsoid, (gm, omega): (f64, f64), degree: usize) -> (f64, f64, Vec<f64>) {
+    let (a, b) = (ellps.semimajor_axis(), ellps.semiminor_axis());
+    let es = ellps.eccentricity_squared();
+    let ep = ellps.second_eccentricity();
+
+    let m = omega * omega * a * a * b / gm;
+    let q0 = 0.5 * ((1. + 3. / (ep * ep)) * ep.atan() - 3. / ep);
+    let q0p = 3. * (1. + 1. / (ep * ep)) * (1. - ep.atan() / ep) - 1.;
+    let gamma_e = gm / (a * b) * (1. - m - m * ep * q0p / (6. * q0));
+    let gamma_p = gm / (a * a) * (1. + m * ep * q0p / (3. * q0));
+
+    let j2 = es / 3. * (1. - 2. * m * ep / (15. * q0));
+    let zonals = (1..=degree / 2)
+        .map(|n| {
+            let nf = n as f64;
+            let sign = if n % 2 == 1 { 1. } else { -1. };
+            let j2n = sign * 3. * es.powi(n as i32) / ((2. * nf + 1.) * (2. * nf + 3.))
+                * (1. - nf + 5. * nf * j2 / es);
+            -j2n / (4. * nf + 1.).sqrt()
+        })
+        .collect();
+    (gamma_e, gamma_p, zonals)
+}
+
+// ----- C O N S T R U C T O R ------------------------------------------------------
+
+#[rustfmt::skip]
+pub const GAMUT: [OpParameter; 7] = [
+    OpParameter::Flag { key: "inv" },
+    OpParameter::Text { key: "model", default: None },
+    OpParameter::Natural { key: "degree", default: Some(0) },
+    OpParameter::Text { key: "ellps", default: Some("GRS80") },
+    OpParameter::Real { key: "gm", default: Some(3.986004415e14) },
+    OpParameter::Real { key: "radius", default: Some(6378136.3) },
+    OpParameter::Real { key: "zero_degree", default: Some(0.) },
+];
+
+pub fn new(parameters: &RawParameters, ctx: &dyn Context) -> Result<Op, Error> {
+    let def = &parameters.definition;
+    let mut params = ParsedParameters::new(parameters, &GAMUT)?;
+
+    let model_file_name = params.text("model")?;
+    let buf = ctx.get_blob(&model_file_name)?;
+    let (mut degree, mut c, s) =
+        read_model(&buf).map_err(|e| e.context(format!("model {model_file_name}")))?;
+
+    // Truncate the model, if so requested (0 means the full model)
+    let truncation = params.natural("degree")?;
+    if truncation > degree {
+        return Err(Error::BadParam(
+            "degree".to_string(),
+            format!("{truncation} exceeds the degree of {model_file_name}, {degree}"),
+        ));
+    }
+    if truncation > 0 {
+        degree = truncation;
+    }
+
+    // Subtract the normal potential, scaled to the GM and radius of the model
+    let ellps = params.ellps[0];
+    let constants = normal_constants(&ellps);
+    let (gamma_e, gamma_p, zonals) = normal_field(&ellps, constants, degree);
+    let gm_ratio = constants.0 / params.real("gm")?;
+    let radius_ratio = ellps.semimajor_axis() / params.real("radius")?;
+    for (i, zonal) in zonals.iter().enumerate() {
+        let n = 2 * (i + 1);
+        c[index(n, 0)] -= zonal * gm_ratio * radius_ratio.powi(n as i32);
+    }
+
+    params.natural.insert("degree", degree);
+    let size = index(degree, degree) + 1;
+    params.prepare(Model {
+        degree,
+        c: c[..size].to_vec(),
+        s: s[..size].to_vec(),
+        gamma: [gamma_e, gamma_p],
+        radius: params.real("radius")?,
+        gm: params.real("gm")?,
+        zero_degree: params.real("zero_degree")?,
+    });
+
+    let fwd = InnerOp(fwd);
+    let inv = InnerOp(inv);
+    let mut descriptor = OpDescriptor::new(def, fwd, Some(inv));
+    descriptor.angular_io = (true, true);
+    let provenance = Provenance::new(ResourceKind::Model, &model_file_name, &buf);
+    descriptor.provenance.push(provenance);
+    let steps = Vec::<Op>::new();
+    let id = OpHandle::new();
+
+    Ok(Op {
+        descriptor,
+        params,
+        steps,
+        id,
+    })
+}
+
+// ----- T E S T S ------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normal_field() -> Result<(), Error> {
+        // The derived constants of WGS84, according to NIMA (2000)
+        let ellps = Ellipsoid::named("WGS84")?;
+        let constants = normal_constants(&ellps);
+        assert_eq!(constants, (3.986004418e14, 7.292115e-5));
+        let (gamma_e, gamma_p, zonals) = super::normal_field(&ellps, constants, 4);
+        let [ge, gp] = Ellipsoid::WGS84_NORMAL_GRAVITY;
+        assert!((gamma_e - ge).abs() < 1e-9);
+        assert!((gamma_p - gp).abs() < 1e-9);
+        assert!((zonals[0] + 0.484166774985e-3).abs() < 1e-12);
+        assert!((zonals[1] - 0.790303733511e-6).abs() < 1e-15);
+
+        // ...and of GRS80, according to Moritz (2000), where J2 = 108263e-8
+        let ellps = Ellipsoid::named("GRS80")?;
+        let constants = normal_constants(&ellps);
+        assert_eq!(constants, (3.986005e14, 7.292115e-5));
+        let (gamma_e, gamma_p, zonals) = super::normal_field(&ellps, constants, 2);
+        let [ge, gp] = Ellipsoid::GRS80_NORMAL_GRAVITY;
+        assert!((gamma_e - ge).abs() < 1e-9);
+        assert!((gamma_p - gp).abs() < 1e-9);
+        assert!((zonals[0] + 108263e-8 / 5_f64.sqrt()).abs() < 1e-12);
+
+        // Other ellipsoids get the constants of WGS84
+        let ellps = Ellipsoid::named("intl")?;
+        assert_eq!(normal_constants(&ellps), (3.986004418e14, 7.292115e-5));
+        Ok(())
+    }
+
+    #[test]
+    fn geoid() -> Result<(), Error> {
+        let mut ctx = Minimal::default();
+
+        // The EGM96 coefficients to degree and order 3. The expected values
+        // are from an independent evaluation, by closed form expressions for
+        // the Legendre functions
+        let op = ctx.op("geoid model=test.egm ellps=WGS84")?;
+        let mut data = [
+            Coord::geo(55., 12., 100., 0.),
+            Coord::geo(0., 0., 100., 0.),
+            Coord::geo(-30., -100., 100., 0.),
+        ];
+        let expected = [100. - 46.470936, 100. - 18.792333, 100. - 6.910197];
+        ctx.apply(op, Fwd, &mut data)?;
+        for i in 0..3 {
+            assert!(
+                (data[i][2] - expected[i]).abs() < 1e-6,
+                "{i}: {}",
+                data[i][2]
+            );
+        }
+        ctx.apply(op, Inv, &mut data)?;
+        for coord in data {
+            assert!((coord[2] - 100.).abs() < 1e-9);
+        }
+
+        // Truncated to degree 2, and with a zero degree term
+        let op = ctx.op("geoid model=test.egm ellps=WGS84 degree=2 zero_degree=-0.53")?;
+        let mut data = [Coord::geo(0., 0., 0., 0.)];
+        ctx.apply(op, Fwd, &mut data)?;
+        assert!(
+            (data[0][2] + 30.170901 - 0.53).abs() < 1e-6,
+            "{}",
+            data[0][2]
+        );
+
+        assert!(matches!(
+            ctx.op("geoid model=test.egm degree=4"),
+            Err(Error::BadParam(..))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn high_degree() {
+        // By the addition theorem, with the coefficients of degree N given by
+        // the Legendre functions of a point on the equator, the harmonic sum
+        // is (2N+1) P_N(cos psi), where psi is the angular distance to that
+        // point. At 65 degrees, u^m underflows for m > 800 or so, while the
+        // Legendre functions of order up to around 900 are of order unity
+        const N: usize = 2190;
+        let mut c = vec![0.; index(N, N) + 1];
+        let mut s = c.clone();
+        let lam = 0.3_f64;
+        let mut pmm = 1.;
+        for m in 0..=N {
+            if m == 1 {
+                pmm *= 3_f64.sqrt();
+            } else if m > 1 {
+                pmm *= ((2 * m + 1) as f64 / (2 * m) as f64).sqrt();
+            }
+            if (N - m) % 2 == 1 {
+                continue;
+            }
+            // At the equator, the recursion degenerates to the b term
+            let mut pnm = pmm;
+            for n in (m + 2..=N).step_by(2) {
+                let (nf, mf) = (n as f64, m as f64);
+                pnm *= -((2. * nf + 1.) * (nf + mf - 1.) * (nf - mf - 1.)
+                    / ((nf - mf) * (nf + mf) * (2. * nf - 3.)))
+                    .sqrt();
+            }
+            c[index(N, m)] = pnm * (m as f64 * lam).cos();
+            s[index(N, m)] = pnm * (m as f64 * lam).sin();
+        }
+
+        let qn = vec![1.; N + 1];
+        let mut sums = vec![0.; N + 1];
+        for (phi, dlam) in [(65_f64, 0.2_f64), (80., 0.7), (-89., 2.)] {
+            let (t, u) = phi.to_radians().sin_cos();
+            let sum = harmonic_sum(&c, &s, N, (t, u, lam + dlam), &qn, &mut sums);
+
+            let x = u * dlam.cos();
+            let (mut p1, mut p) = (1., x);
+            for n in 1..N {
+                let nf = n as f64;
+                (p1, p) = (p, ((2. * nf + 1.) * x * p - nf * p1) / (nf + 1.));
+            }
+            let expected = (2 * N + 1) as f64 * p;
+            assert!(
+                (sum - expected).abs() < 1e-9 * (2 * N + 1) as f64,
+                "{phi}: {sum} {expected}"
+            );
+        }
+    }
+
+    #[test]
+    fn model_files() -> Result<(), Error> {
+        let egm = b"2 0 -0.484165371736D-03 0.0 0.3561D-10 0.0\n 2 1 1.0E-9 2.0E-9";
+        let (degree, c, s) = read_model(egm)?;
+        assert_eq!(degree, 2);
+        assert_eq!(c[index(2, 0)], -0.484165371736e-3);
+        assert_eq!((c[index(2, 1)], s[index(2, 1)]), (1e-9, 2e-9));
+
+        let icgem = b"max_degree 2\nkey L M C S sigma_C sigma_S\nend_of_head\n\
+            gfc 2 2 2.43914352398e-06 -1.40016683654e-06 0 0";
+        let (degree, c, s) = read_model(icgem)?;
+        assert_eq!(degree, 2);
+        assert_eq!(
+            (c[index(2, 2)], s[index(2, 2)]),
+            (2.43914352398e-6, -1.40016683654e-6)
+        );
+
+        assert!(read_model(b"# nothing here").is_err());
+        Ok(())
+    }
+}
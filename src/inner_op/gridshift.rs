@@ -5,11 +5,16 @@ use super::*;
 
 fn fwd(op: &Op, _ctx: &dyn Context, operands: &mut [Coord]) -> Result<usize, Error> {
     let grid = &op.params.grids["grid"];
+    let padding = op.params.real("padding")?;
     let mut successes = 0_usize;
 
     // Geoid
     if grid.bands == 1 {
         for coord in operands {
+            if !grid.contains_with_margin(*coord, padding) {
+                *coord = Coord::failed(PointStatus::OutOfGrid);
+                continue;
+            }
             let d = grid.interpolation(coord, None);
             coord[2] -= d[0];
             successes += 1;
@@ -19,6 +24,10 @@ fn fwd(op: &Op, _ctx: &dyn Context, operands: &mut [Coord]) -> Result<usize, Err
 
     // Datum shift
     for coord in operands {
+        if !grid.contains_with_margin(*coord, padding) {
+            *coord = Coord::failed(PointStatus::OutOfGrid);
+            continue;
+        }
         let d = grid.interpolation(coord, None);
         if grid.bands == 1 {
             coord[2] -= d[0];
@@ -35,11 +44,16 @@ fn fwd(op: &Op, _ctx: &dyn Context, operands: &mut [Coord]) -> Result<usize, Err
 
 fn inv(op: &Op, _ctx: &dyn Context, operands: &mut [Coord]) -> Result<usize, Error> {
     let grid = &op.params.grids["grid"];
+    let padding = op.params.real("padding")?;
     let mut successes = 0_usize;
 
     // Geoid
     if grid.bands == 1 {
         for coord in operands {
+            if !grid.contains_with_margin(*coord, padding) {
+                *coord = Coord::failed(PointStatus::OutOfGrid);
+                continue;
+            }
             let t = grid.interpolation(coord, None);
             coord[2] += t[0];
             successes += 1;
@@ -49,6 +63,10 @@ fn inv(op: &Op, _ctx: &dyn Context, operands: &mut [Coord]) -> Result<usize, Err
 
     // Datum shift - here we need to iterate in the inverse case
     for coord in operands {
+        if !grid.contains_with_margin(*coord, padding) {
+            *coord = Coord::failed(PointStatus::OutOfGrid);
+            continue;
+        }
         let mut t = *coord - grid.interpolation(coord, None);
 
         for _ in 0..10 {
@@ -84,12 +102,21 @@ pub fn new(parameters: &RawParameters, ctx: &dyn Context) -> Result<Op, Error> {
     let grid_file_name = params.text("grids")?;
     let buf = ctx.get_blob(&grid_file_name)?;
 
-    let grid = Grid::gravsoft(&buf)?;
+    let grid = Grid::gravsoft(&buf).map_err(|e| e.context(format!("grid {grid_file_name}")))?;
+    if grid.bands > 2 {
+        return Err(Error::BadParam(
+            "grids".to_string(),
+            format!("{grid_file_name} is a velocity grid, cf. `deformation`"),
+        ));
+    }
     params.grids.insert("grid", grid);
 
     let fwd = InnerOp(fwd);
     let inv = InnerOp(inv);
-    let descriptor = OpDescriptor::new(def, fwd, Some(inv));
+    let mut descriptor = OpDescriptor::new(def, fwd, Some(inv));
+    descriptor.angular_io = (true, true);
+    let provenance = Provenance::new(ResourceKind::Grid, &grid_file_name, &buf);
+    descriptor.provenance.push(provenance);
     let steps = Vec::<Op>::new();
     let id = OpHandle::new();
 
@@ -123,6 +150,12 @@ mod tests {
         assert!((data[0][0] - cph[0]).abs() < 1e-10);
         assert!((data[0][1] - cph[1]).abs() < 1e-10);
 
+        // Outside of the grid, even when padded by half a grid cell
+        let mut data = [Coord::geo(45., 12., 0., 0.)];
+        assert_eq!(
+            ctx.apply_with_status(op, Fwd, &mut data)?,
+            [PointStatus::OutOfGrid]
+        );
         Ok(())
     }
 }
@@ -0,0 +1,189 @@
+//! Orthometric (Helmert) heights to normal (Molodensky) heights, and v.v.
+use super::*;
+
+// The GRS80 normal gravity field: The equatorial and polar normal gravity, and
+// m = ω²a²b/GM. For WGS84, the differences are immaterial here
+const GRAVITY: [f64; 2] = Ellipsoid::GRS80_NORMAL_GRAVITY;
+const M: f64 = 0.003_449_786_003_08;
+
+// The mean gravity along the plumb line, relative to the normal gravity on the
+// ellipsoid, decreases with the height by the free air gradient (0.3086 mGal/m),
+// but increases by the Bouguer plate (0.1119 mGal/m) and the Poincaré-Prey
+// reduction from the surface (0.0424 mGal/m), i.e. it decreases by 0.1543 mGal/m
+const MEAN_GRAVITY_GRADIENT: f64 = -0.1543e-5;
+
+// The mean gravity along the plumb line, ḡ, and the mean normal gravity along
+// the normal plumb line, γ̄, for a point at the latitude `phi` and the height
+// `h`, following [Heiskanen & Moritz, 1967](crate::Bibliography::Hei67),
+// eqs. 4-26 and 4-60
+fn mean_gravity(op: &Op, phi: f64, anomaly: f64, h: f64) -> f64 {
+    let gamma = op.params.ellps[0].normal_gravity(phi, GRAVITY);
+    gamma + MEAN_GRAVITY_GRADIENT * h + anomaly
+}
+
+fn mean_normal_gravity(op: &Op, phi: f64, h: f64) -> f64 {
+    let ellps = op.params.ellps[0];
+    let (a, f) = (ellps.semimajor_axis(), ellps.flattening());
+    let gamma = ellps.normal_gravity(phi, GRAVITY);
+    let s = phi.sin();
+    gamma * (1. - (1. + f + M - 2. * f * s * s) * h / a + h * h / (a * a))
+}
+
+// The constant anomaly (in m/s²), and the grid padding, as prepared by the
+// constructor
+struct Prepared {
+    anomaly: f64,
+    padding: f64,
+}
+
+// The Bouguer gravity anomaly at the point, in m/s²: By interpolation in the
+// grid, if given, otherwise the constant `anomaly`. None if outside of the grid
+fn bouguer_anomaly(grid: Option<&Grid>, params: &Prepared, coord: &Coord) -> Option<f64> {
+    let Some(grid) = grid else {
+        return Some(params.anomaly);
+    };
+    if !grid.contains_with_margin(*coord, params.padding) {
+        return None;
+    }
+    Some(grid.interpolation(coord, None)[0] * 1e-5)
+}
+
+// ----- F O R W A R D --------------------------------------------------------------
+
+// The geopotential number C = ḡH = γ̄H*, solved for H*, the normal height.
+// Since γ̄ depends on H*, we iterate, converging at the micrometre level after
+// a couple of iterations
+fn fwd(op: &Op, _ctx: &dyn Context, operands: &mut [Coord]) -> Result<usize, Error> {
+    let (grid, params) = (op.params.grids.get("grid"), op.params.prepared()?);
+    let mut successes = 0_usize;
+    for coord in operands {
+        let Some(anomaly) = bouguer_anomaly(grid, params, coord) else {
+            *coord = Coord::failed(PointStatus::OutOfGrid);
+            continue;
+        };
+        let (phi, h) = (coord[1], coord[2]);
+        let c = mean_gravity(op, phi, anomaly, h) * h;
+        let mut normal = h;
+        for _ in 0..3 {
+            normal = c / mean_normal_gravity(op, phi, normal);
+        }
+        coord[2] = normal;
+        successes += 1;
+    }
+    Ok(successes)
+}
+
+// ----- I N V E R S E --------------------------------------------------------------
+
+fn inv(op: &Op, _ctx: &dyn Context, operands: &mut [Coord]) -> Result<usize, Error> {
+    let (grid, params) = (op.params.grids.get("grid"), op.params.prepared()?);
+    let mut successes = 0_usize;
+    for coord in operands {
+        let Some(anomaly) = bouguer_anomaly(grid, params, coord) else {
+            *coord = Coord::failed(PointStatus::OutOfGrid);
+            continue;
+        };
+        let (phi, h) = (coord[1], coord[2]);
+        let c = mean_normal_gravity(op, phi, h) * h;
+        let mut orthometric = h;
+        for _ in 0..3 {
+            orthometric = c / mean_gravity(op, phi, anomaly, orthometric);
+        }
+        coord[2] = orthometric;
+        successes += 1;
+    }
+    Ok(successes)
+}
+
+// ----- C O N S T R U C T O R ------------------------------------------------------
+
+#[rustfmt::skip]
+pub const GAMUT: [OpParameter; 5] = [
+    OpParameter::Flag { key: "inv" },
+    OpParameter::Text { key: "ellps", default: Some("GRS80") },
+    OpParameter::Text { key: "grids", default: Some("") },
+    OpParameter::Real { key: "anomaly", default: Some(0.) },
+    OpParameter::Real { key: "padding", default: Some(0.5) },
+];
+
+pub fn new(parameters: &RawParameters, ctx: &dyn Context) -> Result<Op, Error> {
+    let def = &parameters.definition;
+    let mut params = ParsedParameters::new(parameters, &GAMUT)?;
+
+    // The Bouguer anomalies, in mGal, may be given by a grid
+    let grid_file_name = params.text("grids")?;
+    let mut provenance = None;
+    params.prepare(Prepared {
+        anomaly: params.real("anomaly")? * 1e-5,
+        padding: params.real("padding")?,
+    });
+    if !grid_file_name.is_empty() {
+        let buf = ctx.get_blob(&grid_file_name)?;
+        let grid = Grid::gravsoft(&buf).map_err(|e| e.context(format!("grid {grid_file_name}")))?;
+        if grid.bands != 1 {
+            return Err(Error::BadParam(
+                "grids".to_string(),
+                format!("{grid_file_name} is not a gravity anomaly grid"),
+            ));
+        }
+        params.grids.insert("grid", grid);
+        provenance = Some(Provenance::new(ResourceKind::Grid, &grid_file_name, &buf));
+    }
+
+    let fwd = InnerOp(fwd);
+    let inv = InnerOp(inv);
+    let mut descriptor = OpDescriptor::new(def, fwd, Some(inv));
+    descriptor.angular_io = (true, true);
+    descriptor.provenance.extend(provenance);
+    let steps = Vec::<Op>::new();
+    let id = OpHandle::new();
+
+    Ok(Op {
+        descriptor,
+        params,
+        steps,
+        id,
+    })
+}
+
+// ----- T E S T S ------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normal_height() -> Result<(), Error> {
+        let mut ctx = Minimal::default();
+
+        // With a Bouguer anomaly of -100 mGal, the separation between the
+        // geoid and the quasigeoid is N - ζ = H* - H ≈ Δg H / γ̄, i.e. -10 cm
+        // at 1000 m. Without an anomaly, it is negligible
+        let op = ctx.op("normal_height anomaly=-100")?;
+        let mut data = [Coord::geo(45., 10., 1000., 0.)];
+        ctx.apply(op, Fwd, &mut data)?;
+        let separation = -100e-5 * 1000. / 9.806;
+        assert!((data[0][2] - 1000. - separation).abs() < 1e-3);
+
+        ctx.apply(op, Inv, &mut data)?;
+        assert!((data[0][2] - 1000.).abs() < 1e-9);
+
+        let op = ctx.op("normal_height")?;
+        let mut data = [Coord::geo(45., 10., 1000., 0.)];
+        ctx.apply(op, Fwd, &mut data)?;
+        assert!((data[0][2] - 1000.).abs() < 1e-3);
+
+        // The anomalies may also be given by a grid (here, one masquerading as
+        // an anomaly grid, with values from 54 to 58 mGal)
+        let op = ctx.op("normal_height grids=test.geoid")?;
+        let mut data = [Coord::geo(55., 12., 1000., 0.)];
+        ctx.apply(op, Fwd, &mut data)?;
+        assert!((data[0][2] - 1000. - 55.12e-5 * 1000. / 9.815).abs() < 1e-3);
+        let mut data = [Coord::geo(45., 12., 1000., 0.)];
+        assert_eq!(
+            ctx.apply_with_status(op, Fwd, &mut data)?,
+            [PointStatus::OutOfGrid]
+        );
+        Ok(())
+    }
+}
@@ -6,119 +6,46 @@
 /// While generally also applicable to 2D coordinates, this functionality is not yet
 /// implemented.
 use super::*;
+use crate::kernels::{Helmert, Kernel};
 
 // ----- C O M M O N -------------------------------------------------------------------
 
-// The forward and inverse implementations are virtually identical, so we combine them
-// into one, with the functionality selected from the "direction" parameter.
-
-fn helmert_common(
-    op: &Op,
-    _ctx: &dyn Context,
-    operands: &mut [Coord],
-    direction: Direction,
-) -> usize {
-    // Translation, Rotation, Scale
-    let T = op.params.series("T").unwrap();
-    let R = op.params.series("R").unwrap();
-    let S = op.params.real("S").unwrap();
-
-    // ... and their time evolution
-    let DT = op.params.series("DT").unwrap();
-    let DR = op.params.series("DR").unwrap();
-    let DS = op.params.real("DS").unwrap();
-
-    // The precomputed rotation matrix
-    let M = op.params.series("ROTFLAT").unwrap();
-    let mut ROT = [[M[0], M[1], M[2]], [M[3], M[4], M[5]], [M[6], M[7], M[8]]];
-
-    // Predicates
-    let rotated = op.params.boolean("rotated");
-    let dynamic = op.params.boolean("dynamic");
-    let fixed_t = op.params.boolean("fixed_time");
-    let exact = op.params.boolean("exact");
-    let position_vector = op.params.boolean("position_vector");
-
-    let epoch = op.params.real("t_epoch").unwrap_or(0.);
-
-    let mut TT = [T[0], T[1], T[2]];
-    let mut SS = S;
-
-    let mut prev_t = std::f64::NAN;
-    let n = operands.len();
-    for c in operands {
-        // Time varying case?
-        if dynamic && !fixed_t {
-            // Necessary to update parameters?
-            #[allow(clippy::float_cmp)]
-            if c[3] != prev_t {
-                prev_t = c[3];
-                let dt = c[3] - epoch;
-                TT[0] += dt * DT[0];
-                TT[1] += dt * DT[1];
-                TT[2] += dt * DT[2];
-                if rotated {
-                    let RR = [R[0] + dt * DR[0], R[1] + dt * DR[1], R[2] + dt * DR[2]];
-                    ROT = rotation_matrix(&RR, exact, position_vector);
-                }
-                SS = S + dt * DS;
-            }
-        }
-
-        // ----- Forward direction -----
-
-        if direction == Direction::Fwd {
-            if rotated {
-                // Rotate
-                let x = c[0] * ROT[0][0] + c[1] * ROT[0][1] + c[2] * ROT[0][2];
-                let y = c[0] * ROT[1][0] + c[1] * ROT[1][1] + c[2] * ROT[1][2];
-                let z = c[0] * ROT[2][0] + c[1] * ROT[2][1] + c[2] * ROT[2][2];
-
-                // scale and offset
-                c[0] = SS * x + TT[0];
-                c[1] = SS * y + TT[1];
-                c[2] = SS * z + TT[2];
-                continue;
-            }
-
-            // scale and offset without rotation
-            c[0] = SS * c[0] + TT[0];
-            c[1] = SS * c[1] + TT[1];
-            c[2] = SS * c[2] + TT[2];
-            continue;
-        }
-
-        // ----- Inverse direction -----
-
-        // Deoffset and unscale
-        let x = (c[0] - TT[0]) / SS;
-        let y = (c[1] - TT[1]) / SS;
-        let z = (c[2] - TT[2]) / SS;
-
-        // Inverse rotation by transposed multiplication
-        if rotated {
-            c[0] = x * ROT[0][0] + y * ROT[1][0] + z * ROT[2][0];
-            c[1] = x * ROT[0][1] + y * ROT[1][1] + z * ROT[2][1];
-            c[2] = x * ROT[0][2] + y * ROT[1][2] + z * ROT[2][2];
-        } else {
-            c[0] = x;
-            c[1] = y;
-            c[2] = z;
-        }
+// The transformation itself is implemented by `crate::kernels::Helmert`, here
+// set up from the parameters precomputed by the constructor
+fn kernel(op: &Op) -> Result<Helmert, Error> {
+    let params = &op.params;
+    let mut kernel = Helmert::new(
+        params.series_array("T")?,
+        params.series_array("R")?,
+        params.real("S")?,
+    );
+    if !params.boolean("position_vector") {
+        kernel = kernel.coordinate_frame();
     }
-    n
+    if params.boolean("exact") {
+        kernel = kernel.exact();
+    }
+    if params.boolean("dynamic") && !params.boolean("fixed_time") {
+        kernel = kernel.dynamic(
+            params.series_array("DT")?,
+            params.series_array("DR")?,
+            params.real("DS")?,
+            params.real("t_epoch").unwrap_or(0.),
+        );
+    }
+    Ok(kernel)
 }
 
 // ----- F O R W A R D --------------------------------------------------------------
 
 fn helmert_fwd(op: &Op, _ctx: &dyn Context, operands: &mut [Coord]) -> Result<usize, Error> {
-    Ok(helmert_common(op, _ctx, operands, Direction::Fwd))
+    Ok(kernel(op)?.fwd(operands))
 }
 
 // ----- I N V E R S E --------------------------------------------------------------
 
 fn helmert_inv(op: &Op, _ctx: &dyn Context, operands: &mut [Coord]) -> Result<usize, Error> {
-    Ok(helmert_common(op, _ctx, operands, Direction::Inv))
+    Ok(kernel(op)?.inv(operands))
 }
 
 // ----- C O N S T R U C T O R ------------------------------------------------------
@@ -156,10 +83,10 @@ pub const GAMUT: [OpParameter; 19] = [
     OpParameter::Real { key: "ds", default: Some(0f64) },  // TODO: scale by 1e-6
 
     // Epoch - "beginning of time for this transformation"
-    OpParameter::Real { key: "t_epoch", default: Some(std::f64::NAN) },
+    OpParameter::Real { key: "t_epoch", default: Some(f64::NAN) },
 
     // Fixed observation time - ignore the fourth coordinate.
-    OpParameter::Real { key: "t_obs", default: Some(std::f64::NAN) },
+    OpParameter::Real { key: "t_obs", default: Some(f64::NAN) },
 ];
 
 pub fn new(parameters: &RawParameters, _ctx: &dyn Context) -> Result<Op, Error> {
@@ -243,7 +170,6 @@ pub fn new(parameters: &RawParameters, _ctx: &dyn Context) -> Result<Op, Error>
         }
     }
 
-    let exact = params.boolean("exact");
     params.series.insert("T", Vec::from(T));
     params.series.insert("DT", Vec::from(DT));
     params.series.insert("R", Vec::from(R));
@@ -251,16 +177,6 @@ pub fn new(parameters: &RawParameters, _ctx: &dyn Context) -> Result<Op, Error>
     params.real.insert("S", S);
     params.real.insert("DS", DS);
 
-    // The rotation matrix is a 3x3 symmetric matrix
-    let ROT = rotation_matrix(&R, exact, position_vector);
-
-    // We need to turn the 3x3 into 1x9 to make it fit into the "series" store
-    let mut ROTFLAT = Vec::from(ROT[0]);
-    ROTFLAT.extend(ROT[1].iter());
-    ROTFLAT.extend(ROT[2].iter());
-    assert_eq!(ROTFLAT.len(), 9);
-    params.series.insert("ROTFLAT", ROTFLAT);
-
     let fwd = InnerOp(helmert_fwd);
     let inv = InnerOp(helmert_inv);
     let descriptor = OpDescriptor::new(def, fwd, Some(inv));
@@ -274,60 +190,6 @@ pub fn new(parameters: &RawParameters, _ctx: &dyn Context) -> Result<Op, Error>
     })
 }
 
-// ----- R O T A T I O N   M A T R I X   B U I L D E R ---------------------------------
-
-// Based on Karsten Engsager's implementation in set_dtm_1.c (trlib),
-// but adds optional small-angle approximation, and selection between
-// the "position vector" and "coordinate frame" rotation conventions.
-//
-// TO' = scale * [ROTZ * ROTY * ROTX] * FROM' + [translation x, y, z]'
-//
-//        | cz sz 0 |           | cy 0 -sy |           | 1   0  0 |
-// ROTZ = |-sz cz 0 |,   ROTY = | 0  1   0 |,   ROTX = | 0  cx sx |
-//        |  0  0 1 |           | sy 0  cy |           | 0 -sx cx |
-//
-fn rotation_matrix(r: &[f64], exact: bool, position_vector: bool) -> [[f64; 3]; 3] {
-    let (rx, ry, rz) = (r[0], r[1], r[2]);
-
-    // Small-angle approximations: sx = sin(rx) = rx,  cx = cos(rx) = 1,  etc.
-    let (mut sx, mut sy, mut sz) = (rx, ry, rz);
-    let (mut cx, mut cy, mut cz) = (1.0, 1.0, 1.0);
-
-    // Leave out the second order infinitesimals in the rotation
-    // matrix elements, when using small-angle approximations
-    if exact {
-        (sx, cx) = rx.sin_cos();
-        (sy, cy) = ry.sin_cos();
-        (sz, cz) = rz.sin_cos();
-    }
-
-    let r11 = cy * cz;
-    let mut r12 = cx * sz;
-    let mut r13 = -cx * sy * cz;
-
-    let r21 = -cy * sz;
-    let mut r22 = cx * cz;
-    let mut r23 = sx * cz;
-
-    let r31 = sy;
-    let r32 = -sx * cy;
-    let r33 = cx * cy;
-
-    // But apply the second order terms in the exact case
-    if exact {
-        r12 += sx * sy * cz;
-        r13 += sx * sz;
-
-        r22 -= sx * sy * sz;
-        r23 += cx * sy * sz;
-    }
-
-    if position_vector {
-        return [[r11, r21, r31], [r12, r22, r32], [r13, r23, r33]];
-    }
-    [[r11, r12, r13], [r21, r22, r23], [r31, r32, r33]]
-}
-
 // ----- T E S T S ------------------------------------------------------------------
 
 #[cfg(test)]
@@ -158,10 +158,83 @@ fn inv(op: &Op, _ctx: &dyn Context, operands: &mut [Coord]) -> Result<usize, Err
     Ok(successes)
 }
 
+// ----- S P H E R I C A L -------------------------------------------------------------
+
+// On the sphere, the authalic latitude is the geographical, and all aspects are
+// covered by the same closed form expressions, cf. Snyder (1987), pp. 185-186
+
+fn spherical_fwd(op: &Op, _ctx: &dyn Context, operands: &mut [Coord]) -> Result<usize, Error> {
+    let lat_0 = op.params.lat(0);
+    let lon_0 = op.params.lon(0);
+    let x_0 = op.params.x(0);
+    let y_0 = op.params.y(0);
+    let a = op.params.ellps(0).semimajor_axis();
+    let (sin_lat_0, cos_lat_0) = lat_0.sin_cos();
+
+    let mut successes = 0_usize;
+    for coord in operands {
+        let (sin_lat, cos_lat) = coord[1].sin_cos();
+        let (sin_lon, cos_lon) = (coord[0] - lon_0).sin_cos();
+
+        // The antipode of the center maps to a circle, not a point
+        let factor = 1.0 + sin_lat_0 * sin_lat + cos_lat_0 * cos_lat * cos_lon;
+        if factor < EPS10 {
+            coord[0] = PointStatus::OutOfDomain.nan();
+            coord[1] = PointStatus::OutOfDomain.nan();
+            continue;
+        }
+
+        let k = a * (2.0 / factor).sqrt();
+        coord[0] = x_0 + k * cos_lat * sin_lon;
+        coord[1] = y_0 + k * (cos_lat_0 * sin_lat - sin_lat_0 * cos_lat * cos_lon);
+        successes += 1;
+    }
+
+    Ok(successes)
+}
+
+fn spherical_inv(op: &Op, _ctx: &dyn Context, operands: &mut [Coord]) -> Result<usize, Error> {
+    let lat_0 = op.params.lat(0);
+    let lon_0 = op.params.lon(0);
+    let x_0 = op.params.x(0);
+    let y_0 = op.params.y(0);
+    let a = op.params.ellps(0).semimajor_axis();
+    let (sin_lat_0, cos_lat_0) = lat_0.sin_cos();
+
+    let mut successes = 0_usize;
+    for coord in operands {
+        let x = coord[0] - x_0;
+        let y = coord[1] - y_0;
+        let rho = x.hypot(y);
+        if rho < EPS10 {
+            coord[0] = lon_0;
+            coord[1] = lat_0;
+            successes += 1;
+            continue;
+        }
+
+        let asin_argument = 0.5 * rho / a;
+        if asin_argument > 1.0 {
+            warn!("LAEA: ({}, {}) outside domain", coord[0], coord[1]);
+            coord[0] = PointStatus::OutOfDomain.nan();
+            coord[1] = PointStatus::OutOfDomain.nan();
+            continue;
+        }
+
+        let (sin_c, cos_c) = (2.0 * asin_argument.asin()).sin_cos();
+        let sin_lat = cos_c * sin_lat_0 + y * sin_c * cos_lat_0 / rho;
+        coord[1] = sin_lat.clamp(-1.0, 1.0).asin();
+        coord[0] = lon_0 + (x * sin_c).atan2(rho * cos_lat_0 * cos_c - y * sin_lat_0 * sin_c);
+        successes += 1;
+    }
+
+    Ok(successes)
+}
+
 // ----- C O N S T R U C T O R ---------------------------------------------------------
 
 #[rustfmt::skip]
-pub const GAMUT: [OpParameter; 6] = [
+pub const GAMUT: [OpParameter; 7] = [
     OpParameter::Flag { key: "inv" },
     OpParameter::Text { key: "ellps", default: Some("GRS80") },
 
@@ -170,9 +243,13 @@ pub const GAMUT: [OpParameter; 6] = [
 
     OpParameter::Real { key: "x_0",   default: Some(0_f64) },
     OpParameter::Real { key: "y_0",   default: Some(0_f64) },
+
+    // Cf. the same flag in tmerc: Also here, `lon - lon_0` is only used
+    // through its sine and cosine, so wrapping makes no difference
+    OpParameter::Flag { key: "wrap" },
 ];
 
-pub fn new(parameters: &RawParameters, _ctx: &dyn Context) -> Result<Op, Error> {
+pub fn new(parameters: &RawParameters, ctx: &dyn Context) -> Result<Op, Error> {
     let def = &parameters.definition;
     let mut params = ParsedParameters::new(parameters, &GAMUT)?;
 
@@ -230,10 +307,19 @@ pub fn new(parameters: &RawParameters, _ctx: &dyn Context) -> Result<Op, Error>
     params.real.insert("rq", rq);
     params.real.insert("d", d);
 
-    let authalic = ellps.coefficients_for_authalic_latitude_computations();
+    let inputs = [ellps.semimajor_axis(), ellps.flattening()];
+    let authalic = ctx.fourier_coefficients("authalic", &inputs, &|| {
+        ellps.coefficients_for_authalic_latitude_computations()
+    });
     params.fourier_coefficients.insert("authalic", authalic);
 
-    let descriptor = OpDescriptor::new(def, InnerOp(fwd), Some(InnerOp(inv)));
+    let (fwd, inv) = if es == 0.0 {
+        (InnerOp(spherical_fwd), InnerOp(spherical_inv))
+    } else {
+        (InnerOp(fwd), InnerOp(inv))
+    };
+    let mut descriptor = OpDescriptor::new(def, fwd, Some(inv));
+    descriptor.angular_io = (true, false);
     let steps = Vec::<Op>::new();
     let id = OpHandle::new();
     Ok(Op {
@@ -275,4 +361,63 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn laea_spherical() -> Result<(), Error> {
+        let mut ctx = Minimal::default();
+        let geo = [
+            Coord::geo(50.0, 5.0, 0.0, 0.0),
+            Coord::geo(-30.0, 100.0, 0.0, 0.0),
+            Coord::geo(0.0, -20.0, 0.0, 0.0),
+        ];
+        let sphere =
+            |lat_0: f64, lon_0: f64| format!("laea ellps=sphere lat_0={lat_0} lon_0={lon_0}");
+
+        // The oblique and north polar aspects, compared to the ellipsoidal
+        // formulas for a nearly spherical ellipsoid, where these are not degenerate
+        for lat_0 in [52.0, 90.0] {
+            let op = ctx.op(&sphere(lat_0, 10.0))?;
+            let reference = ctx.op(&format!("laea ellps=6370997,1e15 lat_0={lat_0} lon_0=10"))?;
+            let mut operands = geo;
+            let mut expected = geo;
+            ctx.apply(op, Fwd, &mut operands)?;
+            ctx.apply(reference, Fwd, &mut expected)?;
+            for i in 0..operands.len() {
+                assert!(operands[i].hypot2(&expected[i]) < 1e-6);
+            }
+        }
+
+        // The equatorial and south polar aspects: Along the equator,
+        // x = 2R sin(lon/2), and along the central meridian, y = 2R cos(45° - lat/2)
+        let op = ctx.op(&sphere(0.0, 10.0))?;
+        let mut operands = geo;
+        ctx.apply(op, Fwd, &mut operands)?;
+        let x = 2.0 * 6370997.0 * (-15_f64).to_radians().sin();
+        assert!((operands[2][0] - x).abs() < 1e-6);
+        assert!(operands[2][1].abs() < 1e-6);
+
+        let op = ctx.op(&sphere(-90.0, 5.0))?;
+        let mut operands = geo;
+        ctx.apply(op, Fwd, &mut operands)?;
+        let y = 2.0 * 6370997.0 * 20_f64.to_radians().cos();
+        assert!(operands[0][0].abs() < 1e-6);
+        assert!((operands[0][1] - y).abs() < 1e-6);
+
+        // Roundtrips, for all aspects
+        for lat_0 in [52.0, 0.0, 90.0, -90.0] {
+            let op = ctx.op(&sphere(lat_0, 10.0))?;
+            let mut operands = geo;
+            assert_eq!(ctx.apply(op, Fwd, &mut operands)?, 3);
+            ctx.apply(op, Inv, &mut operands)?;
+            for i in 0..operands.len() {
+                assert!(operands[i].hypot2(&geo[i]) < 1e-12);
+            }
+        }
+
+        // The antipode of the center
+        let op = ctx.op(&sphere(52.0, 10.0))?;
+        let mut operands = [Coord::geo(-52.0, -170.0, 0.0, 0.0)];
+        assert_eq!(ctx.apply(op, Fwd, &mut operands)?, 0);
+        Ok(())
+    }
 }
@@ -17,6 +17,11 @@ fn fwd(op: &Op, _ctx: &dyn Context, operands: &mut [Coord]) -> Result<usize, Err
             coord[1] = ellps.latitude_geographic_to_reduced(coord[1]);
             successes += 1;
         }
+    } else if op.params.boolean("isometric") {
+        for coord in operands {
+            coord[1] = ellps.latitude_geographic_to_isometric(coord[1]);
+            successes += 1;
+        }
     } else if op.params.boolean("conformal") {
         let Some(coefficients) = op.params.fourier_coefficients.get("coefficients") else {
             return Ok(0);
@@ -65,6 +70,11 @@ fn inv(op: &Op, _ctx: &dyn Context, operands: &mut [Coord]) -> Result<usize, Err
             coord[1] = ellps.latitude_reduced_to_geographic(coord[1]);
             successes += 1;
         }
+    } else if op.params.boolean("isometric") {
+        for coord in operands {
+            coord[1] = ellps.latitude_isometric_to_geographic(coord[1]);
+            successes += 1;
+        }
     } else if op.params.boolean("conformal") {
         let Some(coefficients) = op.params.fourier_coefficients.get("coefficients") else {
             return Ok(0);
@@ -101,10 +111,11 @@ fn inv(op: &Op, _ctx: &dyn Context, operands: &mut [Coord]) -> Result<usize, Err
 
 // Example...
 #[rustfmt::skip]
-pub const GAMUT: [OpParameter; 7] = [
+pub const GAMUT: [OpParameter; 8] = [
     OpParameter::Flag { key: "inv" },
     OpParameter::Flag { key: "geocentric" },
     OpParameter::Flag { key: "reduced" },
+    OpParameter::Flag { key: "isometric" },
     OpParameter::Flag { key: "conformal" },
     OpParameter::Flag { key: "authalic" },
     OpParameter::Flag { key: "rectifying" },
@@ -113,7 +124,9 @@ pub const GAMUT: [OpParameter; 7] = [
 
 pub fn new(parameters: &RawParameters, ctx: &dyn Context) -> Result<Op, Error> {
     let mut op = Op::plain(parameters, InnerOp(fwd), InnerOp(inv), &GAMUT, ctx)?;
+    op.descriptor.angular_io = (true, true);
     let ellps = op.params.ellps[0];
+    let inputs = [ellps.semimajor_axis(), ellps.flattening()];
 
     let mut number_of_flags = 0_usize;
     if op.params.boolean("geocentric") {
@@ -122,29 +135,38 @@ pub fn new(parameters: &RawParameters, ctx: &dyn Context) -> Result<Op, Error> {
     if op.params.boolean("reduced") || op.params.boolean("parametric") {
         number_of_flags += 1;
     }
+    if op.params.boolean("isometric") {
+        number_of_flags += 1;
+    }
     if op.params.boolean("conformal") {
-        let coefficients = ellps.coefficients_for_conformal_latitude_computations();
+        let coefficients = ctx.fourier_coefficients("conformal", &inputs, &|| {
+            ellps.coefficients_for_conformal_latitude_computations()
+        });
         op.params
             .fourier_coefficients
             .insert("coefficients", coefficients);
         number_of_flags += 1;
     }
     if op.params.boolean("authalic") {
-        let coefficients = ellps.coefficients_for_authalic_latitude_computations();
+        let coefficients = ctx.fourier_coefficients("authalic", &inputs, &|| {
+            ellps.coefficients_for_authalic_latitude_computations()
+        });
         op.params
             .fourier_coefficients
             .insert("coefficients", coefficients);
         number_of_flags += 1;
     }
     if op.params.boolean("rectifying") {
-        let coefficients = ellps.coefficients_for_rectifying_latitude_computations();
+        let coefficients = ctx.fourier_coefficients("rectifying", &inputs, &|| {
+            ellps.coefficients_for_rectifying_latitude_computations()
+        });
         op.params
             .fourier_coefficients
             .insert("coefficients", coefficients);
         number_of_flags += 1;
     }
     if number_of_flags != 1 {
-        return Err(Error::MissingParam("latitude: must specify exactly one of flags authalic/conformal/geocentric/rectifying/reduced/parametric".to_string()));
+        return Err(Error::MissingParam("latitude: must specify exactly one of flags authalic/conformal/geocentric/isometric/rectifying/reduced/parametric".to_string()));
     }
 
     Ok(op)
@@ -184,11 +206,20 @@ mod tests {
         ctx.apply(op, Inv, &mut operands)?;
         assert!((operands[0][1].to_degrees() - 55.).abs() < 1e-12);
 
+        // Isometric (dimensionless, but here given in "degrees" for comparison
+        // with the value from the test in `ellipsoid/latitudes.rs`)
+        let op = ctx.op("latitude isometric ellps=GRS80")?;
+        let mut operands = [Coord::geo(45., 12., 0., 0.)];
+        ctx.apply(op, Fwd, &mut operands)?;
+        assert!((operands[0][1].to_degrees() - 50.227_465_815_385_806).abs() < 1e-12);
+        ctx.apply(op, Inv, &mut operands)?;
+        assert!((operands[0][1].to_degrees() - 45.).abs() < 1e-12);
+
         // Rectifying
         let op = ctx.op("latitude rectifying ellps=GRS80")?;
         let mut operands = [Coord::geo(55., 12., 0., 0.)];
         ctx.apply(op, Fwd, &mut operands)?;
-        assert!((operands[0][1].to_degrees() - 54.772_351_809_646_84).abs() < 1e-12);
+        assert!((operands[0][1].to_degrees() - 54.864_287_983_539_557).abs() < 1e-12);
         ctx.apply(op, Inv, &mut operands)?;
         assert!((operands[0][1].to_degrees() - 55.).abs() < 1e-12);
 
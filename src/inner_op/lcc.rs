@@ -18,17 +18,21 @@ fn fwd(op: &Op, _ctx: &dyn Context, operands: &mut [Coord]) -> Result<usize, Err
     let n = op.params.real("n")?;
     let c = op.params.real("c")?;
     let rho0 = op.params.real("rho0")?;
+    let wrap = op.params.boolean("wrap");
     let mut successes = 0_usize;
 
     for coord in operands {
-        let lam = coord[0] - lon_0;
+        let mut lam = coord[0] - lon_0;
+        if wrap {
+            lam = normalize_angle_symmetric(lam);
+        }
         let phi = coord[1];
         let mut rho = 0.;
 
         // Close to one of the poles?
         if (phi.abs() - FRAC_PI_2).abs() < EPS10 {
             if phi * n <= 0. {
-                *coord = Coord::nan();
+                *coord = Coord::failed(PointStatus::OutOfDomain);
                 continue;
             }
         } else {
@@ -79,7 +83,7 @@ fn inv(op: &Op, _ctx: &dyn Context, operands: &mut [Coord]) -> Result<usize, Err
         let ts0 = (rho / c).powf(1. / n);
         let phi = crate::math::pj_phi2(ts0, e);
         if phi.is_infinite() || phi.is_nan() {
-            *coord = Coord::nan();
+            *coord = Coord::failed(PointStatus::OutOfDomain);
             continue;
         }
         coord[0] = x.atan2(y) / n + lon_0;
@@ -93,7 +97,7 @@ fn inv(op: &Op, _ctx: &dyn Context, operands: &mut [Coord]) -> Result<usize, Err
 
 // Example...
 #[rustfmt::skip]
-pub const GAMUT: [OpParameter; 9] = [
+pub const GAMUT: [OpParameter; 10] = [
     OpParameter::Flag { key: "inv" },
     OpParameter::Text { key: "ellps", default: Some("GRS80") },
 
@@ -105,6 +109,7 @@ pub const GAMUT: [OpParameter; 9] = [
     OpParameter::Real { key: "k_0",   default: Some(1_f64) },
     OpParameter::Real { key: "x_0",   default: Some(0_f64) },
     OpParameter::Real { key: "y_0",   default: Some(0_f64) },
+    OpParameter::Flag { key: "wrap" },
 ];
 
 pub fn new(parameters: &RawParameters, _ctx: &dyn Context) -> Result<Op, Error> {
@@ -136,17 +141,17 @@ pub fn new(parameters: &RawParameters, _ctx: &dyn Context) -> Result<Op, Error>
 
     if (phi1 + phi2).abs() < EPS10 {
         return Err(Error::General(
-            "Lcc: Invalid value for lat_1 and lat_2: |lat_1 + lat_2| should be > 0",
+            "Lcc: Invalid value for lat_1 and lat_2: |lat_1 + lat_2| should be > 0".to_string(),
         ));
     }
     if sc.1.abs() < EPS10 || phi1.abs() >= FRAC_PI_2 {
         return Err(Error::General(
-            "Lcc: Invalid value for lat_1: |lat_1| should be < 90°",
+            "Lcc: Invalid value for lat_1: |lat_1| should be < 90°".to_string(),
         ));
     }
     if phi2.cos().abs() < EPS10 || phi2.abs() >= FRAC_PI_2 {
         return Err(Error::General(
-            "Lcc: Invalid value for lat_2: |lat_2| should be < 90°",
+            "Lcc: Invalid value for lat_2: |lat_2| should be < 90°".to_string(),
         ));
     }
 
@@ -161,12 +166,16 @@ pub fn new(parameters: &RawParameters, _ctx: &dyn Context) -> Result<Op, Error>
         let sc = phi2.sin_cos();
         n = (m1 / crate::math::pj_msfn(sc, es)).ln();
         if n == 0. {
-            return Err(Error::General("Lcc: Invalid value for eccentricity"));
+            return Err(Error::General(
+                "Lcc: Invalid value for eccentricity".to_string(),
+            ));
         }
         let ml2 = crate::math::ts(sc, e);
         let denom = (ml1 / ml2).ln();
         if denom == 0. {
-            return Err(Error::General("Lcc: Invalid value for eccentricity"));
+            return Err(Error::General(
+                "Lcc: Invalid value for eccentricity".to_string(),
+            ));
         }
         n /= denom;
     }
@@ -182,7 +191,8 @@ pub fn new(parameters: &RawParameters, _ctx: &dyn Context) -> Result<Op, Error>
     params.real.insert("rho0", rho0);
     params.lat[0] = lat_0;
 
-    let descriptor = OpDescriptor::new(def, InnerOp(fwd), Some(InnerOp(inv)));
+    let mut descriptor = OpDescriptor::new(def, InnerOp(fwd), Some(InnerOp(inv)));
+    descriptor.angular_io = (true, false);
     let steps = Vec::<Op>::new();
     let id = OpHandle::new();
     Ok(Op {
@@ -220,7 +230,7 @@ mod tests {
             Coord::raw(115005.41456620067765471, 224484.5143763388914522, 0., 0.),
         ];
 
-        let mut operands = geo.clone();
+        let mut operands = geo;
         op.apply(&ctx, &mut operands, Fwd)?;
         for i in 0..operands.len() {
             assert!(operands[i].hypot2(&projected[i]) < 2e-9);
@@ -249,7 +259,7 @@ mod tests {
             0.,
         )];
 
-        let mut operands = geo.clone();
+        let mut operands = geo;
         op.apply(&ctx, &mut operands, Fwd)?;
         for i in 0..operands.len() {
             assert!(operands[i].hypot2(&projected[i]) < 9e-9);
@@ -273,7 +283,7 @@ mod tests {
         let geo = [Coord::geo(40., 12., 0., 0.)];
         let projected = [Coord::raw(170800.011728740647, 557172.361112929415, 0., 0.)];
 
-        let mut operands = geo.clone();
+        let mut operands = geo;
         op.apply(&ctx, &mut operands, Fwd)?;
         for i in 0..operands.len() {
             assert!(operands[i].hypot2(&projected[i]) < 2e-9);
@@ -297,7 +307,7 @@ mod tests {
         let geo = [Coord::geo(40., 12., 0., 0.)];
         let projected = [Coord::raw(169863.026093938359, 554155.440793916583, 0., 0.)];
 
-        let mut operands = geo.clone();
+        let mut operands = geo;
         op.apply(&ctx, &mut operands, Fwd)?;
         for i in 0..operands.len() {
             assert!(operands[i].hypot2(&projected[i]) < 2e-9);
@@ -321,7 +331,7 @@ mod tests {
         let geo = [Coord::geo(40., 12., 0., 0.)];
         let projected = [Coord::raw(182208.026093938301, 622045.440793916583, 0., 0.)];
 
-        let mut operands = geo.clone();
+        let mut operands = geo;
         op.apply(&ctx, &mut operands, Fwd)?;
         for i in 0..operands.len() {
             assert!(operands[i].hypot2(&projected[i]) < 2e-9);
@@ -345,7 +355,7 @@ mod tests {
         let geo = [Coord::geo(40., 12., 0., 0.)];
         let projected = [Coord::raw(180509.395832998911, 616503.886385977501, 0., 0.)];
 
-        let mut operands = geo.clone();
+        let mut operands = geo;
         op.apply(&ctx, &mut operands, Fwd)?;
         for i in 0..operands.len() {
             assert!(operands[i].hypot2(&projected[i]) < 2e-9);
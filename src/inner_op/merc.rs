@@ -9,17 +9,20 @@ fn fwd(op: &Op, _ctx: &dyn Context, operands: &mut [Coord]) -> Result<usize, Err
     let k_0 = op.params.k[0];
     let x_0 = op.params.x[0];
     let y_0 = op.params.y[0];
-    let lat_0 = op.params.lat[0];
     let lon_0 = op.params.lon[0];
+    let wrap = op.params.boolean("wrap");
 
     let mut successes = 0_usize;
     for coord in operands {
         // Longitude
-        coord[0] = (coord[0] - lon_0) * k_0 * a - x_0;
+        let mut lon = coord[0] - lon_0;
+        if wrap {
+            lon = normalize_angle_symmetric(lon);
+        }
+        coord[0] = lon * k_0 * a + x_0;
 
         // Latitude
-        let lat = coord[1] + lat_0;
-        coord[1] = a * k_0 * ellps.latitude_geographic_to_isometric(lat) - y_0;
+        coord[1] = a * k_0 * ellps.latitude_geographic_to_isometric(coord[1]) + y_0;
 
         successes += 1;
     }
@@ -35,19 +38,60 @@ fn inv(op: &Op, _ctx: &dyn Context, operands: &mut [Coord]) -> Result<usize, Err
     let k_0 = op.params.k[0];
     let x_0 = op.params.x[0];
     let y_0 = op.params.y[0];
-    let lat_0 = op.params.lat[0];
     let lon_0 = op.params.lon[0];
 
     let mut successes = 0_usize;
     for coord in operands {
         // Easting -> Longitude
-        let x = coord[0] + x_0;
-        coord[0] = x / (a * k_0) - lon_0;
+        let x = coord[0] - x_0;
+        coord[0] = x / (a * k_0) + lon_0;
 
         // Northing -> Latitude
-        let y = coord[1] + y_0;
+        let y = coord[1] - y_0;
         let psi = y / (a * k_0);
-        coord[1] = ellps.latitude_isometric_to_geographic(psi) - lat_0;
+        coord[1] = ellps.latitude_isometric_to_geographic(psi);
+        successes += 1;
+    }
+
+    Ok(successes)
+}
+
+// ----- S P H E R I C A L -------------------------------------------------------------
+
+// On the sphere, the isometric latitude has the closed form asinh(tan(lat))
+fn spherical_fwd(op: &Op, _ctx: &dyn Context, operands: &mut [Coord]) -> Result<usize, Error> {
+    let a = op.params.ellps[0].semimajor_axis();
+    let k_0 = op.params.k[0];
+    let x_0 = op.params.x[0];
+    let y_0 = op.params.y[0];
+    let lon_0 = op.params.lon[0];
+    let wrap = op.params.boolean("wrap");
+
+    let mut successes = 0_usize;
+    for coord in operands {
+        let mut lon = coord[0] - lon_0;
+        if wrap {
+            lon = normalize_angle_symmetric(lon);
+        }
+        coord[0] = lon * k_0 * a + x_0;
+        coord[1] = a * k_0 * coord[1].tan().asinh() + y_0;
+        successes += 1;
+    }
+
+    Ok(successes)
+}
+
+fn spherical_inv(op: &Op, _ctx: &dyn Context, operands: &mut [Coord]) -> Result<usize, Error> {
+    let a = op.params.ellps[0].semimajor_axis();
+    let k_0 = op.params.k[0];
+    let x_0 = op.params.x[0];
+    let y_0 = op.params.y[0];
+    let lon_0 = op.params.lon[0];
+
+    let mut successes = 0_usize;
+    for coord in operands {
+        coord[0] = (coord[0] - x_0) / (a * k_0) + lon_0;
+        coord[1] = ((coord[1] - y_0) / (a * k_0)).sinh().atan();
         successes += 1;
     }
 
@@ -57,10 +101,12 @@ fn inv(op: &Op, _ctx: &dyn Context, operands: &mut [Coord]) -> Result<usize, Err
 // ----- C O N S T R U C T O R ---------------------------------------------------------
 
 #[rustfmt::skip]
-pub const GAMUT: [OpParameter; 8] = [
+pub const GAMUT: [OpParameter; 9] = [
     OpParameter::Flag { key: "inv" },
     OpParameter::Text { key: "ellps",  default: Some("GRS80") },
 
+    // The equator is the latitude of origin of the Mercator, so, as in PROJ,
+    // `lat_0` is accepted, but has no effect
     OpParameter::Real { key: "lat_0",  default: Some(0_f64) },
     OpParameter::Real { key: "lon_0",  default: Some(0_f64) },
     OpParameter::Real { key: "x_0",    default: Some(0_f64) },
@@ -68,6 +114,7 @@ pub const GAMUT: [OpParameter; 8] = [
 
     OpParameter::Real { key: "k_0",    default: Some(1_f64) },
     OpParameter::Real { key: "lat_ts", default: Some(0_f64) },
+    OpParameter::Flag { key: "wrap" },
 ];
 
 pub fn new(parameters: &RawParameters, _ctx: &dyn Context) -> Result<Op, Error> {
@@ -78,7 +125,7 @@ pub fn new(parameters: &RawParameters, _ctx: &dyn Context) -> Result<Op, Error>
     let lat_ts = params.real("lat_ts")?;
     if lat_ts.abs() > 90. {
         return Err(Error::General(
-            "Merc: Invalid value for lat_ts: |lat_ts| should be <= 90°",
+            "Merc: Invalid value for lat_ts: |lat_ts| should be <= 90°".to_string(),
         ));
     }
 
@@ -88,7 +135,13 @@ pub fn new(parameters: &RawParameters, _ctx: &dyn Context) -> Result<Op, Error>
         params.k[0] = sc.1 / (1. - ellps.eccentricity_squared() * sc.0 * sc.0).sqrt()
     }
 
-    let descriptor = OpDescriptor::new(def, InnerOp(fwd), Some(InnerOp(inv)));
+    let (fwd, inv) = if ellps.eccentricity_squared() == 0. {
+        (InnerOp(spherical_fwd), InnerOp(spherical_inv))
+    } else {
+        (InnerOp(fwd), InnerOp(inv))
+    };
+    let mut descriptor = OpDescriptor::new(def, fwd, Some(inv));
+    descriptor.angular_io = (true, false);
     let steps = Vec::<Op>::new();
     let id = OpHandle::new();
 
@@ -131,7 +184,7 @@ mod tests {
         ];
 
         // Forward
-        let mut operands = geo.clone();
+        let mut operands = geo;
         op.apply(&ctx, &mut operands, Fwd)?;
         for i in 0..operands.len() {
             assert!(operands[i].hypot2(&projected[i]) < 20e-9);
@@ -163,7 +216,7 @@ mod tests {
         )];
 
         // Forward
-        let mut operands = geo.clone();
+        let mut operands = geo;
         op.apply(&ctx, &mut operands, Fwd)?;
         for i in 0..operands.len() {
             assert!(operands[i].hypot2(&projected[i]) < 20e-9);
@@ -177,4 +230,77 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn merc_spherical() -> Result<(), Error> {
+        let ctx = Minimal::default();
+        let sphere = Op::new("merc ellps=sphere lat_ts=30", &ctx)?;
+
+        // The ellipsoidal formulas, for a nearly spherical ellipsoid
+        let reference = Op::new("merc ellps=6370997,1e15 lat_ts=30", &ctx)?;
+
+        let geo = [
+            Coord::geo(55., 12., 0., 0.),
+            Coord::geo(-80., -170., 0., 0.),
+            Coord::geo(0., 0., 0., 0.),
+        ];
+        let mut operands = geo;
+        let mut expected = geo;
+        sphere.apply(&ctx, &mut operands, Fwd)?;
+        reference.apply(&ctx, &mut expected, Fwd)?;
+        for i in 0..operands.len() {
+            assert!(operands[i].hypot2(&expected[i]) < 1e-6);
+        }
+
+        sphere.apply(&ctx, &mut operands, Inv)?;
+        for i in 0..operands.len() {
+            assert!(operands[i].hypot2(&geo[i]) < 1e-12);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn merc_false_origin() -> Result<(), Error> {
+        let ctx = Minimal::default();
+        let plain = Op::new("merc", &ctx)?;
+        let offset = Op::new("merc x_0=500000 y_0=1000000", &ctx)?;
+
+        let geo = [Coord::geo(55., 12., 0., 0.), Coord::geo(-1., -2., 0., 0.)];
+        let mut expected = geo;
+        plain.apply(&ctx, &mut expected, Fwd)?;
+
+        // The false easting and northing are added to the projected coordinates
+        let mut operands = geo;
+        offset.apply(&ctx, &mut operands, Fwd)?;
+        for i in 0..operands.len() {
+            assert!((operands[i][0] - expected[i][0] - 500000.).abs() < 1e-9);
+            assert!((operands[i][1] - expected[i][1] - 1000000.).abs() < 1e-9);
+        }
+
+        // ...and subtracted again by the inverse
+        offset.apply(&ctx, &mut operands, Inv)?;
+        for i in 0..operands.len() {
+            assert!(operands[i].hypot2(&geo[i]) < 20e-9);
+        }
+
+        // The latitude of origin is the equator, whatever lat_0, on the
+        // ellipsoid as well as on the sphere
+        for (definition, reference) in [
+            ("merc lat_0=30", "merc"),
+            ("merc ellps=sphere lat_0=30", "merc ellps=sphere"),
+        ] {
+            let op = Op::new(definition, &ctx)?;
+            let reference = Op::new(reference, &ctx)?;
+            let mut operands = geo;
+            let mut expected = geo;
+            op.apply(&ctx, &mut operands, Fwd)?;
+            reference.apply(&ctx, &mut expected, Fwd)?;
+            assert_eq!(operands, expected);
+            op.apply(&ctx, &mut operands, Inv)?;
+            for i in 0..operands.len() {
+                assert!(operands[i].hypot2(&geo[i]) < 20e-9);
+            }
+        }
+        Ok(())
+    }
 }
@@ -9,7 +9,11 @@ mod adapt;
 mod addone;
 mod btmerc;
 mod cart;
+mod deformation;
+mod geodesic;
+mod geoid;
 mod gridshift;
+mod heights;
 mod helmert;
 mod laea;
 mod latitude;
@@ -18,32 +22,43 @@ mod merc;
 mod molodensky;
 mod nmea;
 mod noop;
+mod normalize;
 pub(crate) mod pipeline;
 mod proj;
+mod time;
 mod tmerc;
 
+pub use tmerc::utm_zone;
+
 #[rustfmt::skip]
-const BUILTIN_OPERATORS: [(&str, OpConstructor); 20] = [
-    ("adapt",      OpConstructor(adapt::new)),
-    ("addone",     OpConstructor(addone::new)),
-    ("btmerc",     OpConstructor(btmerc::new)),
-    ("butm",       OpConstructor(btmerc::utm)),
-    ("cart",       OpConstructor(cart::new)),
-    ("gridshift",  OpConstructor(gridshift::new)),
-    ("helmert",    OpConstructor(helmert::new)),
-    ("laea",       OpConstructor(laea::new)),
-    ("latitude",   OpConstructor(latitude::new)),
-    ("lcc",        OpConstructor(lcc::new)),
-    ("merc",       OpConstructor(merc::new)),
-    ("molodensky", OpConstructor(molodensky::new)),
-    ("nmea",       OpConstructor(nmea::new)),
-    ("noop",       OpConstructor(noop::new)),
-    ("tmerc",      OpConstructor(tmerc::new)),
-    ("utm",        OpConstructor(tmerc::utm)),
-    ("pipeline",   OpConstructor(pipeline::new)),
-    ("pop",        OpConstructor(pipeline::pop)),
-    ("proj",       OpConstructor(proj::new)),
-    ("push",       OpConstructor(pipeline::push)),
+const BUILTIN_OPERATORS: [(&str, OpConstructor, &[OpParameter], &str); 27] = [
+    ("adapt",         OpConstructor(adapt::new),       &adapt::GAMUT,             "Adapt the axis order, signs and angular units of the coordinates"),
+    ("addone",        OpConstructor(addone::new),      &addone::GAMUT,            "Add 1 to the first coordinate element (for testing)"),
+    ("btmerc",        OpConstructor(btmerc::new),      &btmerc::GAMUT,            "Transverse Mercator, following Bowring (1989)"),
+    ("butm",          OpConstructor(btmerc::utm),      &btmerc::UTM_GAMUT,        "Universal Transverse Mercator, following Bowring (1989)"),
+    ("cart",          OpConstructor(cart::new),        &cart::GAMUT,              "Geographical to cartesian coordinates"),
+    ("deformation",   OpConstructor(deformation::new), &deformation::GAMUT,       "Kinematic datum shift, using a velocity grid"),
+    ("geodesic",      OpConstructor(geodesic::new),    &geodesic::GAMUT,          "The forward and inverse geodesic problems"),
+    ("geoid",         OpConstructor(geoid::new),       &geoid::GAMUT,             "Geoid undulations from spherical harmonic models, e.g. EGM96 and EGM2008"),
+    ("gridshift",     OpConstructor(gridshift::new),   &gridshift::GAMUT,         "Datum shift using grid interpolation"),
+    ("helmert",       OpConstructor(helmert::new),     &helmert::GAMUT,           "Helmert transformation between reference frames"),
+    ("laea",          OpConstructor(laea::new),        &laea::GAMUT,              "Lambert azimuthal equal area"),
+    ("latitude",      OpConstructor(latitude::new),    &latitude::GAMUT,          "Auxiliary latitudes"),
+    ("lcc",           OpConstructor(lcc::new),         &lcc::GAMUT,               "Lambert conformal conic"),
+    ("merc",          OpConstructor(merc::new),        &merc::GAMUT,              "Mercator"),
+    ("molodensky",    OpConstructor(molodensky::new),  &molodensky::GAMUT,        "The full and abridged Molodensky transformations"),
+    ("nmea",          OpConstructor(nmea::new),        &nmea::GAMUT,              "Angular coordinates in NMEA format (DDDMM.mmm)"),
+    ("noop",          OpConstructor(noop::new),        &noop::GAMUT,              "The no-operation"),
+    ("normal_height", OpConstructor(heights::new),     &heights::GAMUT,           "Orthometric to normal heights"),
+    ("normalize",     OpConstructor(normalize::new),   &normalize::GAMUT,         "Normalize longitudes to [-180°, 180°), or [0°, 360°)"),
+    ("set_epoch",     OpConstructor(time::set),        &time::SET_EPOCH_GAMUT,    "Set the time coordinate to a constant epoch"),
+    ("timeconvert",   OpConstructor(time::new),        &time::GAMUT,              "Convert the time coordinate between decimal years, GPS weeks or seconds, MJD and Unix time"),
+    ("tmerc",         OpConstructor(tmerc::new),       &tmerc::GAMUT,             "Transverse Mercator, following Engsager & Poder (2007)"),
+    ("utm",           OpConstructor(tmerc::utm),       &tmerc::UTM_GAMUT,         "Universal Transverse Mercator"),
+    ("pipeline",      OpConstructor(pipeline::new),    &pipeline::GAMUT,          "Sequential application of a number of steps"),
+    ("pop",           OpConstructor(pipeline::pop),    &pipeline::PUSH_POP_GAMUT, "Pop coordinate elements from the pipeline stack"),
+    ("proj",          OpConstructor(proj::new),        &proj::GAMUT,              "Projections from the PROJ command line program"),
+    ("push",          OpConstructor(pipeline::push),   &pipeline::PUSH_POP_GAMUT, "Push coordinate elements onto the pipeline stack"),
 ];
 // A BTreeMap would have been a better choice for BUILTIN_OPERATORS, except
 // for the annoying fact that it cannot be compile-time const-constructed.
@@ -59,6 +74,44 @@ pub fn builtin(name: &str) -> Result<OpConstructor, Error> {
     Err(Error::NotFound(name.to_string(), String::default()))
 }
 
+/// Name, parameter gamut and short description of an operator, cf.
+/// [`Context::operators`](crate::Context::operators)
+#[derive(Debug)]
+pub struct OperatorInfo {
+    pub name: String,
+    /// The parameters accepted, with their types and defaults. Empty for
+    /// user defined operators, as their gamut is not known until instantiation
+    pub gamut: &'static [OpParameter],
+    pub doc: &'static str,
+}
+
+/// The built-in operators, and the user defined operators named in
+/// `user_defined`. User defined operators shadow built-ins of the same name,
+/// just as in the `Op` instantiation logic.
+pub fn operator_inventory<'a>(
+    user_defined: impl IntoIterator<Item = &'a str>,
+) -> Vec<OperatorInfo> {
+    let mut inventory: Vec<_> = user_defined
+        .into_iter()
+        .map(|name| OperatorInfo {
+            name: name.to_string(),
+            gamut: &[],
+            doc: "User defined operator",
+        })
+        .collect();
+    for p in BUILTIN_OPERATORS {
+        if inventory.iter().all(|info| info.name != p.0) {
+            inventory.push(OperatorInfo {
+                name: p.0.to_string(),
+                gamut: p.2,
+                doc: p.3,
+            });
+        }
+    }
+    inventory.sort_by(|a, b| a.name.cmp(&b.name));
+    inventory
+}
+
 // ----- S T R U C T   O P C O N S T R U C T O R ---------------------------------------
 
 /// Blueprint for the overall instantiation of an operator.
@@ -107,3 +160,210 @@ fn noop_placeholder(
     // non-existing or non-implemented inverse operation
     Ok(0)
 }
+
+// ----- I T E R A T I V E   I N V E R S E S -------------------------------------------
+
+// Generic inverses for operators lacking an analytical inverse. Both iterate
+// over the first two coordinate elements only, by repeated application of
+// the forward function of the operator, until the correction is smaller
+// than `inv_tolerance`, or `inv_max_iter` iterations have been carried out.
+// Points failing to converge are set to NaN, and are not counted as successes.
+
+/// Inverse by 2D Newton iteration, using a numerically estimated Jacobian.
+/// Starts from (`lon_0`, `lat_0`), hence suitable for projections, and other
+/// operators where input and output are of widely different magnitudes.
+pub fn newton_inverse(op: &Op, ctx: &dyn Context, operands: &mut [Coord]) -> Result<usize, Error> {
+    let tolerance = op.params.real("inv_tolerance")?;
+    let max_iter = op.params.natural("inv_max_iter")?;
+    let mut successes = 0_usize;
+
+    for coord in operands {
+        let target = *coord;
+        let mut guess = Coord::raw(op.params.lon[0], op.params.lat[0], coord[2], coord[3]);
+        let mut converged = false;
+
+        for _ in 0..max_iter {
+            // Evaluate the forward function at the guess, and at a small step
+            // away along each axis, for the finite difference Jacobian
+            let hx = 1e-7 * guess[0].abs().max(1.);
+            let hy = 1e-7 * guess[1].abs().max(1.);
+            let mut probes = [guess, guess, guess];
+            probes[1][0] += hx;
+            probes[2][1] += hy;
+            (op.descriptor.fwd.0)(op, ctx, &mut probes)?;
+
+            let rx = probes[0][0] - target[0];
+            let ry = probes[0][1] - target[1];
+            let j00 = (probes[1][0] - probes[0][0]) / hx;
+            let j10 = (probes[1][1] - probes[0][1]) / hx;
+            let j01 = (probes[2][0] - probes[0][0]) / hy;
+            let j11 = (probes[2][1] - probes[0][1]) / hy;
+
+            // Solve J·d = r by Cramer's rule
+            let det = j00 * j11 - j01 * j10;
+            let dx = (rx * j11 - ry * j01) / det;
+            let dy = (ry * j00 - rx * j10) / det;
+            if !(dx.is_finite() && dy.is_finite()) {
+                break;
+            }
+
+            guess[0] -= dx;
+            guess[1] -= dy;
+            if dx.hypot(dy) < tolerance {
+                converged = true;
+                break;
+            }
+        }
+
+        if converged {
+            coord[0] = guess[0];
+            coord[1] = guess[1];
+            successes += 1;
+        } else {
+            *coord = Coord::failed(PointStatus::NoConvergence);
+        }
+    }
+    Ok(successes)
+}
+
+/// Inverse by fixed-point iteration, starting from the point itself. Suitable
+/// for operators close to the identity, such as datum shifts.
+pub fn fixed_point_inverse(
+    op: &Op,
+    ctx: &dyn Context,
+    operands: &mut [Coord],
+) -> Result<usize, Error> {
+    let tolerance = op.params.real("inv_tolerance")?;
+    let max_iter = op.params.natural("inv_max_iter")?;
+    let mut successes = 0_usize;
+
+    for coord in operands {
+        let target = *coord;
+        let mut guess = [*coord];
+        let mut converged = false;
+
+        for _ in 0..max_iter {
+            let mut probe = guess;
+            (op.descriptor.fwd.0)(op, ctx, &mut probe)?;
+            let dx = probe[0][0] - target[0];
+            let dy = probe[0][1] - target[1];
+            if !(dx.is_finite() && dy.is_finite()) {
+                break;
+            }
+
+            guess[0][0] -= dx;
+            guess[0][1] -= dy;
+            if dx.hypot(dy) < tolerance {
+                converged = true;
+                break;
+            }
+        }
+
+        if converged {
+            coord[0] = guess[0][0];
+            coord[1] = guess[0][1];
+            successes += 1;
+        } else {
+            *coord = Coord::failed(PointStatus::NoConvergence);
+        }
+    }
+    Ok(successes)
+}
+
+// ----- T E S T S ---------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A mildly nonlinear, projection-like (radians in, metres out), forward-only operator
+    fn skew(_op: &Op, _ctx: &dyn Context, operands: &mut [Coord]) -> Result<usize, Error> {
+        for coord in operands.iter_mut() {
+            let (lon, lat) = (coord[0], coord[1]);
+            coord[0] = 6378137. * (lon + 0.1 * lat.sin());
+            coord[1] = 6378137. * (lat + 0.05 * lon * lon);
+        }
+        Ok(operands.len())
+    }
+
+    // ...and a small, near-identity shift
+    fn nudge(_op: &Op, _ctx: &dyn Context, operands: &mut [Coord]) -> Result<usize, Error> {
+        for coord in operands.iter_mut() {
+            coord[0] += 1e-4 * coord[1].cos();
+            coord[1] += 1e-4 * coord[0].sin();
+        }
+        Ok(operands.len())
+    }
+
+    fn skew_op(parameters: &RawParameters, ctx: &dyn Context) -> Result<Op, Error> {
+        let gamut = [OpParameter::Flag { key: "inv" }];
+        Op::iterative(parameters, InnerOp(skew), &gamut, ctx)
+    }
+
+    fn nudge_op(parameters: &RawParameters, _ctx: &dyn Context) -> Result<Op, Error> {
+        let gamut = [OpParameter::Flag { key: "inv" }];
+        let def = &parameters.definition;
+        let params = ParsedParameters::new(parameters, &gamut)?;
+        let inv = Some(InnerOp(fixed_point_inverse));
+        Ok(Op {
+            descriptor: OpDescriptor::new(def, InnerOp(nudge), inv),
+            params,
+            steps: Vec::new(),
+            id: OpHandle::new(),
+        })
+    }
+
+    #[test]
+    fn newton() -> Result<(), Error> {
+        let mut ctx = Minimal::default();
+        ctx.register_op("skew", OpConstructor(skew_op));
+        let op = ctx.op("skew")?;
+
+        let geo = [
+            Coord::gis(12., 55., 0., 0.),
+            Coord::gis(-150., -70., 0., 0.),
+        ];
+        let mut operands = geo;
+        assert_eq!(2, ctx.apply(op, Fwd, &mut operands)?);
+        assert_eq!(2, ctx.apply(op, Inv, &mut operands)?);
+        for i in 0..2 {
+            assert!((operands[i][0] - geo[i][0]).abs() < 1e-12);
+            assert!((operands[i][1] - geo[i][1]).abs() < 1e-12);
+        }
+
+        // The inverse also works when the operator is inverted
+        let op = ctx.op("skew inv")?;
+        ctx.apply(op, Inv, &mut operands)?;
+        ctx.apply(op, Fwd, &mut operands)?;
+        assert!((operands[0][0] - geo[0][0]).abs() < 1e-12);
+
+        // Too few iterations to converge: Report failure
+        let op = ctx.op("skew inv_max_iter=1")?;
+        let mut operands = geo;
+        ctx.apply(op, Fwd, &mut operands)?;
+        assert_eq!(0, ctx.apply(op, Inv, &mut operands)?);
+        assert!(operands[0][0].is_nan());
+        Ok(())
+    }
+
+    #[test]
+    fn fixed_point() -> Result<(), Error> {
+        let mut ctx = Minimal::default();
+        ctx.register_op("nudge", OpConstructor(nudge_op));
+        let op = ctx.op("nudge inv_tolerance=1e-14")?;
+
+        let geo = [
+            Coord::gis(12., 55., 0., 0.),
+            Coord::gis(-150., -70., 0., 0.),
+        ];
+        let mut operands = geo;
+        assert_eq!(2, ctx.apply(op, Fwd, &mut operands)?);
+        assert!((operands[0][0] - geo[0][0]).abs() > 1e-5);
+        assert_eq!(2, ctx.apply(op, Inv, &mut operands)?);
+        for i in 0..2 {
+            assert!((operands[i][0] - geo[i][0]).abs() < 1e-14);
+            assert!((operands[i][1] - geo[i][1]).abs() < 1e-14);
+        }
+        Ok(())
+    }
+}
@@ -110,7 +110,8 @@ pub fn new(parameters: &RawParameters, _ctx: &dyn Context) -> Result<Op, Error>
         params.real.insert("df", df);
     }
 
-    let descriptor = OpDescriptor::new(def, InnerOp(fwd), Some(InnerOp(inv)));
+    let mut descriptor = OpDescriptor::new(def, InnerOp(fwd), Some(InnerOp(inv)));
+    descriptor.angular_io = (true, true);
     let steps = Vec::<Op>::new();
     let id = OpHandle::new();
 
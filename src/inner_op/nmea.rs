@@ -68,7 +68,9 @@ pub const GAMUT: [OpParameter; 2] = [
 ];
 
 pub fn new(parameters: &RawParameters, ctx: &dyn Context) -> Result<Op, Error> {
-    Op::plain(parameters, InnerOp(fwd), InnerOp(inv), &GAMUT, ctx)
+    let mut op = Op::plain(parameters, InnerOp(fwd), InnerOp(inv), &GAMUT, ctx)?;
+    op.descriptor.angular_io = (false, true);
+    Ok(op)
 }
 
 // ----- T E S T S ---------------------------------------------------------------------
@@ -0,0 +1,89 @@
+/// Normalization of longitudes, to [-180°, 180°) or, with `positive`, [0°, 360°)
+use super::*;
+
+// ----- F O R W A R D --------------------------------------------------------------
+
+fn fwd(op: &Op, _ctx: &dyn Context, operands: &mut [Coord]) -> Result<usize, Error> {
+    let positive = op.params.boolean("positive");
+    let mut successes = 0_usize;
+    for coord in operands {
+        *coord = if positive {
+            coord.normalize_longitude_positive()
+        } else {
+            coord.normalize_longitude()
+        };
+        successes += 1;
+    }
+    Ok(successes)
+}
+
+// ----- C O N S T R U C T O R ------------------------------------------------------
+
+// The normalization is its own inverse: Whichever the direction, we normalize
+#[rustfmt::skip]
+pub const GAMUT: [OpParameter; 2] = [
+    OpParameter::Flag { key: "inv" },
+    OpParameter::Flag { key: "positive" },
+];
+
+pub fn new(parameters: &RawParameters, ctx: &dyn Context) -> Result<Op, Error> {
+    let mut op = Op::plain(parameters, InnerOp(fwd), InnerOp(fwd), &GAMUT, ctx)?;
+    op.descriptor.angular_io = (true, true);
+    Ok(op)
+}
+
+// ----- T E S T S ------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalize() -> Result<(), Error> {
+        let mut ctx = Minimal::new();
+        let op = ctx.op("geo:in | normalize | geo:out")?;
+        let mut operands = [
+            Coord::raw(55., 190., 0., 0.),
+            Coord::raw(55., -530., 0., 0.),
+        ];
+        ctx.apply(op, Fwd, &mut operands)?;
+        assert!((operands[0][1] + 170.).abs() < 1e-12);
+        assert!((operands[1][1] + 170.).abs() < 1e-12);
+
+        let op = ctx.op("geo:in | normalize positive | geo:out")?;
+        ctx.apply(op, Inv, &mut operands)?;
+        assert!((operands[0][1] - 190.).abs() < 1e-12);
+        assert!((operands[1][1] - 190.).abs() < 1e-12);
+
+        // Wrapping of the input longitudes of projections
+        let op = ctx.op("merc lon_0=179")?;
+        let wrapped = ctx.op("merc lon_0=179 wrap")?;
+        let mut expected = [Coord::geo(55., 179.5, 0., 0.)];
+        ctx.apply(op, Fwd, &mut expected)?;
+        let mut operands = [Coord::geo(55., -180.5, 0., 0.)];
+        ctx.apply(wrapped, Fwd, &mut operands)?;
+        assert!(operands[0].hypot2(&expected[0]) < 1e-6);
+        let mut operands = [Coord::geo(55., -180.5, 0., 0.)];
+        ctx.apply(op, Fwd, &mut operands)?;
+        assert!(operands[0][0] < -3.9e7);
+
+        // ...also for the transverse projections, and laea
+        let projections = [
+            "tmerc lon_0=179",
+            "utm zone=60",
+            "btmerc lon_0=179",
+            "butm zone=60",
+            "laea lat_0=55 lon_0=179",
+        ];
+        for projection in projections {
+            let op = ctx.op(projection)?;
+            let wrapped = ctx.op(&format!("{projection} wrap"))?;
+            let mut expected = [Coord::geo(55., 179.5, 0., 0.)];
+            ctx.apply(op, Fwd, &mut expected)?;
+            let mut operands = [Coord::geo(55., -180.5, 0., 0.)];
+            ctx.apply(wrapped, Fwd, &mut operands)?;
+            assert!(operands[0].hypot2(&expected[0]) < 1e-6, "{projection}");
+        }
+        Ok(())
+    }
+}
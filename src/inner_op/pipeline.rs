@@ -1,49 +1,77 @@
 use super::*;
 use std::collections::BTreeSet;
+use std::ops::Range;
 
 // ----- F O R W A R D -----------------------------------------------------------------
 
 fn pipeline_fwd(op: &Op, ctx: &dyn Context, operands: &mut [Coord]) -> Result<usize, Error> {
-    let mut stack = Vec::new();
-    let mut n = usize::MAX;
-    for step in &op.steps {
-        if step.params.boolean("omit_fwd") {
-            continue;
-        }
-        let m = match step.params.name.as_str() {
-            "push" => do_the_push(&mut stack, operands, &step.params.boolean),
-            "pop" => do_the_pop(&mut stack, operands, &step.params.boolean),
-            _ => step.apply(ctx, operands, Direction::Fwd)?,
-        };
-        n = n.min(m);
-    }
-
-    // In case every step has been marked as `omit_fwd`
-    if n == usize::MAX {
-        n = operands.len();
-    }
-    Ok(n)
+    run(op, ctx, operands, Direction::Fwd, None)
 }
 
 // ----- I N V E R S E -----------------------------------------------------------------
 
 fn pipeline_inv(op: &Op, ctx: &dyn Context, operands: &mut [Coord]) -> Result<usize, Error> {
+    run(op, ctx, operands, Direction::Inv, None)
+}
+
+// ----- T R A C I N G -----------------------------------------------------------------
+
+// Apply the steps of `op` in the given direction (inversion already accounted for),
+// handing the intermediate results to `tracer`, if any. Under tracing, nested
+// pipelines and macros are traced step by step too.
+pub(crate) fn run(
+    op: &Op,
+    ctx: &dyn Context,
+    operands: &mut [Coord],
+    direction: Direction,
+    mut tracer: Option<&mut Tracer>,
+) -> Result<usize, Error> {
+    let forward = direction == Direction::Fwd;
+    let steps: Box<dyn Iterator<Item = &Op>> = if forward {
+        Box::new(op.steps.iter())
+    } else {
+        Box::new(op.steps.iter().rev())
+    };
+
     let mut stack = Vec::new();
     let mut n = usize::MAX;
-    for step in op.steps.iter().rev() {
-        if step.params.boolean("omit_inv") {
+    for step in steps {
+        if step
+            .params
+            .boolean(if forward { "omit_fwd" } else { "omit_inv" })
+        {
             continue;
         }
         // Note: Under inverse invocation "push" calls pop and vice versa
-        let m = match step.params.name.as_str() {
-            "push" => do_the_pop(&mut stack, operands, &step.params.boolean),
-            "pop" => do_the_push(&mut stack, operands, &step.params.boolean),
-            _ => step.apply(ctx, operands, Direction::Inv)?,
+        let m = match (step.params.name.as_str(), forward) {
+            ("push", true) | ("pop", false) => {
+                do_the_push(&mut stack, operands, &step.params.boolean)
+            }
+            ("pop", true) | ("push", false) => {
+                do_the_pop(&mut stack, operands, &step.params.boolean)
+            }
+            _ => match tracer.as_deref_mut() {
+                Some(tracer) if !step.steps.is_empty() => {
+                    // Inversion of the nested pipeline is handled here, since we
+                    // bypass `Op::apply`
+                    let direction = match (direction, step.descriptor.inverted) {
+                        (Direction::Fwd, true) => Direction::Inv,
+                        (Direction::Inv, true) => Direction::Fwd,
+                        (direction, false) => direction,
+                    };
+                    run(step, ctx, operands, direction, Some(tracer))?
+                }
+                _ => step.apply(ctx, operands, direction)?,
+            },
         };
+        // Nested pipelines have already traced their own steps
+        if let (Some(tracer), true) = (tracer.as_deref_mut(), step.steps.is_empty()) {
+            tracer(step, direction, operands);
+        }
         n = n.min(m);
     }
 
-    // In case every step has been marked as `omit_inv`
+    // In case every step has been marked as omitted
     if n == usize::MAX {
         n = operands.len();
     }
@@ -59,15 +87,70 @@ pub const GAMUT: [OpParameter; 1] = [
 
 pub fn new(parameters: &RawParameters, ctx: &dyn Context) -> Result<Op, Error> {
     let definition = &parameters.definition;
-    let thesteps = split_into_steps(definition).0;
-    let mut steps = Vec::new();
 
-    for step in thesteps {
+    // Steps consisting of just `inv` (or `inv=true`) invert the pipeline as a
+    // whole, i.e. run it back to front, with each step inverted. Steps consisting
+    // of just `angular_units=...` give the angular unit at the pipeline boundaries
+    let (directives, thesteps): (Vec<_>, Vec<_>) = split_into_steps(definition)
+        .0
+        .into_iter()
+        .zip(step_spans(definition))
+        .partition(|(step, _)| {
+            step == "inv" || step == "inv=true" || step.starts_with("angular_units=")
+        });
+    let directives: Vec<_> = directives.into_iter().map(|(step, _)| step).collect();
+
+    // Syntax errors are located in the definition, by the span of the step.
+    // Other errors are given the step as context
+    let mut steps = Vec::new();
+    for (index, (step, span)) in thesteps.into_iter().enumerate() {
+        if !balanced(&step) {
+            let error = Error::Syntax(format!("Unbalanced parentheses in '{step}'"));
+            return Err(locate(error, definition, span));
+        }
         let step_parameters = parameters.next(&step);
-        steps.push(Op::op(step_parameters, ctx)?);
+        let op = Op::op(step_parameters, ctx).map_err(|e| match e {
+            Error::Syntax(_) | Error::SyntaxAt { .. } => locate(e, definition, span),
+            e => e.context(format!("step {} ({step})", index + 1)),
+        })?;
+        steps.push(op);
+    }
+    let units = directives
+        .iter()
+        .rev()
+        .find_map(|directive| directive.strip_prefix("angular_units="));
+    if let Some(units) = units {
+        if angular_units(units)? {
+            install_degree_adaptors(parameters, ctx, &mut steps)?;
+        }
     }
 
-    let params = ParsedParameters::new(parameters, &GAMUT)?;
+    // The parameters of the pipeline itself, as opposed to those of its steps.
+    // An explicit angular unit is recorded, so the context default, if any,
+    // does not apply, cf. `default_angular_units`
+    let mut params = ParsedParameters::new(&parameters.next("pipeline"), &GAMUT)?;
+    if let Some(units) = units {
+        params.text.insert("angular_units", units.to_string());
+    }
+    let fwd = InnerOp(pipeline_fwd);
+    let inv = InnerOp(pipeline_inv);
+    let mut descriptor = OpDescriptor::new(definition, fwd, Some(inv));
+    descriptor.inverted = directives.iter().any(|d| d == "inv" || d == "inv=true");
+    let id = OpHandle::new();
+    Ok(Op {
+        descriptor,
+        params,
+        steps,
+        id,
+    })
+}
+
+// A pipeline of already instantiated steps, e.g. as assembled by the
+// [`Pipeline`](crate::Pipeline) builder, or by [`Op::then`], with `definition`
+// being the equivalent definition
+pub(crate) fn from_steps(definition: &str, steps: Vec<Op>) -> Result<Op, Error> {
+    let parameters = RawParameters::new("pipeline", &Default::default());
+    let params = ParsedParameters::new(&parameters, &GAMUT)?;
     let fwd = InnerOp(pipeline_fwd);
     let inv = InnerOp(pipeline_inv);
     let descriptor = OpDescriptor::new(definition, fwd, Some(inv));
@@ -80,6 +163,61 @@ pub fn new(parameters: &RawParameters, ctx: &dyn Context) -> Result<Op, Error> {
     })
 }
 
+// Whether the angular unit `units` is degrees (rather than radians)
+fn angular_units(units: &str) -> Result<bool, Error> {
+    match units {
+        "rad" => Ok(false),
+        "deg" => Ok(true),
+        _ => Err(Error::BadParam(
+            "angular_units".to_string(),
+            units.to_string(),
+        )),
+    }
+}
+
+// The default angular unit of the context, given by the `angular_units` global
+// (cf. `Context::set_default_angular_unit`), applies at the boundaries of `op` as
+// a whole, unless `op` is a pipeline with an `angular_units` directive of its own.
+// The definition of `op` is left as is.
+pub(crate) fn default_angular_units(
+    op: Op,
+    parameters: &RawParameters,
+    ctx: &dyn Context,
+) -> Result<Op, Error> {
+    let Some(units) = parameters.globals.get("angular_units") else {
+        return Ok(op);
+    };
+    if !angular_units(units)? || op.params.text.contains_key("angular_units") {
+        return Ok(op);
+    }
+    let definition = op.descriptor.definition.clone();
+    let mut steps = vec![op];
+    install_degree_adaptors(parameters, ctx, &mut steps)?;
+    if steps.len() == 1 {
+        return Ok(steps.remove(0));
+    }
+    from_steps(&definition, steps)
+}
+
+// Under `angular_units=deg`, convert the angular input of the first step from
+// degrees, and the angular output of the last step to degrees. The steps taking
+// care of their own angular units (e.g. `geo:in`, `gis:out`) are left as is
+fn install_degree_adaptors(
+    parameters: &RawParameters,
+    ctx: &dyn Context,
+    steps: &mut Vec<Op>,
+) -> Result<(), Error> {
+    let first = steps.first().is_some_and(|step| step.angular_io(Fwd).0);
+    let last = steps.last().is_some_and(|step| step.angular_io(Fwd).1);
+    if first {
+        steps.insert(0, Op::op(parameters.next("adapt from=enuf_deg"), ctx)?);
+    }
+    if last {
+        steps.push(Op::op(parameters.next("adapt to=enuf_deg"), ctx)?);
+    }
+    Ok(())
+}
+
 // The push and pop constructors are extremely simple, since the pipeline operator
 // does all the hard work. Essentially, they are just flags telling pipeline
 // what to do, given their provided options
@@ -136,6 +274,92 @@ pub fn pop(parameters: &RawParameters, _ctx: &dyn Context) -> Result<Op, Error>
 
 // ----- H E L P E R S -----------------------------------------------------------------
 
+// Does every parenthesis of `step` have a counterpart?
+fn balanced(step: &str) -> bool {
+    let mut depth = 0;
+    for c in step.chars() {
+        match c {
+            '(' => depth += 1,
+            ')' if depth == 0 => return false,
+            ')' => depth -= 1,
+            _ => (),
+        }
+    }
+    depth == 0
+}
+
+// The byte ranges of the steps of `definition`, as split by `split_into_steps`,
+// i.e. at the vertical bars outside of comments and parenthesized sub-pipelines
+fn step_spans(definition: &str) -> Vec<Range<usize>> {
+    let mut spans = Vec::new();
+    let mut depth = 0;
+    let mut start = 0;
+    let mut comment = false;
+    for (i, c) in definition.char_indices() {
+        match c {
+            '\n' | '\r' => comment = false,
+            _ if comment => (),
+            '#' => comment = true,
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            '|' if depth == 0 => {
+                spans.push(start..i);
+                start = i + 1;
+            }
+            _ => (),
+        }
+    }
+    spans.push(start..definition.len());
+    spans
+}
+
+// Locate the syntax error `error`, stemming from the step given by `span`, in
+// `definition`: At the token quoted in the error message, if found in the step,
+// otherwise at the start of the step. Errors already located are left as is,
+// except for those from sub-pipelines, which are relocated in the outer definition
+fn locate(error: Error, definition: &str, span: Range<usize>) -> Error {
+    let text = &definition[span.clone()];
+    let (message, token) = match error {
+        Error::Syntax(message) => {
+            let quoted = message.split('\'').nth(1).map(str::to_string);
+            let first = text.split_whitespace().next().unwrap_or_default();
+            let token = quoted.unwrap_or_else(|| first.to_string());
+            (message, token)
+        }
+        Error::SyntaxAt { message, token, .. } if text.trim_start().starts_with('(') => {
+            (message, token)
+        }
+        error => return error,
+    };
+
+    let start = text.len() - text.trim_start().len();
+    let offset = find_token(text, &token).unwrap_or(start);
+    let before = &definition[..span.start + offset];
+    let line = before.matches('\n').count() + 1;
+    let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+    let column = before[line_start..].chars().count() + 1;
+    Error::SyntaxAt {
+        message,
+        line,
+        column,
+        token,
+    }
+}
+
+// The offset of the first occurrence of `token` in `text`, as a whole word, or
+// as the key of a key=value pair
+fn find_token(text: &str, token: &str) -> Option<usize> {
+    if token.is_empty() {
+        return None;
+    }
+    let delimiter = |c: char| c.is_whitespace() || c == '|' || c == '(' || c == ')';
+    text.match_indices(token).map(|(i, _)| i).find(|&i| {
+        let before = text[..i].chars().next_back();
+        let after = text[i + token.len()..].chars().next();
+        before.is_none_or(delimiter) && after.is_none_or(|c| delimiter(c) || c == '=')
+    })
+}
+
 fn do_the_push(
     stack: &mut Vec<Vec<f64>>,
     operands: &mut [Coord],
@@ -215,7 +439,22 @@ pub fn split_into_steps(definition: &str) -> (Vec<String>, String) {
     //     key1=value1 key2=value2
     // as opposed to e.g.
     //     key1= value1            key2    =value2
-    let steps: Vec<_> = trimmed.split('|').collect();
+    // Split at the vertical bars outside of parenthesized sub-pipelines
+    let mut steps = Vec::new();
+    let mut depth = 0;
+    let mut start = 0;
+    for (i, c) in trimmed.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            '|' if depth == 0 => {
+                steps.push(&trimmed[start..i]);
+                start = i + 1;
+            }
+            _ => (),
+        }
+    }
+    steps.push(&trimmed[start..]);
     let mut trimmed_steps = Vec::<String>::new();
     for mut step in steps {
         step = step.trim();
@@ -259,12 +498,191 @@ mod tests {
         assert_eq!(data[0][0], 55.);
         assert_eq!(data[1][0], 59.);
 
-        // Try to invoke garbage as a pipeline step
+        // Try to invoke garbage as a pipeline step: The error is reported in
+        // the context of the step
+        let error = ctx.op("addone|addone|_garbage").unwrap_err();
+        assert!(matches!(error.root(), Error::NotFound(_, _)));
+        assert!(error.to_string().starts_with("step 3 (_garbage): "));
+
+        Ok(())
+    }
+
+    #[test]
+    fn angular_units() -> Result<(), Error> {
+        let mut ctx = Minimal::new();
+        let pairs = [
+            ("utm zone=32 | angular_units=deg", "gis:in | utm zone=32"),
+            (
+                "utm zone=32 inv | angular_units=deg",
+                "utm zone=32 inv | gis:out",
+            ),
+            (
+                "angular_units=deg | cart | helmert z=10 | cart inv",
+                "gis:in | cart | helmert z=10 | cart inv | gis:out",
+            ),
+            // Whole pipeline inversion
+            (
+                "utm zone=32 inv | inv | angular_units=deg",
+                "gis:in | utm zone=32",
+            ),
+            // Adaptors already given are left as is
+            (
+                "geo:in | utm zone=32 | angular_units=deg",
+                "geo:in | utm zone=32",
+            ),
+            ("utm zone=32 | angular_units=rad", "utm zone=32"),
+            // Angular on both sides, or on the output side only
+            (
+                "normal_height anomaly=0.001 | angular_units=deg",
+                "gis:in | normal_height anomaly=0.001 | gis:out",
+            ),
+            (
+                "geodesic lat_0=55 lon_0=12 | angular_units=deg",
+                "geodesic lat_0=55 lon_0=12 | gis:out",
+            ),
+        ];
+        for (definition, expected) in pairs {
+            let op = ctx.op(definition)?;
+            let reference = ctx.op(expected)?;
+            let mut data = [Coord::raw(12., 55., 100., 0.)];
+            let mut expected = data;
+            ctx.apply(op, Fwd, &mut data)?;
+            ctx.apply(reference, Fwd, &mut expected)?;
+            assert_eq!(data, expected, "{definition}");
+            ctx.apply(op, Inv, &mut data)?;
+            ctx.apply(reference, Inv, &mut expected)?;
+            assert_eq!(data, expected, "{definition}");
+        }
+        // User defined operators declare their angular input and output too
+        fn angular(parameters: &RawParameters, ctx: &dyn Context) -> Result<Op, Error> {
+            let mut op = Op::new("noop", ctx)?;
+            op.descriptor.definition = parameters.definition.clone();
+            op.descriptor.angular_io = (true, true);
+            Ok(op)
+        }
+        ctx.register_op("angular", OpConstructor(angular));
+        let steps = Op::new("angular | angular_units=deg", &ctx)?.steps();
+        let names: Vec<_> = steps.iter().map(|step| step.name.as_str()).collect();
+        assert_eq!(names, ["adapt", "noop", "adapt"]);
+
         assert!(matches!(
-            ctx.op("addone|addone|_garbage"),
-            Err(Error::NotFound(_, _))
+            ctx.op("utm zone=32 | angular_units=gon"),
+            Err(Error::BadParam(_, _))
         ));
+        Ok(())
+    }
+
+    #[test]
+    fn syntax_error_location() -> Result<(), Error> {
+        let mut ctx = Minimal::new();
+        let located = |ctx: &Minimal, definition: &str| match Op::new(definition, ctx) {
+            Err(Error::SyntaxAt {
+                message,
+                line,
+                column,
+                token,
+            }) => Some((line, column, token, message)),
+            _ => None,
+        };
+
+        // At the offending token
+        let definition = "geo:in\n| cart # x=$nothing\n| helmert y=1  x=$dx\n| cart inv";
+        let (line, column, token, _) = located(&ctx, definition).unwrap();
+        assert_eq!((line, column, token.as_str()), (3, 16, "x"));
+
+        // At the start of the step, when the token is not found
+        let definition = "addone |\n   (addone | (addone) inv";
+        let (line, column, _, message) = located(&ctx, definition).unwrap();
+        assert_eq!((line, column), (2, 4));
+        assert!(message.starts_with("Unbalanced"));
+
+        // Sub-pipelines are located in the outer definition, macros in their own
+        let definition = "addone | (addone | helmert z=$z)";
+        let (line, column, _, _) = located(&ctx, definition).unwrap();
+        assert_eq!((line, column), (1, 28));
+        ctx.register_resource("foo:bar", "addone\n| helmert z=$z");
+        let (line, column, _, message) = located(&ctx, "addone | foo:bar").unwrap();
+        assert_eq!((line, column), (2, 11));
+        assert!(message.ends_with("in the macro foo:bar"));
+
+        // Other errors are reported as is
+        assert!(located(&ctx, "addone | nothing").is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn pipeline_level_inversion() -> Result<(), Error> {
+        let mut ctx = Minimal::new();
+        let op = ctx.op("geo:in | utm zone=32")?;
+        let mut expected = [Coord::raw(691875.6321, 6098907.8250, 0., 0.)];
+        ctx.apply(op, Inv, &mut expected)?;
+
+        for definition in [
+            "geo:in | utm zone=32 | inv",
+            "inv=true | geo:in | utm zone=32",
+        ] {
+            let inverted = ctx.op(definition)?;
+            let mut data = [Coord::raw(691875.6321, 6098907.8250, 0., 0.)];
+            ctx.apply(inverted, Fwd, &mut data)?;
+            assert_eq!(data, expected);
+            ctx.apply(inverted, Inv, &mut data)?;
+            assert!((data[0][0] - 691875.6321).abs() < 1e-6);
+
+            // The steps, as described, are reversed and inverted
+            let steps = Op::new(definition, &ctx)?.steps();
+            assert_eq!(steps.len(), 2);
+            assert_eq!(steps[0].name, "utm");
+            assert!(steps[0].inverted && steps[1].inverted);
+        }
 
+        // Push and pop swap roles under inversion
+        let op = ctx.op("push v_2 | addone | pop v_1 | inv")?;
+        let mut data = some_basic_coordinates();
+        ctx.apply(op, Fwd, &mut data)?;
+        assert_eq!(data[0][0], 54.);
+        assert_eq!(data[0][1], 55.);
+        Ok(())
+    }
+
+    #[test]
+    fn omission() -> Result<(), Error> {
+        let mut ctx = Minimal::default();
+
+        // A correction applied only on the way in
+        let op = ctx.op("addone | addone omit_inv")?;
+        let mut data = some_basic_coordinates();
+        ctx.apply(op, Fwd, &mut data)?;
+        assert_eq!(data[0][0], 57.);
+        ctx.apply(op, Inv, &mut data)?;
+        assert_eq!(data[0][0], 56.);
+
+        // The flags of a macro invocation apply to the macro as a whole, not to
+        // its individual steps, also when the macro is inverted
+        ctx.register_resource("foo:two", "addone | addone");
+        let op = ctx.op("foo:two inv omit_fwd | addone")?;
+        let mut data = some_basic_coordinates();
+        ctx.apply(op, Fwd, &mut data)?;
+        assert_eq!(data[0][0], 56.);
+        ctx.apply(op, Inv, &mut data)?;
+        assert_eq!(data[0][0], 57.);
+
+        // ...and a macro is not omitted by the flags of its own steps
+        ctx.register_resource("foo:half", "addone | addone omit_inv");
+        let op = ctx.op("foo:half | addone")?;
+        let mut data = some_basic_coordinates();
+        ctx.apply(op, Fwd, &mut data)?;
+        assert_eq!(data[0][0], 58.);
+        ctx.apply(op, Inv, &mut data)?;
+        assert_eq!(data[0][0], 56.);
+
+        // Under pipeline-level inversion, the steps omitted in the forward
+        // direction are the ones omitted in the inverse
+        let op = ctx.op("addone | addone omit_inv | inv")?;
+        let mut data = some_basic_coordinates();
+        ctx.apply(op, Fwd, &mut data)?;
+        assert_eq!(data[0][0], 54.);
+        ctx.apply(op, Inv, &mut data)?;
+        assert_eq!(data[0][0], 56.);
         Ok(())
     }
 
@@ -53,7 +53,7 @@ fn proj(args: &str, forward: bool, operands: &mut [Coord]) -> Result<usize, Erro
     // Read the output bytes
     let output = child.wait_with_output().expect("failed to wait on child");
     if output.stdout.len() != buffer_size {
-        return Err(Error::General("proj: Unexpected return size"));
+        return Err(Error::General("proj: Unexpected return size".to_string()));
     }
 
     // Turn the output bytes into doubles and put them properly back into the operands
@@ -40,6 +40,8 @@ pub const GAMUT: [OpParameter; 3] = [
     OpParameter::Text { key: "convention", default: Some("") },
 ];
 
+// Values needed in the loops over the coordinates may be resolved up front,
+// into a plain struct, cf. `ParsedParameters::prepare`, e.g. `geoid.rs`
 pub fn new(parameters: &RawParameters, Context: &dyn Context) -> Result<Op, Error> {
     Op::plain(parameters, InnerOp(fwd), InnerOp(inv), &GAMUT, Context)
 }
@@ -0,0 +1,203 @@
+//! Conversions of the time coordinate between units, and stamping it with a constant
+use super::*;
+
+// A conversion of the time coordinate, from, or to, Modified Julian Date (MJD)
+type Conversion = fn(f64) -> f64;
+
+// The units of time supported, by their conversions to and from MJD, the hub of
+// the conversions. GPS time is taken as continuous, i.e. leap seconds are not
+// accounted for, and neither are they by PROJ.
+#[rustfmt::skip]
+const UNITS: [(&str, Conversion, Conversion); 5] = [
+    ("mjd",         |t| t,                      |mjd| mjd),
+    ("decimalyear", decimal_year_to_mjd,        mjd_to_decimal_year),
+    ("gps_week",    |w| w * 7. + GPS_EPOCH,     |mjd| (mjd - GPS_EPOCH) / 7.),
+    ("gps_seconds", |s| s / DAY + GPS_EPOCH,    |mjd| (mjd - GPS_EPOCH) * DAY),
+    ("unix",        |s| s / DAY + UNIX_EPOCH,   |mjd| (mjd - UNIX_EPOCH) * DAY),
+];
+
+// The MJD of the GPS epoch (1980-01-06), and of the Unix epoch (1970-01-01)
+const GPS_EPOCH: f64 = 44244.;
+const UNIX_EPOCH: f64 = 40587.;
+const DAY: f64 = 86400.;
+
+// The MJD of January 1st of `year`, following the days-from-civil algorithm of
+// Howard Hinnant, for the proleptic Gregorian calendar
+fn new_year_mjd(year: i64) -> f64 {
+    let y = year - 1;
+    let days = 365 * y + y.div_euclid(4) - y.div_euclid(100) + y.div_euclid(400);
+    // Days from 0001-01-01 to 1858-11-17, the MJD epoch
+    (days - 678575) as f64
+}
+
+// The years handled by the decimal year conversions: Far beyond any geodetic
+// use, while keeping the day counts of `new_year_mjd` well within range.
+// Years outside (and NaN or infinite ones) convert to NaN
+const MAX_YEAR: f64 = 1e9;
+
+fn decimal_year_to_mjd(t: f64) -> f64 {
+    if !(-MAX_YEAR..=MAX_YEAR).contains(&t) {
+        return f64::NAN;
+    }
+    let year = t.floor();
+    let start = new_year_mjd(year as i64);
+    start + (t - year) * (new_year_mjd(year as i64 + 1) - start)
+}
+
+fn mjd_to_decimal_year(mjd: f64) -> f64 {
+    let year = (1858.87 + mjd / 365.2425).floor();
+    if !(-MAX_YEAR..=MAX_YEAR).contains(&year) {
+        return f64::NAN;
+    }
+    let mut year = year as i64;
+    while new_year_mjd(year) > mjd {
+        year -= 1;
+    }
+    while new_year_mjd(year + 1) <= mjd {
+        year += 1;
+    }
+    let start = new_year_mjd(year);
+    year as f64 + (mjd - start) / (new_year_mjd(year + 1) - start)
+}
+
+fn unit(op: &Op, key: &str) -> Result<(Conversion, Conversion), Error> {
+    let name = op.params.text(key)?;
+    UNITS
+        .iter()
+        .find(|unit| unit.0 == name)
+        .map(|unit| (unit.1, unit.2))
+        .ok_or(Error::BadParam(key.to_string(), name))
+}
+
+// ----- F O R W A R D --------------------------------------------------------------
+
+fn fwd(op: &Op, _ctx: &dyn Context, operands: &mut [Coord]) -> Result<usize, Error> {
+    let (from, _) = unit(op, "from")?;
+    let (_, to) = unit(op, "to")?;
+    for coord in operands.iter_mut() {
+        coord[3] = to(from(coord[3]));
+    }
+    Ok(operands.len())
+}
+
+// ----- I N V E R S E --------------------------------------------------------------
+
+fn inv(op: &Op, _ctx: &dyn Context, operands: &mut [Coord]) -> Result<usize, Error> {
+    let (_, from) = unit(op, "from")?;
+    let (to, _) = unit(op, "to")?;
+    for coord in operands.iter_mut() {
+        coord[3] = from(to(coord[3]));
+    }
+    Ok(operands.len())
+}
+
+// ----- S E T   E P O C H ----------------------------------------------------------
+
+// Stamping the time coordinate, whichever the direction, as PROJ's `set` does
+fn set_epoch(op: &Op, _ctx: &dyn Context, operands: &mut [Coord]) -> Result<usize, Error> {
+    let epoch = op.params.real("epoch")?;
+    for coord in operands.iter_mut() {
+        coord[3] = epoch;
+    }
+    Ok(operands.len())
+}
+
+// ----- C O N S T R U C T O R S ----------------------------------------------------
+
+#[rustfmt::skip]
+pub const GAMUT: [OpParameter; 3] = [
+    OpParameter::Flag { key: "inv" },
+    OpParameter::Text { key: "from", default: Some("decimalyear") },
+    OpParameter::Text { key: "to",   default: Some("decimalyear") },
+];
+
+pub fn new(parameters: &RawParameters, ctx: &dyn Context) -> Result<Op, Error> {
+    let op = Op::plain(parameters, InnerOp(fwd), InnerOp(inv), &GAMUT, ctx)?;
+    // Check the units up front, rather than at first use
+    unit(&op, "from")?;
+    unit(&op, "to")?;
+    Ok(op)
+}
+
+#[rustfmt::skip]
+pub const SET_EPOCH_GAMUT: [OpParameter; 2] = [
+    OpParameter::Flag { key: "inv" },
+    OpParameter::Real { key: "epoch", default: None },
+];
+
+pub fn set(parameters: &RawParameters, ctx: &dyn Context) -> Result<Op, Error> {
+    Op::plain(
+        parameters,
+        InnerOp(set_epoch),
+        InnerOp(set_epoch),
+        &SET_EPOCH_GAMUT,
+        ctx,
+    )
+}
+
+// ----- T E S T S ------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn timeconvert() -> Result<(), Error> {
+        let mut ctx = Minimal::new();
+
+        // 2020-01-01 is MJD 58849, GPS week 2086 (a Wednesday, hence the 3/7),
+        // and 1577836800 Unix seconds
+        let mut data = [Coord::raw(0., 0., 0., 2020.)];
+        for (unit, expected) in [
+            ("mjd", 58849.),
+            ("gps_week", 2086. + 3. / 7.),
+            ("gps_seconds", (2086. * 7. + 3.) * 86400.),
+            ("unix", 1577836800.),
+        ] {
+            let op = ctx.op(&format!("timeconvert to={unit}"))?;
+            let mut converted = data;
+            ctx.apply(op, Fwd, &mut converted)?;
+            assert!((converted[0][3] - expected).abs() < 1e-6, "{unit}");
+            ctx.apply(op, Inv, &mut converted)?;
+            assert!((converted[0][3] - 2020.).abs() < 1e-12, "{unit}");
+        }
+
+        // Mid-year in a leap year, and roundtrips across the year boundaries
+        let op = ctx.op("timeconvert from=mjd")?;
+        data[0][3] = 58849. + 183.;
+        ctx.apply(op, Fwd, &mut data)?;
+        assert!((data[0][3] - 2020.5).abs() < 1e-12);
+        for t in [1999.999999, 2000., 2000.5, 2100.25] {
+            assert!((mjd_to_decimal_year(decimal_year_to_mjd(t)) - t).abs() < 1e-9);
+        }
+
+        // Non-finite and absurd times give NaN, rather than overflowing
+        for t in [f64::INFINITY, f64::NEG_INFINITY, f64::NAN, 1e300, -1e300] {
+            assert!(decimal_year_to_mjd(t).is_nan(), "{t}");
+            assert!(mjd_to_decimal_year(t).is_nan(), "{t}");
+        }
+        let op = ctx.op("timeconvert to=unix")?;
+        let mut data = [Coord::raw(0., 0., 0., f64::INFINITY)];
+        ctx.apply(op, Fwd, &mut data)?;
+        assert!(data[0][3].is_nan());
+
+        assert!(matches!(
+            ctx.op("timeconvert from=fortnights"),
+            Err(Error::BadParam(..))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn set_epoch() -> Result<(), Error> {
+        let mut ctx = Minimal::new();
+        let op = ctx.op("set_epoch epoch=2020.5 | timeconvert to=mjd")?;
+        let mut data = [Coord::raw(1., 2., 3., 0.)];
+        ctx.apply(op, Fwd, &mut data)?;
+        assert_eq!(data[0][3], 58849. + 183.);
+        ctx.apply(op, Inv, &mut data)?;
+        assert_eq!(data[0], Coord::raw(1., 2., 3., 2020.5));
+        assert!(ctx.op("set_epoch").is_err());
+        Ok(())
+    }
+}
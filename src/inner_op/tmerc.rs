@@ -1,157 +1,48 @@
 //! Transverse Mercator, following Engsager & Poder (2007)
 use super::*;
+use crate::kernels::{Kernel, Tmerc};
 use crate::math::*;
 
-// ----- F O R W A R D -----------------------------------------------------------------
+// ----- F O R W A R D   A N D   I N V E R S E ---------------------------------------
 
-// Forward transverse mercator, following Engsager & Poder(2007)
-fn fwd(op: &Op, _ctx: &dyn Context, operands: &mut [Coord]) -> Result<usize, Error> {
-    // Make all precomputed parameters directly accessible
-    let ellps = op.params.ellps[0];
-    let lat_0 = op.params.lat[0];
-    let lon_0 = op.params.lon[0];
-    let x_0 = op.params.x[0];
+// The projection itself is implemented by `crate::kernels::Tmerc`, here set
+// up from the parameters precomputed by the constructor
+fn kernel(op: &Op) -> Option<Tmerc> {
     let Some(conformal) = op.params.fourier_coefficients.get("conformal") else {
         warn!("Missing Fourier coefficients for conformal mapping!");
-        return Ok(0);
+        return None;
     };
     let Some(tm) = op.params.fourier_coefficients.get("tm") else {
         warn!("Missing Fourier coefficients for TM!");
-        return Ok(0);
+        return None;
     };
     let Some(qs) = op.params.real.get("scaled_radius") else {
         warn!("Missing a scaled radius!");
-        return Ok(0);
+        return None;
     };
     let Some(zb) = op.params.real.get("zb") else {
         warn!("Missing a zombie parameter!");
-        return Ok(0);
+        return None;
     };
+    Some(Tmerc::from_parts(
+        op.params.ellps[0],
+        op.params.lon[0],
+        op.params.x[0],
+        *qs,
+        *zb,
+        *conformal,
+        *tm,
+    ))
+}
 
-    let mut successes = 0_usize;
-    for coord in operands {
-        // --- 1. Geographical -> Conformal latitude, rotated longitude
-
-        // The conformal latitude
-        let lat = ellps.latitude_geographic_to_conformal(coord[1] + lat_0, conformal);
-        // The longitude as reckoned from the central meridian
-        let lon = coord[0] - lon_0;
-
-        // --- 2. Conformal LAT, LNG -> complex spherical LAT
-
-        let (sin_lat, cos_lat) = lat.sin_cos();
-        let (sin_lon, cos_lon) = lon.sin_cos();
-        let cos_lat_lon = cos_lat * cos_lon;
-        let mut lat = sin_lat.atan2(cos_lat_lon);
-
-        // --- 3. Complex spherical N, E -> ellipsoidal normalized N, E
-
-        // Some numerical optimizations from PROJ modifications by Even Rouault,
-        let inv_denom_tan_lon = 1. / sin_lat.hypot(cos_lat_lon);
-        let tan_lon = sin_lon * cos_lat * inv_denom_tan_lon;
-        // Inverse Gudermannian, using the precomputed tan(lon)
-        let mut lon = tan_lon.asinh();
-
-        // Trigonometric terms for Clenshaw summation
-        // Non-optimized version:  `let trig = (2.*lat).sin_cos()`
-        let two_inv_denom_tan_lon = 2.0 * inv_denom_tan_lon;
-        let two_inv_denom_tan_lon_square = two_inv_denom_tan_lon * inv_denom_tan_lon;
-        let tmp_r = cos_lat_lon * two_inv_denom_tan_lon_square;
-        let trig = [sin_lat * tmp_r, cos_lat_lon * tmp_r - 1.0];
-
-        // Hyperbolic terms for Clenshaw summation
-        // Non-optimized version:  `let hyp = [(2.*lon).sinh(), (2.*lon).sinh()]`
-        let hyp = [
-            tan_lon * two_inv_denom_tan_lon,
-            two_inv_denom_tan_lon_square - 1.0,
-        ];
-
-        // Evaluate and apply the differential term
-        let dc = clenshaw_complex_sin_optimized_for_tmerc(trig, hyp, &tm.fwd);
-        lat += dc[0];
-        lon += dc[1];
-
-        // Don't wanna play if we're too far from the center meridian
-        if lon.abs() > 2.623395162778 {
-            coord[0] = f64::NAN;
-            coord[1] = f64::NAN;
-            continue;
-        }
-
-        // --- 4. ellipsoidal normalized N, E -> metric N, E
-
-        coord[0] = qs * lon + x_0; // Easting
-        coord[1] = qs * lat + zb; // Northing
-        successes += 1;
-    }
-
+fn fwd(op: &Op, _ctx: &dyn Context, operands: &mut [Coord]) -> Result<usize, Error> {
+    let successes = kernel(op).map_or(0, |tmerc| tmerc.fwd(operands));
     info!("Successes: {successes}");
     Ok(successes)
 }
 
-// ----- I N V E R S E -----------------------------------------------------------------
-
-// Inverse Transverse Mercator, following Engsager & Poder (2007) (currently Bowring stands in!)
 fn inv(op: &Op, _ctx: &dyn Context, operands: &mut [Coord]) -> Result<usize, Error> {
-    // Make all precomputed parameters directly accessible
-    let ellps = op.params.ellps[0];
-    let lon_0 = op.params.lon[0];
-    let x_0 = op.params.x[0];
-    let Some(conformal) = op.params.fourier_coefficients.get("conformal") else {
-        warn!("Missing Fourier coefficients for conformal mapping!");
-        return Ok(0);
-    };
-    let Some(tm) = op.params.fourier_coefficients.get("tm") else {
-        warn!("Missing Fourier coefficients for TM!");
-        return Ok(0);
-    };
-    let Some(qs) = op.params.real.get("scaled_radius") else {
-        warn!("Missing a scaled radius!");
-        return Ok(0);
-    };
-    let Some(zb) = op.params.real.get("zb") else {
-        warn!("Missing a zombie parameter!");
-        return Ok(0);
-    };
-
-    let mut successes = 0_usize;
-    for coord in operands {
-        // --- 1. Normalize N, E
-
-        let mut lon = (coord[0] - x_0) / qs;
-        let mut lat = (coord[1] - zb) / qs;
-
-        // Don't wanna play if we're too far from the center meridian
-        if lon.abs() > 2.623395162778 {
-            coord[0] = f64::NAN;
-            coord[1] = f64::NAN;
-            continue;
-        }
-
-        // --- 2. Normalized N, E -> complex spherical LAT, LNG
-
-        let dc = clenshaw_complex_sin([2. * lat, 2. * lon], &tm.inv);
-        lat += dc[0];
-        lon += dc[1];
-        lon = gudermannian(lon);
-
-        // --- 3. Complex spherical LAT -> Gaussian LAT, LNG
-
-        let (sin_lat, cos_lat) = lat.sin_cos();
-        let (sin_lon, cos_lon) = lon.sin_cos();
-        let cos_lat_lon = cos_lat * cos_lon;
-        lon = sin_lon.atan2(cos_lat_lon);
-        lat = (sin_lat * cos_lon).atan2(sin_lon.hypot(cos_lat_lon));
-
-        // --- 4. Gaussian LAT, LNG -> ellipsoidal LAT, LNG
-
-        let lon = normalize_angle_symmetric(lon + lon_0);
-        let lat = ellps.latitude_conformal_to_geographic(lat, conformal);
-        (coord[0], coord[1]) = (lon, lat);
-
-        successes += 1;
-    }
-
+    let successes = kernel(op).map_or(0, |tmerc| tmerc.inv(operands));
     info!("Successes: {successes}");
     Ok(successes)
 }
@@ -159,7 +50,7 @@ fn inv(op: &Op, _ctx: &dyn Context, operands: &mut [Coord]) -> Result<usize, Err
 // ----- C O N S T R U C T O R ---------------------------------------------------------
 
 #[rustfmt::skip]
-pub const GAMUT: [OpParameter; 7] = [
+pub const GAMUT: [OpParameter; 8] = [
     OpParameter::Flag { key: "inv" },
     OpParameter::Text { key: "ellps", default: Some("GRS80") },
 
@@ -169,27 +60,49 @@ pub const GAMUT: [OpParameter; 7] = [
     OpParameter::Real { key: "y_0",   default: Some(0_f64) },
 
     OpParameter::Real { key: "k_0",   default: Some(1_f64) },
+
+    // A no-op: The rotation to the central meridian goes through the sine and
+    // cosine of `lon - lon_0`, so e.g. 190° and -170° give identical results.
+    // Accepted for symmetry with merc and lcc, where it matters
+    OpParameter::Flag { key: "wrap" },
 ];
 
 #[rustfmt::skip]
-pub const UTM_GAMUT: [OpParameter; 3] = [
+pub const UTM_GAMUT: [OpParameter; 4] = [
     OpParameter::Flag { key: "inv" },
     OpParameter::Text { key: "ellps", default: Some("GRS80") },
-    OpParameter::Natural { key: "zone", default: None },
+    OpParameter::Text { key: "zone", default: None },
+    OpParameter::Flag { key: "wrap" },
 ];
 
 // ----- C O N S T R U C T O R,   U T M ------------------------------------------------
 
-pub fn utm(parameters: &RawParameters, _ctx: &dyn Context) -> Result<Op, Error> {
+pub fn utm(parameters: &RawParameters, ctx: &dyn Context) -> Result<Op, Error> {
     let def = &parameters.definition;
     let mut params = ParsedParameters::new(parameters, &UTM_GAMUT)?;
 
+    // With `zone=auto`, the zone is selected per point, and the easting is
+    // prefixed by the zone number. The remaining parameters are set for the
+    // zone centered on the Greenwich meridian, and adjusted point-by-point
+    let zone = params.text("zone")?;
+    let (fwd, inv) = if zone == "auto" {
+        params.boolean.insert("auto");
+        (InnerOp(utm_auto_fwd), InnerOp(utm_auto_inv))
+    } else {
+        (InnerOp(fwd), InnerOp(inv))
+    };
+    let zone = if zone == "auto" {
+        GREENWICH_ZONE
+    } else {
+        zone.parse::<usize>()
+            .map_err(|_| Error::BadParam("zone".to_string(), zone.clone()))?
+    };
+
     // The UTM zone should be an integer between 1 and 60
-    let zone = params.natural("zone")?;
     if !(1..61).contains(&zone) {
         error!("UTM: {zone}. Must be an integer in the interval 1..60");
         return Err(Error::General(
-            "UTM: 'zone' must be an integer in the interval 1..60",
+            "UTM: 'zone' must be an integer in the interval 1..60".to_string(),
         ));
     }
     info!("Zone: {zone}");
@@ -198,7 +111,7 @@ pub fn utm(parameters: &RawParameters, _ctx: &dyn Context) -> Result<Op, Error>
     params.k[0] = 0.9996;
 
     // The center meridian is determined by the zone
-    params.lon[0] = (-183. + 6. * zone as f64).to_radians();
+    params.lon[0] = utm_central_meridian(zone);
 
     // The base parallel is by definition the equator
     params.lat[0] = 0.0;
@@ -209,7 +122,8 @@ pub fn utm(parameters: &RawParameters, _ctx: &dyn Context) -> Result<Op, Error>
     // The false northing is 0 m by definition of UTM
     params.x[0] = 500000.0;
 
-    let descriptor = OpDescriptor::new(def, InnerOp(fwd), Some(InnerOp(inv)));
+    let mut descriptor = OpDescriptor::new(def, fwd, Some(inv));
+    descriptor.angular_io = (true, false);
     let steps = Vec::<Op>::new();
     let id = OpHandle::new();
 
@@ -220,51 +134,102 @@ pub fn utm(parameters: &RawParameters, _ctx: &dyn Context) -> Result<Op, Error>
         id,
     };
 
-    precompute(&mut op);
+    precompute(&mut op, ctx);
     Ok(op)
 }
 
-// ----- A N C I L L A R Y   F U N C T I O N S -----------------------------------------
+// ----- U T M ,   A U T O M A T I C   Z O N E   S E L E C T I O N ----------------------
 
-#[rustfmt::skip]
-const TRANSVERSE_MERCATOR: PolynomialCoefficients = PolynomialCoefficients {
-    // Geodetic to TM. [Engsager & Poder, 2007](crate::Bibliography::Eng07)
-    fwd: [
-        [1./2.,   -2./3.,   5./16.,   41./180.,   -127./288.0 ,   7891./37800.],
-        [0., 13./48.,   -3./5.,   557./1440.,   281./630.,   -1983433./1935360.],
-        [0., 0., 61./240.,  -103./140.,   15061./26880.,   167603./181440.],
-        [0., 0., 0., 49561./161280.,   -179./168.,   6601661./7257600.],
-        [0., 0., 0., 0., 34729./80640.,   -3418889./1995840.],
-        [0., 0., 0., 0., 0., 212378941./319334400.]
-    ],
-
-    // TM to Geodetic. [Engsager & Poder, 2007](crate::Bibliography::Eng07)
-    inv: [
-        [-1./2.,   2./3.,   -37./96.,   1./360.,   81./512.,   -96199./604800.],
-        [0., -1./48.,   -1./15.,   437./1440.,   -46./105.,   1118711./3870720.],
-        [0., 0., -17./480.,   37./840.,   209./4480.,   -5569./90720.],
-        [0., 0., 0., -4397./161280.,   11./504.,   830251./7257600.],
-        [0., 0., 0., 0., -4583./161280.,   108847./3991680.],
-        [0., 0., 0., 0., 0., -20648693./638668800.]
-    ]
-};
+// The zone with central meridian at 3°E, i.e. the one used for constructing
+// the TM-part of `utm zone=auto`
+const GREENWICH_ZONE: usize = 31;
+
+// Forward UTM, with each point projected in its own zone, as given by
+// `utm_zone()`, and the zone number prefixed to the easting, i.e.
+// easting = zone · 1_000_000 + zone specific easting
+fn utm_auto_fwd(op: &Op, ctx: &dyn Context, operands: &mut [Coord]) -> Result<usize, Error> {
+    let lon_0 = op.params.lon[0];
+    let mut successes = 0_usize;
+    for coord in operands {
+        let zone = utm_zone(coord[0], coord[1]);
+        coord[0] -= utm_central_meridian(zone) - lon_0;
+        if fwd(op, ctx, std::slice::from_mut(coord))? == 1 {
+            coord[0] += 1e6 * zone as f64;
+            successes += 1;
+        }
+    }
+    Ok(successes)
+}
+
+// Inverse of `utm_auto_fwd`: The zone is given by the leading digits of the easting
+fn utm_auto_inv(op: &Op, ctx: &dyn Context, operands: &mut [Coord]) -> Result<usize, Error> {
+    let lon_0 = op.params.lon[0];
+    let mut successes = 0_usize;
+    for coord in operands {
+        let zone = (coord[0] / 1e6).floor();
+        if !(1. ..=60.).contains(&zone) {
+            *coord = Coord::failed(PointStatus::OutOfDomain);
+            continue;
+        }
+        coord[0] -= 1e6 * zone;
+        if inv(op, ctx, std::slice::from_mut(coord))? == 1 {
+            let lon = coord[0] + utm_central_meridian(zone as usize) - lon_0;
+            coord[0] = normalize_angle_symmetric(lon);
+            successes += 1;
+        }
+    }
+    Ok(successes)
+}
+
+// The central meridian of a UTM zone, in radians
+fn utm_central_meridian(zone: usize) -> f64 {
+    (-183. + 6. * zone as f64).to_radians()
+}
+
+/// The UTM zone number, 1..=60, for a point given by its `longitude` and
+/// `latitude` (in radians), taking into account the exceptions for south
+/// western Norway (zone 32V extended westward to 3°E), and Svalbard (zones
+/// 31X, 33X, 35X, 37X widened to cover the unused 32X, 34X, 36X).
+///
+/// The hemisphere follows from the sign of the latitude: The `utm` operator
+/// uses no false northing, so southern hemisphere northings are negative.
+#[must_use]
+pub fn utm_zone(longitude: f64, latitude: f64) -> usize {
+    let lon = normalize_angle_symmetric(longitude).to_degrees();
+    let lat = latitude.to_degrees();
+
+    // South western Norway
+    if (56. ..64.).contains(&lat) && (3. ..12.).contains(&lon) {
+        return 32;
+    }
+
+    // Svalbard
+    if (72. ..=84.).contains(&lat) && (0. ..42.).contains(&lon) {
+        return match lon {
+            lon if lon < 9. => 31,
+            lon if lon < 21. => 33,
+            lon if lon < 33. => 35,
+            _ => 37,
+        };
+    }
+
+    // The general case (wrapping 180°E to zone 1)
+    (((lon + 180.) / 6.).floor() as usize) % 60 + 1
+}
+
+// ----- A N C I L L A R Y   F U N C T I O N S -----------------------------------------
 
 // Common setup workhorse between utm and the plain tmerc:
 // Pre-compute some of the computationally heavy prerequisites,
 // to get better amortization over the full operator lifetime.
-fn precompute(op: &mut Op) {
+fn precompute(op: &mut Op, ctx: &dyn Context) {
     let ellps = op.params.ellps[0];
-    let n = ellps.third_flattening();
-    let lat_0 = op.params.lat[0];
-    let y_0 = op.params.y[0];
-
-    // The scaled spherical Earth radius - Qn in Engsager's implementation
-    let qs = op.params.k[0] * ellps.semimajor_axis() * ellps.normalized_meridian_arc_unit();
-    op.params.real.insert("scaled_radius", qs);
-    info!("Scaled radius: {qs}");
+    let inputs = [ellps.semimajor_axis(), ellps.flattening()];
 
     // The Fourier series for the conformal latitude
-    let conformal = ellps.coefficients_for_conformal_latitude_computations();
+    let conformal = ctx.fourier_coefficients("conformal", &inputs, &|| {
+        ellps.coefficients_for_conformal_latitude_computations()
+    });
     op.params
         .fourier_coefficients
         .insert("conformal", conformal);
@@ -273,24 +238,30 @@ fn precompute(op: &mut Op) {
         conformal
     );
 
-    // The Fourier series for the transverse mercator coordinates, from [Engsager & Poder, 2007](crate::Bibliography::Eng07),
-    // with extensions to 6th order by [Karney, 2011](crate::Bibliography::Kar11).
-    let tm = fourier_coefficients(n, &TRANSVERSE_MERCATOR);
+    // The Fourier series for the transverse mercator coordinates
+    let tm = ctx.fourier_coefficients("tm", &inputs, &|| Tmerc::coefficients(&ellps));
     op.params.fourier_coefficients.insert("tm", tm);
     info!("Fourier coefficients for TM: {:#?}", conformal);
 
-    // Conformal latitude value of the latitude-of-origin - Z in Engsager's notation
-    let z = ellps.latitude_geographic_to_conformal(lat_0, &conformal);
-    // Origin northing minus true northing at the origin latitude
+    // The scaled spherical Earth radius, and the zombie parameter,
     // i.e. true northing = N - zb
-    let zb = y_0 - qs * (z + clenshaw_sin(2. * z, &tm.fwd));
+    let (k_0, lat_0, y_0) = (op.params.k[0], op.params.lat[0], op.params.y[0]);
+    let (qs, zb) = Tmerc::scaled_radius_and_zb(&ellps, k_0, lat_0, y_0, &conformal, &tm);
+    op.params.real.insert("scaled_radius", qs);
+    info!("Scaled radius: {qs}");
     op.params.real.insert("zb", zb);
     info!("Zombie parameter: {zb}");
+
+    // On the sphere, the kernel switches to the closed form expressions
+    if ellps.eccentricity_squared() == 0. {
+        op.params.boolean.insert("spherical");
+    }
 }
 
 pub fn new(parameters: &RawParameters, ctx: &dyn Context) -> Result<Op, Error> {
     let mut op = Op::plain(parameters, InnerOp(fwd), InnerOp(inv), &GAMUT, ctx)?;
-    precompute(&mut op);
+    op.descriptor.angular_io = (true, false);
+    precompute(&mut op, ctx);
     Ok(op)
 }
 
@@ -324,7 +295,7 @@ mod tests {
         let definition = "tmerc k_0=0.9996 lon_0=9 x_0=500000";
         let op = Op::new(definition, &ctx)?;
 
-        let mut operands = geo.clone();
+        let mut operands = geo;
         op.apply(&ctx, &mut operands, Fwd)?;
 
         for i in 0..operands.len() {
@@ -341,6 +312,123 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn tmerc_spherical() -> Result<(), Error> {
+        let ctx = Minimal::default();
+        let sphere = Op::new("tmerc ellps=sphere lat_0=30 lon_0=9 k_0=0.9996", &ctx)?;
+        assert!(sphere.params.boolean("spherical"));
+
+        // The ellipsoidal formulas, for a nearly spherical ellipsoid
+        let reference = Op::new("tmerc ellps=6370997,1e15 lat_0=30 lon_0=9 k_0=0.9996", &ctx)?;
+        assert!(!reference.params.boolean("spherical"));
+
+        let geo = [
+            Coord::geo(55., 12., 0., 0.),
+            Coord::geo(-55., 40., 0., 0.),
+            Coord::geo(89.9, -100., 0., 0.),
+            Coord::geo(0., 9., 0., 0.),
+        ];
+        let mut operands = geo;
+        let mut expected = geo;
+        sphere.apply(&ctx, &mut operands, Fwd)?;
+        reference.apply(&ctx, &mut expected, Fwd)?;
+        for i in 0..operands.len() {
+            assert!(operands[i].hypot2(&expected[i]) < 1e-6);
+        }
+
+        sphere.apply(&ctx, &mut operands, Inv)?;
+        for i in 0..operands.len() {
+            assert!(operands[i].hypot2(&geo[i]) < 1e-12);
+        }
+
+        // Too far from the central meridian
+        let mut operands = [Coord::geo(0., 99., 0., 0.)];
+        assert_eq!(sphere.apply(&ctx, &mut operands, Fwd)?, 0);
+
+        // UTM, with fixed and automatic zones
+        let auto = Op::new("utm zone=auto ellps=sphere", &ctx)?;
+        let z32 = Op::new("utm zone=32 ellps=sphere", &ctx)?;
+        let mut operands = [Coord::geo(55., 11., 0., 0.)];
+        let mut expected = operands;
+        auto.apply(&ctx, &mut operands, Fwd)?;
+        z32.apply(&ctx, &mut expected, Fwd)?;
+        assert!((operands[0][0] - 32e6 - expected[0][0]).abs() < 1e-6);
+        auto.apply(&ctx, &mut operands, Inv)?;
+        assert!(operands[0].hypot2(&Coord::geo(55., 11., 0., 0.)) < 1e-12);
+        Ok(())
+    }
+
+    #[test]
+    fn utm_zone() {
+        let zone = |lon: f64, lat: f64| super::utm_zone(lon.to_radians(), lat.to_radians());
+
+        // The general case
+        assert_eq!(zone(-180., 0.), 1);
+        assert_eq!(zone(-177., -60.), 1);
+        assert_eq!(zone(11.99, 55.), 32);
+        assert_eq!(zone(12., 55.), 33);
+        assert_eq!(zone(179.99, 0.), 60);
+        assert_eq!(zone(180., 0.), 1);
+
+        // Norway
+        assert_eq!(zone(5., 60.), 32);
+        assert_eq!(zone(2.99, 60.), 31);
+        assert_eq!(zone(5., 64.), 31);
+
+        // Svalbard
+        assert_eq!(zone(8.99, 78.), 31);
+        assert_eq!(zone(9., 78.), 33);
+        assert_eq!(zone(25., 78.), 35);
+        assert_eq!(zone(40., 78.), 37);
+        assert_eq!(zone(43., 78.), 38);
+    }
+
+    #[test]
+    fn utm_auto() -> Result<(), Error> {
+        let ctx = Minimal::default();
+        let auto = Op::new("utm zone=auto", &ctx)?;
+        let z32 = Op::new("utm zone=32", &ctx)?;
+        let z33 = Op::new("utm zone=33", &ctx)?;
+
+        let geo = [
+            Coord::geo(55., 11., 0., 0.),
+            Coord::geo(-55., 12., 0., 0.),
+            Coord::geo(60., 5., 0., 0.),
+        ];
+
+        // The individual points, projected in their expected zones
+        let mut expected = geo;
+        z32.apply(&ctx, &mut expected[0..1], Fwd)?;
+        z33.apply(&ctx, &mut expected[1..2], Fwd)?;
+        z32.apply(&ctx, &mut expected[2..3], Fwd)?;
+        for (i, zone) in [32., 33., 32.].iter().enumerate() {
+            expected[i][0] += zone * 1e6;
+        }
+
+        let mut operands = geo;
+        assert_eq!(auto.apply(&ctx, &mut operands, Fwd)?, 3);
+        for i in 0..operands.len() {
+            assert!(operands[i].hypot2(&expected[i]) < 1e-9);
+        }
+
+        assert_eq!(auto.apply(&ctx, &mut operands, Inv)?, 3);
+        for i in 0..operands.len() {
+            assert!(operands[i].default_ellps_dist(&geo[i]) < 1e-6);
+        }
+
+        // Not a zone prefixed easting
+        let mut operands = [Coord::raw(500_000., 6e6, 0., 0.)];
+        assert_eq!(auto.apply(&ctx, &mut operands, Inv)?, 0);
+        assert!(operands[0][0].is_nan());
+
+        // Neither a zone number, nor "auto"
+        assert!(matches!(
+            Op::new("utm zone=eleventy", &ctx),
+            Err(Error::BadParam(_, _))
+        ));
+        Ok(())
+    }
+
     #[test]
     fn utm() -> Result<(), Error> {
         let ctx = Minimal::default();
@@ -365,7 +453,7 @@ mod tests {
             Coord::raw(-455_673.814_189_040,-6_198_246.671_090_279, 0., 0.)
         ];
 
-        let mut operands = geo.clone();
+        let mut operands = geo;
         op.apply(&ctx, &mut operands, Fwd)?;
         for i in 0..operands.len() {
             assert!(operands[i].hypot2(&projected[i]) < 5e-3);
@@ -0,0 +1,187 @@
+use crate::coord::Interleaved;
+use crate::internal::*;
+use arrow_array::builder::Float64Builder;
+use arrow_array::cast::downcast_array;
+use arrow_array::{Array, ArrayRef, FixedSizeListArray, Float64Array, StructArray};
+use arrow_schema::DataType;
+use std::sync::Arc;
+
+// ----- A R R O W   /   G E O A R R O W -----------------------------------------------
+
+/// Apply `op` to the points of a GeoArrow point array, in either of the two
+/// GeoArrow point layouts: *Interleaved* (a `FixedSizeList` of 2, 3 or 4 `Float64`
+/// values), or *separated* (a `Struct` of 2, 3 or 4 `Float64` fields, e.g. `x`,
+/// `y`, `z`). Returns an array of the same type and layout, holding the transformed
+/// coordinates, along with the number of successfully transformed points.
+///
+/// The coordinates are transformed in place, in the buffers of `points`, unless
+/// these are shared with other arrays (e.g. clones, or slices, of `points`), or
+/// not allocated by Arrow (e.g. converted from a `Vec`), in which case they are
+/// copied once, into the buffers of the result. Null points
+/// are transformed too, whatever their values, and count among the successes if
+/// those transform.
+pub fn apply_to_arrow(
+    ctx: &dyn Context,
+    op: OpHandle,
+    direction: Direction,
+    points: ArrayRef,
+) -> Result<(ArrayRef, usize), Error> {
+    match points.data_type().clone() {
+        DataType::FixedSizeList(_, dim) => {
+            let dim = check_dimension(dim as usize)?;
+            let list: FixedSizeListArray = downcast_array(&points);
+            drop(points);
+            let (field, size, values, nulls) = list.into_parts();
+            let mut values = float64_builder(values)?;
+            let mut coordinates = Interleaved {
+                values: values.values_slice_mut(),
+                dim,
+            };
+            let successes = ctx.apply(op, direction, &mut coordinates)?;
+            let values = Arc::new(values.finish());
+            let result = FixedSizeListArray::try_new(field, size, values, nulls)
+                .map_err(|e| Error::Syntax(e.to_string()))?;
+            Ok((Arc::new(result), successes))
+        }
+
+        DataType::Struct(fields) => {
+            check_dimension(fields.len())?;
+            let array: StructArray = downcast_array(&points);
+            drop(points);
+            let (fields, columns, nulls) = array.into_parts();
+            let mut columns = columns
+                .into_iter()
+                .map(float64_builder)
+                .collect::<Result<Vec<_>, Error>>()?;
+
+            let mut slices = columns.iter_mut().map(|c| c.values_slice_mut());
+            let (Some(x), Some(y)) = (slices.next(), slices.next()) else {
+                unreachable!("The dimension is checked above");
+            };
+            let mut coordinates = CoordinateSoA::new(x, y);
+            if let Some(z) = slices.next() {
+                coordinates = coordinates.z(z);
+            }
+            if let Some(t) = slices.next() {
+                coordinates = coordinates.t(t);
+            }
+            let successes = ctx.apply(op, direction, &mut coordinates)?;
+
+            let columns = columns
+                .iter_mut()
+                .map(|column| Arc::new(column.finish()) as ArrayRef)
+                .collect();
+            let result = StructArray::try_new(fields, columns, nulls)
+                .map_err(|e| Error::Syntax(e.to_string()))?;
+            Ok((Arc::new(result), successes))
+        }
+
+        other => Err(Error::Unexpected {
+            message: "Unsupported Arrow array type".to_string(),
+            expected: "FixedSizeList or Struct of Float64".to_string(),
+            found: other.to_string(),
+        }),
+    }
+}
+
+fn float64(array: &ArrayRef) -> Result<&Float64Array, Error> {
+    array
+        .as_any()
+        .downcast_ref::<Float64Array>()
+        .ok_or_else(|| Error::Unexpected {
+            message: "Unsupported Arrow coordinate type".to_string(),
+            expected: "Float64".to_string(),
+            found: array.data_type().to_string(),
+        })
+}
+
+// A builder for modifying the values of `array` in place, if its buffers are not
+// shared (and allocated by Arrow, hence suitably aligned), otherwise for modifying
+// a copy
+fn float64_builder(array: ArrayRef) -> Result<Float64Builder, Error> {
+    let values: Float64Array = downcast_array(float64(&array)?);
+    drop(array);
+    values.into_builder().or_else(|shared| {
+        let values = shared.values().iter().copied().collect();
+        let copy = Float64Array::new(values, shared.nulls().cloned());
+        copy.into_builder()
+            .map_err(|_| Error::General("Arrow: Unexpectedly shared buffer".to_string()))
+    })
+}
+
+fn check_dimension(dim: usize) -> Result<usize, Error> {
+    if !(2..=4).contains(&dim) {
+        return Err(Error::BadParam("dimension".to_string(), dim.to_string()));
+    }
+    Ok(dim)
+}
+
+// ----- T E S T S ------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow_schema::{Field, Fields};
+
+    #[test]
+    fn geoarrow() -> Result<(), Error> {
+        let mut ctx = Minimal::new();
+        let op = ctx.op("gis:in | utm zone=32")?;
+        let mut expected = [Coord::raw(12., 55., 0., 0.)];
+        ctx.apply(op, Fwd, &mut expected)?;
+
+        // Interleaved, with a null point
+        let field = Arc::new(Field::new("xy", DataType::Float64, false));
+        let values = Float64Array::from_iter_values([12., 55., 0., 0., 12., 55.]);
+        let nulls = Some(vec![true, false, true].into());
+        let points = FixedSizeListArray::try_new(field, 2, Arc::new(values), nulls).unwrap();
+        let data_type = points.data_type().clone();
+        let buffer = float64(points.values())?.values().as_ptr();
+        let (result, successes) = apply_to_arrow(&ctx, op, Fwd, Arc::new(points))?;
+        assert_eq!(successes, 3);
+        assert_eq!(result.data_type(), &data_type);
+        assert!(result.is_null(1));
+        let result = result
+            .as_any()
+            .downcast_ref::<FixedSizeListArray>()
+            .unwrap();
+        let values = float64(result.values())?;
+        assert_eq!(values.value(4), expected[0][0]);
+        assert_eq!(values.value(5), expected[0][1]);
+
+        // Transformed in place, since the buffer is not shared...
+        assert_eq!(values.values().as_ptr(), buffer);
+
+        // ...while shared buffers are left alone
+        let shared: ArrayRef = Arc::new(result.clone());
+        let (again, _) = apply_to_arrow(&ctx, op, Inv, shared)?;
+        assert_eq!(float64(result.values())?.value(4), expected[0][0]);
+        let again = again.as_any().downcast_ref::<FixedSizeListArray>().unwrap();
+        assert!((float64(again.values())?.value(4) - 12.).abs() < 1e-9);
+
+        // Separated, 3D
+        let fields: Fields = ["x", "y", "z"]
+            .into_iter()
+            .map(|name| Field::new(name, DataType::Float64, false))
+            .collect();
+        let columns: Vec<ArrayRef> = [12., 55., 100.]
+            .map(|v| Arc::new(Float64Array::from_iter_values([v; 2])) as ArrayRef)
+            .into();
+        let points = StructArray::try_new(fields, columns, None).unwrap();
+        let buffer = float64(points.column(0))?.values().as_ptr();
+        let (result, successes) = apply_to_arrow(&ctx, op, Fwd, Arc::new(points))?;
+        assert_eq!(successes, 2);
+        let result = result.as_any().downcast_ref::<StructArray>().unwrap();
+        assert_eq!(float64(result.column(0))?.value(1), expected[0][0]);
+        assert_eq!(float64(result.column(2))?.value(1), 100.);
+        assert_eq!(float64(result.column(0))?.values().as_ptr(), buffer);
+
+        // Not a point array
+        let values: ArrayRef = Arc::new(Float64Array::from(vec![1., 2.]));
+        assert!(matches!(
+            apply_to_arrow(&ctx, op, Fwd, values),
+            Err(Error::Unexpected { .. })
+        ));
+        Ok(())
+    }
+}
@@ -0,0 +1,246 @@
+use crate::internal::*;
+use geo_types::{
+    Geometry, GeometryCollection, Line, LineString, MultiLineString, MultiPoint, MultiPolygon,
+    Point, Polygon, Triangle,
+};
+
+// ----- C O N V E R S I O N S ---------------------------------------------------------
+
+// The third and fourth coordinate elements are zeroed on conversion to `Coord`,
+// and dropped on conversion from `Coord`
+
+impl From<geo_types::Coord<f64>> for Coord {
+    fn from(c: geo_types::Coord<f64>) -> Coord {
+        Coord::raw(c.x, c.y, 0., 0.)
+    }
+}
+
+impl From<Coord> for geo_types::Coord<f64> {
+    fn from(c: Coord) -> geo_types::Coord<f64> {
+        geo_types::coord! { x: c[0], y: c[1] }
+    }
+}
+
+impl From<Point<f64>> for Coord {
+    fn from(p: Point<f64>) -> Coord {
+        p.0.into()
+    }
+}
+
+impl From<Coord> for Point<f64> {
+    fn from(c: Coord) -> Point<f64> {
+        Point(c.into())
+    }
+}
+
+// ----- C O O R D I N A T E   S E T S -------------------------------------------------
+
+impl CoordinateSet for [geo_types::Coord<f64>] {
+    fn len(&self) -> usize {
+        <[geo_types::Coord<f64>]>::len(self)
+    }
+    fn get_coord(&self, index: usize) -> Coord {
+        self[index].into()
+    }
+    fn set_coord(&mut self, index: usize, value: &Coord) {
+        self[index] = (*value).into();
+    }
+}
+
+impl CoordinateSet for LineString<f64> {
+    fn len(&self) -> usize {
+        self.0.len()
+    }
+    fn get_coord(&self, index: usize) -> Coord {
+        self.0[index].into()
+    }
+    fn set_coord(&mut self, index: usize, value: &Coord) {
+        self.0[index] = (*value).into();
+    }
+}
+
+// ----- T R A N S F O R M A T I O N   O F   G E O M E T R I E S -----------------------
+
+/// In place transformation of `geo-types` geometries. The coordinates are handed
+/// to the operator as 2D (x, y), i.e. for most operators (longitude, latitude)
+/// in degrees, when prefixed by `gis:in`.
+pub trait TransformGeometry {
+    /// Apply the operator `op` to all coordinates of the geometry. Returns the
+    /// number of successfully transformed coordinates.
+    fn apply(
+        &mut self,
+        ctx: &dyn Context,
+        op: OpHandle,
+        direction: Direction,
+    ) -> Result<usize, Error>;
+}
+
+impl TransformGeometry for Point<f64> {
+    fn apply(
+        &mut self,
+        ctx: &dyn Context,
+        op: OpHandle,
+        direction: Direction,
+    ) -> Result<usize, Error> {
+        let mut coords = [self.0];
+        let successes = ctx.apply(op, direction, &mut &mut coords[..])?;
+        self.0 = coords[0];
+        Ok(successes)
+    }
+}
+
+impl TransformGeometry for Line<f64> {
+    fn apply(
+        &mut self,
+        ctx: &dyn Context,
+        op: OpHandle,
+        direction: Direction,
+    ) -> Result<usize, Error> {
+        let mut coords = [self.start, self.end];
+        let successes = ctx.apply(op, direction, &mut &mut coords[..])?;
+        [self.start, self.end] = coords;
+        Ok(successes)
+    }
+}
+
+impl TransformGeometry for LineString<f64> {
+    fn apply(
+        &mut self,
+        ctx: &dyn Context,
+        op: OpHandle,
+        direction: Direction,
+    ) -> Result<usize, Error> {
+        ctx.apply(op, direction, self)
+    }
+}
+
+impl TransformGeometry for Triangle<f64> {
+    fn apply(
+        &mut self,
+        ctx: &dyn Context,
+        op: OpHandle,
+        direction: Direction,
+    ) -> Result<usize, Error> {
+        let mut coords = self.to_array();
+        let successes = ctx.apply(op, direction, &mut &mut coords[..])?;
+        *self = Triangle::new(coords[0], coords[1], coords[2]);
+        Ok(successes)
+    }
+}
+
+impl TransformGeometry for Polygon<f64> {
+    fn apply(
+        &mut self,
+        ctx: &dyn Context,
+        op: OpHandle,
+        direction: Direction,
+    ) -> Result<usize, Error> {
+        let mut exterior = Ok(0);
+        self.exterior_mut(|ring| exterior = ring.apply(ctx, op, direction));
+        let mut interiors = Ok(0);
+        self.interiors_mut(|rings| {
+            interiors = rings
+                .iter_mut()
+                .map(|ring| ring.apply(ctx, op, direction))
+                .sum()
+        });
+        Ok(exterior? + interiors?)
+    }
+}
+
+// Collections of geometries
+macro_rules! transform_collection {
+    ($($collection:ty),+) => {$(
+        impl TransformGeometry for $collection {
+            fn apply(
+                &mut self,
+                ctx: &dyn Context,
+                op: OpHandle,
+                direction: Direction,
+            ) -> Result<usize, Error> {
+                self.0.iter_mut().map(|g| g.apply(ctx, op, direction)).sum()
+            }
+        }
+    )+};
+}
+
+transform_collection!(
+    MultiPoint<f64>,
+    MultiLineString<f64>,
+    MultiPolygon<f64>,
+    GeometryCollection<f64>
+);
+
+impl TransformGeometry for Geometry<f64> {
+    fn apply(
+        &mut self,
+        ctx: &dyn Context,
+        op: OpHandle,
+        direction: Direction,
+    ) -> Result<usize, Error> {
+        match self {
+            Geometry::Point(g) => g.apply(ctx, op, direction),
+            Geometry::Line(g) => g.apply(ctx, op, direction),
+            Geometry::LineString(g) => g.apply(ctx, op, direction),
+            Geometry::Polygon(g) => g.apply(ctx, op, direction),
+            Geometry::MultiPoint(g) => g.apply(ctx, op, direction),
+            Geometry::MultiLineString(g) => g.apply(ctx, op, direction),
+            Geometry::MultiPolygon(g) => g.apply(ctx, op, direction),
+            Geometry::GeometryCollection(g) => g.apply(ctx, op, direction),
+            Geometry::Triangle(g) => g.apply(ctx, op, direction),
+            // A transformed rectangle is generally not a rectangle
+            Geometry::Rect(_) => Err(Error::General(
+                "Cannot transform Rect in place - convert to Polygon first".to_string(),
+            )),
+        }
+    }
+}
+
+// ----- T E S T S ------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use geo_types::{coord, line_string, point};
+
+    #[test]
+    fn conversions() {
+        let c = Coord::raw(12., 55., 100., 2000.);
+        let g: geo_types::Coord<f64> = c.into();
+        assert_eq!(g, coord! { x: 12., y: 55. });
+        assert_eq!(Coord::from(g), Coord::raw(12., 55., 0., 0.));
+        let p: Point<f64> = c.into();
+        assert_eq!(Coord::from(p), Coord::raw(12., 55., 0., 0.));
+    }
+
+    #[test]
+    fn geometries() -> Result<(), Error> {
+        let mut ctx = Minimal::new();
+        let op = ctx.op("gis:in | utm zone=32")?;
+        let mut expected = [Coord::raw(12., 55., 0., 0.)];
+        ctx.apply(op, Fwd, &mut expected)?;
+        let expected = coord! { x: expected[0][0], y: expected[0][1] };
+
+        let mut p = point! { x: 12., y: 55. };
+        assert_eq!(p.apply(&ctx, op, Fwd)?, 1);
+        assert_eq!(p.0, expected);
+
+        let mut ls = line_string![(x: 12., y: 55.), (x: 9., y: 56.)];
+        assert_eq!(ls.apply(&ctx, op, Fwd)?, 2);
+        assert_eq!(ls.0[0], expected);
+
+        let ring = LineString::from(vec![(12., 55.), (13., 55.), (13., 56.), (12., 55.)]);
+        let mut polygon = Geometry::Polygon(Polygon::new(ring.clone(), vec![ring]));
+        assert_eq!(polygon.apply(&ctx, op, Fwd)?, 8);
+        assert_eq!(polygon.apply(&ctx, op, Inv)?, 8);
+        let Geometry::Polygon(polygon) = polygon else {
+            unreachable!()
+        };
+        let corner = polygon.interiors()[0].0[2];
+        assert!((corner.x - 13.).abs() < 1e-10 && (corner.y - 56.).abs() < 1e-10);
+
+        let mut rect = Geometry::Rect(geo_types::Rect::new(p.0, expected));
+        assert!(rect.apply(&ctx, op, Fwd).is_err());
+        Ok(())
+    }
+}
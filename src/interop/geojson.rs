@@ -0,0 +1,153 @@
+use crate::internal::*;
+use serde_json::Value;
+
+// ----- G E O J S O N -----------------------------------------------------------------
+
+/// Transform, in place, all positions of the GeoJSON object `geojson`: A
+/// geometry, a `Feature`, or a `FeatureCollection`, with geometries of any kind,
+/// and at any nesting depth. The positions are handed to the operator as given,
+/// i.e. as (longitude, latitude[, height]) in degrees, so `op` will typically
+/// start with `gis:in`.
+///
+/// Since the bounding boxes of transformed objects cannot be derived from the
+/// transformed bounding boxes, any `bbox` members are removed. Failed positions
+/// are marked by `null` elements. Returns the number of successfully
+/// transformed positions.
+pub fn transform_geojson(
+    ctx: &dyn Context,
+    op: OpHandle,
+    direction: Direction,
+    geojson: &mut Value,
+) -> Result<usize, Error> {
+    let mut positions = Vec::new();
+    collect_positions(geojson, &mut positions)?;
+
+    let mut coords: Vec<Coord> = positions
+        .iter()
+        .map(|position| {
+            let mut coord = Coord::origin();
+            for (i, element) in position.iter().take(4).enumerate() {
+                coord[i] = element.as_f64().unwrap_or(f64::NAN);
+            }
+            coord
+        })
+        .collect();
+    let successes = ctx.apply(op, direction, &mut coords)?;
+
+    for (position, coord) in positions.into_iter().zip(coords) {
+        for (i, element) in position.iter_mut().take(4).enumerate() {
+            *element = Value::from(coord[i]);
+        }
+    }
+    Ok(successes)
+}
+
+// Collect the positions (arrays of numbers) of `value`, recursing through the
+// members that may hold geometries
+fn collect_positions<'a>(
+    value: &'a mut Value,
+    positions: &mut Vec<&'a mut Vec<Value>>,
+) -> Result<(), Error> {
+    match value {
+        Value::Object(object) => {
+            object.remove("bbox");
+            for (key, member) in object.iter_mut() {
+                match key.as_str() {
+                    "coordinates" => collect_coordinates(member, positions)?,
+                    "geometry" | "geometries" | "features" => collect_positions(member, positions)?,
+                    _ => {}
+                }
+            }
+        }
+        Value::Array(array) => {
+            for element in array {
+                collect_positions(element, positions)?;
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+// Positions are arrays of numbers, at any depth of the `coordinates` arrays
+fn collect_coordinates<'a>(
+    value: &'a mut Value,
+    positions: &mut Vec<&'a mut Vec<Value>>,
+) -> Result<(), Error> {
+    let Value::Array(array) = value else {
+        return Err(Error::Unexpected {
+            message: "GeoJSON: Malformed coordinates".to_string(),
+            expected: "array".to_string(),
+            found: value.to_string(),
+        });
+    };
+    if array.first().is_some_and(|first| first.is_number()) {
+        positions.push(array);
+        return Ok(());
+    }
+    for element in array {
+        collect_coordinates(element, positions)?;
+    }
+    Ok(())
+}
+
+// ----- T E S T S ------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn geojson() -> Result<(), Error> {
+        let mut ctx = Minimal::new();
+        let op = ctx.op("gis:in | utm zone=32")?;
+        let mut expected = [Coord::raw(12., 55., 100., 0.)];
+        ctx.apply(op, Fwd, &mut expected)?;
+
+        let mut collection = json!({
+            "type": "FeatureCollection",
+            "bbox": [12, 55, 13, 56],
+            "features": [
+                {
+                    "type": "Feature",
+                    "properties": {"name": "Copenhagen", "coordinates": [1, 2]},
+                    "geometry": {"type": "Point", "coordinates": [12, 55, 100]}
+                },
+                {
+                    "type": "Feature",
+                    "properties": null,
+                    "geometry": {
+                        "type": "GeometryCollection",
+                        "geometries": [
+                            {"type": "LineString", "coordinates": [[12, 55], [13, 56]]},
+                            {"type": "MultiPolygon", "coordinates": [[[[12, 55], [13, 55], [13, 56], [12, 55]]]]}
+                        ]
+                    }
+                }
+            ]
+        });
+
+        assert_eq!(transform_geojson(&ctx, op, Fwd, &mut collection)?, 7);
+        assert!(collection.get("bbox").is_none());
+        let point = &collection["features"][0]["geometry"]["coordinates"];
+        assert_eq!(point.as_array().unwrap().len(), 3);
+        assert_eq!(point[0].as_f64(), Some(expected[0][0]));
+        assert_eq!(point[2].as_f64(), Some(100.));
+        // Properties are left alone
+        assert_eq!(
+            collection["features"][0]["properties"]["coordinates"],
+            json!([1, 2])
+        );
+
+        assert_eq!(transform_geojson(&ctx, op, Inv, &mut collection)?, 7);
+        let polygon = &collection["features"][1]["geometry"]["geometries"][1]["coordinates"];
+        let corner = &polygon[0][0][2];
+        assert!((corner[0].as_f64().unwrap() - 13.).abs() < 1e-9);
+        assert!((corner[1].as_f64().unwrap() - 56.).abs() < 1e-9);
+
+        let mut bad = json!({"type": "Point", "coordinates": 12});
+        assert!(transform_geojson(&ctx, op, Fwd, &mut bad).is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,203 @@
+use crate::internal::*;
+use std::ops::Range;
+
+// ----- G P X -------------------------------------------------------------------------
+
+// The GPX elements holding positions: Waypoints, track points and route points
+const POINT_ELEMENTS: [&str; 3] = ["wpt", "trkpt", "rtept"];
+
+/// Transform the positions of the waypoints, track points and route points of the
+/// GPX document `gpx`, returning the transformed document, the number of
+/// positions found, and the number of successfully transformed positions.
+///
+/// The positions are handed to the operator as (latitude, longitude, elevation),
+/// in degrees and metres, with elevation 0 where not given, so `op` will
+/// typically start with `geo:in`, and end with `geo:out`. Everything but the
+/// `lat` and `lon` attributes, and the `ele` elements, of the points is left
+/// untouched. So are the points failing to transform.
+pub fn transform_gpx(
+    ctx: &dyn Context,
+    op: OpHandle,
+    direction: Direction,
+    gpx: &str,
+) -> Result<(String, usize, usize), Error> {
+    let points = points(gpx)?;
+    let total = points.len();
+    let mut coords: Vec<Coord> = points
+        .iter()
+        .map(|point| {
+            let ele = point
+                .ele
+                .as_ref()
+                .map_or(Ok(0.), |ele| number(&gpx[ele.clone()]));
+            Ok(Coord::raw(
+                number(&gpx[point.lat.clone()])?,
+                number(&gpx[point.lon.clone()])?,
+                ele?,
+                0.,
+            ))
+        })
+        .collect::<Result<_, Error>>()?;
+    ctx.apply(op, direction, &mut coords)?;
+
+    // Replace the values in order of appearance, leaving failed points alone
+    let mut edits = Vec::new();
+    let mut successes = 0;
+    for (point, coord) in points.into_iter().zip(coords) {
+        if coord.status() != PointStatus::Ok {
+            continue;
+        }
+        successes += 1;
+        edits.push((point.lat, format!("{:.9}", coord[0])));
+        edits.push((point.lon, format!("{:.9}", coord[1])));
+        if let Some(ele) = point.ele {
+            edits.push((ele, format!("{:.4}", coord[2])));
+        }
+    }
+    edits.sort_by_key(|edit| edit.0.start);
+
+    let mut result = String::with_capacity(gpx.len());
+    let mut position = 0;
+    for (range, value) in edits {
+        result.push_str(&gpx[position..range.start]);
+        result.push_str(&value);
+        position = range.end;
+    }
+    result.push_str(&gpx[position..]);
+    Ok((result, total, successes))
+}
+
+// The locations of the values of a point in the GPX text
+struct Point {
+    lat: Range<usize>,
+    lon: Range<usize>,
+    ele: Option<Range<usize>>,
+}
+
+// Find the points of `gpx`, in order of appearance
+fn points(gpx: &str) -> Result<Vec<Point>, Error> {
+    let mut points = Vec::new();
+    let mut position = 0;
+    while let Some(offset) = gpx[position..].find('<') {
+        let start = position + offset + 1;
+        let Some(length) = gpx[start..].find('>') else {
+            return Err(malformed("unterminated tag", &gpx[start - 1..]));
+        };
+        let tag = &gpx[start..start + length];
+        position = start + length + 1;
+
+        let name = tag.split(|c: char| c.is_whitespace() || c == '/').next();
+        let Some(name) = name.filter(|name| POINT_ELEMENTS.contains(name)) else {
+            continue;
+        };
+        let lat = attribute(tag, "lat").ok_or_else(|| malformed("missing lat", tag))?;
+        let lon = attribute(tag, "lon").ok_or_else(|| malformed("missing lon", tag))?;
+        let lat = start + lat.start..start + lat.end;
+        let lon = start + lon.start..start + lon.end;
+
+        // The elevation is found in the content of the element, if any
+        let mut ele = None;
+        if !tag.ends_with('/') {
+            let end = format!("</{name}>");
+            let Some(length) = gpx[position..].find(&end) else {
+                return Err(malformed("unterminated element", tag));
+            };
+            let content = &gpx[position..position + length];
+            if let Some(open) = content.find("<ele>") {
+                let value = open + "<ele>".len();
+                let Some(close) = content[value..].find("</ele>") else {
+                    return Err(malformed("unterminated ele", content));
+                };
+                ele = Some(position + value..position + value + close);
+            }
+            position += length + end.len();
+        }
+        points.push(Point { lat, lon, ele });
+    }
+    Ok(points)
+}
+
+// The location of the value of the attribute `name` in `tag`
+fn attribute(tag: &str, name: &str) -> Option<Range<usize>> {
+    let mut position = 0;
+    while let Some(offset) = tag[position..].find(name) {
+        let start = position + offset;
+        position = start + name.len();
+        if !tag[..start].ends_with(char::is_whitespace) {
+            continue;
+        }
+        let rest = tag[position..].trim_start();
+        let Some(rest) = rest.strip_prefix('=') else {
+            continue;
+        };
+        let rest = rest.trim_start();
+        let quote = rest.chars().next().filter(|c| *c == '"' || *c == '\'')?;
+        let value = tag.len() - rest.len() + 1;
+        let length = tag[value..].find(quote)?;
+        return Some(value..value + length);
+    }
+    None
+}
+
+fn number(text: &str) -> Result<f64, Error> {
+    let text = text.trim();
+    text.parse::<f64>()
+        .map_err(|_| malformed("bad number", text))
+}
+
+fn malformed(message: &str, found: &str) -> Error {
+    let found: String = found.chars().take(80).collect();
+    Error::Unexpected {
+        message: format!("GPX: {message}"),
+        expected: "a GPX document".to_string(),
+        found,
+    }
+}
+
+// ----- T E S T S ------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GPX: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<gpx version="1.1" creator="test">
+  <wpt lat="55.0" lon="12.0"><ele>100</ele><name>Copenhagen</name></wpt>
+  <trk><name>lat="1" lon="2"</name><trkseg>
+    <trkpt lon='18.0' lat='59.0'/>
+    <trkpt lat="56.0"  lon = "13.0" ><time>2024-01-01T00:00:00Z</time></trkpt>
+  </trkseg></trk>
+</gpx>
+"#;
+
+    #[test]
+    fn gpx() -> Result<(), Error> {
+        let mut ctx = Minimal::new();
+        let op = ctx.op("geo:in | cart | helmert z=10 | cart inv | geo:out")?;
+        let (transformed, total, successes) = transform_gpx(&ctx, op, Fwd, GPX)?;
+        assert_eq!(total, 3);
+        assert_eq!(successes, 3);
+
+        // The points are shifted, everything else left as is
+        assert!(!transformed.contains(r#"lat="55.0""#));
+        assert!(transformed.contains("<name>Copenhagen</name>"));
+        assert!(transformed.contains(r#"<name>lat="1" lon="2"</name>"#));
+        assert!(transformed.contains("<time>2024-01-01T00:00:00Z</time>"));
+        let found = points(&transformed)?;
+        assert_eq!(found.len(), 3);
+        let lat = number(&transformed[found[1].lat.clone()])?;
+        assert!((lat - 59.).abs() > 1e-6 && (lat - 59.).abs() < 1e-3);
+        let ele = number(&transformed[found[0].ele.clone().unwrap()])?;
+        assert!((ele - 100.).abs() > 1. && (ele - 100.).abs() < 10.);
+
+        // And back again
+        let (roundtrip, _, _) = transform_gpx(&ctx, op, Inv, &transformed)?;
+        let found = points(&roundtrip)?;
+        assert!((number(&roundtrip[found[2].lon.clone()])? - 13.).abs() < 1e-9);
+        assert!((number(&roundtrip[found[0].ele.clone().unwrap()])? - 100.).abs() < 1e-3);
+
+        assert!(transform_gpx(&ctx, op, Fwd, r#"<wpt lat="55"/>"#).is_err());
+        assert!(transform_gpx(&ctx, op, Fwd, r#"<wpt lat="55" lon="12">"#).is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,41 @@
+// Translation between the RG operator definition syntax, and the syntaxes
+// of other geodetic software, and conversions to and from the data types of
+// other geospatial libraries
+#[cfg(feature = "arrow")]
+mod arrow;
+#[cfg(feature = "geo")]
+mod geo;
+#[cfg(feature = "serde")]
+mod geojson;
+mod gpx;
+#[cfg(feature = "ndarray")]
+mod ndarray;
+mod proj;
+#[cfg(feature = "proj-verify")]
+mod proj_verify;
+#[cfg(feature = "serde")]
+mod projjson;
+mod wkb;
+mod wkt;
+
+#[cfg(feature = "arrow")]
+pub use arrow::apply_to_arrow;
+#[cfg(feature = "geo")]
+pub use geo::TransformGeometry;
+#[cfg(feature = "serde")]
+pub use geojson::transform_geojson;
+pub use gpx::transform_gpx;
+pub use proj::parse_proj;
+pub(crate) use proj::to_proj_string;
+#[cfg(feature = "proj-verify")]
+pub use proj_verify::verify_against_proj;
+#[cfg(feature = "proj-verify")]
+pub use proj_verify::ProjDiscrepancy;
+#[cfg(feature = "proj-verify")]
+pub use proj_verify::ProjReport;
+#[cfg(feature = "serde")]
+pub use projjson::parse_projjson;
+#[cfg(feature = "serde")]
+pub(crate) use projjson::to_projjson;
+pub use wkb::transform_wkb;
+pub use wkt::parse_wkt;
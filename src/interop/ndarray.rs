@@ -0,0 +1,56 @@
+use crate::internal::*;
+use ::ndarray::{ArrayBase, DataMut, Ix2};
+
+// ----- N D A R R A Y   C O O R D I N A T E   S E T S ---------------------------------
+
+/// Arrays (`Array2`, `ArrayViewMut2`) with one coordinate per row, i.e. N×2, N×3 or
+/// N×4. Missing coordinate elements are read as 0, and ignored on writing. Columns
+/// beyond the fourth are left untouched.
+impl<S: DataMut<Elem = f64>> CoordinateSet for ArrayBase<S, Ix2> {
+    fn len(&self) -> usize {
+        self.nrows()
+    }
+    fn get_coord(&self, index: usize) -> Coord {
+        let mut coord = Coord::default();
+        for (i, value) in self.row(index).iter().take(4).enumerate() {
+            coord[i] = *value;
+        }
+        coord
+    }
+    fn set_coord(&mut self, index: usize, value: &Coord) {
+        for (i, element) in self.row_mut(index).iter_mut().take(4).enumerate() {
+            *element = value[i];
+        }
+    }
+}
+
+// ----- T E S T S ------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ::ndarray::array;
+
+    #[test]
+    fn ndarray() -> Result<(), Error> {
+        let mut ctx = Minimal::new();
+        let op = ctx.op("geo:in | utm zone=32")?;
+        let mut expected = [Coord::raw(55., 12., 0., 0.)];
+        ctx.apply(op, Fwd, &mut expected)?;
+
+        // N×2
+        let mut data = array![[55., 12.], [55., 12.]];
+        assert_eq!(ctx.apply(op, Fwd, &mut data)?, 2);
+        assert_eq!(data[[1, 0]], expected[0][0]);
+        assert_eq!(data[[1, 1]], expected[0][1]);
+
+        // N×3, through a view of the first row
+        let mut data = array![[55., 12., 100.], [55., 12., 100.]];
+        let mut view = data.slice_mut(::ndarray::s![0..1, ..]);
+        assert_eq!(ctx.apply(op, Fwd, &mut view)?, 1);
+        assert_eq!(data[[0, 0]], expected[0][0]);
+        assert_eq!(data[[0, 2]], 100.);
+        assert_eq!(data[[1, 0]], 55.);
+        Ok(())
+    }
+}
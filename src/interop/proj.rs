@@ -0,0 +1,661 @@
+use crate::internal::*;
+
+// ----- P R O J   S T R I N G S   T O   R G ---------------------------------------
+
+/// Translate a PROJ string, e.g. `+proj=utm +zone=32 +ellps=GRS80`, or a
+/// `+proj=pipeline +step ...` definition, into the corresponding RG definition,
+/// e.g. `utm zone=32 ellps=GRS80`.
+///
+/// As in PROJ, parameters given ahead of the first `+step` of a pipeline are
+/// added to each step, unless given explicitly for the step. Also as in PROJ
+/// (but unlike the `proj` and `cct` command line programs), the resulting
+/// operation uses radians for angular coordinates: Instantiate it as e.g.
+/// `"gis:in | " + &parse_proj(...)?` for input in degrees.
+pub fn parse_proj(definition: &str) -> Result<String, Error> {
+    let elements = proj_elements(definition);
+
+    // A single step?
+    if !elements.iter().any(|(k, v)| k == "proj" && v == "pipeline") {
+        return proj_step(&elements);
+    }
+
+    // A pipeline: Separate the global parameters from the steps
+    let mut steps = elements.split(|(k, _)| k == "step");
+    let mut globals = steps.next().unwrap_or_default().to_vec();
+    globals.retain(|(k, v)| !(k == "proj" && v == "pipeline"));
+    let inverted = globals.iter().any(|(k, _)| k == "inv");
+    globals.retain(|(k, _)| k != "inv");
+
+    let mut rg_steps = Vec::new();
+    for step in steps {
+        let mut step = step.to_vec();
+        for (key, value) in &globals {
+            if !step.iter().any(|(k, _)| k == key) {
+                step.push((key.clone(), value.clone()));
+            }
+        }
+
+        // Inverting the pipeline means inverting each step in reverse order. The
+        // steps omitted in one direction are then omitted in the other
+        if inverted {
+            if let Some(i) = step.iter().position(|(k, _)| k == "inv") {
+                step.remove(i);
+            } else {
+                step.push(("inv".to_string(), "true".to_string()));
+            }
+            for (key, _) in &mut step {
+                match key.as_str() {
+                    "omit_fwd" => *key = "omit_inv".to_string(),
+                    "omit_inv" => *key = "omit_fwd".to_string(),
+                    _ => (),
+                }
+            }
+        }
+        rg_steps.push(proj_step(&step)?);
+    }
+
+    if inverted {
+        rg_steps.reverse();
+    }
+    Ok(rg_steps.join(" | "))
+}
+
+// Split a PROJ string into key-value pairs, handling flags as `true`-valued keys,
+// and removing the (optional) leading '+'
+fn proj_elements(definition: &str) -> Vec<(String, String)> {
+    definition
+        .split_whitespace()
+        .map(|element| element.trim_start_matches('+'))
+        .filter(|element| !element.is_empty())
+        .map(|element| match element.split_once('=') {
+            Some((key, value)) => (key.to_string(), value.to_string()),
+            None => (element.to_string(), "true".to_string()),
+        })
+        .collect()
+}
+
+// Translate a single (non-pipeline) PROJ step into its RG counterpart
+fn proj_step(elements: &[(String, String)]) -> Result<String, Error> {
+    let Some((_, proj)) = elements.iter().find(|(k, _)| k == "proj") else {
+        return Err(Error::MissingParam("proj".to_string()));
+    };
+    let value = |key: &str| {
+        elements
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.as_str())
+    };
+
+    // The parameters carried unchanged into the RG definition
+    let mut params = Vec::new();
+    for (key, value) in elements {
+        match key.as_str() {
+            // Already handled, or insignificant in RG
+            "proj" | "no_defs" | "wktext" | "type" => continue,
+            "units" if value == "m" => continue,
+            "units" => return Err(Error::BadParam(key.clone(), value.clone())),
+            // Datum shift shorthands, without RG counterparts
+            "towgs84" | "nadgrids" | "init" | "geoidgrids" => {
+                return Err(Error::BadParam(key.clone(), value.clone()))
+            }
+            _ => (),
+        }
+
+        // PROJ accepts `k` as an alias for `k_0`
+        let key = if key == "k" { "k_0" } else { key.as_str() };
+        match value.as_str() {
+            "true" => params.push(key.to_string()),
+            _ => params.push(format!("{key}={value}")),
+        }
+    }
+
+    let name = match proj.as_str() {
+        "cart" | "deformation" | "helmert" | "laea" | "lcc" | "merc" | "molodensky" | "noop"
+        | "pop" | "push" | "tmerc" => proj.as_str(),
+        "etmerc" => "tmerc",
+
+        // RG `utm` does not support the southern hemisphere variant
+        "utm" if value("south").is_some() => {
+            let Some(zone) = value("zone") else {
+                return Err(Error::MissingParam("zone".to_string()));
+            };
+            let Ok(zone) = zone.parse::<i32>() else {
+                return Err(Error::BadParam("zone".to_string(), zone.to_string()));
+            };
+            let lon_0 = -183 + 6 * zone;
+            params.retain(|p| p != "south" && !p.starts_with("zone="));
+            params.insert(
+                0,
+                format!("lon_0={lon_0} k_0=0.9996 x_0=500000 y_0=10000000"),
+            );
+            "tmerc"
+        }
+        "utm" => "utm",
+
+        "hgridshift" | "vgridshift" => {
+            // PROJ's '@' prefix marks a grid as optional. RG grids are mandatory
+            for p in params.iter_mut() {
+                if p.starts_with("grids=") {
+                    *p = p.replace('@', "");
+                }
+            }
+            "gridshift"
+        }
+
+        "axisswap" => {
+            let adapt = axisswap(value("order"))?;
+            return Ok(if value("inv").is_some() {
+                adapt + " inv"
+            } else {
+                adapt
+            });
+        }
+        "unitconvert" => return unitconvert(elements),
+
+        // Only the time coordinate may be set, since RG `set_epoch` only sets that
+        "set" => {
+            let Some(epoch) = value("v_4") else {
+                return Err(Error::MissingParam("v_4".to_string()));
+            };
+            if let Some((key, value)) = elements
+                .iter()
+                .find(|(k, _)| k.starts_with("v_") && k != "v_4")
+            {
+                return Err(Error::BadParam(key.clone(), value.clone()));
+            }
+            return Ok(format!("set_epoch epoch={epoch}"));
+        }
+
+        _ => {
+            return Err(Error::NotFound(
+                proj.to_string(),
+                ": PROJ operator without RG counterpart".to_string(),
+            ))
+        }
+    };
+
+    params.insert(0, name.to_string());
+    Ok(params.join(" "))
+}
+
+// PROJ `axisswap order=...` as RG `adapt to=...`
+fn axisswap(order: Option<&str>) -> Result<String, Error> {
+    const AXES: [[char; 2]; 4] = [['e', 'w'], ['n', 's'], ['u', 'd'], ['f', 'r']];
+    let Some(order) = order else {
+        return Err(Error::MissingParam("order".to_string()));
+    };
+
+    let mut axes = ['e', 'n', 'u', 'f'];
+    for (i, axis) in order.split(',').enumerate() {
+        let bad = || Error::BadParam("order".to_string(), order.to_string());
+        let axis = axis.trim().parse::<i32>().map_err(|_| bad())?;
+        let index = axis.unsigned_abs() as usize;
+        if i > 3 || !(1..=4).contains(&index) {
+            return Err(bad());
+        }
+        axes[i] = AXES[index - 1][usize::from(axis < 0)];
+    }
+
+    let axes: String = axes.iter().collect();
+    Ok(format!("adapt to={axes}"))
+}
+
+// PROJ `unitconvert` as RG `adapt`, for the angular units supported by `adapt`,
+// followed by `timeconvert` for the time units
+fn unitconvert(elements: &[(String, String)]) -> Result<String, Error> {
+    let value = |key: &str| {
+        elements
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.as_str())
+    };
+
+    // Vertical unit conversions are not supported
+    if value("z_in") != value("z_out") {
+        return Err(Error::BadParam(
+            "z_in".to_string(),
+            value("z_in").unwrap_or_default().to_string(),
+        ));
+    }
+
+    let horizontal = horizontal_unitconvert(elements)?;
+    let (mut t_in, mut t_out) = (value("t_in"), value("t_out"));
+    if t_in == t_out {
+        return Ok(horizontal);
+    }
+    if value("inv").is_some() {
+        (t_in, t_out) = (t_out, t_in);
+    }
+    let mut time = "timeconvert".to_string();
+    for (key, unit) in [("t_in", t_in), ("t_out", t_out)] {
+        let unit = unit.unwrap_or("decimalyear");
+        if !PROJ_TIME_UNITS.contains(&unit) {
+            return Err(Error::BadParam(key.to_string(), unit.to_string()));
+        }
+        let direction = if key == "t_in" { "from" } else { "to" };
+        time += &format!(" {direction}={unit}");
+    }
+    Ok(match horizontal.as_str() {
+        "noop" => time,
+        _ => format!("{horizontal} | {time}"),
+    })
+}
+
+// The PROJ time units with RG `timeconvert` counterparts
+const PROJ_TIME_UNITS: [&str; 3] = ["decimalyear", "gps_week", "mjd"];
+
+// The horizontal part of PROJ `unitconvert`
+fn horizontal_unitconvert(elements: &[(String, String)]) -> Result<String, Error> {
+    let value = |key: &str| {
+        elements
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.as_str())
+    };
+
+    let mut xy_in = value("xy_in").unwrap_or("rad");
+    let mut xy_out = value("xy_out").unwrap_or("rad");
+    if value("inv").is_some() {
+        (xy_in, xy_out) = (xy_out, xy_in);
+    }
+    for (key, unit) in [("xy_in", xy_in), ("xy_out", xy_out)] {
+        if !["deg", "gon", "rad"].contains(&unit) {
+            return Err(Error::BadParam(key.to_string(), unit.to_string()));
+        }
+    }
+
+    let direction = if xy_in == "rad" { "to" } else { "from" };
+    Ok(match (xy_in, xy_out) {
+        (i, o) if i == o => "noop".to_string(),
+        ("rad", unit) | (unit, "rad") => format!("adapt {direction}=enuf_{unit}"),
+        (i, o) => format!("adapt from=enuf_{i} | adapt to=enuf_{o}"),
+    })
+}
+
+// ----- R G   T O   P R O J   S T R I N G S ---------------------------------------
+
+// The PROJ string corresponding to `op`, cf. `Op::to_proj_string()`
+pub(crate) fn to_proj_string(op: &Op) -> Result<String, Error> {
+    let steps = proj_steps(op)?;
+    if steps.len() == 1 {
+        return Ok(steps[0].join(" "));
+    }
+    let steps: Vec<_> = steps.iter().map(|step| step.join(" ")).collect();
+    Ok(format!("+proj=pipeline +step {}", steps.join(" +step ")))
+}
+
+// The PROJ steps corresponding to `op`, as lists of "+key=value" elements,
+// with pipelines (including those from macros) flattened
+fn proj_steps(op: &Op) -> Result<Vec<Vec<String>>, Error> {
+    let mut steps = Vec::new();
+    if op.steps.is_empty() {
+        steps = proj_leaf(op)?;
+    } else {
+        for step in &op.steps {
+            steps.extend(proj_steps(step)?);
+        }
+    }
+
+    // Inverting a sequence of steps means inverting each step in reverse order
+    if op.descriptor.inverted {
+        steps.reverse();
+        for step in &mut steps {
+            if let Some(i) = step.iter().position(|e| e == "+inv") {
+                step.remove(i);
+            } else {
+                step.insert(1, "+inv".to_string());
+            }
+        }
+    }
+    Ok(steps)
+}
+
+// The PROJ step(s) corresponding to a (non-pipeline) RG operator, disregarding
+// inversion, which is handled by the caller
+fn proj_leaf(op: &Op) -> Result<Vec<Vec<String>>, Error> {
+    let params = &op.params;
+    let name = match params.name.as_str() {
+        "cart" | "deformation" | "helmert" | "laea" | "lcc" | "merc" | "molodensky" | "noop"
+        | "pop" | "push" | "tmerc" | "utm" => params.name.as_str(),
+        "btmerc" => "tmerc",
+        "butm" => "utm",
+        "adapt" => return proj_adapt(op),
+        "set_epoch" => {
+            let epoch = params.real("epoch")?;
+            return Ok(vec![vec!["+proj=set".to_string(), format!("+v_4={epoch}")]]);
+        }
+        "timeconvert" => {
+            let mut step = vec!["+proj=unitconvert".to_string()];
+            for (key, proj_key) in [("from", "t_in"), ("to", "t_out")] {
+                let unit = params.text(key)?;
+                if !PROJ_TIME_UNITS.contains(&unit.as_str()) {
+                    return Err(Error::BadParam(key.to_string(), unit));
+                }
+                step.push(format!("+{proj_key}={unit}"));
+            }
+            return Ok(vec![step]);
+        }
+        "gridshift" => match params.grids.get("grid") {
+            Some(grid) if grid.bands == 1 => "vgridshift",
+            _ => "hgridshift",
+        },
+        _ => {
+            return Err(Error::NotFound(
+                params.name.clone(),
+                ": RG operator without PROJ counterpart".to_string(),
+            ))
+        }
+    };
+    let mut step = vec![format!("+proj={name}")];
+
+    // The ellipsoid, by name if possible
+    if let Some(ellps) = params.text.get("ellps") {
+        if crate::op::INLINE_ELLIPSOID
+            .iter()
+            .any(|key| params.given.contains_key(*key))
+        {
+            let e = params.ellps[0];
+            if e.flattening() == 0. {
+                step.push(format!("+R={}", e.semimajor_axis()));
+            } else {
+                step.push(format!("+a={}", e.semimajor_axis()));
+                step.push(format!("+rf={}", 1. / e.flattening()));
+            }
+        } else {
+            step.push(format!("+ellps={ellps}"));
+        }
+        for key in crate::op::DERIVED_SPHERES {
+            match params.given.get(key).map(String::as_str) {
+                Some("true") => step.push(format!("+{key}")),
+                Some(value) => step.push(format!("+{key}={value}")),
+                None => {}
+            }
+        }
+    }
+
+    // Molodensky's ellipsoid pair is given by `ellps` and the differences `da`, `df`
+    if name == "molodensky" && params.given.contains_key("ellps_0") {
+        let ellps_0 = params.text.get("ellps_0").cloned().unwrap_or_default();
+        step[1] = format!("+ellps={ellps_0}");
+        for key in ["da", "df"] {
+            step.push(format!("+{key}={}", params.real(key)?));
+        }
+    }
+
+    if params.text.get("zone").is_some_and(|zone| zone == "auto") {
+        return Err(Error::BadParam("zone".to_string(), "auto".to_string()));
+    }
+
+    // The remaining parameters, as resolved by the operator constructor.
+    // PROJ wraps the input longitudes of projections by default, hence no `wrap`
+    for key in params.given.keys() {
+        let key = key.as_str();
+        if [
+            "name", "inv", "ellps", "ellps_0", "ellps_1", "da", "df", "wrap",
+        ]
+        .contains(&key)
+            || crate::op::INLINE_ELLIPSOID.contains(&key)
+            || key.starts_with("inv_")
+        {
+            continue;
+        }
+        if params.boolean(key) {
+            step.push(format!("+{key}"));
+        } else if let Ok(value) = params.real(key) {
+            step.push(format!("+{key}={value}"));
+        } else if let Ok(value) = params.natural(key) {
+            step.push(format!("+{key}={value}"));
+        } else if let Ok(value) = params.integer(key) {
+            step.push(format!("+{key}={value}"));
+        } else if let Ok(value) = params.text(key) {
+            step.push(format!("+{key}={value}"));
+        } else if let Ok(value) = params.series(key) {
+            let value: Vec<_> = value.iter().map(|v| v.to_string()).collect();
+            step.push(format!("+{key}={}", value.join(",")));
+        }
+    }
+    Ok(vec![step])
+}
+
+// RG `adapt` as PROJ `axisswap` and/or `unitconvert`
+fn proj_adapt(op: &Op) -> Result<Vec<Vec<String>>, Error> {
+    if op.params.boolean("noop") {
+        return Ok(vec![vec!["+proj=noop".to_string()]]);
+    }
+    let post = op.params.series("post")?;
+    let mult = op.params.series("mult")?;
+    let mut steps = Vec::new();
+
+    // The axis order, omitting any trailing unmodified axes
+    let mut order: Vec<_> = (0..4)
+        .map(|i| (post[i] as i32 + 1) * mult[i].signum() as i32)
+        .collect();
+    while order.len() > 2 && order.last() == Some(&(order.len() as i32)) {
+        order.pop();
+    }
+    if order != [1, 2] {
+        let order: Vec<_> = order.iter().map(|o| o.to_string()).collect();
+        steps.push(vec![
+            "+proj=axisswap".to_string(),
+            format!("+order={}", order.join(",")),
+        ]);
+    }
+
+    // The angular units
+    let factor = mult[0].abs();
+    let bad = || Error::BadParam("adapt".to_string(), op.descriptor.definition.clone());
+    if mult[1].abs() != factor || mult[2].abs() != 1. || mult[3].abs() != 1. {
+        return Err(bad());
+    }
+    if factor != 1. {
+        let units = [
+            ("deg", 1f64.to_radians()),
+            ("gon", std::f64::consts::PI / 200.),
+            ("rad", 1.),
+        ];
+        let (xy_in, xy_out) = units
+            .iter()
+            .flat_map(|i| units.iter().map(move |o| (i, o)))
+            .find(|(i, o)| (i.1 / o.1 - factor).abs() < 1e-12 * factor)
+            .map(|(i, o)| (i.0, o.0))
+            .ok_or_else(bad)?;
+        steps.push(vec![
+            "+proj=unitconvert".to_string(),
+            format!("+xy_in={xy_in}"),
+            format!("+xy_out={xy_out}"),
+        ]);
+    }
+    Ok(steps)
+}
+
+// ----- T E S T S ------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn translation() -> Result<(), Error> {
+        assert_eq!(
+            parse_proj("+proj=utm +zone=32 +ellps=GRS80 +units=m +no_defs")?,
+            "utm zone=32 ellps=GRS80"
+        );
+        assert_eq!(parse_proj("proj=tmerc k=0.9996")?, "tmerc k_0=0.9996");
+        assert_eq!(
+            parse_proj("+proj=utm +zone=33 +south")?,
+            "tmerc lon_0=15 k_0=0.9996 x_0=500000 y_0=10000000"
+        );
+
+        // Global parameters are distributed to the steps
+        let pipeline =
+            "+proj=pipeline +ellps=GRS80 +step +proj=cart +step +inv +proj=cart +ellps=intl";
+        assert_eq!(
+            parse_proj(pipeline)?,
+            "cart ellps=GRS80 | cart inv ellps=intl"
+        );
+
+        // Inverted pipelines are inverted step by step
+        let pipeline = "+proj=pipeline +inv +step +proj=axisswap +order=2,1 +step +proj=unitconvert +xy_in=deg +xy_out=rad";
+        assert_eq!(
+            parse_proj(pipeline)?,
+            "adapt to=enuf_deg | adapt to=neuf inv"
+        );
+
+        // ...with the omission of steps carried over accordingly
+        let pipeline = "+proj=pipeline +inv +step +proj=cart +omit_fwd +step +proj=helmert +x=1";
+        assert_eq!(parse_proj(pipeline)?, "helmert x=1 inv | cart omit_inv inv");
+
+        // Axis swapping with reversal
+        assert_eq!(
+            parse_proj("+proj=axisswap +order=-2,1,-3")?,
+            "adapt to=sedf"
+        );
+
+        // Things we cannot handle
+        assert!(matches!(
+            parse_proj("+proj=robin"),
+            Err(Error::NotFound(_, _))
+        ));
+        assert!(matches!(
+            parse_proj("+ellps=GRS80"),
+            Err(Error::MissingParam(_))
+        ));
+        assert!(matches!(
+            parse_proj("+proj=utm +zone=32 +towgs84=1,2,3"),
+            Err(Error::BadParam(_, _))
+        ));
+        assert!(matches!(
+            parse_proj("+proj=unitconvert +z_in=m +z_out=ft"),
+            Err(Error::BadParam(_, _))
+        ));
+
+        // Time units, and setting the time coordinate
+        assert_eq!(
+            parse_proj("+proj=unitconvert +t_in=decimalyear +t_out=gps_week")?,
+            "timeconvert from=decimalyear to=gps_week"
+        );
+        assert_eq!(
+            parse_proj("+proj=unitconvert +xy_in=deg +t_in=mjd +t_out=decimalyear +inv")?,
+            "adapt to=enuf_deg | timeconvert from=decimalyear to=mjd"
+        );
+        assert_eq!(
+            parse_proj("+proj=set +v_4=2010.5")?,
+            "set_epoch epoch=2010.5"
+        );
+        assert!(matches!(
+            parse_proj("+proj=unitconvert +t_in=decimalyear +t_out=yyyymmdd"),
+            Err(Error::BadParam(_, _))
+        ));
+        assert!(matches!(
+            parse_proj("+proj=set +v_3=0 +v_4=2010"),
+            Err(Error::BadParam(_, _))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn export() -> Result<(), Error> {
+        let mut ctx = Minimal::new();
+        let proj = |ctx: &Minimal, definition: &str| Op::new(definition, ctx)?.to_proj_string();
+
+        assert_eq!(
+            proj(&ctx, "utm zone=32")?,
+            "+proj=utm +ellps=GRS80 +zone=32"
+        );
+        assert_eq!(
+            proj(
+                &ctx,
+                "tmerc lat_0=49 lon_0=-2 k_0=0.9996012717 ellps=airy inv"
+            )?,
+            "+proj=tmerc +inv +ellps=airy +k_0=0.9996012717 +lat_0=49 +lon_0=-2"
+        );
+        assert_eq!(
+            proj(&ctx, "cart a=6378137 rf=300")?,
+            "+proj=cart +a=6378137 +rf=300"
+        );
+        assert_eq!(proj(&ctx, "adapt from=neuf")?, "+proj=axisswap +order=2,1");
+        assert_eq!(
+            proj(&ctx, "set_epoch epoch=2010 | timeconvert to=gps_week")?,
+            "+proj=pipeline +step +proj=set +v_4=2010 \
+             +step +proj=unitconvert +t_in=decimalyear +t_out=gps_week"
+        );
+        assert!(matches!(
+            proj_fails(&ctx, "timeconvert to=unix"),
+            Err(Error::BadParam(_, _))
+        ));
+
+        // A pipeline, including macros and adaptors. Note the inversion of the
+        // `geo:out` macro, and the flattening of the macros
+        ctx.register_resource(
+            "ed50:wgs84",
+            "cart ellps=intl | helmert x=-87 y=-98 z=-121 | cart inv ellps=WGS84",
+        );
+        assert_eq!(
+            proj(&ctx, "geo:in | ed50:wgs84 | geo:out")?,
+            "+proj=pipeline \
+             +step +proj=axisswap +order=2,1 \
+             +step +proj=unitconvert +xy_in=deg +xy_out=rad \
+             +step +proj=cart +ellps=intl \
+             +step +proj=helmert +x=-87 +y=-98 +z=-121 \
+             +step +proj=cart +inv +ellps=WGS84 \
+             +step +proj=axisswap +order=2,1 \
+             +step +proj=unitconvert +xy_in=rad +xy_out=deg"
+        );
+        assert_eq!(
+            proj(&ctx, "ed50:wgs84 inv")?,
+            "+proj=pipeline \
+             +step +proj=cart +ellps=WGS84 \
+             +step +proj=helmert +inv +x=-87 +y=-98 +z=-121 \
+             +step +proj=cart +inv +ellps=intl"
+        );
+
+        // Round trip through PROJ syntax
+        let definition = "geo:in | ed50:wgs84 inv | utm zone=32";
+        let rg = ctx.op(definition)?;
+        let proj = ctx.op(&parse_proj(&Op::new(definition, &ctx)?.to_proj_string()?)?)?;
+        let mut data = [Coord::raw(55., 12., 0., 0.)];
+        let mut expected = data;
+        ctx.apply(rg, Fwd, &mut expected)?;
+        ctx.apply(proj, Fwd, &mut data)?;
+        assert!(data[0].hypot2(&expected[0]) < 1e-9);
+
+        // Things PROJ cannot handle
+        assert!(matches!(
+            proj_fails(&ctx, "addone"),
+            Err(Error::NotFound(_, _))
+        ));
+        assert!(matches!(
+            proj_fails(&ctx, "utm zone=auto"),
+            Err(Error::BadParam(_, _))
+        ));
+        Ok(())
+    }
+
+    fn proj_fails(ctx: &Minimal, definition: &str) -> Result<String, Error> {
+        Op::new(definition, ctx)?.to_proj_string()
+    }
+
+    #[test]
+    fn operation() -> Result<(), Error> {
+        let mut ctx = Minimal::new();
+
+        // The PROJ way of giving UTM zone 32, including input in degrees...
+        let proj = "+proj=pipeline +ellps=GRS80
+            +step +proj=unitconvert +xy_in=deg +xy_out=rad
+            +step +proj=tmerc +lon_0=9 +k=0.9996 +x_0=500000";
+        let proj = ctx.op(&parse_proj(proj)?)?;
+
+        // ...and the RG way
+        let utm = ctx.op("gis:in | utm zone=32")?;
+
+        let mut data = [Coord::raw(12., 55., 0., 0.)];
+        let mut expected = data;
+        ctx.apply(proj, Fwd, &mut data)?;
+        ctx.apply(utm, Fwd, &mut expected)?;
+        assert!(data[0].hypot2(&expected[0]) < 1e-9);
+
+        ctx.apply(proj, Inv, &mut data)?;
+        assert!(data[0].hypot2(&Coord::raw(12., 55., 0., 0.)) < 1e-12);
+        Ok(())
+    }
+}
@@ -0,0 +1,254 @@
+use crate::internal::*;
+use std::io::Write;
+use std::process::{Command, Output, Stdio};
+
+// ----- D I F F E R E N T I A L   T E S T I N G   A G A I N S T   P R O J -------------
+
+/// A point where RG and PROJ disagree, cf. [`verify_against_proj`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProjDiscrepancy {
+    /// The index of the point in the operands
+    pub index: usize,
+    /// The RG result
+    pub rg: Coord,
+    /// The PROJ result
+    pub proj: Coord,
+    /// The Euclidean distance between the first three coordinates of the
+    /// results, in the output units, and angles in degrees. Infinite if only
+    /// one of them failed
+    pub deviation: f64,
+}
+
+/// The outcome of [`verify_against_proj`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProjReport {
+    /// The PROJ equivalent of the RG operation, cf. [`Op::to_proj_string`]
+    pub proj_string: String,
+    /// The number of points compared
+    pub points: usize,
+    /// The points deviating more than the tolerance given
+    pub discrepancies: Vec<ProjDiscrepancy>,
+    /// The largest deviation, not counting points failing in RG only or in
+    /// PROJ only (these are always among the discrepancies)
+    pub max: f64,
+    /// The root mean square of the deviations, on the same conditions
+    pub rms: f64,
+}
+
+/// Compare the results of the operation `definition` with those of its PROJ
+/// equivalent (cf. [`Op::to_proj_string`]), point by point, reporting the points
+/// deviating more than `tolerance`.
+///
+/// PROJ is run by its coordinate transformation program, `cct`, which must be
+/// available in the search path, or given by the environment variable `PROJ_CCT`.
+/// As `cct` does, the comparison takes angular input, and gives angular output,
+/// in degrees, when the first (resp. last) step of the operation, in the
+/// direction given, is angular on that side.
+///
+/// Points failing in both systems are considered in agreement.
+pub fn verify_against_proj(
+    ctx: &dyn Context,
+    definition: &str,
+    direction: Direction,
+    operands: &[Coord],
+    tolerance: f64,
+) -> Result<ProjReport, Error> {
+    let op = Op::new(definition, ctx)?;
+    let proj_string = op.to_proj_string()?;
+    let (angular_input, angular_output) = op.angular_io(direction);
+
+    // The input, as given to cct
+    let input: Vec<Coord> = operands
+        .iter()
+        .map(|c| match angular_input {
+            true => Coord::raw(c[0].to_degrees(), c[1].to_degrees(), c[2], c[3]),
+            false => *c,
+        })
+        .collect();
+
+    // The RG output, with angular output in degrees
+    let mut rg = operands.to_vec();
+    op.apply(ctx, &mut rg, direction)?;
+    if angular_output {
+        for c in &mut rg {
+            (c[0], c[1]) = (c[0].to_degrees(), c[1].to_degrees());
+        }
+    }
+
+    let proj = cct(&proj_string, direction, &input)?;
+
+    let mut discrepancies = Vec::new();
+    let (mut max, mut sum, mut n) = (0_f64, 0_f64, 0_usize);
+    for (index, (rg, proj)) in rg.into_iter().zip(proj).enumerate() {
+        let deviation = match (ok(&rg), ok(&proj)) {
+            (false, false) => continue,
+            (true, true) => (0..3)
+                .map(|i| (rg[i] - proj[i]).powi(2))
+                .sum::<f64>()
+                .sqrt(),
+            _ => f64::INFINITY,
+        };
+        if deviation.is_finite() {
+            max = max.max(deviation);
+            sum += deviation * deviation;
+            n += 1;
+        }
+        if deviation > tolerance {
+            discrepancies.push(ProjDiscrepancy {
+                index,
+                rg,
+                proj,
+                deviation,
+            });
+        }
+    }
+    let rms = if n == 0 { 0. } else { (sum / n as f64).sqrt() };
+
+    Ok(ProjReport {
+        proj_string,
+        points: operands.len(),
+        discrepancies,
+        max,
+        rms,
+    })
+}
+
+// The cct program: As given by the environment variable `PROJ_CCT`, otherwise
+// looked up in the search path
+fn cct_program() -> String {
+    std::env::var("PROJ_CCT").unwrap_or_else(|_| "cct".to_string())
+}
+
+// Run `proj_string` by cct, on `input`
+fn cct(proj_string: &str, direction: Direction, input: &[Coord]) -> Result<Vec<Coord>, Error> {
+    let program = cct_program();
+    let mut command = Command::new(&program);
+    command.args(["-d", "15"]);
+    if direction == Inv {
+        command.arg("-I");
+    }
+    command.args(proj_string.split_whitespace());
+
+    let mut text = String::new();
+    for c in input {
+        text += &format!("{} {} {} {}\n", c[0], c[1], c[2], c[3]);
+    }
+    let output = filter(command, text)?;
+    if !output.status.success() {
+        return Err(Error::General(format!(
+            "{program} failed for '{proj_string}'"
+        )));
+    }
+
+    let output = parse_cct_output(&String::from_utf8_lossy(&output.stdout));
+    if output.len() != input.len() {
+        return Err(Error::General(format!(
+            "{program} gave {} points for {}",
+            output.len(),
+            input.len()
+        )));
+    }
+    Ok(output)
+}
+
+// Run `command` with `input` as its stdin, collecting its stdout. The input
+// is written from a separate thread, as the output is read concurrently: With
+// both written in full before reading, the command blocks when the output pipe
+// is full, and we block when the input pipe is
+fn filter(mut command: Command, input: String) -> Result<Output, Error> {
+    let program = command.get_program().to_string_lossy().to_string();
+    let mut child = command
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|e| Error::General(format!("Cannot run {program}: {e}")))?;
+
+    // stdin is closed when the writer is done, by dropping it
+    let mut stdin = child
+        .stdin
+        .take()
+        .ok_or_else(|| Error::General(format!("No stdin for {program}")))?;
+    let writer = std::thread::spawn(move || stdin.write_all(input.as_bytes()));
+    let output = child.wait_with_output()?;
+
+    // A failing command may not read all of its input, so its exit status
+    // is what to report, rather than the broken pipe
+    let written = writer
+        .join()
+        .unwrap_or_else(|e| std::panic::resume_unwind(e));
+    if output.status.success() {
+        written?;
+    }
+    Ok(output)
+}
+
+// The coordinates of the cct output, one tuple per line. Comment lines
+// (e.g. error messages) are skipped, and failed points come out as NaN
+fn parse_cct_output(output: &str) -> Vec<Coord> {
+    let mut coords = Vec::new();
+    for line in output.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let mut coord = Coord::nan();
+        for (i, value) in line.split_whitespace().take(4).enumerate() {
+            coord[i] = value.parse::<f64>().unwrap_or(f64::NAN);
+        }
+        coords.push(coord);
+    }
+    coords
+}
+
+// A point is transformed successfully if its first three coordinates are finite
+fn ok(c: &Coord) -> bool {
+    (0..3).all(|i| c[i].is_finite())
+}
+
+// ----- T E S T S ------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cct_output() {
+        let output = "# Record 1 TRANSFORMATION ERROR: 1 2 0 0\n\
+            inf inf inf inf\n\
+            \n\
+            691875.632139660884  6098907.825005010888  0.0000  0.0000\n";
+        let coords = parse_cct_output(output);
+        assert_eq!(coords.len(), 2);
+        assert!(!ok(&coords[0]));
+        assert!(ok(&coords[1]));
+        assert_eq!(coords[1][0], 691875.632139660884);
+    }
+
+    // Large inputs must not fill the pipes, and block
+    #[cfg(unix)]
+    #[test]
+    fn large_input() -> Result<(), Error> {
+        let input = "1 2 3 4\n".repeat(100_000);
+        let output = filter(Command::new("cat"), input.clone())?;
+        assert!(output.status.success());
+        assert_eq!(output.stdout, input.as_bytes());
+        Ok(())
+    }
+
+    // Run by `cargo test --features proj-verify -- --ignored`, where cct is
+    // available
+    #[test]
+    #[ignore = "needs PROJ's cct, in the search path or given by PROJ_CCT"]
+    fn against_proj() -> Result<(), Error> {
+        let program = cct_program();
+        let available = Command::new(&program).arg("--version").output().is_ok();
+        assert!(available, "Cannot run {program}");
+        let ctx = Minimal::new();
+        let points = [Coord::raw(55., 12., 0., 0.), Coord::raw(60., 10., 0., 0.)];
+        let report = verify_against_proj(&ctx, "geo:in | utm zone=32", Fwd, &points, 1e-6)?;
+        assert_eq!(report.points, 2);
+        assert!(report.discrepancies.is_empty(), "{report:#?}");
+        Ok(())
+    }
+}
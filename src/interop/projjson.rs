@@ -0,0 +1,222 @@
+use super::proj::parse_proj;
+use super::wkt::{epsg_method, epsg_parameter};
+use crate::internal::*;
+use serde_json::{json, Value};
+
+// ----- P R O J J S O N ------------------------------------------------------------
+
+// The prefix PROJ uses for naming operation methods given by PROJ strings
+const PROJ_METHOD: &str = "PROJ-based operation method: ";
+
+// The PROJJSON representation of `op`, cf. `Op::to_projjson()`
+pub(crate) fn to_projjson(op: &Op) -> Result<String, Error> {
+    let proj = op.to_proj_string()?;
+    let json = json!({
+        "$schema": "https://proj.org/schemas/v0.7/projjson.schema.json",
+        "type": "Conversion",
+        "name": normalized_definition(&op.descriptor.definition),
+        "method": { "name": format!("{PROJ_METHOD}{proj}") }
+    });
+    serde_json::to_string_pretty(&json).map_err(|e| Error::Syntax(e.to_string()))
+}
+
+/// Translate a PROJJSON coordinate operation into the corresponding RG definition.
+///
+/// Supports the `Conversion`, `Transformation` and `CoordinateOperation` types
+/// with PROJ string based methods (as written by PROJ, and by
+/// [`Op::to_projjson`](crate::preamble::Op::to_projjson)), or with the EPSG
+/// projection methods supported by [`parse_wkt`](crate::parse_wkt),
+/// and `ConcatenatedOperation`s made of those. Since conversions do not carry
+/// their ellipsoid, EPSG method based ones use the default ellipsoid.
+pub fn parse_projjson(json: &str) -> Result<String, Error> {
+    let json: Value = serde_json::from_str(json).map_err(|e| Error::Syntax(e.to_string()))?;
+    operation(&json)
+}
+
+fn operation(json: &Value) -> Result<String, Error> {
+    let kind = json["type"].as_str().unwrap_or_default();
+    match kind {
+        "Conversion" | "Transformation" | "CoordinateOperation" => {
+            let method = json["method"]["name"].as_str().unwrap_or_default();
+            if let Some(proj) = method.strip_prefix(PROJ_METHOD) {
+                return parse_proj(proj);
+            }
+            let Some((operator, keys)) = epsg_method(epsg_code(&json["method"]), method) else {
+                return Err(Error::NotFound(
+                    method.to_string(),
+                    ": PROJJSON method without RG counterpart".to_string(),
+                ));
+            };
+            let mut definition = operator.to_string();
+            for parameter in json["parameters"].as_array().into_iter().flatten() {
+                let name = parameter["name"].as_str().unwrap_or_default();
+                if let Some(key) = epsg_parameter(epsg_code(parameter), name, keys) {
+                    definition += &format!(" {key}={}", parameter_value(parameter)?);
+                }
+            }
+            Ok(definition)
+        }
+        "ConcatenatedOperation" => {
+            let Some(steps) = json["steps"].as_array() else {
+                return Err(Error::MissingParam("steps".to_string()));
+            };
+            let steps = steps.iter().map(operation).collect::<Result<Vec<_>, _>>()?;
+            Ok(steps.join(" | "))
+        }
+        _ => Err(Error::Unexpected {
+            message: "Unsupported PROJJSON type".to_string(),
+            expected: "a coordinate operation".to_string(),
+            found: kind.to_string(),
+        }),
+    }
+}
+
+// The EPSG code of a PROJJSON method or parameter, from its `id`, if any
+fn epsg_code(json: &Value) -> Option<u32> {
+    let id = &json["id"];
+    if id["authority"].as_str() != Some("EPSG") {
+        return None;
+    }
+    match &id["code"] {
+        Value::Number(code) => code.as_u64().map(|code| code as u32),
+        Value::String(code) => code.parse().ok(),
+        _ => None,
+    }
+}
+
+// The value of a PROJJSON parameter: Angles in degrees, lengths in metres. Units
+// are given by name, for the common ones, otherwise by their conversion factor
+fn parameter_value(parameter: &Value) -> Result<f64, Error> {
+    let name = parameter["name"].as_str().unwrap_or_default();
+    let Some(value) = parameter["value"].as_f64() else {
+        return Err(Error::BadParam(
+            name.to_string(),
+            parameter["value"].to_string(),
+        ));
+    };
+    let unit = &parameter["unit"];
+    let angular = match unit {
+        Value::Null => return Ok(value),
+        Value::String(unit) => match unit.as_str() {
+            "degree" | "metre" | "unity" => return Ok(value),
+            "radian" => 1.,
+            "grad" => std::f64::consts::PI / 200.,
+            _ => return Err(Error::BadParam(name.to_string(), unit.to_string())),
+        },
+        _ => {
+            let Some(factor) = unit["conversion_factor"].as_f64() else {
+                return Err(Error::BadParam(name.to_string(), unit.to_string()));
+            };
+            if unit["type"] != "AngularUnit" {
+                return Ok(value * factor);
+            }
+            factor
+        }
+    };
+    Ok((value * angular).to_degrees())
+}
+
+// ----- T E S T S ------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn roundtrip() -> Result<(), Error> {
+        let mut ctx = Minimal::new();
+        let definition =
+            "geo:in | cart ellps=intl | helmert x=-87 y=-98 z=-121 | cart inv | utm zone=32";
+        let json = Op::new(definition, &ctx)?.to_projjson()?;
+        let value: Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["type"], "Conversion");
+        assert!(value["method"]["name"]
+            .as_str()
+            .unwrap()
+            .starts_with("PROJ-based operation method: +proj=pipeline +step +proj=axisswap"));
+
+        // Import what we exported, and check that we get the same results
+        let op = ctx.op(definition)?;
+        let imported = ctx.op(&parse_projjson(&json)?)?;
+        let mut data = [Coord::raw(55., 12., 0., 0.)];
+        let mut expected = data;
+        ctx.apply(op, Fwd, &mut expected)?;
+        ctx.apply(imported, Fwd, &mut data)?;
+        assert!(data[0].hypot2(&expected[0]) < 1e-9);
+        Ok(())
+    }
+
+    #[test]
+    fn concatenated() -> Result<(), Error> {
+        let json = r#"{
+            "type": "ConcatenatedOperation",
+            "name": "Inverse of UTM zone 32N + UTM zone 33N",
+            "steps": [
+                {"type": "Conversion", "name": "UTM zone 32N, inverse",
+                 "method": {"name": "PROJ-based operation method: +proj=utm +zone=32 +inv"}},
+                {"type": "Conversion", "name": "UTM zone 33N",
+                 "method": {"name": "PROJ-based operation method: +proj=utm +zone=33"}}
+            ]
+        }"#;
+        assert_eq!(parse_projjson(json)?, "utm zone=32 inv | utm zone=33");
+
+        // Not a coordinate operation, or a bad one
+        assert!(matches!(
+            parse_projjson(r#"{"type": "GeographicCRS"}"#),
+            Err(Error::Unexpected { .. })
+        ));
+        let helmert =
+            r#"{"type": "Transformation", "method": {"name": "Position Vector transformation"}}"#;
+        assert!(matches!(
+            parse_projjson(helmert),
+            Err(Error::NotFound(_, _))
+        ));
+        assert!(matches!(parse_projjson("{"), Err(Error::Syntax(_))));
+        Ok(())
+    }
+
+    #[test]
+    fn epsg_methods() -> Result<(), Error> {
+        // UTM zone 32N, as written by PROJ, i.e. with EPSG method and parameter ids
+        let json = r#"{
+            "type": "Conversion",
+            "name": "UTM zone 32N",
+            "method": {"name": "Transverse Mercator", "id": {"authority": "EPSG", "code": 9807}},
+            "parameters": [
+                {"name": "Latitude of natural origin", "value": 0, "unit": "degree",
+                 "id": {"authority": "EPSG", "code": 8801}},
+                {"name": "Longitude of natural origin", "value": 9, "unit": "degree",
+                 "id": {"authority": "EPSG", "code": 8802}},
+                {"name": "Scale factor at natural origin", "value": 0.9996, "unit": "unity",
+                 "id": {"authority": "EPSG", "code": 8805}},
+                {"name": "False easting", "value": 500000, "unit": "metre",
+                 "id": {"authority": "EPSG", "code": 8806}},
+                {"name": "False northing", "value": 0, "unit": "metre",
+                 "id": {"authority": "EPSG", "code": 8807}}
+            ]
+        }"#;
+        assert_eq!(
+            parse_projjson(json)?,
+            "tmerc lat_0=0 lon_0=9 k_0=0.9996 x_0=500000 y_0=0"
+        );
+
+        // Without ids, and with units given by their conversion factors
+        let json = r#"{
+            "type": "Conversion",
+            "method": {"name": "Lambert Azimuthal Equal Area"},
+            "parameters": [
+                {"name": "Latitude of natural origin", "value": 58.5,
+                 "unit": {"type": "AngularUnit", "name": "grad", "conversion_factor": 0.015707963267949}},
+                {"name": "Longitude of natural origin", "value": 10, "unit": "degree"},
+                {"name": "False easting", "value": 1000,
+                 "unit": {"type": "LinearUnit", "name": "kilometre", "conversion_factor": 1000}}
+            ]
+        }"#;
+        let lat_0 = (58.5 * 0.015707963267949_f64).to_degrees();
+        assert_eq!(
+            parse_projjson(json)?,
+            format!("laea lat_0={lat_0} lon_0=10 x_0=1000000")
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,355 @@
+use crate::internal::*;
+
+// ----- W K B -------------------------------------------------------------------------
+
+/// Transform, in place, the coordinates of the WKB geometry `wkb`. Handles the
+/// OGC/ISO flavours of WKB (2D, Z, M and ZM), the PostGIS EWKB extensions
+/// (Z and M flags, and embedded SRID, which is left unchanged), and GeoPackage
+/// geometry blobs (with the envelope, if any, recomputed), of any geometry type,
+/// including nested geometry collections.
+///
+/// The coordinates are handed to the operator as (x, y, z, m), with z and m taken
+/// as 0 where not given, so for geographical coordinates, i.e. (longitude,
+/// latitude) in degrees, `op` will typically start with `gis:in`. Failed
+/// coordinates are written as NaNs. Returns the number of successfully
+/// transformed coordinates.
+pub fn transform_wkb(
+    ctx: &dyn Context,
+    op: OpHandle,
+    direction: Direction,
+    wkb: &mut [u8],
+) -> Result<usize, Error> {
+    let envelope = geopackage_header(wkb)?;
+    let start = envelope.as_ref().map_or(0, |e| e.end);
+
+    let mut positions = Vec::new();
+    let end = collect_positions(wkb, start, &mut positions)?;
+    if end != wkb.len() {
+        return Err(malformed("trailing bytes", end));
+    }
+
+    let mut coords: Vec<Coord> = positions.iter().map(|p| p.read(wkb)).collect();
+    let successes = ctx.apply(op, direction, &mut coords)?;
+    for (position, coord) in positions.iter().zip(&coords) {
+        position.write(wkb, coord);
+    }
+
+    if let Some(envelope) = envelope {
+        envelope.update(wkb, &coords);
+    }
+    Ok(successes)
+}
+
+// The location of a coordinate tuple in the WKB
+struct Position {
+    offset: usize,
+    dimensions: Dimensions,
+    little_endian: bool,
+}
+
+#[derive(Clone, Copy)]
+struct Dimensions {
+    z: bool,
+    m: bool,
+}
+
+impl Dimensions {
+    fn count(&self) -> usize {
+        2 + self.z as usize + self.m as usize
+    }
+
+    // The index of each element of the tuple in the `Coord`
+    fn indices(&self) -> impl Iterator<Item = usize> {
+        let (z, m) = (self.z, self.m);
+        [Some(0), Some(1), z.then_some(2), m.then_some(3)]
+            .into_iter()
+            .flatten()
+    }
+}
+
+impl Position {
+    fn read(&self, wkb: &[u8]) -> Coord {
+        let mut coord = Coord::origin();
+        for (i, index) in self.dimensions.indices().enumerate() {
+            coord[index] = read_f64(wkb, self.offset + 8 * i, self.little_endian);
+        }
+        coord
+    }
+
+    fn write(&self, wkb: &mut [u8], coord: &Coord) {
+        for (i, index) in self.dimensions.indices().enumerate() {
+            let value = coord[index];
+            let bytes = if self.little_endian {
+                value.to_le_bytes()
+            } else {
+                value.to_be_bytes()
+            };
+            wkb[self.offset + 8 * i..self.offset + 8 * i + 8].copy_from_slice(&bytes);
+        }
+    }
+}
+
+// Collect the positions of the geometry starting at `offset`, returning the
+// offset following it
+fn collect_positions(
+    wkb: &[u8],
+    offset: usize,
+    positions: &mut Vec<Position>,
+) -> Result<usize, Error> {
+    let little_endian = match wkb.get(offset) {
+        Some(0) => false,
+        Some(1) => true,
+        _ => return Err(malformed("bad byte order", offset)),
+    };
+    let mut offset = offset + 1;
+    let mut geometry_type = read_u32(wkb, offset, little_endian)?;
+    offset += 4;
+
+    // EWKB flags, and embedded SRID
+    let mut dimensions = Dimensions {
+        z: geometry_type & 0x8000_0000 != 0,
+        m: geometry_type & 0x4000_0000 != 0,
+    };
+    if geometry_type & 0x2000_0000 != 0 {
+        read_u32(wkb, offset, little_endian)?;
+        offset += 4;
+    }
+    geometry_type &= 0x0fff_ffff;
+
+    // ISO dimensionality
+    match geometry_type / 1000 {
+        0 => {}
+        1 => dimensions.z = true,
+        2 => dimensions.m = true,
+        3 => (dimensions.z, dimensions.m) = (true, true),
+        _ => return Err(malformed("bad geometry type", offset - 4)),
+    }
+
+    let tuple = 8 * dimensions.count();
+    let points = |offset: usize, n: usize, positions: &mut Vec<Position>| {
+        let end = offset + n * tuple;
+        if end > wkb.len() {
+            return Err(malformed("truncated coordinates", offset));
+        }
+        positions.extend((0..n).map(|i| Position {
+            offset: offset + i * tuple,
+            dimensions,
+            little_endian,
+        }));
+        Ok(end)
+    };
+
+    match geometry_type % 1000 {
+        // Point
+        1 => points(offset, 1, positions),
+        // LineString
+        2 => {
+            let n = read_u32(wkb, offset, little_endian)? as usize;
+            points(offset + 4, n, positions)
+        }
+        // Polygon: A number of rings
+        3 => {
+            let rings = read_u32(wkb, offset, little_endian)?;
+            offset += 4;
+            for _ in 0..rings {
+                let n = read_u32(wkb, offset, little_endian)? as usize;
+                offset = points(offset + 4, n, positions)?;
+            }
+            Ok(offset)
+        }
+        // MultiPoint, MultiLineString, MultiPolygon, GeometryCollection:
+        // A number of complete geometries
+        4..=7 => {
+            let n = read_u32(wkb, offset, little_endian)?;
+            offset += 4;
+            for _ in 0..n {
+                offset = collect_positions(wkb, offset, positions)?;
+            }
+            Ok(offset)
+        }
+        _ => Err(malformed("unsupported geometry type", offset - 4)),
+    }
+}
+
+// ----- G E O P A C K A G E -----------------------------------------------------------
+
+// The envelope of a GeoPackage geometry blob: Its location, and which
+// dimensions it covers
+struct Envelope {
+    start: usize,
+    end: usize,
+    z: bool,
+    m: bool,
+    little_endian: bool,
+}
+
+// The envelope of `blob`, if it is a GeoPackage geometry blob, i.e. starts with
+// the magic "GP". The WKB geometry follows the envelope.
+fn geopackage_header(blob: &[u8]) -> Result<Option<Envelope>, Error> {
+    if !blob.starts_with(b"GP") {
+        return Ok(None);
+    }
+    let Some(&flags) = blob.get(3) else {
+        return Err(malformed("truncated GeoPackage header", 0));
+    };
+    let (z, m) = match (flags >> 1) & 0x07 {
+        0 | 1 => (false, false),
+        2 => (true, false),
+        3 => (false, true),
+        4 => (true, true),
+        _ => return Err(malformed("bad GeoPackage envelope indicator", 3)),
+    };
+    let size = if (flags >> 1) & 0x07 == 0 {
+        0
+    } else {
+        8 * (4 + 2 * z as usize + 2 * m as usize)
+    };
+    let start = 8;
+    if blob.len() < start + size {
+        return Err(malformed("truncated GeoPackage header", 0));
+    }
+    Ok(Some(Envelope {
+        start,
+        end: start + size,
+        z,
+        m,
+        little_endian: flags & 0x01 != 0,
+    }))
+}
+
+impl Envelope {
+    // Recompute the envelope from the transformed coordinates
+    fn update(&self, blob: &mut [u8], coords: &[Coord]) {
+        if self.start == self.end {
+            return;
+        }
+        let indices = [Some(0), Some(1), self.z.then_some(2), self.m.then_some(3)];
+        let mut offset = self.start;
+        for index in indices.into_iter().flatten() {
+            let values = coords.iter().map(|c| c[index]).filter(|v| !v.is_nan());
+            let (min, max) = values.fold((f64::NAN, f64::NAN), |(min, max), v| {
+                (v.min(min), v.max(max))
+            });
+            for value in [min, max] {
+                let bytes = if self.little_endian {
+                    value.to_le_bytes()
+                } else {
+                    value.to_be_bytes()
+                };
+                blob[offset..offset + 8].copy_from_slice(&bytes);
+                offset += 8;
+            }
+        }
+    }
+}
+
+// ----- H E L P E R S -----------------------------------------------------------------
+
+fn read_u32(wkb: &[u8], offset: usize, little_endian: bool) -> Result<u32, Error> {
+    let Some(bytes) = wkb.get(offset..offset + 4) else {
+        return Err(malformed("truncated geometry", offset));
+    };
+    let bytes = bytes.try_into().unwrap_or_default();
+    Ok(if little_endian {
+        u32::from_le_bytes(bytes)
+    } else {
+        u32::from_be_bytes(bytes)
+    })
+}
+
+// Only used for ranges already checked
+fn read_f64(wkb: &[u8], offset: usize, little_endian: bool) -> f64 {
+    let bytes = wkb[offset..offset + 8].try_into().unwrap_or_default();
+    if little_endian {
+        f64::from_le_bytes(bytes)
+    } else {
+        f64::from_be_bytes(bytes)
+    }
+}
+
+fn malformed(message: &str, offset: usize) -> Error {
+    Error::Unexpected {
+        message: format!("WKB: {message}"),
+        expected: "a WKB geometry".to_string(),
+        found: format!("byte offset {offset}"),
+    }
+}
+
+// ----- T E S T S ------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A little endian 2D LineString
+    fn line_string() -> Vec<u8> {
+        let mut wkb = vec![1];
+        wkb.extend(2_u32.to_le_bytes());
+        wkb.extend(2_u32.to_le_bytes());
+        for v in [12., 55., 13., 56.] {
+            wkb.extend(f64::to_le_bytes(v));
+        }
+        wkb
+    }
+
+    // A big endian EWKB PointZ with SRID 4326
+    fn ewkb_point() -> Vec<u8> {
+        let mut wkb = vec![0];
+        wkb.extend((0xa000_0001_u32).to_be_bytes());
+        wkb.extend(4326_u32.to_be_bytes());
+        for v in [12., 55., 100.] {
+            wkb.extend(f64::to_be_bytes(v));
+        }
+        wkb
+    }
+
+    #[test]
+    fn wkb() -> Result<(), Error> {
+        let mut ctx = Minimal::new();
+        let op = ctx.op("gis:in | utm zone=32")?;
+        let mut expected = [Coord::raw(12., 55., 100., 0.), Coord::raw(13., 56., 0., 0.)];
+        ctx.apply(op, Fwd, &mut expected)?;
+
+        let mut wkb = line_string();
+        assert_eq!(transform_wkb(&ctx, op, Fwd, &mut wkb)?, 2);
+        assert_eq!(read_f64(&wkb, 9, true), expected[0][0]);
+        assert_eq!(read_f64(&wkb, 33, true), expected[1][1]);
+        assert_eq!(transform_wkb(&ctx, op, Inv, &mut wkb)?, 2);
+        assert!((read_f64(&wkb, 25, true) - 13.).abs() < 1e-9);
+
+        let mut ewkb = ewkb_point();
+        assert_eq!(transform_wkb(&ctx, op, Fwd, &mut ewkb)?, 1);
+        assert_eq!(read_u32(&ewkb, 5, false)?, 4326);
+        assert_eq!(read_f64(&ewkb, 9, false), expected[0][0]);
+        assert_eq!(read_f64(&ewkb, 25, false), 100.);
+
+        // A GeometryCollection of both, as an ISO Z geometry, in a GeoPackage
+        // blob with an XY envelope
+        let mut gpkg = b"GP".to_vec();
+        gpkg.extend([0, 0b0000_0011]);
+        gpkg.extend(4326_u32.to_le_bytes());
+        gpkg.extend([0; 32]);
+        gpkg.push(1);
+        gpkg.extend(1007_u32.to_le_bytes());
+        gpkg.extend(2_u32.to_le_bytes());
+        let mut point = vec![1];
+        point.extend(1001_u32.to_le_bytes());
+        for v in [12., 55., 100.] {
+            point.extend(f64::to_le_bytes(v));
+        }
+        gpkg.extend(point);
+        gpkg.extend(line_string());
+        assert_eq!(transform_wkb(&ctx, op, Fwd, &mut gpkg)?, 3);
+        assert_eq!(read_f64(&gpkg, 8, true), expected[0][0]);
+        assert_eq!(read_f64(&gpkg, 16, true), expected[1][0]);
+
+        // Malformed input
+        let mut truncated = line_string();
+        truncated.pop();
+        assert!(transform_wkb(&ctx, op, Fwd, &mut truncated).is_err());
+        let mut trailing = line_string();
+        trailing.push(0);
+        assert!(transform_wkb(&ctx, op, Fwd, &mut trailing).is_err());
+        Ok(())
+    }
+}
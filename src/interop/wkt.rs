@@ -0,0 +1,509 @@
+use crate::internal::*;
+
+// ----- W K T 2   C R S   D E F I N I T I O N S   T O   R G -------------------------
+
+/// Translate a WKT2 (ISO 19162:2019) projected CRS definition into the RG
+/// definition of its conversion, i.e. the operation taking coordinates from the
+/// base geographic CRS to the projected CRS.
+///
+/// Input coordinates are expected in the EPSG axis order (latitude, longitude),
+/// in the angular unit of the base CRS. Output coordinates follow the axis order
+/// of the coordinate system of the projected CRS. Only metric output is supported,
+/// and only the projection methods having RG counterparts: Transverse Mercator,
+/// Lambert Conic Conformal (1SP and 2SP), Lambert Azimuthal Equal Area,
+/// and Mercator (variant A and B).
+pub fn parse_wkt(wkt: &str) -> Result<String, Error> {
+    let crs = WktParser::new(wkt).parse()?;
+    if !["PROJCRS", "PROJECTEDCRS"].contains(&crs.keyword.as_str()) {
+        return Err(Error::Unexpected {
+            message: "Unsupported WKT CRS type".to_string(),
+            expected: "PROJCRS".to_string(),
+            found: crs.keyword,
+        });
+    }
+
+    // The base CRS: Ellipsoid and angular unit
+    let base = crs
+        .child(&["BASEGEOGCRS", "BASEGEODCRS"])
+        .ok_or_else(|| missing("BASEGEOGCRS"))?;
+    let ellipsoid = base
+        .descendant(&["ELLIPSOID", "SPHEROID"])
+        .ok_or_else(|| missing("ELLIPSOID"))?;
+    let a = ellipsoid.number(1)? * unit_factor(ellipsoid, "LENGTHUNIT");
+    let rf = ellipsoid.number(2)?;
+    let ellps = if rf == 0. {
+        format!("a={a}")
+    } else {
+        format!("a={a} rf={rf}")
+    };
+
+    // Input adaptation, from latitude/longitude in the base CRS angular unit
+    let angular = base
+        .child(&["ANGLEUNIT", "UNIT"])
+        .map_or(Ok(1f64.to_radians()), |unit| unit.number(1))?;
+    let input = angular_unit_name(angular)
+        .map(|unit| format!("adapt from=neuf_{unit}"))
+        .ok_or_else(|| Error::BadParam("ANGLEUNIT".to_string(), angular.to_string()))?;
+
+    // The projection
+    let conversion = crs
+        .child(&["CONVERSION"])
+        .ok_or_else(|| missing("CONVERSION"))?;
+    let projection = projection(conversion)?;
+
+    // Output adaptation, to the axis order of the CS
+    let mut axes = String::new();
+    for axis in crs.children("AXIS") {
+        if unit_factor(axis, "LENGTHUNIT") != 1. {
+            return Err(Error::BadParam("LENGTHUNIT".to_string(), axis.text(0)?));
+        }
+        let direction = axis.word(1)?.to_lowercase();
+        axes.push(match direction.as_str() {
+            "east" => 'e',
+            "west" => 'w',
+            "north" => 'n',
+            "south" => 's',
+            _ => return Err(Error::BadParam("AXIS".to_string(), direction)),
+        });
+    }
+    if unit_factor(&crs, "LENGTHUNIT") != 1. {
+        return Err(Error::BadParam("LENGTHUNIT".to_string(), crs.text(0)?));
+    }
+
+    let mut steps = vec![input, format!("{projection} {ellps}")];
+    let eastish = axes.contains(['e', 'w']);
+    let northish = axes.contains(['n', 's']);
+    match axes.as_str() {
+        "" | "en" => (),
+        _ if axes.len() == 2 && eastish && northish => steps.push(format!("adapt to={axes}uf")),
+        _ => return Err(Error::BadParam("AXIS".to_string(), axes)),
+    }
+    Ok(steps.join(" | "))
+}
+
+fn missing(keyword: &str) -> Error {
+    Error::Syntax(format!("WKT: Missing {keyword}"))
+}
+
+// The conversion factor of the unit embedded in `node`, if any, otherwise 1
+fn unit_factor(node: &WktNode, keyword: &str) -> f64 {
+    node.child(&[keyword, "UNIT"])
+        .and_then(|unit| unit.number(1).ok())
+        .unwrap_or(1.)
+}
+
+// The `adapt` name for an angular unit, given by its conversion factor to radians
+fn angular_unit_name(factor: f64) -> Option<&'static str> {
+    [
+        ("deg", 1f64.to_radians()),
+        ("gon", std::f64::consts::PI / 200.),
+        ("rad", 1.),
+    ]
+    .iter()
+    .find(|(_, f)| (factor - f).abs() < 1e-15)
+    .map(|(name, _)| *name)
+}
+
+// EPSG parameter codes and names
+#[rustfmt::skip]
+const PARAMETERS: [(u32, &str); 11] = [
+    (8801, "latitude of natural origin"),
+    (8802, "longitude of natural origin"),
+    (8805, "scale factor at natural origin"),
+    (8806, "false easting"),
+    (8807, "false northing"),
+    (8821, "latitude of false origin"),
+    (8822, "longitude of false origin"),
+    (8823, "latitude of 1st standard parallel"),
+    (8824, "latitude of 2nd standard parallel"),
+    (8826, "easting at false origin"),
+    (8827, "northing at false origin"),
+];
+
+// Mapping from EPSG parameter codes to RG parameter names
+pub(crate) type ParameterKeys = &'static [(u32, &'static str)];
+
+// EPSG method codes and names, the corresponding RG operators, and their parameters
+#[rustfmt::skip]
+const METHODS: [(u32, &str, &str, ParameterKeys); 6] = [
+    (9807, "transverse mercator", "tmerc",
+        &[(8801, "lat_0"), (8802, "lon_0"), (8805, "k_0"), (8806, "x_0"), (8807, "y_0")]),
+    (9801, "lambert conic conformal (1sp)", "lcc",
+        &[(8801, "lat_1"), (8802, "lon_0"), (8805, "k_0"), (8806, "x_0"), (8807, "y_0")]),
+    (9802, "lambert conic conformal (2sp)", "lcc",
+        &[(8821, "lat_0"), (8822, "lon_0"), (8823, "lat_1"), (8824, "lat_2"), (8826, "x_0"), (8827, "y_0")]),
+    (9820, "lambert azimuthal equal area", "laea",
+        &[(8801, "lat_0"), (8802, "lon_0"), (8806, "x_0"), (8807, "y_0")]),
+    (9804, "mercator (variant a)", "merc",
+        &[(8802, "lon_0"), (8805, "k_0"), (8806, "x_0"), (8807, "y_0")]),
+    (9805, "mercator (variant b)", "merc",
+        &[(8823, "lat_ts"), (8802, "lon_0"), (8806, "x_0"), (8807, "y_0")]),
+];
+
+// The EPSG code of an entry in `names`: `code`, if given, otherwise by its name
+fn epsg_code(code: Option<u32>, name: &str, names: &[(u32, &str)]) -> Option<u32> {
+    code.or_else(|| {
+        names
+            .iter()
+            .find(|(_, n)| n.eq_ignore_ascii_case(name))
+            .map(|(code, _)| *code)
+    })
+}
+
+// The RG operator, and parameter keys, corresponding to the EPSG method given by
+// its code, or failing that, by its name. Also used by the PROJJSON importer
+pub(crate) fn epsg_method(code: Option<u32>, name: &str) -> Option<(&'static str, ParameterKeys)> {
+    let names: Vec<_> = METHODS.iter().map(|m| (m.0, m.1)).collect();
+    let code = epsg_code(code, name, &names)?;
+    METHODS.iter().find(|m| m.0 == code).map(|m| (m.2, m.3))
+}
+
+// The RG key, among the `keys` of its method, of the EPSG parameter given by its
+// code, or failing that, by its name
+pub(crate) fn epsg_parameter(
+    code: Option<u32>,
+    name: &str,
+    keys: ParameterKeys,
+) -> Option<&'static str> {
+    let code = epsg_code(code, name, &PARAMETERS)?;
+    keys.iter().find(|k| k.0 == code).map(|k| k.1)
+}
+
+// The EPSG code of a METHOD or PARAMETER node, from its ID, if any
+fn node_code(node: &WktNode) -> Option<u32> {
+    let id = node.child(&["ID", "AUTHORITY"])?;
+    if !id.text(0).ok()?.eq_ignore_ascii_case("EPSG") {
+        return None;
+    }
+    match &id.args.get(1)? {
+        WktValue::Number(code) => Some(*code as u32),
+        WktValue::Text(code) => code.parse().ok(),
+        _ => None,
+    }
+}
+
+// The RG definition of the projection given by a CONVERSION node
+fn projection(conversion: &WktNode) -> Result<String, Error> {
+    let method = conversion
+        .child(&["METHOD"])
+        .ok_or_else(|| missing("METHOD"))?;
+    let Some((operator, keys)) = epsg_method(node_code(method), &method.text(0)?) else {
+        return Err(Error::NotFound(
+            method.text(0)?,
+            ": WKT method without RG counterpart".to_string(),
+        ));
+    };
+
+    let mut definition = operator.to_string();
+    for parameter in conversion.children("PARAMETER") {
+        let Some(key) = epsg_parameter(node_code(parameter), &parameter.text(0)?, keys) else {
+            continue;
+        };
+
+        // Angles in degrees, lengths in metres
+        let mut value = parameter.number(1)?;
+        if let Some(unit) = parameter.child(&["ANGLEUNIT"]) {
+            let factor = unit.number(1)?;
+            if angular_unit_name(factor) != Some("deg") {
+                value = (value * factor).to_degrees();
+            }
+        } else if let Some(unit) = parameter.child(&["LENGTHUNIT"]) {
+            value *= unit.number(1)?;
+        }
+        definition += &format!(" {key}={value}");
+    }
+    Ok(definition)
+}
+
+// ----- A   W K T   P A R S E R ---------------------------------------------------
+
+#[derive(Debug, Clone, PartialEq)]
+enum WktValue {
+    Node(WktNode),
+    Text(String),
+    Number(f64),
+    Word(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct WktNode {
+    keyword: String,
+    args: Vec<WktValue>,
+}
+
+impl WktNode {
+    fn nodes(&self) -> impl Iterator<Item = &WktNode> {
+        self.args.iter().filter_map(|arg| match arg {
+            WktValue::Node(node) => Some(node),
+            _ => None,
+        })
+    }
+
+    fn children<'a>(&'a self, keyword: &'a str) -> impl Iterator<Item = &'a WktNode> {
+        self.nodes().filter(move |node| node.keyword == keyword)
+    }
+
+    // The first direct child with one of the `keywords`
+    fn child(&self, keywords: &[&str]) -> Option<&WktNode> {
+        self.nodes()
+            .find(|node| keywords.contains(&node.keyword.as_str()))
+    }
+
+    // The first descendant with one of the `keywords`, searching depth first
+    fn descendant(&self, keywords: &[&str]) -> Option<&WktNode> {
+        self.child(keywords)
+            .or_else(|| self.nodes().find_map(|node| node.descendant(keywords)))
+    }
+
+    fn number(&self, index: usize) -> Result<f64, Error> {
+        match self.args.get(index) {
+            Some(WktValue::Number(n)) => Ok(*n),
+            _ => Err(self.bad_arg(index, "number")),
+        }
+    }
+
+    fn text(&self, index: usize) -> Result<String, Error> {
+        match self.args.get(index) {
+            Some(WktValue::Text(t)) => Ok(t.clone()),
+            _ => Err(self.bad_arg(index, "quoted text")),
+        }
+    }
+
+    fn word(&self, index: usize) -> Result<String, Error> {
+        match self.args.get(index) {
+            Some(WktValue::Word(w)) => Ok(w.clone()),
+            _ => Err(self.bad_arg(index, "keyword")),
+        }
+    }
+
+    fn bad_arg(&self, index: usize, expected: &str) -> Error {
+        Error::Unexpected {
+            message: format!("WKT: Bad argument #{index} for {}", self.keyword),
+            expected: expected.to_string(),
+            found: format!("{:?}", self.args.get(index)),
+        }
+    }
+}
+
+struct WktParser<'a> {
+    text: &'a str,
+    pos: usize,
+}
+
+impl<'a> WktParser<'a> {
+    fn new(text: &'a str) -> WktParser<'a> {
+        WktParser { text, pos: 0 }
+    }
+
+    fn parse(mut self) -> Result<WktNode, Error> {
+        let value = self.value()?;
+        self.skip_whitespace();
+        match value {
+            WktValue::Node(node) if self.pos == self.text.len() => Ok(node),
+            _ => Err(self.error("a single WKT node")),
+        }
+    }
+
+    fn error(&self, expected: &str) -> Error {
+        Error::Syntax(format!("WKT: Expected {expected} at position {}", self.pos))
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.text[self.pos..].chars().next()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(c) = self.peek().filter(|c| c.is_whitespace()) {
+            self.pos += c.len_utf8();
+        }
+    }
+
+    fn value(&mut self) -> Result<WktValue, Error> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some('"') => self.quoted(),
+            Some(c) if c.is_ascii_digit() || "+-.".contains(c) => self.number(),
+            Some(c) if c.is_alphabetic() => self.node_or_word(),
+            _ => Err(self.error("a WKT value")),
+        }
+    }
+
+    // Quoted text, with embedded quotes doubled
+    fn quoted(&mut self) -> Result<WktValue, Error> {
+        self.pos += 1;
+        let mut text = String::new();
+        loop {
+            let Some(c) = self.peek() else {
+                return Err(self.error("a closing quote"));
+            };
+            self.pos += c.len_utf8();
+            if c == '"' {
+                if self.peek() != Some('"') {
+                    return Ok(WktValue::Text(text));
+                }
+                self.pos += 1;
+            }
+            text.push(c);
+        }
+    }
+
+    fn number(&mut self) -> Result<WktValue, Error> {
+        let start = self.pos;
+        while self
+            .peek()
+            .is_some_and(|c| c.is_ascii_digit() || "+-.eE".contains(c))
+        {
+            self.pos += 1;
+        }
+        self.text[start..self.pos]
+            .parse::<f64>()
+            .map(WktValue::Number)
+            .map_err(|_| self.error("a number"))
+    }
+
+    fn node_or_word(&mut self) -> Result<WktValue, Error> {
+        let start = self.pos;
+        while let Some(c) = self.peek().filter(|&c| c.is_alphanumeric() || c == '_') {
+            self.pos += c.len_utf8();
+        }
+        let word = self.text[start..self.pos].to_string();
+
+        self.skip_whitespace();
+        let close = match self.peek() {
+            Some('[') => ']',
+            Some('(') => ')',
+            _ => return Ok(WktValue::Word(word)),
+        };
+        self.pos += 1;
+
+        let mut args = Vec::new();
+        loop {
+            args.push(self.value()?);
+            self.skip_whitespace();
+            match self.peek() {
+                Some(',') => self.pos += 1,
+                Some(c) if c == close => break,
+                _ => return Err(self.error(&format!("',' or '{close}'"))),
+            }
+        }
+        self.pos += 1;
+        Ok(WktValue::Node(WktNode {
+            keyword: word.to_uppercase(),
+            args,
+        }))
+    }
+}
+
+// ----- T E S T S ------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // EPSG:25832, as given by `projinfo -o WKT2_2019 EPSG:25832` (slightly abridged)
+    const ETRS89_UTM32: &str = r#"PROJCRS["ETRS89 / UTM zone 32N",
+        BASEGEOGCRS["ETRS89",
+            ENSEMBLE["European Terrestrial Reference System 1989 ensemble",
+                MEMBER["European Terrestrial Reference Frame 2014"],
+                ELLIPSOID["GRS 1980",6378137,298.257222101,
+                    LENGTHUNIT["metre",1]],
+                ENSEMBLEACCURACY[0.1]],
+            PRIMEM["Greenwich",0,
+                ANGLEUNIT["degree",0.0174532925199433]],
+            ID["EPSG",4258]],
+        CONVERSION["UTM zone 32N",
+            METHOD["Transverse Mercator",
+                ID["EPSG",9807]],
+            PARAMETER["Latitude of natural origin",0,
+                ANGLEUNIT["degree",0.0174532925199433],
+                ID["EPSG",8801]],
+            PARAMETER["Longitude of natural origin",9,
+                ANGLEUNIT["degree",0.0174532925199433],
+                ID["EPSG",8802]],
+            PARAMETER["Scale factor at natural origin",0.9996,
+                SCALEUNIT["unity",1],
+                ID["EPSG",8805]],
+            PARAMETER["False easting",500000,
+                LENGTHUNIT["metre",1],
+                ID["EPSG",8806]],
+            PARAMETER["False northing",0,
+                LENGTHUNIT["metre",1],
+                ID["EPSG",8807]]],
+        CS[Cartesian,2],
+            AXIS["(E)",east,
+                ORDER[1],
+                LENGTHUNIT["metre",1]],
+            AXIS["(N)",north,
+                ORDER[2],
+                LENGTHUNIT["metre",1]],
+        USAGE[
+            SCOPE["Engineering survey, topographic mapping."],
+            AREA["Europe between 6°E and 12°E."],
+            BBOX[38.76,6,84.33,12.01]],
+        ID["EPSG",25832]]"#;
+
+    // EPSG:3035, with northing first, and identified by names rather than IDs
+    const LAEA_EUROPE: &str = r#"PROJCRS["ETRS89-extended / LAEA Europe",
+        BASEGEOGCRS["ETRS89",
+            DATUM["European Terrestrial Reference System 1989",
+                ELLIPSOID["GRS 1980",6378137,298.257222101]],
+            ANGLEUNIT["degree",0.0174532925199433]],
+        CONVERSION["Europe Equal Area 2001",
+            METHOD["Lambert Azimuthal Equal Area"],
+            PARAMETER["Latitude of natural origin",52,ANGLEUNIT["degree",0.0174532925199433]],
+            PARAMETER["Longitude of natural origin",10,ANGLEUNIT["degree",0.0174532925199433]],
+            PARAMETER["False easting",4321000,LENGTHUNIT["metre",1]],
+            PARAMETER["False northing",3210000,LENGTHUNIT["metre",1]]],
+        CS[Cartesian,2],
+            AXIS["northing (Y)",north],
+            AXIS["easting (X)",east],
+            LENGTHUNIT["metre",1]]"#;
+
+    #[test]
+    fn translation() -> Result<(), Error> {
+        assert_eq!(
+            parse_wkt(ETRS89_UTM32)?,
+            "adapt from=neuf_deg | tmerc lat_0=0 lon_0=9 k_0=0.9996 x_0=500000 y_0=0 a=6378137 rf=298.257222101"
+        );
+        assert_eq!(
+            parse_wkt(LAEA_EUROPE)?,
+            "adapt from=neuf_deg | laea lat_0=52 lon_0=10 x_0=4321000 y_0=3210000 a=6378137 rf=298.257222101 | adapt to=neuf"
+        );
+
+        // Syntax errors and unsupported CRS types
+        assert!(matches!(
+            parse_wkt("PROJCRS[\"foo\""),
+            Err(Error::Syntax(_))
+        ));
+        assert!(matches!(
+            parse_wkt("GEOGCRS[\"WGS 84\"]"),
+            Err(Error::Unexpected { .. })
+        ));
+        let feet = LAEA_EUROPE.trim_end_matches("LENGTHUNIT[\"metre\",1]]");
+        let feet = feet.to_string() + "LENGTHUNIT[\"foot\",0.3048]]";
+        assert!(matches!(parse_wkt(&feet), Err(Error::BadParam(_, _))));
+
+        // Non-ASCII words and whitespace outside of quoted text
+        assert!(parse_wkt("PROJCRS[\"x\",ÄB]").is_err());
+        let nbsp = LAEA_EUROPE.replace(",\n", ",\u{a0}\n");
+        assert_eq!(parse_wkt(&nbsp)?, parse_wkt(LAEA_EUROPE)?);
+        Ok(())
+    }
+
+    #[test]
+    fn operation() -> Result<(), Error> {
+        // The WKT and the embedded EPSG definitions must agree
+        let mut ctx = Minimal::new();
+        for (wkt, code) in [(ETRS89_UTM32, 25832), (LAEA_EUROPE, 3035)] {
+            let wkt = ctx.op(&parse_wkt(wkt)?)?;
+            let epsg = ctx.op_from_epsg(code)?;
+
+            let mut data = [Coord::raw(55., 12., 0., 0.)];
+            let mut expected = data;
+            ctx.apply(wkt, Fwd, &mut data)?;
+            ctx.apply(epsg, Fwd, &mut expected)?;
+            assert!(data[0].hypot2(&expected[0]) < 1e-6);
+        }
+        Ok(())
+    }
+}
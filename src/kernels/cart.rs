@@ -0,0 +1,197 @@
+//! Geographical to cartesian (and v.v.) conversion
+use super::*;
+use crate::math::simd;
+use core::f64::consts::FRAC_PI_2;
+
+// The coordinates are converted in batches, cf. `crate::math::simd`, since
+// the conversions dominate the run time of most datum shift pipelines
+
+/// Conversion of geographical coordinates (longitude, latitude, ellipsoidal
+/// height) on `ellps`, to earth centered cartesian coordinates (X, Y, Z).
+/// The inverse follows [Fukushima, 2006](crate::Bibliography::Fuk06) and
+/// [Claessens, 2019](crate::Bibliography::Cla19)
+#[derive(Clone, Copy, Debug)]
+pub struct Cart {
+    ellps: Ellipsoid,
+}
+
+impl Cart {
+    #[must_use]
+    pub fn new(ellps: Ellipsoid) -> Cart {
+        Cart { ellps }
+    }
+}
+
+impl Kernel for Cart {
+    fn fwd(&self, operands: &mut [Coord]) -> usize {
+        let a = self.ellps.semimajor_axis();
+        let es = self.ellps.eccentricity_squared();
+        simd::batched(operands, &Cartesian { a, es });
+        successes(operands)
+    }
+
+    fn inv(&self, operands: &mut [Coord]) -> usize {
+        let es = self.ellps.eccentricity_squared();
+        let a = self.ellps.semimajor_axis();
+        let b = self.ellps.semiminor_axis();
+        let ra = 1. / a;
+        let kernel = Geographic {
+            es,
+            a,
+            b,
+            ra,
+            ar: b * ra,
+            ce4: 1.5 * es * es,
+            cutoff: a * 1e-16,
+        };
+        simd::batched(operands, &kernel);
+        successes(operands)
+    }
+}
+
+// Equivalent to `Ellipsoid::cartesian`, for a batch of coordinates
+struct Cartesian {
+    a: f64,
+    es: f64,
+}
+
+impl simd::Kernel for Cartesian {
+    #[inline(always)]
+    #[allow(non_snake_case)]
+    fn apply(&self, batch: &mut simd::Batch) {
+        let Cartesian { a, es } = *self;
+        let (sinlam, coslam) = simd::sin_cos(batch[0]);
+        let (sinphi, cosphi) = simd::sin_cos(batch[1]);
+        for i in 0..simd::LANES {
+            let h = batch[2][i];
+            let N = a / (1.0 - sinphi[i] * sinphi[i] * es).sqrt();
+            batch[0][i] = (N + h) * cosphi[i] * coslam[i];
+            batch[1][i] = (N + h) * cosphi[i] * sinlam[i];
+            batch[2][i] = (N * (1.0 - es) + h) * sinphi[i];
+        }
+    }
+}
+
+// Fukushima's and Claessens' cartesian to geographic conversion, for a batch
+// of coordinates, given the ellipsoidal constants involved
+struct Geographic {
+    // eccentricity squared, Fukushima's E, Claessens' c3 = 1-c2`
+    es: f64,
+    // semimajor and semiminor axes
+    a: f64,
+    b: f64,
+    // reciproque of a
+    ra: f64,
+    // aspect ratio, b/a: Fukushima's ec, Claessens' c4
+    ar: f64,
+    // 1.5 times the fourth power of the eccentricity
+    ce4: f64,
+    // if we're closer than this to the Z axis, we force latitude to one of the poles
+    cutoff: f64,
+}
+
+impl simd::Kernel for Geographic {
+    #[inline(always)]
+    #[allow(non_snake_case)]
+    fn apply(&self, batch: &mut simd::Batch) {
+        let Geographic {
+            es,
+            a,
+            b,
+            ra,
+            ar,
+            ce4,
+            cutoff,
+        } = *self;
+
+        // The longitude is straightforward
+        let lam = simd::atan2(batch[1], batch[0]);
+
+        let mut S1 = [0.; simd::LANES];
+        let mut CC = [0.; simd::LANES];
+        let mut p = [0.; simd::LANES];
+        for i in 0..simd::LANES {
+            let X = batch[0][i];
+            let Y = batch[1][i];
+            let Z = batch[2][i];
+
+            // The perpendicular distance from the point coordinate to the Z-axis (HM eq. 5-28)
+            p[i] = (X * X + Y * Y).sqrt();
+
+            let P = ra * p[i];
+            let S0 = ra * Z;
+            let C0 = ar * P;
+
+            // There's a lot of common subexpressions in the following which,
+            // in Fukushima's and Claessens' Fortranesque implementations,
+            // were explicitly eliminated (by introducing s02 = S0*S0, etc.).
+            // For clarity, we keep the full expressions here, and leave the
+            // elimination task to the optimizer.
+            let A = (S0 * S0 + C0 * C0).sqrt();
+            let F = P * A * A * A - es * C0 * C0 * C0;
+            let B = ce4 * S0 * S0 * C0 * C0 * P * (A - ar);
+
+            S1[i] = (ar * S0 * A * A * A + es * S0 * S0 * S0) * F - B * S0;
+            let C1 = F * F - B * C0;
+            CC[i] = ar * C1;
+        }
+
+        let phi = simd::atan2(S1, CC);
+        for i in 0..simd::LANES {
+            let Z = batch[2][i];
+            let (S1, CC) = (S1[i], CC[i]);
+            let h = (p[i] * CC.abs() + Z.abs() * S1.abs()
+                - a * (CC * CC + ar * ar * S1 * S1).sqrt())
+                / (CC * CC + S1 * S1).sqrt();
+
+            // If we're close to the Z-axis, the full algorithm breaks down. But if
+            // we're close to the Z-axis, we also know that the latitude must be close
+            // to one of the poles. So we force the latitude to the relevant pole and
+            // compute the height as |Z| - b
+            let polar = p[i] < cutoff;
+            batch[0][i] = lam[i];
+            batch[1][i] = if polar { FRAC_PI_2.copysign(Z) } else { phi[i] };
+            batch[2][i] = if polar { Z.abs() - b } else { h };
+        }
+    }
+}
+
+// ----- T E S T S ---------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The batched conversions against the scalar ones of `Ellipsoid`: The
+    // forward conversion does the same operations, and must be identical.
+    // The inverse uses another algorithm, and must agree to within 1e-13
+    // radians (i.e. less than 1 µm) horizontally, and 10 nm in height
+    #[test]
+    fn batched_vs_scalar() {
+        let ellps = Ellipsoid::named("GRS80").unwrap();
+        let cart = Cart::new(ellps);
+
+        // An odd number of coordinates, so the last batch is padded
+        let geo: Vec<Coord> = (0..=180)
+            .map(|i| {
+                let lat = -90. + i as f64;
+                Coord::geo(lat, 2. * lat + 0.5, 1000. * (i % 7) as f64 - 3000., 0.)
+            })
+            .collect();
+
+        let mut operands = geo.clone();
+        assert_eq!(cart.fwd(&mut operands), geo.len());
+        for (batched, scalar) in operands.iter().zip(&geo) {
+            assert_eq!(batched.0[..3], ellps.cartesian(scalar).0[..3]);
+        }
+
+        let xyz = operands.clone();
+        assert_eq!(cart.inv(&mut operands), geo.len());
+        for (batched, scalar) in operands.iter().zip(&xyz) {
+            let scalar = ellps.geographic(scalar);
+            assert!((batched[0] - scalar[0]).abs() < 1e-13);
+            assert!((batched[1] - scalar[1]).abs() < 1e-13);
+            assert!((batched[2] - scalar[2]).abs() < 1e-8);
+        }
+    }
+}
@@ -0,0 +1,298 @@
+#![allow(non_snake_case)]
+//! The Helmert transform, in the 3D cartesian space, for static and dynamic
+//! (time varying) reference frame shifts
+use super::*;
+use crate::math::simd;
+
+/// The 3D Helmert transform, with translation in metres, rotation in
+/// radians, and scale as a factor (i.e. 1 + s·10⁻⁶ for a scale of s ppm).
+///
+/// The rotations follow the position vector convention, and use the
+/// small-angle approximation, unless [`coordinate_frame`](Helmert::coordinate_frame),
+/// resp. [`exact`](Helmert::exact) is given. With [`dynamic`](Helmert::dynamic),
+/// the parameters evolve with the time, given by the fourth coordinate
+#[derive(Clone, Copy, Debug)]
+pub struct Helmert {
+    T: [f64; 3],
+    R: [f64; 3],
+    S: f64,
+    exact: bool,
+    position_vector: bool,
+    // The time evolution of T, R, and S, and the epoch of T, R, S
+    rates: Option<([f64; 3], [f64; 3], f64, f64)>,
+}
+
+impl Helmert {
+    #[must_use]
+    pub fn new(translation: [f64; 3], rotation: [f64; 3], scale: f64) -> Helmert {
+        Helmert {
+            T: translation,
+            R: rotation,
+            S: scale,
+            exact: false,
+            position_vector: true,
+            rates: None,
+        }
+    }
+
+    /// Use the coordinate frame rotation convention
+    #[must_use]
+    pub fn coordinate_frame(mut self) -> Helmert {
+        self.position_vector = false;
+        self
+    }
+
+    /// Use the exact rotation matrix, rather than the small-angle approximation
+    #[must_use]
+    pub fn exact(mut self) -> Helmert {
+        self.exact = true;
+        self
+    }
+
+    /// The yearly rates of change of the translation, rotation and scale,
+    /// and the epoch at which they are zero
+    #[must_use]
+    pub fn dynamic(
+        mut self,
+        translation: [f64; 3],
+        rotation: [f64; 3],
+        scale: f64,
+        epoch: f64,
+    ) -> Helmert {
+        self.rates = Some((translation, rotation, scale, epoch));
+        self
+    }
+
+    // The forward and inverse implementations are virtually identical, so we
+    // combine them into one, with the functionality selected from `direction`
+    fn common(&self, operands: &mut [Coord], direction: Direction) -> usize {
+        let Helmert {
+            T,
+            R,
+            S,
+            exact,
+            position_vector,
+            rates,
+        } = *self;
+        let n = operands.len();
+        let mut ROT = rotation_matrix(&R, exact, position_vector);
+
+        // Static transformations are applied in batches, cf. `crate::math::simd`
+        let Some((DT, DR, DS, epoch)) = rates else {
+            let kernel = StaticHelmert {
+                ROT: (R != [0., 0., 0.]).then_some(ROT),
+                SS: S,
+                TT: T,
+                direction,
+            };
+            simd::batched(operands, &kernel);
+            return n;
+        };
+
+        let rotated = R != [0., 0., 0.] || DR != [0., 0., 0.];
+        let mut TT = T;
+        let mut SS = S;
+
+        let mut prev_t = f64::NAN;
+        for c in operands {
+            // Necessary to update parameters?
+            #[allow(clippy::float_cmp)]
+            if c[3] != prev_t {
+                prev_t = c[3];
+                let dt = c[3] - epoch;
+                TT[0] = T[0] + dt * DT[0];
+                TT[1] = T[1] + dt * DT[1];
+                TT[2] = T[2] + dt * DT[2];
+                if rotated {
+                    let RR = [R[0] + dt * DR[0], R[1] + dt * DR[1], R[2] + dt * DR[2]];
+                    ROT = rotation_matrix(&RR, exact, position_vector);
+                }
+                SS = S + dt * DS;
+            }
+
+            // ----- Forward direction -----
+
+            if direction == Direction::Fwd {
+                if rotated {
+                    // Rotate
+                    let x = c[0] * ROT[0][0] + c[1] * ROT[0][1] + c[2] * ROT[0][2];
+                    let y = c[0] * ROT[1][0] + c[1] * ROT[1][1] + c[2] * ROT[1][2];
+                    let z = c[0] * ROT[2][0] + c[1] * ROT[2][1] + c[2] * ROT[2][2];
+
+                    // scale and offset
+                    c[0] = SS * x + TT[0];
+                    c[1] = SS * y + TT[1];
+                    c[2] = SS * z + TT[2];
+                    continue;
+                }
+
+                // scale and offset without rotation
+                c[0] = SS * c[0] + TT[0];
+                c[1] = SS * c[1] + TT[1];
+                c[2] = SS * c[2] + TT[2];
+                continue;
+            }
+
+            // ----- Inverse direction -----
+
+            // Deoffset and unscale
+            let x = (c[0] - TT[0]) / SS;
+            let y = (c[1] - TT[1]) / SS;
+            let z = (c[2] - TT[2]) / SS;
+
+            // Inverse rotation by transposed multiplication
+            if rotated {
+                c[0] = x * ROT[0][0] + y * ROT[1][0] + z * ROT[2][0];
+                c[1] = x * ROT[0][1] + y * ROT[1][1] + z * ROT[2][1];
+                c[2] = x * ROT[0][2] + y * ROT[1][2] + z * ROT[2][2];
+            } else {
+                c[0] = x;
+                c[1] = y;
+                c[2] = z;
+            }
+        }
+        n
+    }
+}
+
+impl Kernel for Helmert {
+    fn fwd(&self, operands: &mut [Coord]) -> usize {
+        self.common(operands, Direction::Fwd)
+    }
+
+    fn inv(&self, operands: &mut [Coord]) -> usize {
+        self.common(operands, Direction::Inv)
+    }
+}
+
+// The static case of `Helmert::common`, for a batch of coordinates
+struct StaticHelmert {
+    ROT: Option<[[f64; 3]; 3]>,
+    SS: f64,
+    TT: [f64; 3],
+    direction: Direction,
+}
+
+impl simd::Kernel for StaticHelmert {
+    #[inline(always)]
+    fn apply(&self, batch: &mut simd::Batch) {
+        let StaticHelmert {
+            ROT,
+            SS,
+            TT,
+            direction,
+        } = self;
+        let (SS, direction) = (*SS, *direction);
+        let [X, Y, Z, _] = batch;
+        for i in 0..simd::LANES {
+            let c = [X[i], Y[i], Z[i]];
+            let c = match (direction, ROT) {
+                (Direction::Fwd, Some(ROT)) => {
+                    let x = c[0] * ROT[0][0] + c[1] * ROT[0][1] + c[2] * ROT[0][2];
+                    let y = c[0] * ROT[1][0] + c[1] * ROT[1][1] + c[2] * ROT[1][2];
+                    let z = c[0] * ROT[2][0] + c[1] * ROT[2][1] + c[2] * ROT[2][2];
+                    [SS * x + TT[0], SS * y + TT[1], SS * z + TT[2]]
+                }
+                (Direction::Fwd, None) => [SS * c[0] + TT[0], SS * c[1] + TT[1], SS * c[2] + TT[2]],
+                (Direction::Inv, rotation) => {
+                    let x = (c[0] - TT[0]) / SS;
+                    let y = (c[1] - TT[1]) / SS;
+                    let z = (c[2] - TT[2]) / SS;
+                    match rotation {
+                        Some(ROT) => [
+                            x * ROT[0][0] + y * ROT[1][0] + z * ROT[2][0],
+                            x * ROT[0][1] + y * ROT[1][1] + z * ROT[2][1],
+                            x * ROT[0][2] + y * ROT[1][2] + z * ROT[2][2],
+                        ],
+                        None => [x, y, z],
+                    }
+                }
+            };
+            [X[i], Y[i], Z[i]] = c;
+        }
+    }
+}
+
+// ----- R O T A T I O N   M A T R I X   B U I L D E R ---------------------------------
+
+// Based on Karsten Engsager's implementation in set_dtm_1.c (trlib),
+// but adds optional small-angle approximation, and selection between
+// the "position vector" and "coordinate frame" rotation conventions.
+//
+// TO' = scale * [ROTZ * ROTY * ROTX] * FROM' + [translation x, y, z]'
+//
+//        | cz sz 0 |           | cy 0 -sy |           | 1   0  0 |
+// ROTZ = |-sz cz 0 |,   ROTY = | 0  1   0 |,   ROTX = | 0  cx sx |
+//        |  0  0 1 |           | sy 0  cy |           | 0 -sx cx |
+//
+fn rotation_matrix(r: &[f64], exact: bool, position_vector: bool) -> [[f64; 3]; 3] {
+    let (rx, ry, rz) = (r[0], r[1], r[2]);
+
+    // Small-angle approximations: sx = sin(rx) = rx,  cx = cos(rx) = 1,  etc.
+    let (mut sx, mut sy, mut sz) = (rx, ry, rz);
+    let (mut cx, mut cy, mut cz) = (1.0, 1.0, 1.0);
+
+    // Leave out the second order infinitesimals in the rotation
+    // matrix elements, when using small-angle approximations
+    if exact {
+        (sx, cx) = rx.sin_cos();
+        (sy, cy) = ry.sin_cos();
+        (sz, cz) = rz.sin_cos();
+    }
+
+    let r11 = cy * cz;
+    let mut r12 = cx * sz;
+    let mut r13 = -cx * sy * cz;
+
+    let r21 = -cy * sz;
+    let mut r22 = cx * cz;
+    let mut r23 = sx * cz;
+
+    let r31 = sy;
+    let r32 = -sx * cy;
+    let r33 = cx * cy;
+
+    // But apply the second order terms in the exact case
+    if exact {
+        r12 += sx * sy * cz;
+        r13 += sx * sz;
+
+        r22 -= sx * sy * sz;
+        r23 += cx * sy * sz;
+    }
+
+    if position_vector {
+        return [[r11, r21, r31], [r12, r22, r32], [r13, r23, r33]];
+    }
+    [[r11, r12, r13], [r21, r22, r23], [r31, r32, r33]]
+}
+
+// ----- T E S T S ---------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The batched static case against the point-by-point dynamic one: With
+    // zero rates, the two do the same operations, and must be identical
+    #[test]
+    fn batched_vs_scalar() {
+        let T = [0.06155, -0.01087, -0.04019];
+        let R = [-0.0394924, -0.0327221, -0.0328979].map(|r: f64| (r / 3600.).to_radians());
+        let S = 1. - 0.009994e-6;
+        let batched = Helmert::new(T, R, S).coordinate_frame().exact();
+        let scalar = batched.dynamic([0.; 3], [0.; 3], 0., 2020.);
+
+        let xyz: Vec<Coord> = (0..7)
+            .map(|i| Coord::raw(-4052051.7643 + i as f64, 4212836.2017, -2545106.0245, 2018.))
+            .collect();
+        for direction in [Direction::Fwd, Direction::Inv] {
+            let mut a = xyz.clone();
+            let mut b = xyz.clone();
+            assert_eq!(batched.apply(&mut a, direction), 7);
+            assert_eq!(scalar.apply(&mut b, direction), 7);
+            assert_eq!(a, b);
+            assert!(a[0].hypot3(&xyz[0]) > 0.01);
+        }
+    }
+}
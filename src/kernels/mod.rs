@@ -0,0 +1,145 @@
+//! The computational kernels of the most used operators, free of the `Op`
+//! and `Context` machinery, and hence also available in the no_std
+//! configuration (`--no-default-features --features libm`).
+//!
+//! The kernels take their parameters as plain numbers, with angles in
+//! radians, and operate on angular coordinates in radians, i.e. without
+//! the degree adaptors of the operator layer:
+//!
+//! ```
+//! use geodesy::kernels::{Cart, Chain, Helmert, Kernel};
+//! use geodesy::{Coord, Ellipsoid, Fwd};
+//!
+//! // ED50 to WGS84, by a translation of the cartesian coordinates
+//! let chain = Chain::new()
+//!     .then(Cart::new(Ellipsoid::named("intl").unwrap()))
+//!     .then(Helmert::new([-87., -96., -120.], [0., 0., 0.], 1.))
+//!     .then_inverse(Cart::new(Ellipsoid::named("GRS80").unwrap()));
+//!
+//! let mut data = [Coord::geo(55., 12., 0., 0.)];
+//! assert_eq!(chain.apply(&mut data, Fwd), 1);
+//! ```
+use crate::internal::*;
+
+mod cart;
+mod helmert;
+mod tmerc;
+
+pub use cart::Cart;
+pub use helmert::Helmert;
+pub use tmerc::Tmerc;
+
+/// The forward and inverse transformations of an operator, applied to a
+/// slice of coordinates in place. Both return the number of coordinates
+/// successfully transformed. The coordinates not successfully transformed
+/// are set to NaN
+pub trait Kernel {
+    fn fwd(&self, operands: &mut [Coord]) -> usize;
+    fn inv(&self, operands: &mut [Coord]) -> usize;
+
+    fn apply(&self, operands: &mut [Coord], direction: Direction) -> usize {
+        match direction {
+            Direction::Fwd => self.fwd(operands),
+            Direction::Inv => self.inv(operands),
+        }
+    }
+}
+
+/// A sequence of kernels, applied as a pipeline: In the forward direction,
+/// each step is applied in its own given direction, in the order given.
+/// In the inverse direction, the steps are applied in reverse order, and in
+/// the opposite of their given direction
+#[derive(Default)]
+pub struct Chain {
+    steps: Vec<(Box<dyn Kernel>, Direction)>,
+}
+
+impl Chain {
+    #[must_use]
+    pub fn new() -> Chain {
+        Chain::default()
+    }
+
+    /// Append `kernel`, to be applied in its forward direction
+    #[must_use]
+    pub fn then(mut self, kernel: impl Kernel + 'static) -> Chain {
+        self.steps.push((Box::new(kernel), Direction::Fwd));
+        self
+    }
+
+    /// Append `kernel`, to be applied in its inverse direction
+    #[must_use]
+    pub fn then_inverse(mut self, kernel: impl Kernel + 'static) -> Chain {
+        self.steps.push((Box::new(kernel), Direction::Inv));
+        self
+    }
+
+    pub fn len(&self) -> usize {
+        self.steps.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.steps.is_empty()
+    }
+}
+
+impl Kernel for Chain {
+    // As for the pipeline operator, the number of successes is that of the
+    // least successful step
+    fn fwd(&self, operands: &mut [Coord]) -> usize {
+        let mut n = operands.len();
+        for (kernel, direction) in &self.steps {
+            n = n.min(kernel.apply(operands, *direction));
+        }
+        n
+    }
+
+    fn inv(&self, operands: &mut [Coord]) -> usize {
+        let mut n = operands.len();
+        for (kernel, direction) in self.steps.iter().rev() {
+            let direction = match direction {
+                Direction::Fwd => Direction::Inv,
+                Direction::Inv => Direction::Fwd,
+            };
+            n = n.min(kernel.apply(operands, direction));
+        }
+        n
+    }
+}
+
+// Number of coordinates without any NaN elements
+fn successes(operands: &[Coord]) -> usize {
+    operands
+        .iter()
+        .filter(|coord| !coord.0.iter().any(|c| c.is_nan()))
+        .count()
+}
+
+// ----- T E S T S ---------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn chain() {
+        let grs80 = Ellipsoid::named("GRS80").unwrap();
+        let chain = Chain::new()
+            .then(Cart::new(grs80))
+            .then(Helmert::new([1., 2., 3.], [0., 0., 0.], 1.))
+            .then_inverse(Cart::new(grs80));
+        assert_eq!(chain.len(), 3);
+
+        let geo = Coord::geo(55., 12., 100., 0.);
+        let mut data = [geo];
+        assert_eq!(chain.apply(&mut data, Direction::Fwd), 1);
+        let d = data[0].default_ellps_3d_dist(&geo);
+        assert!((d - 14_f64.sqrt()).abs() < 1e-6);
+        assert_eq!(chain.apply(&mut data, Direction::Inv), 1);
+        assert!(data[0].default_ellps_3d_dist(&geo) < 1e-6);
+
+        // The empty chain is the identity
+        assert_eq!(Chain::new().fwd(&mut data), 1);
+        assert!(Chain::new().is_empty());
+    }
+}
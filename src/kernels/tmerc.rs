@@ -0,0 +1,335 @@
+//! Transverse Mercator, following Engsager & Poder (2007)
+use super::*;
+use crate::math::*;
+
+/// The transverse Mercator projection, following [Engsager & Poder,
+/// 2007](crate::Bibliography::Eng07), with the scale factor `k_0` on the
+/// central meridian `lon_0`, and the false easting and northing `x_0`,
+/// `y_0` at the latitude of origin, `lat_0`. On the sphere, the closed form
+/// expressions are used
+#[derive(Clone, Copy, Debug)]
+pub struct Tmerc {
+    ellps: Ellipsoid,
+    lon_0: f64,
+    x_0: f64,
+    // The scaled spherical Earth radius - Qn in Engsager's implementation
+    qs: f64,
+    // Origin northing minus true northing at the origin latitude
+    zb: f64,
+    // The Fourier series for the conformal latitude, and for the TM coordinates
+    conformal: FourierCoefficients,
+    tm: FourierCoefficients,
+}
+
+impl Tmerc {
+    #[must_use]
+    pub fn new(ellps: Ellipsoid, k_0: f64, lat_0: f64, lon_0: f64, x_0: f64, y_0: f64) -> Tmerc {
+        let conformal = ellps.coefficients_for_conformal_latitude_computations();
+        let tm = Tmerc::coefficients(&ellps);
+        let (qs, zb) = Tmerc::scaled_radius_and_zb(&ellps, k_0, lat_0, y_0, &conformal, &tm);
+        Tmerc::from_parts(ellps, lon_0, x_0, qs, zb, conformal, tm)
+    }
+
+    // For the `tmerc` and `utm` operators, which precompute the parts once,
+    // and cache the Fourier coefficients in the context
+    pub(crate) fn from_parts(
+        ellps: Ellipsoid,
+        lon_0: f64,
+        x_0: f64,
+        qs: f64,
+        zb: f64,
+        conformal: FourierCoefficients,
+        tm: FourierCoefficients,
+    ) -> Tmerc {
+        Tmerc {
+            ellps,
+            lon_0,
+            x_0,
+            qs,
+            zb,
+            conformal,
+            tm,
+        }
+    }
+
+    /// The Fourier series for the transverse mercator coordinates, from
+    /// [Engsager & Poder, 2007](crate::Bibliography::Eng07), with extensions
+    /// to 6th order by [Karney, 2011](crate::Bibliography::Kar11).
+    pub(crate) fn coefficients(ellps: &Ellipsoid) -> FourierCoefficients {
+        fourier_coefficients(ellps.third_flattening(), &TRANSVERSE_MERCATOR)
+    }
+
+    // The scaled radius, and the "zombie parameter", i.e. the origin
+    // northing minus the true northing at the origin latitude
+    pub(crate) fn scaled_radius_and_zb(
+        ellps: &Ellipsoid,
+        k_0: f64,
+        lat_0: f64,
+        y_0: f64,
+        conformal: &FourierCoefficients,
+        tm: &FourierCoefficients,
+    ) -> (f64, f64) {
+        let qs = k_0 * ellps.semimajor_axis() * ellps.normalized_meridian_arc_unit();
+        // Conformal latitude value of the latitude-of-origin - Z in Engsager's notation
+        let z = ellps.latitude_geographic_to_conformal(lat_0, conformal);
+        (qs, y_0 - qs * (z + clenshaw_sin(2. * z, &tm.fwd)))
+    }
+
+    fn spherical(&self) -> bool {
+        self.ellps.eccentricity_squared() == 0.
+    }
+
+    // ----- S P H E R I C A L ---------------------------------------------------------
+
+    // On the sphere, the conformal latitude is the geographical, and the Fourier
+    // series vanish, leaving the closed form expressions, cf. Snyder (1987), pp. 58-60.
+    // The scaled radius and the zombie parameter are the same as for the ellipsoid
+
+    fn spherical_fwd(&self, operands: &mut [Coord]) -> usize {
+        let Tmerc {
+            lon_0, x_0, qs, zb, ..
+        } = *self;
+        let mut successes = 0_usize;
+        for coord in operands {
+            let (sin_lat, cos_lat) = coord[1].sin_cos();
+            let (sin_lon, cos_lon) = (coord[0] - lon_0).sin_cos();
+            let lon = (cos_lat * sin_lon).atanh();
+            let lat = sin_lat.atan2(cos_lat * cos_lon);
+
+            // Don't wanna play if we're too far from the center meridian
+            if lon.abs() > 2.623395162778 {
+                coord[0] = PointStatus::OutOfDomain.nan();
+                coord[1] = PointStatus::OutOfDomain.nan();
+                continue;
+            }
+
+            coord[0] = qs * lon + x_0;
+            coord[1] = qs * lat + zb;
+            successes += 1;
+        }
+        successes
+    }
+
+    fn spherical_inv(&self, operands: &mut [Coord]) -> usize {
+        let Tmerc {
+            lon_0, x_0, qs, zb, ..
+        } = *self;
+        let mut successes = 0_usize;
+        for coord in operands {
+            let lon = (coord[0] - x_0) / qs;
+            let lat = (coord[1] - zb) / qs;
+
+            // Don't wanna play if we're too far from the center meridian
+            if lon.abs() > 2.623395162778 {
+                coord[0] = PointStatus::OutOfDomain.nan();
+                coord[1] = PointStatus::OutOfDomain.nan();
+                continue;
+            }
+
+            let (sin_lat, cos_lat) = lat.sin_cos();
+            let sinh_lon = lon.sinh();
+            coord[0] = normalize_angle_symmetric(sinh_lon.atan2(cos_lat) + lon_0);
+            coord[1] = sin_lat.atan2(sinh_lon.hypot(cos_lat));
+            successes += 1;
+        }
+        successes
+    }
+}
+
+impl Kernel for Tmerc {
+    // Forward transverse mercator, following Engsager & Poder(2007)
+    fn fwd(&self, operands: &mut [Coord]) -> usize {
+        if self.spherical() {
+            return self.spherical_fwd(operands);
+        }
+        let Tmerc {
+            ellps,
+            lon_0,
+            x_0,
+            qs,
+            zb,
+            conformal,
+            tm,
+        } = self;
+        let (lon_0, x_0, qs, zb) = (*lon_0, *x_0, *qs, *zb);
+        let mut successes = 0_usize;
+        for coord in operands {
+            // --- 1. Geographical -> Conformal latitude, rotated longitude
+
+            // The conformal latitude
+            let lat = ellps.latitude_geographic_to_conformal(coord[1], conformal);
+            // The longitude as reckoned from the central meridian
+            let lon = coord[0] - lon_0;
+
+            // --- 2. Conformal LAT, LNG -> complex spherical LAT
+
+            let (sin_lat, cos_lat) = lat.sin_cos();
+            let (sin_lon, cos_lon) = lon.sin_cos();
+            let cos_lat_lon = cos_lat * cos_lon;
+            let mut lat = sin_lat.atan2(cos_lat_lon);
+
+            // --- 3. Complex spherical N, E -> ellipsoidal normalized N, E
+
+            // Some numerical optimizations from PROJ modifications by Even Rouault,
+            let inv_denom_tan_lon = 1. / sin_lat.hypot(cos_lat_lon);
+            let tan_lon = sin_lon * cos_lat * inv_denom_tan_lon;
+            // Inverse Gudermannian, using the precomputed tan(lon)
+            let mut lon = tan_lon.asinh();
+
+            // Trigonometric terms for Clenshaw summation
+            // Non-optimized version:  `let trig = (2.*lat).sin_cos()`
+            let two_inv_denom_tan_lon = 2.0 * inv_denom_tan_lon;
+            let two_inv_denom_tan_lon_square = two_inv_denom_tan_lon * inv_denom_tan_lon;
+            let tmp_r = cos_lat_lon * two_inv_denom_tan_lon_square;
+            let trig = [sin_lat * tmp_r, cos_lat_lon * tmp_r - 1.0];
+
+            // Hyperbolic terms for Clenshaw summation
+            // Non-optimized version:  `let hyp = [(2.*lon).sinh(), (2.*lon).sinh()]`
+            let hyp = [
+                tan_lon * two_inv_denom_tan_lon,
+                two_inv_denom_tan_lon_square - 1.0,
+            ];
+
+            // Evaluate and apply the differential term
+            let dc = clenshaw_complex_sin_optimized_for_tmerc(trig, hyp, &tm.fwd);
+            lat += dc[0];
+            lon += dc[1];
+
+            // Don't wanna play if we're too far from the center meridian
+            if lon.abs() > 2.623395162778 {
+                coord[0] = PointStatus::OutOfDomain.nan();
+                coord[1] = PointStatus::OutOfDomain.nan();
+                continue;
+            }
+
+            // --- 4. ellipsoidal normalized N, E -> metric N, E
+
+            coord[0] = qs * lon + x_0; // Easting
+            coord[1] = qs * lat + zb; // Northing
+            successes += 1;
+        }
+        successes
+    }
+
+    // Inverse Transverse Mercator, following Engsager & Poder (2007)
+    fn inv(&self, operands: &mut [Coord]) -> usize {
+        if self.spherical() {
+            return self.spherical_inv(operands);
+        }
+        let Tmerc {
+            ellps,
+            lon_0,
+            x_0,
+            qs,
+            zb,
+            conformal,
+            tm,
+        } = self;
+        let (lon_0, x_0, qs, zb) = (*lon_0, *x_0, *qs, *zb);
+        let mut successes = 0_usize;
+        for coord in operands {
+            // --- 1. Normalize N, E
+
+            let mut lon = (coord[0] - x_0) / qs;
+            let mut lat = (coord[1] - zb) / qs;
+
+            // Don't wanna play if we're too far from the center meridian
+            if lon.abs() > 2.623395162778 {
+                coord[0] = PointStatus::OutOfDomain.nan();
+                coord[1] = PointStatus::OutOfDomain.nan();
+                continue;
+            }
+
+            // --- 2. Normalized N, E -> complex spherical LAT, LNG
+
+            let dc = clenshaw_complex_sin([2. * lat, 2. * lon], &tm.inv);
+            lat += dc[0];
+            lon += dc[1];
+            lon = gudermannian(lon);
+
+            // --- 3. Complex spherical LAT -> Gaussian LAT, LNG
+
+            let (sin_lat, cos_lat) = lat.sin_cos();
+            let (sin_lon, cos_lon) = lon.sin_cos();
+            let cos_lat_lon = cos_lat * cos_lon;
+            lon = sin_lon.atan2(cos_lat_lon);
+            lat = (sin_lat * cos_lon).atan2(sin_lon.hypot(cos_lat_lon));
+
+            // --- 4. Gaussian LAT, LNG -> ellipsoidal LAT, LNG
+
+            let lon = normalize_angle_symmetric(lon + lon_0);
+            let lat = ellps.latitude_conformal_to_geographic(lat, conformal);
+            (coord[0], coord[1]) = (lon, lat);
+
+            successes += 1;
+        }
+        successes
+    }
+}
+
+// ----- A N C I L L A R Y   F U N C T I O N S -----------------------------------------
+
+#[rustfmt::skip]
+const TRANSVERSE_MERCATOR: PolynomialCoefficients = PolynomialCoefficients {
+    // Geodetic to TM. [Engsager & Poder, 2007](crate::Bibliography::Eng07)
+    fwd: [
+        [1./2.,   -2./3.,   5./16.,   41./180.,   -127./288.0 ,   7891./37800.],
+        [0., 13./48.,   -3./5.,   557./1440.,   281./630.,   -1983433./1935360.],
+        [0., 0., 61./240.,  -103./140.,   15061./26880.,   167603./181440.],
+        [0., 0., 0., 49561./161280.,   -179./168.,   6601661./7257600.],
+        [0., 0., 0., 0., 34729./80640.,   -3418889./1995840.],
+        [0., 0., 0., 0., 0., 212378941./319334400.]
+    ],
+
+    // TM to Geodetic. [Engsager & Poder, 2007](crate::Bibliography::Eng07)
+    inv: [
+        [-1./2.,   2./3.,   -37./96.,   1./360.,   81./512.,   -96199./604800.],
+        [0., -1./48.,   -1./15.,   437./1440.,   -46./105.,   1118711./3870720.],
+        [0., 0., -17./480.,   37./840.,   209./4480.,   -5569./90720.],
+        [0., 0., 0., -4397./161280.,   11./504.,   830251./7257600.],
+        [0., 0., 0., 0., -4583./161280.,   108847./3991680.],
+        [0., 0., 0., 0., 0., -20648693./638668800.]
+    ]
+};
+
+// ----- T E S T S ---------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn utm() {
+        // UTM zone 32, i.e. as `utm zone=32`, and the same validation values
+        let utm32 = Tmerc::new(
+            Ellipsoid::named("GRS80").unwrap(),
+            0.9996,
+            0.,
+            9_f64.to_radians(),
+            500_000.,
+            0.,
+        );
+        let geo = [Coord::geo(55., 12., 0., 0.), Coord::geo(-55., 12., 0., 0.)];
+        let projected = [
+            Coord::raw(691_875.632_139_661, 6_098_907.825_005_012, 0., 0.),
+            Coord::raw(691_875.632_139_661, -6_098_907.825_005_012, 0., 0.),
+        ];
+
+        let mut operands = geo;
+        assert_eq!(utm32.fwd(&mut operands), 2);
+        for i in 0..2 {
+            assert!(operands[i].hypot2(&projected[i]) < 1e-6);
+        }
+        assert_eq!(utm32.inv(&mut operands), 2);
+        for i in 0..2 {
+            assert!(operands[i].default_ellps_dist(&geo[i]) < 1e-6);
+        }
+
+        // On the sphere, the closed form expressions take over
+        let sphere = Tmerc::new(Ellipsoid::new(6_400_000., 0.), 1., 0., 0., 0., 0.);
+        let mut operands = [Coord::geo(55., 12., 0., 0.)];
+        assert_eq!(sphere.fwd(&mut operands), 1);
+        assert_eq!(sphere.inv(&mut operands), 1);
+        assert!(operands[0].default_ellps_dist(&Coord::geo(55., 12., 0., 0.)) < 1e-6);
+    }
+}
@@ -1,40 +1,152 @@
 #![doc = include_str!("../README.md")]
+// Validation values in the test suites are given with the full precision
+// of the reference implementations from which they were obtained
+#![cfg_attr(test, allow(clippy::excessive_precision))]
+// Without std, only the computational core is available: Coordinates,
+// ellipsoids, the supporting math, and the operator kernels
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(not(feature = "std"))]
+extern crate alloc;
+
+#[cfg(not(any(feature = "std", feature = "libm")))]
+compile_error!("Without the `std` feature, the `libm` feature is required");
 
 mod bibliography;
+#[cfg(feature = "std")]
 mod context;
 mod coord;
 mod ellipsoid;
+#[cfg(all(feature = "ffi", not(target_arch = "wasm32")))]
+pub mod ffi;
+#[cfg(feature = "std")]
+mod gie;
+#[cfg(feature = "std")]
 mod grid;
+#[cfg(feature = "std")]
 mod inner_op;
+#[cfg(feature = "std")]
+mod interop;
+pub mod kernels;
 mod math;
+#[cfg(feature = "std")]
 mod op;
+#[cfg(feature = "wasm")]
+pub mod wasm;
 
 // The bread-and-butter
+#[cfg(feature = "std")]
 pub use crate::context::Context;
+#[cfg(feature = "std")]
 pub use crate::context::Minimal;
+#[cfg(all(feature = "std", not(target_arch = "wasm32")))]
 pub use crate::context::Plain;
+#[cfg(feature = "std")]
+pub use crate::context::Shared;
+#[cfg(feature = "async")]
+pub use crate::context::{AsyncProvider, Remote};
+pub use crate::coord::Coor2D;
+pub use crate::coord::Coor3D;
 pub use crate::coord::Coord;
+pub use crate::coord::CoordinateSet;
+pub use crate::coord::CoordinateSoA;
+pub use crate::coord::DmsFormat;
+pub use crate::coord::PointStatus;
 pub use crate::ellipsoid::Ellipsoid;
+#[cfg(feature = "std")]
+pub use crate::gie::run_gie;
+#[cfg(feature = "std")]
+pub use crate::gie::GieFailure;
+#[cfg(feature = "std")]
+pub use crate::gie::GieReport;
+#[cfg(feature = "std")]
+pub use crate::inner_op::utm_zone;
+#[cfg(feature = "arrow")]
+pub use crate::interop::apply_to_arrow;
+#[cfg(feature = "std")]
+pub use crate::interop::parse_proj;
+#[cfg(feature = "serde")]
+pub use crate::interop::parse_projjson;
+#[cfg(feature = "std")]
+pub use crate::interop::parse_wkt;
+#[cfg(feature = "serde")]
+pub use crate::interop::transform_geojson;
+#[cfg(feature = "std")]
+pub use crate::interop::transform_gpx;
+#[cfg(feature = "std")]
+pub use crate::interop::transform_wkb;
+#[cfg(feature = "proj-verify")]
+pub use crate::interop::verify_against_proj;
+#[cfg(feature = "proj-verify")]
+pub use crate::interop::ProjDiscrepancy;
+#[cfg(feature = "proj-verify")]
+pub use crate::interop::ProjReport;
+#[cfg(feature = "geo")]
+pub use crate::interop::TransformGeometry;
 pub use crate::Direction::Fwd;
 pub use crate::Direction::Inv;
 
+// Typed builders for pipelines, and their steps
+#[cfg(feature = "std")]
+pub use crate::op::{Adapt, Cart, Gridshift, Helmert, Laea, Lcc, Merc, Tmerc, Utm};
+#[cfg(feature = "std")]
+pub use crate::op::{Pipeline, Step};
+
 /// The bread-and-butter, shrink-wrapped for external use
 pub mod preamble {
+    #[cfg(feature = "std")]
     pub use crate::context::Context;
+    #[cfg(feature = "std")]
     pub use crate::grid::Grid;
+    #[cfg(feature = "std")]
+    pub use crate::inner_op::OperatorInfo;
+    #[cfg(feature = "std")]
+    pub use crate::op::AreaCheck;
+    #[cfg(feature = "std")]
+    pub use crate::op::AreaOfUse;
+    #[cfg(feature = "std")]
+    pub use crate::op::Metadata;
+    #[cfg(feature = "std")]
     pub use crate::op::Op;
+    #[cfg(feature = "std")]
     pub use crate::op::OpHandle;
+    #[cfg(feature = "std")]
+    pub use crate::op::Pipeline;
+    #[cfg(feature = "std")]
+    pub use crate::op::Provenance;
+    #[cfg(feature = "std")]
+    pub use crate::op::ResourceKind;
+    #[cfg(feature = "std")]
+    pub use crate::op::RoundtripError;
+    #[cfg(feature = "std")]
+    pub use crate::op::SampleRegion;
+    #[cfg(feature = "std")]
+    pub use crate::op::Step;
+    #[cfg(feature = "std")]
+    pub use crate::op::StepDescription;
+    #[cfg(feature = "std")]
+    pub use crate::op::Tracer;
+    pub use crate::Coor2D;
+    pub use crate::Coor3D;
     pub use crate::Coord;
+    pub use crate::CoordinateSet;
+    pub use crate::CoordinateSoA;
     pub use crate::Direction;
     pub use crate::Direction::Fwd;
     pub use crate::Direction::Inv;
     pub use crate::Ellipsoid;
     pub use crate::Error;
+    #[cfg(feature = "std")]
     pub use crate::Minimal;
+    #[cfg(all(feature = "std", not(target_arch = "wasm32")))]
     pub use crate::Plain;
+    pub use crate::PointStatus;
+    #[cfg(feature = "std")]
+    pub use crate::Shared;
 }
 
 /// Preamble for InnerOp modules (built-in or user defined)
+#[cfg(feature = "std")]
 pub mod inner_op_authoring {
     pub use crate::preamble::*;
     pub use log::error;
@@ -43,6 +155,8 @@ pub mod inner_op_authoring {
     pub use log::warn;
 
     pub use crate::context::Context;
+    pub use crate::inner_op::fixed_point_inverse;
+    pub use crate::inner_op::newton_inverse;
     pub use crate::inner_op::InnerOp;
     pub use crate::inner_op::OpConstructor;
     pub use crate::math::*;
@@ -56,10 +170,16 @@ pub mod inner_op_authoring {
 }
 
 /// Preamble for crate-internal modules, and authoring of Context providers
+#[cfg(feature = "std")]
 pub mod internal {
     pub use crate::context::Context;
+    pub use crate::context::Precomputed;
     pub use crate::context::BUILTIN_ADAPTORS;
+    pub use crate::context::BUILTIN_MACROS;
+    pub use crate::context::ITRF_MACROS;
+    pub use crate::inner_op::operator_inventory;
     pub use crate::inner_op_authoring::*;
+    pub(crate) use crate::op::normalized_definition;
     pub use std::collections::BTreeMap;
     pub use std::collections::BTreeSet;
     pub use std::path::PathBuf;
@@ -67,21 +187,51 @@ pub mod internal {
     pub use uuid::Uuid;
 }
 
+/// Preamble for crate-internal modules, in the no_std configuration: The
+/// computational core, and the parts of std it needs, from alloc and libm
+#[cfg(not(feature = "std"))]
+pub mod internal {
+    pub use crate::math::float::Float;
+    pub use crate::math::*;
+    pub use crate::preamble::*;
+    pub use alloc::borrow::ToOwned;
+    pub use alloc::boxed::Box;
+    pub use alloc::collections::BTreeMap;
+    pub use alloc::format;
+    pub use alloc::string::{String, ToString};
+    pub use alloc::vec;
+    pub use alloc::vec::Vec;
+}
+
+#[cfg(not(feature = "std"))]
+use alloc::{boxed::Box, string::String};
 use thiserror::Error;
 /// The *Rust Geodesy* errror messaging enumeration. Badly needs reconsideration
 #[derive(Error, Debug)]
 pub enum Error {
+    #[cfg(feature = "std")]
     #[error("i/o error")]
     Io(#[from] std::io::Error),
 
     #[error("error: {0}")]
-    General(&'static str),
+    General(String),
 
     #[error("syntax error: {0}")]
     Syntax(String),
 
+    /// A syntax error located in the text of a definition: At the offending
+    /// token, if identified, otherwise at the start of the step in question.
+    /// Lines and columns are counted from 1
+    #[error("syntax error at line {line}, column {column}: {message}")]
+    SyntaxAt {
+        message: String,
+        line: usize,
+        column: usize,
+        token: String,
+    },
+
     #[error("{0}: {1}")]
-    Operator(&'static str, &'static str),
+    Operator(String, String),
 
     #[error("invalid header (expected {expected:?}, found {found:?})")]
     InvalidHeader { expected: String, found: String },
@@ -109,13 +259,41 @@ pub enum Error {
 
     #[error("unknown error")]
     Unknown,
+
+    /// An error, with the context it occurred in, e.g. the step of a pipeline,
+    /// or the grid file, it stems from, cf. [`Error::context`]
+    #[error("{context}: {source}")]
+    Context {
+        context: String,
+        #[source]
+        source: Box<Error>,
+    },
+}
+
+impl Error {
+    /// The error, in the `context` given, e.g. `step 3 (helmert x=1)`
+    #[must_use]
+    pub fn context(self, context: impl Into<String>) -> Error {
+        Error::Context {
+            context: context.into(),
+            source: Box::new(self),
+        }
+    }
+
+    /// The innermost error, i.e. the error itself, stripped of any context
+    pub fn root(&self) -> &Error {
+        match self {
+            Error::Context { source, .. } => source.root(),
+            error => error,
+        }
+    }
 }
 
 /// `Fwd`: Indicate that a two-way operator, function, or method,
 /// should run in the *forward* direction.
 /// `Inv`: Indicate that a two-way operator, function, or method,
 /// should run in the *inverse* direction.
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
 pub enum Direction {
     Fwd,
     Inv,
@@ -0,0 +1,156 @@
+//! The floating point functions which std provides as inherent methods of
+//! `f64`, provided by libm, for the no_std configuration. With the trait in
+//! scope, e.g. `x.sin()` reads the same in both configurations.
+
+pub trait Float {
+    fn abs(self) -> Self;
+    fn signum(self) -> Self;
+    fn copysign(self, sign: Self) -> Self;
+    fn floor(self) -> Self;
+    fn ceil(self) -> Self;
+    fn round(self) -> Self;
+    fn trunc(self) -> Self;
+    fn fract(self) -> Self;
+    fn rem_euclid(self, rhs: Self) -> Self;
+    fn mul_add(self, a: Self, b: Self) -> Self;
+    fn sqrt(self) -> Self;
+    fn cbrt(self) -> Self;
+    fn hypot(self, other: Self) -> Self;
+    fn powi(self, n: i32) -> Self;
+    fn powf(self, n: Self) -> Self;
+    fn exp(self) -> Self;
+    fn exp_m1(self) -> Self;
+    fn ln(self) -> Self;
+    fn ln_1p(self) -> Self;
+    fn log10(self) -> Self;
+    fn sin(self) -> Self;
+    fn cos(self) -> Self;
+    fn tan(self) -> Self;
+    fn sin_cos(self) -> (Self, Self)
+    where
+        Self: Sized;
+    fn asin(self) -> Self;
+    fn acos(self) -> Self;
+    fn atan(self) -> Self;
+    fn atan2(self, other: Self) -> Self;
+    fn sinh(self) -> Self;
+    fn cosh(self) -> Self;
+    fn tanh(self) -> Self;
+    fn asinh(self) -> Self;
+    fn acosh(self) -> Self;
+    fn atanh(self) -> Self;
+}
+
+impl Float for f64 {
+    fn abs(self) -> f64 {
+        libm::fabs(self)
+    }
+    fn signum(self) -> f64 {
+        if self.is_nan() {
+            f64::NAN
+        } else {
+            libm::copysign(1., self)
+        }
+    }
+    fn copysign(self, sign: f64) -> f64 {
+        libm::copysign(self, sign)
+    }
+    fn floor(self) -> f64 {
+        libm::floor(self)
+    }
+    fn ceil(self) -> f64 {
+        libm::ceil(self)
+    }
+    fn round(self) -> f64 {
+        libm::round(self)
+    }
+    fn trunc(self) -> f64 {
+        libm::trunc(self)
+    }
+    fn fract(self) -> f64 {
+        self - libm::trunc(self)
+    }
+    fn rem_euclid(self, rhs: f64) -> f64 {
+        let r = libm::fmod(self, rhs);
+        if r < 0. {
+            r + libm::fabs(rhs)
+        } else {
+            r
+        }
+    }
+    fn mul_add(self, a: f64, b: f64) -> f64 {
+        libm::fma(self, a, b)
+    }
+    fn sqrt(self) -> f64 {
+        libm::sqrt(self)
+    }
+    fn cbrt(self) -> f64 {
+        libm::cbrt(self)
+    }
+    fn hypot(self, other: f64) -> f64 {
+        libm::hypot(self, other)
+    }
+    fn powi(self, n: i32) -> f64 {
+        libm::pow(self, n as f64)
+    }
+    fn powf(self, n: f64) -> f64 {
+        libm::pow(self, n)
+    }
+    fn exp(self) -> f64 {
+        libm::exp(self)
+    }
+    fn exp_m1(self) -> f64 {
+        libm::expm1(self)
+    }
+    fn ln(self) -> f64 {
+        libm::log(self)
+    }
+    fn ln_1p(self) -> f64 {
+        libm::log1p(self)
+    }
+    fn log10(self) -> f64 {
+        libm::log10(self)
+    }
+    fn sin(self) -> f64 {
+        libm::sin(self)
+    }
+    fn cos(self) -> f64 {
+        libm::cos(self)
+    }
+    fn tan(self) -> f64 {
+        libm::tan(self)
+    }
+    fn sin_cos(self) -> (f64, f64) {
+        libm::sincos(self)
+    }
+    fn asin(self) -> f64 {
+        libm::asin(self)
+    }
+    fn acos(self) -> f64 {
+        libm::acos(self)
+    }
+    fn atan(self) -> f64 {
+        libm::atan(self)
+    }
+    fn atan2(self, other: f64) -> f64 {
+        libm::atan2(self, other)
+    }
+    fn sinh(self) -> f64 {
+        libm::sinh(self)
+    }
+    fn cosh(self) -> f64 {
+        libm::cosh(self)
+    }
+    fn tanh(self) -> f64 {
+        libm::tanh(self)
+    }
+    fn asinh(self) -> f64 {
+        libm::asinh(self)
+    }
+    fn acosh(self) -> f64 {
+        libm::acosh(self)
+    }
+    fn atanh(self) -> f64 {
+        libm::atanh(self)
+    }
+}
@@ -1,32 +1,61 @@
+// The floating point functions of std, when it is not available
+#[cfg(not(feature = "std"))]
+pub mod float;
+#[cfg(not(feature = "std"))]
+use float::Float;
+
+// Batch kernels for the operators
+pub(crate) mod simd;
+
 /// The order of the Fourier series used to compute e.g. auxiliary latitudes
 pub const POLYNOMIAL_ORDER: usize = 6;
 
 /// Two upper triangular matrices of polynomium coefficients for computing
-/// the Fourier coefficients for (a.o.) the auxiliary latitudes
-#[derive(Clone, Copy, Debug, Default)]
-pub struct PolynomialCoefficients {
-    pub fwd: [[f64; POLYNOMIAL_ORDER]; POLYNOMIAL_ORDER],
-    pub inv: [[f64; POLYNOMIAL_ORDER]; POLYNOMIAL_ORDER],
+/// the Fourier coefficients for (a.o.) the auxiliary latitudes. The order,
+/// `N`, is a compile time constant, so the series evaluations can be unrolled
+#[derive(Clone, Copy, Debug)]
+pub struct PolynomialCoefficients<const N: usize = POLYNOMIAL_ORDER> {
+    pub fwd: [[f64; N]; N],
+    pub inv: [[f64; N]; N],
+}
+
+impl<const N: usize> Default for PolynomialCoefficients<N> {
+    fn default() -> Self {
+        PolynomialCoefficients {
+            fwd: [[0.; N]; N],
+            inv: [[0.; N]; N],
+        }
+    }
 }
 
 /// The Fourier coefficients used when computing e.g. auxiliary latitudes
-#[derive(Clone, Copy, Debug, Default)]
-pub struct FourierCoefficients {
-    pub fwd: [f64; POLYNOMIAL_ORDER],
-    pub inv: [f64; POLYNOMIAL_ORDER],
+#[derive(Clone, Copy, Debug)]
+pub struct FourierCoefficients<const N: usize = POLYNOMIAL_ORDER> {
+    pub fwd: [f64; N],
+    pub inv: [f64; N],
     pub etc: [f64; 2],
 }
 
+impl<const N: usize> Default for FourierCoefficients<N> {
+    fn default() -> Self {
+        FourierCoefficients {
+            fwd: [0.; N],
+            inv: [0.; N],
+            etc: [0.; 2],
+        }
+    }
+}
+
 // --- Taylor series polynomium evaluation ----
 
 /// Compute Fourier coefficients by evaluating their corresponding
 /// Taylor polynomiums
-pub fn fourier_coefficients(
+pub fn fourier_coefficients<const N: usize>(
     arg: f64,
-    coefficients: &PolynomialCoefficients,
-) -> FourierCoefficients {
+    coefficients: &PolynomialCoefficients<N>,
+) -> FourierCoefficients<N> {
     let mut result = FourierCoefficients::default();
-    for i in 0..POLYNOMIAL_ORDER {
+    for i in 0..N {
         result.fwd[i] = arg * horner(arg, &coefficients.fwd[i]);
         result.inv[i] = arg * horner(arg, &coefficients.inv[i]);
     }
@@ -34,13 +63,12 @@ pub fn fourier_coefficients(
 }
 
 /// Evaluate Σ cᵢ · xⁱ using Horner's scheme
-pub fn horner(arg: f64, coefficients: &[f64]) -> f64 {
-    if coefficients.is_empty() {
+pub fn horner<const N: usize>(arg: f64, coefficients: &[f64; N]) -> f64 {
+    let Some((last, coefficients)) = coefficients.split_last() else {
         return 0.;
-    }
-    let mut coefficients = coefficients.iter().rev();
-    let mut value = *(coefficients.next().unwrap());
-    for c in coefficients {
+    };
+    let mut value = *last;
+    for c in coefficients.iter().rev() {
         value = value.mul_add(arg, *c);
     }
     value
@@ -49,7 +77,7 @@ pub fn horner(arg: f64, coefficients: &[f64]) -> f64 {
 // --- Fourier series summation using Clenshaw's recurrence ---
 
 /// Evaluate Σ cᵢ sin( i · arg ), for i ∈ {order, ... , 1}, using Clenshaw summation
-pub fn clenshaw_sin(arg: f64, coefficients: &[f64]) -> f64 {
+pub fn clenshaw_sin<const N: usize>(arg: f64, coefficients: &[f64; N]) -> f64 {
     let (sin_arg, cos_arg) = arg.sin_cos();
     let x = 2.0 * cos_arg;
     let mut c0 = 0.0;
@@ -62,7 +90,7 @@ pub fn clenshaw_sin(arg: f64, coefficients: &[f64]) -> f64 {
 }
 
 // Evaluate Σ cᵢ cos( i · arg ), for i ∈ {order, ... , 1}, using Clenshaw summation
-pub fn clenshaw_cos(arg: f64, coefficients: &[f64]) -> f64 {
+pub fn clenshaw_cos<const N: usize>(arg: f64, coefficients: &[f64; N]) -> f64 {
     let cos_arg = arg.cos();
     let x = 2.0 * cos_arg;
     let mut c0 = 0.0;
@@ -77,7 +105,7 @@ pub fn clenshaw_cos(arg: f64, coefficients: &[f64]) -> f64 {
 /// Evaluate Σ cᵢ Sin( i · arg ), for i ∈ {order, ... , 1}, using Clenshaw summation.
 /// i.e. a series of complex sines with real coefficients
 #[allow(unused_assignments)] // For symmetric initialization of hr2, hi2
-pub fn clenshaw_complex_sin(arg: [f64; 2], coefficients: &[f64]) -> [f64; 2] {
+pub fn clenshaw_complex_sin<const N: usize>(arg: [f64; 2], coefficients: &[f64; N]) -> [f64; 2] {
     // Prepare the trigonometric factors
     let (sin_r, cos_r) = arg[0].sin_cos();
     let sinh_i = arg[1].sinh();
@@ -121,7 +149,10 @@ pub fn clenshaw_complex_sin(arg: [f64; 2], coefficients: &[f64]) -> [f64; 2] {
 /// we assert that, despite that compiler heuristics may beg to differ, this function should
 /// always be inlined.
 #[inline(always)]
-pub fn clenshaw_sin_optimized_for_tmerc(trig: [f64; 2], coefficients: &[f64]) -> f64 {
+pub fn clenshaw_sin_optimized_for_tmerc<const N: usize>(
+    trig: [f64; 2],
+    coefficients: &[f64; N],
+) -> f64 {
     // Unpack the trigonometric factors for better readability.
     let (sin_arg, cos_arg) = (trig[0], trig[1]);
     let x = 2.0 * cos_arg;
@@ -145,10 +176,10 @@ pub fn clenshaw_sin_optimized_for_tmerc(trig: [f64; 2], coefficients: &[f64]) ->
 /// always be inlined.
 #[allow(unused_assignments)] // For symmetric initialization of hr2, hi2
 #[inline(always)]
-pub fn clenshaw_complex_sin_optimized_for_tmerc(
+pub fn clenshaw_complex_sin_optimized_for_tmerc<const N: usize>(
     trig: [f64; 2],
     hyp: [f64; 2],
-    coefficients: &[f64],
+    coefficients: &[f64; N],
 ) -> [f64; 2] {
     // Unpack the trigonometric and hyperbolic factors for better readability.
     let (sin_r, cos_r) = (trig[0], trig[1]);
@@ -197,14 +228,14 @@ pub fn inverse_gudermannian(arg: f64) -> f64 {
 
 /// normalize arbitrary angles to [-π, π):
 pub fn normalize_angle_symmetric(angle: f64) -> f64 {
-    use std::f64::consts::PI;
+    use core::f64::consts::PI;
     let angle = (angle + PI) % (2.0 * PI);
     angle - PI * angle.signum()
 }
 
 /// normalize arbitrary angles to [0, 2π):
 pub fn normalize_angle_positive(angle: f64) -> f64 {
-    use std::f64::consts::PI;
+    use core::f64::consts::PI;
     let angle = angle % (2.0 * PI);
     if angle < 0. {
         return angle + 2.0 * PI;
@@ -241,6 +272,8 @@ pub fn normalize_angle_positive(angle: f64) -> f64 {
 //
 // where 𝜒 is the conformal latitude
 //
+// Used by the operators only, i.e. not in the no_std configuration
+#[cfg_attr(not(feature = "std"), allow(dead_code))]
 pub(crate) fn ts(sincos: (f64, f64), e: f64) -> f64 {
     // exp(-asinh(tan 𝜙))
     //    = 1 / (tan 𝜙 + sec 𝜙)
@@ -255,16 +288,19 @@ pub(crate) fn ts(sincos: (f64, f64), e: f64) -> f64 {
 }
 
 // Snyder (1982) eq. 12-15, PROJ's pj_msfn()
+#[cfg_attr(not(feature = "std"), allow(dead_code))]
 pub(crate) fn pj_msfn(sincos: (f64, f64), es: f64) -> f64 {
     sincos.1 / (1. - sincos.0 * sincos.0 * es).sqrt()
 }
 
 // Equivalent to the PROJ pj_phi2 function
+#[cfg_attr(not(feature = "std"), allow(dead_code))]
 pub(crate) fn pj_phi2(ts0: f64, e: f64) -> f64 {
     sinhpsi_to_tanphi((1. / ts0 - ts0) / 2., e).atan()
 }
 
 // Snyder (1982) eq. ??, PROJ's pj_qsfn()
+#[cfg_attr(not(feature = "std"), allow(dead_code))]
 pub(crate) fn qs(sinphi: f64, e: f64) -> f64 {
     let es = e * e;
     let one_es = 1.0 - es;
@@ -356,6 +392,18 @@ mod tests {
         let expected = 0.9983242984230415;
         assert!((result - expected).abs() < 1e-14);
 
+        // Fourier coefficients of orders other than the default
+        let polynomials = PolynomialCoefficients {
+            fwd: [[1., 2.], [0., 3.]],
+            inv: [[1., 0.], [0., 1.]],
+        };
+        let fourier = fourier_coefficients(0.5, &polynomials);
+        assert_eq!(fourier.fwd, [1., 0.75]);
+        assert_eq!(fourier.inv, [0.5, 0.25]);
+        assert!(fourier_coefficients::<0>(0.5, &Default::default())
+            .fwd
+            .is_empty());
+
         Ok(())
     }
 
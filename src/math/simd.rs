@@ -0,0 +1,91 @@
+//! Batches of coordinates: Coordinates are processed `LANES` at a time,
+//! transposed into one array of lanes per coordinate element, so the
+//! arithmetic of a kernel runs over contiguous arrays, without branching on
+//! the individual coordinate. This is a layout for the compiler to optimize,
+//! not explicit vectorization: Whether, and how much, the kernels are
+//! vectorized is up to the optimizer.
+//!
+//! The trigonometric functions are those of the scalar code, applied lane by
+//! lane, so the batched computations give the same results as their scalar
+//! counterparts, given the same sequence of operations.
+
+use crate::internal::*;
+
+pub(crate) const LANES: usize = 4;
+
+/// One coordinate element, e.g. the latitude, for each of the coordinates of a batch
+pub(crate) type Lanes = [f64; LANES];
+
+/// A batch of coordinates, transposed: `batch[element][lane]`
+pub(crate) type Batch = [Lanes; 4];
+
+/// A computation on a batch of coordinates
+pub(crate) trait Kernel {
+    fn apply(&self, batch: &mut Batch);
+}
+
+/// Apply `kernel` to `operands`, in batches. The last batch is padded with
+/// zeros, so all coordinates are handled by the same kernel
+pub(crate) fn batched<K: Kernel>(operands: &mut [Coord], kernel: &K) {
+    for chunk in operands.chunks_mut(LANES) {
+        let mut batch = [[0.; LANES]; 4];
+        for (lane, coord) in chunk.iter().enumerate() {
+            for element in 0..4 {
+                batch[element][lane] = coord[element];
+            }
+        }
+        kernel.apply(&mut batch);
+        for (lane, coord) in chunk.iter_mut().enumerate() {
+            for element in 0..4 {
+                coord[element] = batch[element][lane];
+            }
+        }
+    }
+}
+
+// ----- T R I G O N O M E T R Y ----------------------------------------------------
+
+/// Sine and cosine of each lane
+#[inline(always)]
+pub(crate) fn sin_cos(x: Lanes) -> (Lanes, Lanes) {
+    let mut sin = [0.; LANES];
+    let mut cos = [0.; LANES];
+    for i in 0..LANES {
+        (sin[i], cos[i]) = x[i].sin_cos();
+    }
+    (sin, cos)
+}
+
+/// The four quadrant arctangent of `y / x`, for each lane
+#[inline(always)]
+pub(crate) fn atan2(y: Lanes, x: Lanes) -> Lanes {
+    let mut result = [0.; LANES];
+    for i in 0..LANES {
+        result[i] = y[i].atan2(x[i]);
+    }
+    result
+}
+
+// ----- T E S T S ------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn batches() {
+        let mut operands: Vec<Coord> = (0..7).map(|i| Coord::raw(i as f64, 1., 2., 3.)).collect();
+        struct AddTime;
+        impl Kernel for AddTime {
+            fn apply(&self, batch: &mut Batch) {
+                let [x, _, _, t] = batch;
+                for (x, t) in x.iter_mut().zip(t.iter()) {
+                    *x += t;
+                }
+            }
+        }
+        batched(&mut operands, &AddTime);
+        assert_eq!(operands[6], Coord::raw(9., 1., 2., 3.));
+        assert_eq!(operands[0], Coord::raw(3., 1., 2., 3.));
+    }
+}
@@ -0,0 +1,314 @@
+// The `Pipeline` builder: Assembling operators from structured descriptions,
+// rather than from definition strings. Each step is instantiated directly
+// from its parameters, by the constructor registered under its name, and the
+// steps combined into a pipeline, without going through the pipeline parser.
+// Real values are carried over losslessly, since the shortest representation
+// reproducing a floating point value is used
+
+use super::*;
+
+/// A step of a [`Pipeline`]: An operator (or a macro) given by name, and its
+/// parameters, e.g. `Step::new("utm").natural("zone", 32).text("ellps", "intl")`.
+/// For the most common operators, the typed builders, e.g. [`Utm`], are more
+/// convenient.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Step {
+    name: String,
+    // Flags have no value
+    parameters: Vec<(String, Option<String>)>,
+}
+
+impl Step {
+    pub fn new(name: &str) -> Step {
+        Step {
+            name: name.to_string(),
+            parameters: Vec::new(),
+        }
+    }
+
+    pub fn real(self, key: &str, value: f64) -> Step {
+        self.parameter(key, value.to_string())
+    }
+
+    pub fn natural(self, key: &str, value: usize) -> Step {
+        self.parameter(key, value.to_string())
+    }
+
+    pub fn integer(self, key: &str, value: i64) -> Step {
+        self.parameter(key, value.to_string())
+    }
+
+    pub fn series(self, key: &str, values: &[f64]) -> Step {
+        let values: Vec<_> = values.iter().map(f64::to_string).collect();
+        self.parameter(key, values.join(","))
+    }
+
+    pub fn text(self, key: &str, value: &str) -> Step {
+        self.parameter(key, value.to_string())
+    }
+
+    pub fn flag(mut self, key: &str) -> Step {
+        self.parameters.retain(|(k, _)| k != key);
+        self.parameters.push((key.to_string(), None));
+        self
+    }
+
+    pub fn inv(self) -> Step {
+        self.flag("inv")
+    }
+
+    // A later value for the same key replaces the earlier one
+    fn parameter(mut self, key: &str, value: String) -> Step {
+        self.parameters.retain(|(k, _)| k != key);
+        self.parameters.push((key.to_string(), Some(value)));
+        self
+    }
+
+    /// Instantiate the step as an operator on its own
+    pub fn build(&self, ctx: &dyn Context) -> Result<Op, Error> {
+        // Values given as text are not parsed any further, so they must not
+        // carry any syntax of the definition language
+        let syntax = |c: char| c.is_whitespace() || "|=()#".contains(c);
+        for (key, value) in &self.parameters {
+            let Some(value) = value else { continue };
+            if value.is_empty() || value.contains(syntax) {
+                return Err(Error::BadParam(key.clone(), value.clone()));
+            }
+        }
+        if self.name.is_empty() || self.name.contains(syntax) {
+            return Err(Error::BadParam("name".to_string(), self.name.clone()));
+        }
+        let parameters = RawParameters::new(&self.to_string(), &ctx.globals());
+
+        // User defined operators take precedence over the built in ones, as
+        // in `Op::op`, which also handles macros, and aliases
+        if !is_resource_name(&self.name) {
+            let constructor = ctx
+                .get_op(&self.name)
+                .or_else(|_| crate::inner_op::builtin(&self.name));
+            if let Ok(constructor) = constructor {
+                return constructor.0(&parameters, ctx)?.handle_op_inversion();
+            }
+        }
+        Op::op(parameters, ctx)
+    }
+}
+
+// The equivalent definition, e.g. `utm zone=32 ellps=intl`
+impl std::fmt::Display for Step {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name)?;
+        for (key, value) in &self.parameters {
+            match value {
+                Some(value) => write!(f, " {key}={value}")?,
+                None => write!(f, " {key}")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A builder for pipelines, e.g.
+/// `Pipeline::new().step(Adapt::builder().from("neuf_deg")).step(Utm::builder().zone(32))`,
+/// for applications generating pipelines from structured configuration, rather
+/// than by formatting definitions
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Pipeline {
+    steps: Vec<Step>,
+}
+
+impl Pipeline {
+    pub fn new() -> Pipeline {
+        Pipeline::default()
+    }
+
+    pub fn step(mut self, step: impl Into<Step>) -> Pipeline {
+        self.steps.push(step.into());
+        self
+    }
+
+    /// Instantiate the pipeline, for use through [`Op::apply`]
+    pub fn build(&self, ctx: &dyn Context) -> Result<Op, Error> {
+        if self.steps.is_empty() {
+            return Err(Error::General("Pipeline: No steps given".to_string()));
+        }
+        let mut steps = Vec::with_capacity(self.steps.len());
+        for (index, step) in self.steps.iter().enumerate() {
+            let op = step
+                .build(ctx)
+                .map_err(|e| e.context(format!("step {} ({step})", index + 1)))?;
+            steps.push(op);
+        }
+        crate::inner_op::pipeline::from_steps(&self.to_string(), steps)
+    }
+}
+
+// The equivalent definition, e.g. `adapt from=neuf_deg | utm zone=32`
+impl std::fmt::Display for Pipeline {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let steps: Vec<_> = self.steps.iter().map(Step::to_string).collect();
+        write!(f, "{}", steps.join(" | "))
+    }
+}
+
+// ----- T Y P E D   S T E P S ------------------------------------------------------
+
+// The typed builders of the most common operators, each with a setter per
+// parameter, and the `inv` flag
+macro_rules! typed_steps {
+    ($($(#[$doc:meta])* $builder:ident $name:literal { $($kind:ident $key:ident),* $(,)? })*) => {$(
+        $(#[$doc])*
+        #[derive(Debug, Clone, PartialEq)]
+        pub struct $builder(Step);
+
+        impl $builder {
+            pub fn builder() -> $builder {
+                $builder(Step::new($name))
+            }
+
+            pub fn inv(self) -> $builder {
+                $builder(self.0.inv())
+            }
+
+            $(typed_steps!(@setter $kind $key);)*
+        }
+
+        impl From<$builder> for Step {
+            fn from(builder: $builder) -> Step {
+                builder.0
+            }
+        }
+    )*};
+
+    (@setter real $key:ident) => {
+        pub fn $key(self, value: f64) -> Self {
+            Self(self.0.real(stringify!($key), value))
+        }
+    };
+    (@setter natural $key:ident) => {
+        pub fn $key(self, value: usize) -> Self {
+            Self(self.0.natural(stringify!($key), value))
+        }
+    };
+    (@setter text $key:ident) => {
+        pub fn $key(self, value: &str) -> Self {
+            Self(self.0.text(stringify!($key), value))
+        }
+    };
+    (@setter flag $key:ident) => {
+        pub fn $key(self) -> Self {
+            Self(self.0.flag(stringify!($key)))
+        }
+    };
+}
+
+typed_steps! {
+    /// Builder for the `adapt` operator, cf. [`Pipeline`]
+    Adapt "adapt" { text from, text to }
+    /// Builder for the `cart` operator, cf. [`Pipeline`]
+    Cart "cart" { text ellps }
+    /// Builder for the `gridshift` operator, cf. [`Pipeline`]
+    Gridshift "gridshift" { text grids, real padding }
+    /// Builder for the `helmert` operator, cf. [`Pipeline`]
+    Helmert "helmert" {
+        real x, real y, real z, real rx, real ry, real rz, real s,
+        real dx, real dy, real dz, real drx, real dry, real drz, real ds,
+        real t_epoch, real t_obs, text convention, flag exact,
+    }
+    /// Builder for the `laea` operator, cf. [`Pipeline`]
+    Laea "laea" { real lat_0, real lon_0, real x_0, real y_0, text ellps }
+    /// Builder for the `lcc` operator, cf. [`Pipeline`]
+    Lcc "lcc" {
+        real lat_1, real lat_2, real lat_0, real lon_0, real k_0, real x_0, real y_0,
+        text ellps,
+    }
+    /// Builder for the `merc` operator, cf. [`Pipeline`]
+    Merc "merc" { real lat_ts, real lon_0, real k_0, real x_0, real y_0, text ellps }
+    /// Builder for the `tmerc` operator, cf. [`Pipeline`]
+    Tmerc "tmerc" { real lat_0, real lon_0, real k_0, real x_0, real y_0, text ellps }
+    /// Builder for the `utm` operator, cf. [`Pipeline`]
+    Utm "utm" { natural zone, text ellps }
+}
+
+// ----- T E S T S ------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builder() -> Result<(), Error> {
+        let mut ctx = Minimal::new();
+        let pipeline = Pipeline::new()
+            .step(Adapt::builder().from("neuf_deg"))
+            .step(Tmerc::builder().lon_0(9.).k_0(0.9996).x_0(500000.));
+        assert_eq!(
+            pipeline.to_string(),
+            "adapt from=neuf_deg | tmerc lon_0=9 k_0=0.9996 x_0=500000"
+        );
+        let op = pipeline.build(&ctx)?;
+        let reference = ctx.op("geo:in | utm zone=32")?;
+
+        let mut data = [Coord::raw(55., 12., 0., 0.)];
+        let mut expected = data;
+        op.apply(&ctx, &mut data, Fwd)?;
+        ctx.apply(reference, Fwd, &mut expected)?;
+        assert!(data[0].hypot2(&expected[0]) < 1e-9);
+        op.apply(&ctx, &mut data, Inv)?;
+        assert!((data[0][0] - 55.).abs() < 1e-12);
+        assert_eq!(op.steps().len(), 2);
+
+        // Untyped steps, macros, and inverted steps
+        let op = Pipeline::new()
+            .step(Step::new("geo:in"))
+            .step(Step::new("utm").natural("zone", 32))
+            .step(Utm::builder().zone(32).inv())
+            .step(Step::new("geo:out"))
+            .build(&ctx)?;
+        let mut data = [Coord::raw(55., 12., 0., 0.)];
+        op.apply(&ctx, &mut data, Fwd)?;
+        assert!(data[0].hypot2(&Coord::raw(55., 12., 0., 0.)) < 1e-9);
+
+        // Real values are carried over losslessly
+        let op = Pipeline::new()
+            .step(Step::new("ed50:etrs89").inv())
+            .step(Step::new("ed50:etrs89"))
+            .build(&ctx)?;
+        let mut data = [Coord::raw(55., 12., 0., 0.)];
+        op.apply(&ctx, &mut data, Fwd)?;
+        assert!(data[0].hypot2(&Coord::raw(55., 12., 0., 0.)) < 1e-9);
+        assert!(op.steps[0].descriptor.inverted);
+
+        // User defined operators, and aliases
+        ctx.register_op("cartesian", crate::inner_op::builtin("cart")?);
+        let op = Step::new("cartesian").text("ellps", "intl").build(&ctx)?;
+        assert_eq!(*op.params.ellps(0), Ellipsoid::named("intl")?);
+        let op = Step::new("itrf2014_to_itrf2008").build(&ctx)?;
+        let reference = Op::new("itrf2014_to_itrf2008", &ctx)?;
+        let mut data = [Coord::raw(3513649.6, 778954.5, 5248201.6, 2020.)];
+        let mut expected = data;
+        op.apply(&ctx, &mut data, Fwd)?;
+        reference.apply(&ctx, &mut expected, Fwd)?;
+        assert_ne!(data, [Coord::raw(3513649.6, 778954.5, 5248201.6, 2020.)]);
+        assert_eq!(data, expected);
+
+        let x = 0.1 + 0.2;
+        let op = Pipeline::new().step(Helmert::builder().x(x)).build(&ctx)?;
+        assert_eq!(op.steps[0].params.real("x")?, x);
+
+        // Errors are given the step as context, and values carrying syntax
+        // are rejected
+        let Err(Error::Context { context, .. }) = Pipeline::new()
+            .step(Cart::builder())
+            .step(Cart::builder().ellps("unknown"))
+            .build(&ctx)
+        else {
+            panic!("Expected an error with context");
+        };
+        assert_eq!(context, "step 2 (cart ellps=unknown)");
+        let step = Step::new("cart").text("ellps", "GRS80 | addone");
+        assert!(matches!(step.build(&ctx), Err(Error::BadParam(..))));
+        assert!(Pipeline::new().build(&ctx).is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,157 @@
+use super::*;
+
+/// The area of use of an operator, as a bounding box in geographical
+/// coordinates, in degrees. The box may straddle the antimeridian, in which
+/// case `west` is larger than `east`, following the EPSG convention
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AreaOfUse {
+    pub west: f64,
+    pub south: f64,
+    pub east: f64,
+    pub north: f64,
+}
+
+impl AreaOfUse {
+    pub fn new(west: f64, south: f64, east: f64, north: f64) -> AreaOfUse {
+        AreaOfUse {
+            west,
+            south,
+            east,
+            north,
+        }
+    }
+
+    /// Is the point given by `longitude` and `latitude` (in degrees) inside the area?
+    pub fn contains(&self, longitude: f64, latitude: f64) -> bool {
+        if latitude < self.south || latitude > self.north {
+            return false;
+        }
+        if self.west <= self.east {
+            return self.west <= longitude && longitude <= self.east;
+        }
+        longitude >= self.west || longitude <= self.east
+    }
+}
+
+// e.g. `-10.56,34.88,32.88,84.73`, i.e. west, south, east, north, the same way
+// it is given in the `#@ area_of_use=...` line of a macro definition
+impl std::fmt::Display for AreaOfUse {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let AreaOfUse {
+            west,
+            south,
+            east,
+            north,
+        } = self;
+        write!(f, "{west},{south},{east},{north}")
+    }
+}
+
+/// The area of use and accuracy of an operator, as given by the metadata lines
+/// (prefixed by `#@`) of the macro defining it, cf. [`Op::area_of_use`] and
+/// [`Op::accuracy`]
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Metadata {
+    pub area_of_use: Option<AreaOfUse>,
+    /// The accuracy of the operator, in metres
+    pub accuracy: Option<f64>,
+}
+
+/// What to do when input coordinates fall outside of the area of use of an
+/// operator: Nothing (the default), log a warning, or fail. Selected by the
+/// `area_check` parameter of a macro invocation, or the context global of the
+/// same name
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum AreaCheck {
+    #[default]
+    Ignore,
+    Warn,
+    Error,
+}
+
+impl AreaCheck {
+    pub fn named(name: &str) -> Result<AreaCheck, Error> {
+        match name {
+            "ignore" => Ok(AreaCheck::Ignore),
+            "warn" => Ok(AreaCheck::Warn),
+            "error" => Ok(AreaCheck::Error),
+            _ => Err(Error::BadParam("area_check".to_string(), name.to_string())),
+        }
+    }
+}
+
+// The metadata lines of a macro definition, e.g.
+//     #@ area_of_use=-10.56,34.88,32.88,84.73 accuracy=10
+pub(crate) fn metadata(definition: &str) -> Result<Metadata, Error> {
+    let mut metadata = Metadata::default();
+    let items = definition
+        .lines()
+        .filter_map(|line| line.trim().strip_prefix("#@"))
+        .flat_map(str::split_whitespace);
+    for item in items {
+        let bad = || Error::BadParam("metadata".to_string(), item.to_string());
+        let (key, value) = item.split_once('=').ok_or_else(bad)?;
+        match key {
+            "area_of_use" => {
+                let bounds: Vec<f64> = value
+                    .split(',')
+                    .map(|v| v.trim().parse::<f64>().map_err(|_| bad()))
+                    .collect::<Result<_, _>>()?;
+                let [west, south, east, north] = bounds[..] else {
+                    return Err(bad());
+                };
+                if south > north {
+                    return Err(bad());
+                }
+                metadata.area_of_use = Some(AreaOfUse::new(west, south, east, north));
+            }
+            "accuracy" => metadata.accuracy = Some(value.parse().map_err(|_| bad())?),
+            _ => return Err(bad()),
+        }
+    }
+    Ok(metadata)
+}
+
+// ----- T E S T S ------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn area_of_use() -> Result<(), Error> {
+        let definition = "## A docstring
+            #@ area_of_use=-10,35,30,72 accuracy=10
+            geo:in | cart | helmert x=1 | cart inv | geo:out";
+        let meta = metadata(definition)?;
+        let area = meta.area_of_use.unwrap();
+        assert_eq!(area, AreaOfUse::new(-10., 35., 30., 72.));
+        assert_eq!(area.to_string(), "-10,35,30,72");
+        assert_eq!(meta.accuracy, Some(10.));
+        assert!(area.contains(12., 55.));
+        assert!(!area.contains(12., 75.));
+        assert!(!area.contains(-12., 55.));
+
+        // Straddling the antimeridian, e.g. New Zealand and its islands
+        let area = AreaOfUse::new(160.6, -55.95, -171.2, -25.88);
+        assert!(area.contains(175., -41.));
+        assert!(area.contains(-175., -41.));
+        assert!(!area.contains(0., -41.));
+
+        // No metadata, and malformed metadata
+        assert_eq!(metadata("cart | helmert x=1")?, Metadata::default());
+        for bad in [
+            "area_of_use=1,2,3",
+            "area_of_use=1,50,3,40",
+            "accuracy=good",
+            "age=7",
+        ] {
+            assert!(matches!(
+                metadata(&format!("#@ {bad}\n cart")),
+                Err(Error::BadParam(..))
+            ));
+        }
+        assert!(AreaCheck::named("complain").is_err());
+        Ok(())
+    }
+}
@@ -1,13 +1,23 @@
 use super::internal::*;
 
+mod builder;
+mod metadata;
 mod op_descriptor;
 mod parameter;
 mod parsed_parameters;
+mod provenance;
 mod raw_parameters;
 
+pub use builder::{Adapt, Cart, Gridshift, Helmert, Laea, Lcc, Merc, Tmerc, Utm};
+pub use builder::{Pipeline, Step};
+use metadata::metadata;
+pub use metadata::{AreaCheck, AreaOfUse, Metadata};
 pub use op_descriptor::OpDescriptor;
 pub use parameter::OpParameter;
+pub(crate) use parsed_parameters::parse_real;
 pub use parsed_parameters::ParsedParameters;
+pub(crate) use parsed_parameters::{DERIVED_SPHERES, INLINE_ELLIPSOID};
+pub use provenance::{Provenance, ResourceKind};
 pub use raw_parameters::RawParameters;
 
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Copy, Clone)]
@@ -23,6 +33,11 @@ impl Default for OpHandle {
     }
 }
 
+/// Handler for the intermediate results of [`Op::apply_traced`]: Called with
+/// each step, the direction it has been applied in, and the operands as they
+/// are after applying it.
+pub type Tracer<'a> = dyn FnMut(&Op, Direction, &[Coord]) + 'a;
+
 /// The defining parameters and functions for an operator
 #[derive(Debug)]
 pub struct Op {
@@ -34,7 +49,56 @@ pub struct Op {
 
 impl Op {
     // operate fwd/inv, taking operator inversion into account.
-    pub fn apply(
+    pub fn apply<S: CoordinateSet + ?Sized>(
+        &self,
+        ctx: &dyn Context,
+        operands: &mut S,
+        direction: Direction,
+    ) -> Result<usize, Error> {
+        if let Some(operands) = operands.as_mut_slice() {
+            return self.apply_slice(ctx, operands, direction);
+        }
+
+        // Otherwise, go through a buffer, chunk by chunk
+        const CHUNK_SIZE: usize = 64;
+        let mut buffer = [Coord::default(); CHUNK_SIZE];
+        let mut successes = 0;
+        for start in (0..operands.len()).step_by(CHUNK_SIZE) {
+            let chunk = &mut buffer[..CHUNK_SIZE.min(operands.len() - start)];
+            operands.get_coords(start, chunk);
+            successes += self.apply_slice(ctx, chunk, direction)?;
+            operands.set_coords(start, chunk);
+        }
+        Ok(successes)
+    }
+
+    /// Apply `self` to `operands`, like [`Op::apply`], but handing the
+    /// intermediate results after each step over to `tracer`, for debugging
+    /// purposes. Nested pipelines and macros are traced step by step, while a
+    /// non-pipeline operator is traced as a single step.
+    pub fn apply_traced(
+        &self,
+        ctx: &dyn Context,
+        operands: &mut [Coord],
+        direction: Direction,
+        tracer: &mut Tracer,
+    ) -> Result<usize, Error> {
+        if self.steps.is_empty() {
+            let n = self.apply_slice(ctx, operands, direction)?;
+            tracer(self, direction, operands);
+            return Ok(n);
+        }
+
+        // The direction the steps are actually applied in
+        let direction = match (direction, self.descriptor.inverted) {
+            (Direction::Fwd, true) => Direction::Inv,
+            (Direction::Inv, true) => Direction::Fwd,
+            (direction, false) => direction,
+        };
+        super::inner_op::pipeline::run(self, ctx, operands, direction, Some(tracer))
+    }
+
+    fn apply_slice(
         &self,
         ctx: &dyn Context,
         operands: &mut [Coord],
@@ -43,15 +107,65 @@ impl Op {
         let forward = direction == Direction::Fwd;
         // Short form of (inverted && !forward) || (forward && !inverted)
         if self.descriptor.inverted != forward {
+            self.check_area_of_use(operands)?;
             return self.descriptor.fwd.0(self, ctx, operands);
         }
-        self.descriptor.inv.0(self, ctx, operands)
+        // In the inverse case, the output is what must be inside the area of
+        // use, so when failing, we restore the input, as in the forward case
+        let checked = self.descriptor.area_check == AreaCheck::Error
+            && self.descriptor.metadata.area_of_use.is_some();
+        let input = checked.then(|| operands.to_vec());
+        let successes = self.descriptor.inv.0(self, ctx, operands)?;
+        if let Err(error) = self.check_area_of_use(operands) {
+            if let Some(input) = input {
+                operands.copy_from_slice(&input);
+            }
+            return Err(error);
+        }
+        Ok(successes)
+    }
+
+    // The coordinates on the forward input side of a macro are taken as
+    // geographical coordinates in degrees, in the EPSG axis order (latitude,
+    // longitude), as is the convention for the built in macros. Coordinates
+    // already failed are not counted
+    fn check_area_of_use(&self, operands: &[Coord]) -> Result<(), Error> {
+        let Some(area) = self.descriptor.metadata.area_of_use else {
+            return Ok(());
+        };
+        if self.descriptor.area_check == AreaCheck::Ignore {
+            return Ok(());
+        }
+        let outside = operands
+            .iter()
+            .filter(|c| c[0].is_finite() && c[1].is_finite() && !area.contains(c[1], c[0]))
+            .count();
+        if outside == 0 {
+            return Ok(());
+        }
+
+        let name = self.descriptor.invocation.split_whitespace().next();
+        let name = name.unwrap_or(&self.params.name).to_string();
+        let message = format!(
+            "{outside} of {} coordinates outside of the area of use ({area})",
+            operands.len()
+        );
+        if self.descriptor.area_check == AreaCheck::Warn {
+            warn!("{name}: {message}");
+            return Ok(());
+        }
+        Err(Error::Operator(name, message))
     }
 
     pub fn new(definition: &str, ctx: &dyn Context) -> Result<Op, Error> {
         let globals = ctx.globals();
         let parameters = RawParameters::new(definition, &globals);
-        Self::op(parameters, ctx)
+        let op = Self::op(parameters, ctx)?;
+
+        // A default angular unit applies at the boundaries of the operator as a
+        // whole, i.e. only here, at the top level
+        let parameters = RawParameters::new("", &globals);
+        super::inner_op::pipeline::default_angular_units(op, &parameters, ctx)
     }
 
     // Helper for implementation of `InnerOp`s: Instantiate an `Op` for the simple
@@ -79,6 +193,28 @@ impl Op {
         })
     }
 
+    // Helper for implementation of `InnerOp`s lacking an analytical inverse: As
+    // `Op::plain`, but with the inverse synthesized by iteration of `fwd`
+    pub fn iterative(
+        parameters: &RawParameters,
+        fwd: InnerOp,
+        gamut: &[OpParameter],
+        _ctx: &dyn Context,
+    ) -> Result<Op, Error> {
+        let def = parameters.definition.as_str();
+        let params = ParsedParameters::new(parameters, gamut)?;
+        let descriptor = OpDescriptor::with_iterative_inverse(def, fwd);
+        let steps = Vec::<Op>::new();
+        let id = OpHandle::new();
+
+        Ok(Op {
+            descriptor,
+            params,
+            steps,
+            id,
+        })
+    }
+
     // Instantiate the actual operator, taking into account the relative order
     // of precendence between pipelines, user defined operators, macros, and
     // built-in operators
@@ -95,6 +231,24 @@ impl Op {
 
         // A pipeline?
         if is_pipeline(&parameters.definition) {
+            // A parenthesized sub-pipeline, possibly inverted or omitted as a whole?
+            if let Some((inner, flags)) = sub_pipeline(&parameters.definition)? {
+                let mut op = Op::op(parameters.next(&inner), ctx)?;
+                let mut inverted = false;
+                for flag in flags.split_whitespace() {
+                    match flag.strip_suffix("=true").unwrap_or(flag) {
+                        "inv" => inverted = true,
+                        "omit_fwd" => _ = op.params.boolean.insert("omit_fwd"),
+                        "omit_inv" => _ = op.params.boolean.insert("omit_inv"),
+                        _ => {
+                            return Err(Error::Syntax(format!(
+                                "Unexpected '{flag}' following the sub-pipeline '({inner})'"
+                            )))
+                        }
+                    }
+                }
+                return op.handle_inversion(inverted);
+            }
             return super::inner_op::pipeline::new(&parameters, ctx);
         }
 
@@ -104,16 +258,86 @@ impl Op {
                 return constructor.0(&parameters, ctx)?.handle_op_inversion();
             }
         }
-        // A user defined macro?
-        else if let Ok(macro_definition) = ctx.get_resource(&name) {
+        // A user defined macro, or an EPSG code from the embedded subset? Other
+        // errors than the resource not being found, e.g. a malformed resource
+        // file, are reported as is
+        else if let Some(macro_definition) = match ctx.get_resource(&name) {
+            Ok(definition) => Some(definition),
+            Err(Error::NotFound(_, _)) => crate::context::epsg_definition(&name),
+            Err(e) => return Err(e),
+        } {
             // search for whitespace-delimited "inv" in order to avoid matching
             // tokens *containing* inv (INVariant, subINVolution, and a few other
             // pathological cases)
             let def = &parameters.definition;
             let inverted = def.contains(" inv ") || def.ends_with(" inv");
             let mut next_param = parameters.next(def);
+            let provenance =
+                Provenance::new(ResourceKind::Macro, &name, macro_definition.as_bytes());
+
+            // The placeholders declared by the macro must be given, by this or an
+            // outer macro invocation, unless defaulted. Defaults take precedence
+            // over the context globals
+            let invocation = split_into_parameters(def);
+            for (key, default) in placeholders(&macro_definition) {
+                if next_param.given.contains(&key) {
+                    continue;
+                }
+                match default {
+                    Some(default) => {
+                        next_param.globals.insert(key.clone(), default);
+                        next_param.given.insert(key);
+                    }
+                    None if next_param.globals.contains_key(&key) => continue,
+                    None => {
+                        return Err(Error::MissingParam(format!(
+                            "{key} (required by the macro {name})"
+                        )))
+                    }
+                }
+            }
+            // Whether to check the input coordinates against the area of use
+            let area_check = invocation
+                .get("area_check")
+                .or(next_param.globals.get("area_check"))
+                .map(|check| AreaCheck::named(check))
+                .transpose()?
+                .unwrap_or_default();
+            let macro_metadata = metadata(&macro_definition)?;
+
+            // Syntax errors located in the macro definition are reported as such
             next_param.definition = macro_definition;
-            return Op::op(next_param, ctx)?.handle_inversion(inverted);
+            let mut op = Op::op(next_param, ctx).map_err(|e| match e {
+                Error::SyntaxAt {
+                    message,
+                    line,
+                    column,
+                    token,
+                } => Error::SyntaxAt {
+                    message: format!("{message}, in the macro {name}"),
+                    line,
+                    column,
+                    token,
+                },
+                e => e,
+            })?;
+            op.descriptor.provenance.insert(0, provenance);
+
+            // The metadata of the macro take precedence over those of its
+            // definition, in case that is a single invocation of another macro
+            let own = &mut op.descriptor.metadata;
+            own.area_of_use = macro_metadata.area_of_use.or(own.area_of_use);
+            own.accuracy = macro_metadata.accuracy.or(own.accuracy);
+            op.descriptor.area_check = area_check;
+            op.descriptor.invocation = def.to_string();
+
+            // The omission flags apply to the macro as a whole
+            for flag in ["omit_fwd", "omit_inv"] {
+                if invocation.get(flag).is_some_and(|value| value == "true") {
+                    op.params.boolean.insert(flag);
+                }
+            }
+            return op.handle_inversion(inverted);
         }
 
         // A built in operator?
@@ -121,12 +345,253 @@ impl Op {
             return constructor.0(&parameters, ctx)?.handle_op_inversion();
         }
 
+        // An ITRF macro, by its `itrfXXXX_to_itrfYYYY` alias?
+        if let Some(macro_name) = crate::context::itrf_macro_name(&name) {
+            let definition = parameters.definition.replacen(&name, macro_name, 1);
+            return Op::op(parameters.next(&definition), ctx);
+        }
+
         Err(Error::NotFound(
             name,
             ": ".to_string() + &parameters.definition,
         ))
     }
 
+    /// The composite of `self`, followed by `other`, i.e. a pipeline of the
+    /// two, for combining already instantiated operators without going
+    /// through their definitions. The inversion of each is retained.
+    pub fn then(self, other: Op) -> Result<Op, Error> {
+        let definition = format!("{} | {}", self.definition(), other.definition());
+        super::inner_op::pipeline::from_steps(&definition, vec![self, other])
+    }
+
+    /// The operator, with the directions flipped, i.e. the forward direction of
+    /// the result is the inverse direction of `self`, and vice versa
+    pub fn inverted(self) -> Result<Op, Error> {
+        self.handle_inversion(true)
+    }
+
+    /// An equivalent PROJ definition, e.g. `+proj=utm +zone=32 +ellps=GRS80`, or
+    /// `+proj=pipeline +step ...`, for operators (and macros made of operators)
+    /// having PROJ counterparts.
+    pub fn to_proj_string(&self) -> Result<String, Error> {
+        crate::interop::to_proj_string(self)
+    }
+
+    /// The PROJJSON representation of the operator: A `Conversion`, with the
+    /// operation method given by its PROJ string (cf. [`Op::to_proj_string`]),
+    /// following the PROJ convention for operations without EPSG counterparts.
+    #[cfg(feature = "serde")]
+    pub fn to_projjson(&self) -> Result<String, Error> {
+        crate::interop::to_projjson(self)
+    }
+
+    /// The docstring (the `##`-prefixed lines) of the definition of the operator,
+    /// typically a macro. For built-in operators without a docstring, their
+    /// short description, cf. [`Context::operators`].
+    pub fn documentation(&self) -> String {
+        let docstring = super::inner_op::pipeline::split_into_steps(&self.descriptor.definition).1;
+        if !docstring.is_empty() || !self.steps.is_empty() {
+            return docstring;
+        }
+        operator_inventory([])
+            .into_iter()
+            .find(|op| op.name == self.params.name)
+            .map(|op| op.doc.to_string())
+            .unwrap_or_default()
+    }
+
+    /// The macros and grids used in the instantiation of the operator, in order
+    /// of use, i.e. depth first, with each macro preceding the resources it uses
+    pub fn provenance(&self) -> Vec<Provenance> {
+        let mut provenance = self.descriptor.provenance.clone();
+        for step in &self.steps {
+            provenance.extend(step.provenance());
+        }
+        provenance
+    }
+
+    /// The area of use declared by the macro defining the operator, if any, by
+    /// a metadata line of the form `#@ area_of_use=west,south,east,north`, in
+    /// degrees. Cf. the `area_check` parameter of macro invocations, for
+    /// checking input coordinates against it.
+    pub fn area_of_use(&self) -> Option<AreaOfUse> {
+        self.descriptor.metadata.area_of_use
+    }
+
+    /// The accuracy, in metres, declared by the macro defining the operator,
+    /// by a metadata line of the form `#@ accuracy=10`. Otherwise, for pipelines,
+    /// the combined accuracy of the steps declaring one, taking their errors
+    /// as independent, i.e. the root of the sum of their squares. Steps without
+    /// an accuracy, e.g. conversions, are taken as exact. None if no step
+    /// declares an accuracy.
+    pub fn accuracy(&self) -> Option<f64> {
+        if let Some(accuracy) = self.descriptor.metadata.accuracy {
+            return Some(accuracy);
+        }
+        let accuracies: Vec<f64> = self.steps.iter().filter_map(Op::accuracy).collect();
+        if accuracies.is_empty() {
+            return None;
+        }
+        Some(accuracies.iter().map(|a| a * a).sum::<f64>().sqrt())
+    }
+
+    /// The area of use and accuracy of the operator, cf. [`Op::area_of_use`]
+    /// and [`Op::accuracy`]
+    pub fn metadata(&self) -> Metadata {
+        Metadata {
+            area_of_use: self.area_of_use(),
+            accuracy: self.accuracy(),
+        }
+    }
+
+    /// The warnings from the instantiation of the operator and its steps, e.g.
+    /// about ignored parameters, or the use of parameter aliases, each prefixed
+    /// by the name of the operator in question
+    pub fn warnings(&self) -> Vec<String> {
+        let own = self.params.warnings.iter();
+        let mut warnings: Vec<_> = own.map(|w| format!("{}: {w}", self.params.name)).collect();
+        for step in &self.steps {
+            warnings.extend(step.warnings());
+        }
+        warnings
+    }
+
+    /// The numerical noise of the operator over `region`: The discrepancies
+    /// between the sample points of the region, and the results of their
+    /// forward-inverse roundtrips. Points failing to transform are counted, but
+    /// do not enter the discrepancy statistics.
+    pub fn roundtrip_error(
+        &self,
+        ctx: &dyn Context,
+        region: &SampleRegion,
+    ) -> Result<RoundtripError, Error> {
+        let input = region.points();
+        if input.is_empty() {
+            return Err(Error::General(
+                "roundtrip_error: Empty sample region".to_string(),
+            ));
+        }
+        let mut output = input.clone();
+        self.apply(ctx, &mut output, Fwd)?;
+        self.apply(ctx, &mut output, Inv)?;
+
+        let mut error = RoundtripError {
+            points: input.len(),
+            ..Default::default()
+        };
+        let mut sum = 0.;
+        for (a, b) in input.iter().zip(&output) {
+            if (0..4).any(|i| !b[i].is_finite()) {
+                error.failed += 1;
+                continue;
+            }
+            let d = match region.angular {
+                true => a.default_ellps_3d_dist(b),
+                false => a.hypot3(b).hypot(a[3] - b[3]),
+            };
+            error.max = error.max.max(d);
+            sum += d * d;
+        }
+        let n = error.points - error.failed;
+        if n > 0 {
+            error.rms = (sum / n as f64).sqrt();
+        }
+        Ok(error)
+    }
+
+    /// Check the implementation of the methods used by the operator against the
+    /// worked examples of the EPSG Guidance Note 7-2, cf. [`run_gie`](crate::run_gie).
+    /// Failures are prefixed by the EPSG method in question. Operators without
+    /// examples, e.g. `adapt`, are not tested.
+    #[cfg(feature = "validation")]
+    pub fn self_test(&self) -> Result<crate::GieReport, Error> {
+        let name = |step: StepDescription| {
+            let alias = crate::gie::epsg::EPSG_ALIASES
+                .iter()
+                .find(|(alias, _)| *alias == step.name);
+            alias.map_or(step.name, |(_, operator)| operator.to_string())
+        };
+        let names: BTreeSet<_> = self.steps().into_iter().map(name).collect();
+        let mut ctx = Minimal::new();
+        let mut report = crate::GieReport::default();
+        for (operator, method, suite) in crate::gie::epsg::EPSG_EXAMPLES {
+            if !names.contains(operator) {
+                continue;
+            }
+            let outcome = crate::run_gie(&mut ctx, suite)?;
+            report.tests += outcome.tests;
+            for mut failure in outcome.failures {
+                failure.message = format!("{method}: {}", failure.message);
+                report.failures.push(failure);
+            }
+        }
+        Ok(report)
+    }
+
+    /// Whether the input, and the output, of the operator, in the `direction`
+    /// given, are angular, i.e. have angles in radians as their first two
+    /// coordinates: For a pipeline, the input of its first step and the output
+    /// of its last, otherwise as declared by the operator, cf.
+    /// [`OpDescriptor::angular_io`]
+    pub fn angular_io(&self, direction: Direction) -> (bool, bool) {
+        let (input, output) = match (self.steps.first(), self.steps.last()) {
+            (Some(first), Some(last)) => (first.angular_io(Fwd).0, last.angular_io(Fwd).1),
+            _ => self.descriptor.angular_io,
+        };
+        if (direction == Fwd) == self.descriptor.inverted {
+            return (output, input);
+        }
+        (input, output)
+    }
+
+    /// The steps of the operator, as actually instantiated: Macros are expanded,
+    /// nested pipelines flattened, and the inversion of an inverted pipeline or
+    /// macro is carried over to its individual steps. A non-pipeline operator
+    /// comes out as a single step.
+    pub fn steps(&self) -> Vec<StepDescription> {
+        // The inversion of a single step may be given directly, or by inverting
+        // the macro it stems from
+        if self.steps.is_empty() {
+            let mut step = StepDescription::new(&self.params);
+            step.inverted = self.descriptor.inverted;
+            return vec![step];
+        }
+        let mut steps = Vec::new();
+        for step in &self.steps {
+            steps.extend(step.steps());
+        }
+
+        if self.descriptor.inverted {
+            StepDescription::invert_sequence(&mut steps);
+        }
+
+        // Omitting a macro or a sub-pipeline means omitting each of its steps
+        for step in &mut steps {
+            step.omit_fwd |= self.params.boolean("omit_fwd");
+            step.omit_inv |= self.params.boolean("omit_inv");
+        }
+        steps
+    }
+
+    /// A description of the operator, in the form of a definition with one step
+    /// per line, and all parameters given explicitly, cf. [`Op::steps`]
+    pub fn describe(&self) -> String {
+        let steps: Vec<_> = self.steps().iter().map(|s| s.to_string()).collect();
+        steps.join("\n| ")
+    }
+
+    /// The fully expanded definition of the operator, for archiving: A single
+    /// line, with macros expanded, all parameters given explicitly, including the
+    /// defaults and the context globals in effect, and values in canonical units,
+    /// cf. [`Op::steps`]. Instantiating the definition reproduces the operator,
+    /// whatever the resources and globals of the context, as long as the
+    /// operators and grids used are available.
+    pub fn definition(&self) -> String {
+        let steps: Vec<_> = self.steps().iter().map(|s| s.to_string()).collect();
+        steps.join(" | ")
+    }
+
     fn handle_op_inversion(self) -> Result<Op, Error> {
         let inverted = self.params.boolean("inv");
         self.handle_inversion(inverted)
@@ -147,10 +612,238 @@ impl Op {
     }
 }
 
+// ----- R O U N D T R I P   E R R O R S -----------------------------------------------
+
+/// A rectangular region of the input space of an operator, sampled by a grid
+/// of `n` × `n` points over the first two coordinates, cf. [`Op::roundtrip_error`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SampleRegion {
+    /// The corner of the region with the smallest coordinates. The third and
+    /// fourth coordinates of the sample points are taken from here
+    pub lower: Coord,
+    /// The corner of the region with the largest coordinates
+    pub upper: Coord,
+    /// The number of samples along each of the first two axes
+    pub n: usize,
+    /// Whether the first two coordinates are longitude and latitude in radians,
+    /// in which case the discrepancies are measured in metres, as 3D distances
+    /// on the default ellipsoid. Otherwise they are Euclidean distances
+    pub angular: bool,
+}
+
+impl SampleRegion {
+    /// A region of linear coordinates, e.g. projected or cartesian
+    pub fn new(lower: Coord, upper: Coord, n: usize) -> SampleRegion {
+        SampleRegion {
+            lower,
+            upper,
+            n,
+            angular: false,
+        }
+    }
+
+    /// A region of geographical coordinates, from its bounds in degrees, for
+    /// operators taking longitude and latitude in radians as input
+    pub fn geographic(west: f64, south: f64, east: f64, north: f64, n: usize) -> SampleRegion {
+        SampleRegion {
+            lower: Coord::gis(west, south, 0., 0.),
+            upper: Coord::gis(east, north, 0., 0.),
+            n,
+            angular: true,
+        }
+    }
+
+    /// The sample points, row by row
+    pub fn points(&self) -> Vec<Coord> {
+        let step = |i: usize, axis: usize| match self.n {
+            1 => self.lower[axis],
+            n => {
+                self.lower[axis] + (self.upper[axis] - self.lower[axis]) * i as f64 / (n - 1) as f64
+            }
+        };
+        let mut points = Vec::with_capacity(self.n * self.n);
+        for j in 0..self.n {
+            for i in 0..self.n {
+                points.push(Coord::raw(
+                    step(i, 0),
+                    step(j, 1),
+                    self.lower[2],
+                    self.lower[3],
+                ));
+            }
+        }
+        points
+    }
+}
+
+/// The discrepancies of the forward-inverse roundtrips over a sample region,
+/// cf. [`Op::roundtrip_error`]
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct RoundtripError {
+    /// The number of sample points
+    pub points: usize,
+    /// The number of points failing to transform in either direction
+    pub failed: usize,
+    /// The largest discrepancy
+    pub max: f64,
+    /// The root mean square of the discrepancies
+    pub rms: f64,
+}
+
+// ----- S T R U C T   S T E P D E S C R I P T I O N -----------------------------------
+
+/// A single step of an operator, cf. [`Op::steps`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct StepDescription {
+    /// The name of the built-in or user defined operator
+    pub name: String,
+    /// The parameter values in effect, whether given explicitly, by context
+    /// globals, or by default. Angular parameters in degrees.
+    pub parameters: BTreeMap<String, String>,
+    pub inverted: bool,
+    pub omit_fwd: bool,
+    pub omit_inv: bool,
+}
+
+impl StepDescription {
+    /// Invert the sequence of `steps`, i.e. invert each step, in reverse order.
+    /// The steps omitted in the forward direction then become the ones omitted
+    /// in the inverse direction, and vice versa
+    pub fn invert_sequence(steps: &mut [StepDescription]) {
+        steps.reverse();
+        for step in steps {
+            step.inverted = !step.inverted;
+            std::mem::swap(&mut step.omit_fwd, &mut step.omit_inv);
+        }
+    }
+
+    fn new(params: &ParsedParameters) -> StepDescription {
+        // Values derived by the constructor are not parameters. The direction
+        // flags are given as such. The convergence criteria for iterative inverses
+        // are valid for any operator, so we only include them when explicitly given
+        let skip = |key: &str| {
+            !params.gamut.contains(key)
+                || matches!(key, "inv" | "omit_fwd" | "omit_inv")
+                || (key.starts_with("inv_") && !params.given.contains_key(key))
+        };
+
+        let mut parameters = BTreeMap::new();
+        // Empty values cannot be expressed in definition syntax, and are the
+        // defaults anyway
+        let mut add = |key: &str, value: String| {
+            if !skip(key) && !value.is_empty() {
+                parameters.insert(key.to_string(), value);
+            }
+        };
+        for key in &params.boolean {
+            add(key, "true".to_string());
+        }
+        for (key, value) in &params.natural {
+            add(key, value.to_string());
+        }
+        for (key, value) in &params.integer {
+            add(key, value.to_string());
+        }
+        for (key, value) in &params.real {
+            add(key, value.to_string());
+        }
+        for (key, value) in &params.series {
+            let elements: Vec<_> = value.iter().map(|v| v.to_string()).collect();
+            add(key, elements.join(","));
+        }
+        for (key, value) in &params.text {
+            add(key, value.to_string());
+        }
+        for (key, value) in &params.uuid {
+            add(key, value.to_string());
+        }
+
+        // Inline ellipsoid definitions trump `ellps`
+        for key in INLINE_ELLIPSOID {
+            if let Some(value) = params.given.get(key) {
+                let value = parse_real(key, value).map_or(value.to_string(), |v| v.to_string());
+                parameters.remove("ellps");
+                parameters.insert(key.to_string(), value);
+            }
+        }
+
+        // ...while the spheres derived from the ellipsoid need it
+        for key in DERIVED_SPHERES {
+            if let Some(value) = params.given.get(key) {
+                let value = parse_real(key, value).map_or(value.to_string(), |v| v.to_string());
+                parameters.insert(key.to_string(), value);
+            }
+        }
+
+        StepDescription {
+            name: params.name.clone(),
+            parameters,
+            inverted: params.boolean("inv"),
+            omit_fwd: params.boolean("omit_fwd"),
+            omit_inv: params.boolean("omit_inv"),
+        }
+    }
+}
+
+// In definition syntax, e.g. `utm ellps=GRS80 zone=32 inv`
+impl std::fmt::Display for StepDescription {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name)?;
+        for (key, value) in &self.parameters {
+            match value.as_str() {
+                "true" => write!(f, " {key}")?,
+                _ => write!(f, " {key}={value}")?,
+            }
+        }
+        for (flag, given) in [
+            ("inv", self.inverted),
+            ("omit_fwd", self.omit_fwd),
+            ("omit_inv", self.omit_inv),
+        ] {
+            if given {
+                write!(f, " {flag}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
 // ----- A N C I L L A R Y   F U N C T I O N S -----------------------------------------
 
+// A pipeline, or a parenthesized sub-pipeline
 pub fn is_pipeline(definition: &str) -> bool {
+    let mut lines = definition
+        .lines()
+        .map(|line| line.split('#').next().unwrap_or_default().trim());
     definition.contains('|')
+        || lines
+            .find(|line| !line.is_empty())
+            .is_some_and(|line| line.starts_with('('))
+}
+
+// The inner definition and the trailing flags of `definition`, if it is a
+// parenthesized sub-pipeline, e.g. `(cart | helmert z=1 | cart inv) inv`
+pub(crate) fn sub_pipeline(definition: &str) -> Result<Option<(String, String)>, Error> {
+    let (steps, _) = super::inner_op::pipeline::split_into_steps(definition);
+    let [step] = &steps[..] else {
+        return Ok(None);
+    };
+    let Some(rest) = step.strip_prefix('(') else {
+        return Ok(None);
+    };
+    let mut depth = 1;
+    for (i, c) in rest.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            _ => continue,
+        }
+        if depth == 0 {
+            let (inner, flags) = (rest[..i].trim(), rest[i + 1..].trim());
+            return Ok(Some((inner.to_string(), flags.to_string())));
+        }
+    }
+    Err(Error::Syntax(format!("Unbalanced parentheses in '{step}'")))
 }
 
 pub fn is_resource_name(definition: &str) -> bool {
@@ -167,9 +860,57 @@ pub fn operator_name(definition: &str, default: &str) -> String {
         .to_string()
 }
 
+// The placeholders declared by a macro definition, on lines of the form
+// `#$ zone grid=egm96_15.gtx`, i.e. the keys required at invocation, and the
+// default values, if any
+pub(crate) fn placeholders(definition: &str) -> Vec<(String, Option<String>)> {
+    definition
+        .lines()
+        .filter_map(|line| line.trim().strip_prefix("#$"))
+        .flat_map(str::split_whitespace)
+        .map(|placeholder| match placeholder.split_once('=') {
+            Some((key, default)) => (key.to_string(), Some(default.to_string())),
+            None => (placeholder.to_string(), None),
+        })
+        .collect()
+}
+
+// A canonical form of `definition`, used by the context providers for recognizing
+// repeated instantiations of the same operation: Comments and docstrings removed,
+// steps separated by " | ", and within each step, the operator name followed by
+// the parameters in alphabetical order
+pub(crate) fn normalized_definition(definition: &str) -> String {
+    let (steps, _) = super::inner_op::pipeline::split_into_steps(definition);
+    let steps: Vec<_> = steps
+        .iter()
+        .map(|step| {
+            if let Ok(Some((inner, flags))) = sub_pipeline(step) {
+                let mut flags: Vec<_> = flags.split_whitespace().collect();
+                flags.sort();
+                flags.insert(0, "");
+                return format!("({}){}", normalized_definition(&inner), flags.join(" "));
+            }
+            let mut params = split_into_parameters(step);
+            let mut elements = Vec::from_iter(params.remove("name"));
+            elements.extend(params.iter().map(|(k, v)| match v.as_str() {
+                "true" => k.to_string(),
+                _ => format!("{k}={v}"),
+            }));
+            elements.join(" ")
+        })
+        .collect();
+    steps.join(" | ")
+}
+
 pub fn split_into_parameters(step: &str) -> BTreeMap<String, String> {
+    // Remove comments and docstrings
+    let step: Vec<_> = step
+        .lines()
+        .map(|line| line.split('#').next().unwrap_or_default())
+        .collect();
+
     // Conflate contiguous whitespace, then remove whitespace after {"=",  ":",  ","}
-    let step = step.trim().to_string();
+    let step = step.join(" ").trim().to_string();
     let elements: Vec<_> = step.split_whitespace().collect();
     let step = elements
         .join(" ")
@@ -208,6 +949,287 @@ pub fn split_into_parameters(step: &str) -> BTreeMap<String, String> {
 mod tests {
     use super::*;
 
+    #[test]
+    fn normalization() -> Result<(), Error> {
+        let def = "## docstring\n  utm   zone=32  ellps =intl # comment \n|addone inv=true";
+        assert_eq!(
+            normalized_definition(def),
+            "utm ellps=intl zone=32 | addone inv"
+        );
+
+        // Equivalent definitions give the same handle
+        let mut ctx = Minimal::new();
+        let op = ctx.op("utm zone=32")?;
+        assert_eq!(op, ctx.op("utm  zone = 32")?);
+        assert_eq!(op, ctx.op("utm zone=32 # UTM 32")?);
+        assert_ne!(op, ctx.op("utm zone=33")?);
+        assert_ne!(op, ctx.op("utm zone=32 inv")?);
+
+        // ...unless resources have been redefined meanwhile
+        ctx.register_resource("foo:bar", "addone");
+        assert_ne!(op, ctx.op("utm zone=32")?);
+        Ok(())
+    }
+
+    #[test]
+    fn steps() -> Result<(), Error> {
+        let mut ctx = Minimal::new();
+        ctx.register_resource("foo:utm", "utm zone=32 | addone omit_inv");
+
+        // Macros expanded, and the inversion of the macro carried over to its steps
+        let op = Op::new("geo:in | foo:utm inv", &ctx)?;
+        let steps = op.steps();
+        assert_eq!(steps.len(), 3);
+        assert_eq!(steps[0].name, "adapt");
+        assert_eq!(steps[1].name, "addone");
+        assert!(steps[1].inverted && steps[1].omit_fwd && !steps[1].omit_inv);
+        assert_eq!(steps[2].name, "utm");
+        assert!(steps[2].inverted);
+        assert_eq!(steps[2].parameters["zone"], "32");
+
+        // Defaults and globals included
+        assert_eq!(steps[2].parameters["ellps"], "GRS80");
+        assert_eq!(
+            op.describe(),
+            "adapt from=neuf_deg to=enuf\n| addone inv omit_fwd\n| utm ellps=GRS80 zone=32 inv"
+        );
+
+        // A non-pipeline operator is a single step
+        let op = Op::new("tmerc lat_0=49 lon_0=-2", &ctx)?;
+        let steps = op.steps();
+        assert_eq!(steps.len(), 1);
+        assert_eq!(steps[0].parameters["lon_0"], "-2");
+        assert_eq!(steps[0].parameters["k_0"], "1");
+        assert!(!steps[0].parameters.contains_key("inv_max_iter"));
+        let op = Op::new("cart a=6378137 rf=298.25", &ctx)?;
+        assert_eq!(op.describe(), "cart a=6378137 rf=298.25");
+        Ok(())
+    }
+
+    #[test]
+    fn definition() -> Result<(), Error> {
+        let mut ctx = Minimal::new();
+        ctx.register_resource("foo:utm", "utm zone=32 | helmert x=1km y=-50cm");
+        let op = Op::new("geo:in | foo:utm inv | cart a=6378.137km rf=298.25", &ctx)?;
+        let definition = op.definition();
+        assert_eq!(
+            definition,
+            "adapt from=neuf_deg to=enuf | helmert drx=0 dry=0 drz=0 ds=0 dx=0 dy=0 dz=0 \
+             rx=0 ry=0 rz=0 s=0 t_epoch=NaN t_obs=NaN x=1000 y=-0.5 z=0 inv \
+             | utm ellps=GRS80 zone=32 inv | cart a=6378137 rf=298.25"
+        );
+
+        // Reproducible without the macro, and without the context globals
+        let mut bare = Minimal::default();
+        let reproduced = Op::new(&definition, &bare)?;
+        assert_eq!(reproduced.definition(), definition);
+        let mut expected = [Coord::raw(55., 12., 0., 0.)];
+        let mut found = expected;
+        let op = ctx.op("geo:in | foo:utm inv | cart a=6378.137km rf=298.25")?;
+        ctx.apply(op, Fwd, &mut expected)?;
+        let op = bare.op(&definition)?;
+        bare.apply(op, Fwd, &mut found)?;
+        assert_eq!(found, expected);
+
+        // The derived spheres need the ellipsoid they are derived from
+        let op = Op::new("cart ellps=intl R_lat_a=55d30'", &ctx)?;
+        assert_eq!(op.definition(), "cart R_lat_a=55.5 ellps=intl");
+        Ok(())
+    }
+
+    #[test]
+    fn warnings() -> Result<(), Error> {
+        let mut ctx = Minimal::new();
+        ctx.register_resource("foo:tm", "tmerc k=0.9996 | addone");
+        let op = Op::new("geo:in | foo:tm | cart bar=baz", &ctx)?;
+        assert_eq!(
+            op.warnings(),
+            [
+                "tmerc: k is an alias of the preferred k_0",
+                "cart: Ignoring the unknown parameter bar"
+            ]
+        );
+        assert!(Op::new("geo:in | utm zone=32", &ctx)?.warnings().is_empty());
+
+        // ...also by handle, along with the steps, and the traced application
+        let handle = ctx.op("geo:in | foo:tm | cart bar=baz")?;
+        assert_eq!(ctx.warnings(handle)?, op.warnings());
+        assert_eq!(ctx.steps(handle)?, op.steps());
+        let mut traced = Vec::new();
+        let mut tracer =
+            |step: &Op, _: Direction, _: &[Coord]| traced.push(step.params.name.clone());
+        let mut data = [Coord::geo(55., 12., 0., 0.)];
+        ctx.apply_traced(handle, Fwd, &mut data, &mut tracer)?;
+        assert_eq!(traced, ["adapt", "tmerc", "addone", "cart"]);
+        Ok(())
+    }
+
+    #[test]
+    fn roundtrip_error() -> Result<(), Error> {
+        let ctx = Minimal::new();
+        let op = Op::new("utm zone=32", &ctx)?;
+        let region = SampleRegion::geographic(6., 50., 12., 70., 11);
+        assert_eq!(region.points().len(), 121);
+        let error = op.roundtrip_error(&ctx, &region)?;
+        assert_eq!((error.points, error.failed), (121, 0));
+        assert!(error.max < 1e-6);
+        assert!(error.rms <= error.max);
+
+        // Exact roundtrips over a linear region
+        let op = Op::new("helmert x=1 y=2 z=3", &ctx)?;
+        let region = SampleRegion::new(Coord::raw(0., 0., 0., 0.), Coord::raw(1., 1., 0., 0.), 3);
+        let error = op.roundtrip_error(&ctx, &region)?;
+        assert_eq!((error.points, error.max), (9, 0.));
+
+        let region = SampleRegion::new(Coord::origin(), Coord::origin(), 0);
+        assert!(op.roundtrip_error(&ctx, &region).is_err());
+        Ok(())
+    }
+
+    #[cfg(feature = "validation")]
+    #[test]
+    fn self_test() -> Result<(), Error> {
+        let ctx = Minimal::new();
+        // Examples for 9603, 9606 and 9607, and for 9602
+        let op = Op::new("geo:in | cart | helmert x=1 | cart inv | geo:out", &ctx)?;
+        let report = op.self_test()?;
+        assert_eq!((report.tests, report.failures.len()), (8, 0));
+        // No examples for adapt and addone
+        let report = Op::new("addone | geo:in", &ctx)?.self_test()?;
+        assert_eq!(report.tests, 0);
+        // UTM is tested by the Transverse Mercator examples
+        for (utm, tmerc) in [("utm zone=32", "tmerc"), ("butm zone=32", "btmerc")] {
+            let report = Op::new(utm, &ctx)?.self_test()?;
+            assert!(report.tests > 0 && report.failures.is_empty(), "{utm}");
+            assert_eq!(report.tests, Op::new(tmerc, &ctx)?.self_test()?.tests);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn angular_io() -> Result<(), Error> {
+        let ctx = Minimal::new();
+        let op = Op::new("geo:in | utm zone=32", &ctx)?;
+        assert_eq!(op.angular_io(Fwd), (false, false));
+        let op = Op::new("utm zone=32 | cart inv", &ctx)?;
+        assert_eq!(op.angular_io(Fwd), (true, true));
+        assert_eq!(op.angular_io(Inv), (true, true));
+        let op = Op::new("cart | helmert x=1", &ctx)?;
+        assert_eq!(op.angular_io(Fwd), (true, false));
+        assert_eq!(op.angular_io(Inv), (false, true));
+        let op = Op::new("helmert x=1 | cart inv", &ctx)?;
+        assert_eq!(op.angular_io(Fwd), (false, true));
+
+        // Inverted pipelines and macros
+        let op = Op::new("cart | helmert x=1 | inv", &ctx)?;
+        assert_eq!(op.angular_io(Fwd), (false, true));
+        let op = Op::new("(cart | helmert x=1) inv | noop", &ctx)?;
+        assert_eq!(op.angular_io(Fwd), (false, false));
+        let op = Op::new("utm zone=32 | (cart | helmert x=1) inv", &ctx)?;
+        assert_eq!(op.angular_io(Fwd), (true, true));
+        Ok(())
+    }
+
+    #[test]
+    fn sub_pipelines() -> Result<(), Error> {
+        let mut ctx = Minimal::new();
+        let flat = ctx.op("geo:in | cart | helmert z=10 | cart inv | geo:out")?;
+        let mut expected = [Coord::raw(55., 12., 0., 0.)];
+        ctx.apply(flat, Fwd, &mut expected)?;
+
+        // Grouping makes no difference, and an inverted group is inverted as a whole
+        for definition in [
+            "geo:in | (cart | helmert z=10 | cart inv) | geo:out",
+            "geo:in | ((cart | helmert z=10) | cart inv) | geo:out",
+            "geo:in | (cart | helmert z=-10 | cart inv) inv | geo:out",
+            "geo:in | (cart | (helmert z=-10) inv | cart inv) | geo:out",
+        ] {
+            let op = ctx.op(definition)?;
+            let mut data = [Coord::raw(55., 12., 0., 0.)];
+            ctx.apply(op, Fwd, &mut data)?;
+            assert!(data[0].hypot3(&expected[0]) < 1e-9, "{definition}");
+            ctx.apply(op, Inv, &mut data)?;
+            assert!((data[0][2]).abs() < 1e-6, "{definition}");
+        }
+
+        // Groups may be named, i.e. registered as macros, and omitted as a whole
+        ctx.register_resource("foo:shift", "cart | helmert z=10 | cart inv");
+        let op = ctx.op("geo:in | (foo:shift | noop) omit_inv | geo:out")?;
+        let mut data = [Coord::raw(55., 12., 0., 0.)];
+        ctx.apply(op, Fwd, &mut data)?;
+        assert!(data[0].hypot3(&expected[0]) < 1e-9);
+        ctx.apply(op, Inv, &mut data)?;
+        assert!(data[0].hypot3(&expected[0]) < 1e-9);
+        let steps = Op::new("geo:in | (foo:shift | addone) inv omit_inv", &ctx)?.steps();
+        assert_eq!(steps.len(), 5);
+        assert_eq!(steps[1].name, "addone");
+        assert!(steps.iter().skip(1).all(|s| s.omit_inv));
+        let inverted: Vec<_> = steps.iter().map(|s| s.inverted).collect();
+        assert_eq!(inverted, [false, true, false, true, true]);
+
+        // ...i.e. each step of the group is inverted, in reverse order. The steps
+        // inverted within the group come out uninverted
+        let group = Op::new("geo:in | (foo:shift | addone) omit_inv", &ctx)?.steps();
+        for (step, original) in steps.iter().skip(1).zip(group.iter().skip(1).rev()) {
+            assert_eq!(step.name, original.name);
+            assert!(step.inverted != original.inverted && step.omit_inv);
+        }
+
+        // Equivalent definitions give the same handle, as long as the grouping is
+        assert_eq!(
+            ctx.op("(addone  | addone) inv | addone")?,
+            ctx.op("(addone|addone) inv|addone")?
+        );
+        assert_ne!(
+            ctx.op("(addone | addone inv) | addone")?,
+            ctx.op("(addone | addone) inv | addone")?
+        );
+
+        // Malformed groups
+        for definition in [
+            "(addone | addone",
+            "addone | addone) inv",
+            "(addone) foo",
+            "(addone) (addone)",
+        ] {
+            assert!(
+                matches!(
+                    ctx.op(definition),
+                    Err(Error::Syntax(_) | Error::SyntaxAt { .. })
+                ),
+                "{definition}"
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn documentation() -> Result<(), Error> {
+        let mut ctx = Minimal::new();
+        let utm32 = "## UTM zone 32\n## on GRS80\nutm zone=32 # Not a docstring";
+        ctx.register_resource("foo:utm32", utm32);
+        ctx.register_resource("foo:pipe", "## Degrees to UTM\ngeo:in | foo:utm32");
+
+        assert_eq!(ctx.documentation("foo:utm32")?, "UTM zone 32\non GRS80");
+        let op = Op::new("foo:utm32", &ctx)?;
+        assert_eq!(op.documentation(), "UTM zone 32\non GRS80");
+        assert_eq!(op.params.text("zone")?, "32");
+        assert_eq!(
+            Op::new("foo:pipe inv", &ctx)?.documentation(),
+            "Degrees to UTM"
+        );
+
+        // Built-ins have a short description
+        assert_eq!(Op::new("merc", &ctx)?.documentation(), "Mercator");
+        assert_eq!(ctx.documentation("merc")?, "Mercator");
+        assert_eq!(Op::new("merc | merc inv", &ctx)?.documentation(), "");
+        assert!(matches!(
+            ctx.documentation("foo:bar"),
+            Err(Error::NotFound(_, _))
+        ));
+        Ok(())
+    }
+
     // Test the fundamental Op-functionality: That we can actually instantiate
     // an Op, and invoke its forward and backward operational modes
     #[test]
@@ -275,6 +1297,50 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn tracing() -> Result<(), Error> {
+        let mut ctx = Minimal::default();
+        ctx.register_resource("sub:two", "addone | addone");
+        let op = Op::new("addone | push v_1 | sub:two inv | pop v_1 | addone", &ctx)?;
+        let mut data = some_basic_coordinates();
+
+        let mut trace = Vec::new();
+        let mut tracer = |step: &Op, direction: Direction, operands: &[Coord]| {
+            trace.push((step.params.name.clone(), direction, operands[0][0]));
+        };
+        assert_eq!(op.apply_traced(&ctx, &mut data, Fwd, &mut tracer)?, 2);
+        let names: Vec<_> = trace.iter().map(|t| t.0.as_str()).collect();
+        assert_eq!(
+            names,
+            ["addone", "push", "addone", "addone", "pop", "addone"]
+        );
+        let values: Vec<_> = trace.iter().map(|t| t.2).collect();
+        assert_eq!(values, [56., 56., 55., 54., 56., 57.]);
+        // The steps of the inverted macro are applied in the inverse direction
+        assert_eq!(trace[2].1, Inv);
+        assert_eq!(data[0][0], 57.);
+
+        // Same result as the untraced operator, also in the inverse direction
+        trace.clear();
+        let mut tracer = |step: &Op, direction: Direction, operands: &[Coord]| {
+            trace.push((step.params.name.clone(), direction, operands[0][0]));
+        };
+        op.apply_traced(&ctx, &mut data, Inv, &mut tracer)?;
+        assert_eq!(data[0][0], 55.);
+        assert_eq!(trace.len(), 6);
+        assert_eq!(trace[0], ("addone".to_string(), Inv, 56.));
+
+        // A plain operator is a single step
+        trace.clear();
+        let op = Op::new("addone inv", &ctx)?;
+        let mut tracer = |step: &Op, direction: Direction, operands: &[Coord]| {
+            trace.push((step.params.name.clone(), direction, operands[0][0]));
+        };
+        op.apply_traced(&ctx, &mut data, Fwd, &mut tracer)?;
+        assert_eq!(trace, [("addone".to_string(), Fwd, 54.)]);
+        Ok(())
+    }
+
     #[test]
     fn macro_expansion() -> Result<(), Error> {
         let mut data = some_basic_coordinates();
@@ -338,6 +1404,185 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn macro_placeholders() -> Result<(), Error> {
+        let mut ctx = Minimal::new();
+        ctx.register_resource(
+            "my:utm",
+            "## UTM on a selectable ellipsoid\n#$ zone ellps=intl\ngeo:in | utm zone=$zone ellps=$ellps",
+        );
+        let mut expected = [Coord::raw(55., 12., 0., 0.)];
+        let op = ctx.op("geo:in | utm zone=33 ellps=intl")?;
+        ctx.apply(op, Fwd, &mut expected)?;
+
+        // Placeholders given, or defaulted
+        let op = ctx.op("my:utm zone=33")?;
+        let mut data = [Coord::raw(55., 12., 0., 0.)];
+        ctx.apply(op, Fwd, &mut data)?;
+        assert_eq!(data, expected);
+        let op = ctx.op("my:utm zone=33 ellps=GRS80")?;
+        let mut data = [Coord::raw(55., 12., 0., 0.)];
+        ctx.apply(op, Fwd, &mut data)?;
+        assert!(data[0].hypot2(&expected[0]) > 1.);
+
+        // ...and the placeholders of macros invoked by macros may be given by
+        // the invoking macro's invocation
+        ctx.register_resource("my:utm33", "my:utm | noop");
+        assert!(ctx.op("my:utm33 zone=33").is_ok());
+
+        // ...even when equal to the context global, which the default overrides
+        let op = ctx.op("my:utm33 zone=33 ellps=GRS80")?;
+        let mut given = [Coord::raw(55., 12., 0., 0.)];
+        ctx.apply(op, Fwd, &mut given)?;
+        assert_eq!(given, data);
+
+        // A required placeholder missing
+        let Err(Error::MissingParam(message)) = ctx.op("my:utm") else {
+            panic!("Expected a missing parameter error");
+        };
+        assert_eq!(message, "zone (required by the macro my:utm)");
+        assert!(ctx.op("geo:in | my:utm33").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn macro_metadata() -> Result<(), Error> {
+        let mut ctx = Minimal::new();
+        let op = ctx.op("ed50:etrs89")?;
+        let metadata = ctx.metadata(op)?;
+        assert_eq!(metadata.accuracy, Some(10.));
+        let area = metadata.area_of_use.unwrap();
+        assert_eq!(area.to_string(), "-16.1,25.71,48.61,84.73");
+        assert_eq!(Op::new("EPSG:1314", &ctx)?.accuracy(), Some(2.));
+        let op = ctx.op("utm zone=32")?;
+        assert_eq!(ctx.metadata(op)?, Metadata::default());
+
+        // The metadata of a macro invoking a macro are those of the former,
+        // unless it has none
+        ctx.register_resource(
+            "my:ed50",
+            "#@ accuracy=20
+ed50:etrs89",
+        );
+        ctx.register_resource("their:ed50", "ed50:etrs89");
+        let op = Op::new("my:ed50", &ctx)?;
+        assert_eq!(op.accuracy(), Some(20.));
+        assert_eq!(op.area_of_use(), Some(area));
+        assert_eq!(Op::new("their:ed50", &ctx)?.accuracy(), Some(10.));
+
+        // By default, coordinates outside of the area of use are transformed
+        // as any other
+        let copenhagen = Coord::raw(55., 12., 0., 0.);
+        let new_york = Coord::raw(40.7, -74., 0., 0.);
+        let op = ctx.op("ed50:etrs89")?;
+        let mut data = [copenhagen, new_york];
+        ctx.apply(op, Fwd, &mut data)?;
+        assert!(data[1][0].is_finite());
+
+        // Checked on request, in the forward case on the input, in the inverse
+        // case on the output. Either way, the coordinates are left unchanged
+        // when failing
+        let op = ctx.op("ed50:etrs89 area_check=error")?;
+        let mut data = [copenhagen, new_york];
+        let Err(Error::Operator(name, message)) = ctx.apply(op, Fwd, &mut data) else {
+            panic!("Expected an area of use error");
+        };
+        assert_eq!(name, "ed50:etrs89");
+        assert!(message.starts_with("1 of 2 coordinates outside"));
+        assert_eq!(data[1], new_york);
+        let mut data = [copenhagen];
+        ctx.apply(op, Fwd, &mut data)?;
+        ctx.apply(op, Inv, &mut data)?;
+        assert!(data[0].hypot2(&copenhagen) < 1e-6);
+
+        let op = ctx.op("ed50:etrs89 inv area_check=error")?;
+        let mut data = [new_york];
+        assert!(ctx.apply(op, Fwd, &mut data).is_err());
+        assert_eq!(data[0], new_york);
+        let op = ctx.op("ed50:etrs89 area_check=error")?;
+        let mut data = [copenhagen, new_york];
+        assert!(ctx.apply(op, Inv, &mut data).is_err());
+        assert_eq!(data, [copenhagen, new_york]);
+        let op = ctx.op("ed50:etrs89 area_check=warn")?;
+        ctx.apply(op, Fwd, &mut [new_york])?;
+        assert!(ctx.op("ed50:etrs89 area_check=sometimes").is_err());
+
+        // ...or by a context global
+        ctx.register_global("area_check", "error")?;
+        let op = ctx.op("noop | ed50:etrs89")?;
+        assert!(ctx.apply(op, Fwd, &mut [new_york]).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn composition() -> Result<(), Error> {
+        let ctx = Minimal::new();
+        let utm = Op::new("geo:in | utm zone=32", &ctx)?;
+        let back = Op::new("geo:in | utm zone=32", &ctx)?.inverted()?;
+        let roundtrip = utm.then(back)?;
+        assert_eq!(roundtrip.steps.len(), 2);
+        let definition = roundtrip.definition();
+        assert!(definition.starts_with("adapt from=neuf_deg to=enuf | utm"));
+        assert!(
+            definition.ends_with("utm ellps=GRS80 zone=32 inv | adapt from=neuf_deg to=enuf inv")
+        );
+
+        let copenhagen = Coord::raw(55., 12., 0., 0.);
+        let mut data = [copenhagen];
+        roundtrip.apply(&ctx, &mut data, Fwd)?;
+        assert!(data[0].hypot2(&copenhagen) < 1e-9);
+
+        // Inverting twice is a no-op
+        let utm = Op::new("geo:in | utm zone=32", &ctx)?;
+        let reference = Op::new("geo:in | utm zone=32", &ctx)?.inverted()?;
+        let utm = utm.inverted()?.inverted()?;
+        let mut data = [copenhagen];
+        utm.apply(&ctx, &mut data, Fwd)?;
+        reference.apply(&ctx, &mut data, Fwd)?;
+        assert!(data[0].hypot2(&copenhagen) < 1e-9);
+
+        // ...and the inversions are retained in the composite
+        let reference = Op::new("geo:in | utm zone=32", &ctx)?.inverted()?;
+        let utm = Op::new("geo:in | utm zone=32", &ctx)?;
+        let composite = reference.then(utm)?;
+        let mut data = [Coord::raw(691875.632, 6098907.825, 0., 0.)];
+        composite.apply(&ctx, &mut data, Fwd)?;
+        assert!((data[0][0] - 691875.632).abs() < 1e-6);
+        assert!(matches!(
+            Op::new("geodesic pairs", &ctx)?.inverted(),
+            Err(Error::NonInvertible(_))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn accuracy_propagation() -> Result<(), Error> {
+        let mut ctx = Minimal::new();
+
+        // Independent errors add up in quadrature, while conversions are exact
+        let op = Op::new("ed50:etrs89 | osgb36:etrs89 inv", &ctx)?;
+        assert_eq!(op.accuracy(), Some(104_f64.sqrt()));
+        let op = Op::new(
+            "geo:in | (geo:out | ed50:etrs89 | geo:in) | utm zone=32",
+            &ctx,
+        )?;
+        assert_eq!(op.accuracy(), Some(10.));
+        assert_eq!(Op::new("geo:in | utm zone=32", &ctx)?.accuracy(), None);
+
+        // ...unless the accuracy of the combination is declared explicitly
+        ctx.register_resource(
+            "my:ed50:osgb36",
+            "#@ accuracy=5\ned50:etrs89 | osgb36:etrs89 inv",
+        );
+        let direct = ctx.op("my:ed50:osgb36")?;
+        assert_eq!(ctx.metadata(direct)?.accuracy, Some(5.));
+
+        // Comparing the alternatives
+        let via_etrs89 = ctx.op("ed50:etrs89 | osgb36:etrs89 inv")?;
+        assert!(ctx.metadata(direct)?.accuracy < ctx.metadata(via_etrs89)?.accuracy);
+        Ok(())
+    }
+
     #[test]
     fn macro_expansion_with_defaults_provided() -> Result<(), Error> {
         let mut data = some_basic_coordinates();
@@ -10,6 +10,16 @@ pub struct OpDescriptor {
     pub fwd: InnerOp,
     pub inv: InnerOp,
     pub id: OpHandle,
+    /// The macros and grids used by the operator itself, cf. [`Op::provenance`]
+    pub provenance: Vec<Provenance>,
+    /// The area of use and accuracy declared by the macro defining the operator
+    pub metadata: Metadata,
+    /// Whether to check the input coordinates against the area of use
+    pub area_check: AreaCheck,
+    /// Whether the input, and the output, of the forward operation are angular,
+    /// i.e. have angles in radians as their first two coordinates. Set by the
+    /// constructor, cf. [`Op::angular_io`]
+    pub angular_io: (bool, bool),
 }
 
 impl OpDescriptor {
@@ -20,6 +30,10 @@ impl OpDescriptor {
         let invocation = "".to_string(); // Handled higher up in the call hierarchy
         let inv = inv.unwrap_or_default();
         let id = OpHandle::new();
+        let provenance = Vec::new();
+        let metadata = Metadata::default();
+        let area_check = AreaCheck::default();
+        let angular_io = (false, false);
         OpDescriptor {
             invocation,
             definition,
@@ -28,6 +42,19 @@ impl OpDescriptor {
             fwd,
             inv,
             id,
+            provenance,
+            metadata,
+            area_check,
+            angular_io,
         }
     }
+
+    /// For operators providing only a forward function: Synthesize the inverse
+    /// by Newton iteration over the first two coordinate elements, starting from
+    /// the point given by `lon_0`, `lat_0`. The iteration stops when the correction
+    /// is smaller than `inv_tolerance` (in the units of the forward input), or after
+    /// `inv_max_iter` iterations. Cf. [`newton_inverse`]
+    pub fn with_iterative_inverse(definition: &str, fwd: InnerOp) -> OpDescriptor {
+        Self::new(definition, fwd, Some(InnerOp(newton_inverse)))
+    }
 }
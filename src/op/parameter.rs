@@ -31,7 +31,9 @@ pub enum OpParameter {
         key: &'static str,
         default: Option<f64>,
     },
-    /// A series of reals (𝐑ⁿ in math terms)
+    /// A series of reals (𝐑ⁿ in math terms), given as a comma separated list,
+    /// where each element may also be an inclusive range, `first:last` or
+    /// `first:last:step`. So `0,1:3,5:9:2` is the series [0, 1, 2, 3, 5, 7, 9]
     Series {
         key: &'static str,
         default: Option<&'static str>,
@@ -41,4 +43,26 @@ pub enum OpParameter {
         key: &'static str,
         default: Option<&'static str>,
     },
+    /// An alternative key for the parameter `canonical` of the gamut, e.g. for
+    /// compatibility with PROJ, or with older versions of Rust Geodesy. A value
+    /// given under an alias is handled as if given under the canonical key
+    Alias {
+        key: &'static str,
+        canonical: &'static str,
+    },
+}
+
+impl OpParameter {
+    /// The key of the parameter, irrespective of its type
+    pub fn key(&self) -> &'static str {
+        match *self {
+            OpParameter::Flag { key } => key,
+            OpParameter::Natural { key, .. } => key,
+            OpParameter::Integer { key, .. } => key,
+            OpParameter::Real { key, .. } => key,
+            OpParameter::Series { key, .. } => key,
+            OpParameter::Text { key, .. } => key,
+            OpParameter::Alias { key, .. } => key,
+        }
+    }
 }
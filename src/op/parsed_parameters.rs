@@ -7,6 +7,7 @@
 
 #![allow(clippy::needless_range_loop)]
 use crate::math::FourierCoefficients;
+use std::any::Any;
 
 use super::*;
 
@@ -34,6 +35,22 @@ pub struct ParsedParameters {
     pub fourier_coefficients: BTreeMap<&'static str, FourierCoefficients>,
     pub ignored: Vec<String>,
     pub given: BTreeMap<String, String>,
+    /// Warnings from the parsing, e.g. about ignored parameters, or the use of
+    /// aliases, cf. [`Op::warnings`]
+    pub warnings: Vec<String>,
+    // The keys of the gamut, including the implicitly valid ones
+    pub gamut: BTreeSet<&'static str>,
+    // The values needed at run time, cf. `ParsedParameters::prepare`
+    prepared: Option<Prepared>,
+}
+
+// A plain struct, of a type known only to the operator storing it
+struct Prepared(Box<dyn Any + Send + Sync>);
+
+impl std::fmt::Debug for Prepared {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Prepared(..)")
+    }
 }
 
 // Accessors
@@ -65,6 +82,14 @@ impl ParsedParameters {
         }
         Err(Error::MissingParam(key.to_string()))
     }
+    /// The series `key`, which must have exactly `N` elements
+    pub fn series_array<const N: usize>(&self, key: &str) -> Result<[f64; N], Error> {
+        let series = self.series(key)?;
+        series.try_into().map_err(|_| {
+            let series: Vec<String> = series.iter().map(|v| v.to_string()).collect();
+            Error::BadParam(key.to_string(), series.join(","))
+        })
+    }
     pub fn text(&self, key: &str) -> Result<String, Error> {
         if let Some(value) = self.text.get(key) {
             return Ok(value.to_string());
@@ -83,6 +108,20 @@ impl ParsedParameters {
         }
         Err(Error::MissingParam(key.to_string()))
     }
+    /// Store the values needed at run time, resolved by the constructor into a
+    /// plain struct of the operator's own choice, for [`ParsedParameters::prepared`]
+    pub fn prepare<T: Any + Send + Sync>(&mut self, values: T) {
+        self.prepared = Some(Prepared(Box::new(values)));
+    }
+    /// The values stored by [`ParsedParameters::prepare`]. To be retrieved once
+    /// per call, outside of the loop over the coordinates, rather than looking
+    /// up each value in the B-trees for each coordinate
+    pub fn prepared<T: Any>(&self) -> Result<&T, Error> {
+        self.prepared
+            .as_ref()
+            .and_then(|prepared| prepared.0.downcast_ref())
+            .ok_or_else(|| Error::MissingParam("prepared values".to_string()))
+    }
     pub fn ignored(&self) -> Vec<String> {
         self.ignored.clone()
     }
@@ -111,7 +150,7 @@ impl ParsedParameters {
         parameters: &RawParameters,
         gamut: &[OpParameter],
     ) -> Result<ParsedParameters, Error> {
-        let locals = super::split_into_parameters(&parameters.definition);
+        let mut locals = super::split_into_parameters(&parameters.definition);
         let globals = &parameters.globals;
         let mut boolean = BTreeSet::<&'static str>::new();
         let mut natural = BTreeMap::<&'static str, usize>::new();
@@ -133,9 +172,53 @@ impl ParsedParameters {
         gamutt.push(&OpParameter::Flag { key: "omit_fwd" });
         gamutt.push(&OpParameter::Flag { key: "omit_inv" });
 
+        // Likewise, the convergence criteria for synthesized iterative inverses
+        // (cf. `OpDescriptor::with_iterative_inverse`) may be given for any operator
+        gamutt.push(&OpParameter::Real {
+            key: "inv_tolerance",
+            default: Some(1e-12),
+        });
+        gamutt.push(&OpParameter::Natural {
+            key: "inv_max_iter",
+            default: Some(20),
+        });
+        let mut keys = BTreeSet::<&'static str>::new();
+        let mut warnings = Vec::new();
+        let mut warning = |message: String| {
+            warn!("{message}");
+            warnings.push(message);
+        };
+
+        // The aliases accepted by all operators having the canonical parameter,
+        // and the ones given by the gamut itself. A value given under its
+        // canonical key takes precedence
+        for p in COMMON_ALIASES.iter().chain(gamut) {
+            let OpParameter::Alias { key, canonical } = *p else {
+                continue;
+            };
+            if !gamut.iter().any(|p| p.key() == canonical) {
+                continue;
+            }
+            let Some(value) = locals.remove(key) else {
+                continue;
+            };
+            if locals.contains_key(canonical) {
+                warning(format!(
+                    "Ignoring {key}={value}, as {canonical} is also given"
+                ));
+                continue;
+            }
+            warning(format!("{key} is an alias of the preferred {canonical}"));
+            locals.insert(canonical.to_string(), value);
+        }
+
         // Try to locate all accepted parameters, type check, and place them into
         // their proper bins
         for p in gamutt {
+            if let OpParameter::Alias { .. } = p {
+                continue;
+            }
+            keys.insert(p.key());
             match *p {
                 OpParameter::Flag { key } => {
                     if let Some(value) = chase(globals, &locals, key)? {
@@ -196,7 +279,7 @@ impl ParsedParameters {
 
                 OpParameter::Real { key, default } => {
                     if let Some(value) = chase(globals, &locals, key)? {
-                        if let Ok(v) = value.parse::<f64>() {
+                        if let Some(v) = parse_real(key, &value) {
                             real.insert(key, v);
                             continue;
                         }
@@ -218,18 +301,13 @@ impl ParsedParameters {
                 }
 
                 OpParameter::Series { key, default } => {
-                    let mut elements = Vec::<f64>::new();
                     if let Some(value) = chase(globals, &locals, key)? {
-                        for element in value.split(',') {
-                            if let Ok(v) = element.parse::<f64>() {
-                                elements.push(v);
-                                continue;
-                            }
-                            warn!("Cannot parse {key}:{value} as a series");
-                            return Err(Error::BadParam(key.to_string(), value.to_string()));
+                        if let Some(elements) = parse_series(&value) {
+                            series.insert(key, elements);
+                            continue;
                         }
-                        series.insert(key, elements);
-                        continue;
+                        warn!("Cannot parse {key}:{value} as a series");
+                        return Err(Error::BadParam(key.to_string(), value));
                     }
 
                     // If we're here, the key was not found
@@ -240,16 +318,12 @@ impl ParsedParameters {
                         if value.is_empty() {
                             continue;
                         }
-                        for element in value.split(',') {
-                            if let Ok(v) = element.parse::<f64>() {
-                                elements.push(v);
-                                continue;
-                            }
-                            warn!("Cannot parse {key}:{value} as a series");
-                            return Err(Error::BadParam(key.to_string(), value.to_string()));
+                        if let Some(elements) = parse_series(value) {
+                            series.insert(key, elements);
+                            continue;
                         }
-                        series.insert(key, elements);
-                        continue;
+                        warn!("Cannot parse {key}:{value} as a series");
+                        return Err(Error::BadParam(key.to_string(), value.to_string()));
                     }
 
                     // Missing a required parameter
@@ -276,6 +350,9 @@ impl ParsedParameters {
                     error!("Missing required parameter '{key}'");
                     return Err(Error::MissingParam(key.to_string()));
                 }
+
+                // Already handled above
+                OpParameter::Alias { .. } => continue,
             };
         }
 
@@ -289,15 +366,32 @@ impl ParsedParameters {
         let mut k = [1.; 4];
 
         // ellps_{n}
+        let named = |key: &str, name: &str| {
+            Ellipsoid::named(name).map_err(|_| Error::BadParam(key.to_string(), name.to_string()))
+        };
         for i in 0..2 {
             let key = format!("ellps_{}", i);
             if let Some(e) = text.get(&key[..]) {
-                ellps[i] = Ellipsoid::named(e)?;
+                ellps[i] = named(&key, e)?;
             }
         }
         // But `ellps` trumps `ellps_0`
         if let Some(e) = text.get("ellps") {
-            ellps[0] = Ellipsoid::named(e)?;
+            ellps[0] = named("ellps", e)?;
+        }
+
+        // And the inline definitions `a`, `rf`, `f`, `b`, `es` and `R` trump everything
+        let inline = inline_ellipsoid(globals, &locals)?;
+        if let Some(e) = inline {
+            ellps[0] = e;
+        } else if keys.contains("ellps") && chase(globals, &locals, "ellps")?.is_none() {
+            let default = text.get("ellps").cloned().unwrap_or_default();
+            warning(format!("No ellipsoid given, using the default {default}"));
+        }
+
+        // Possibly replaced by a sphere derived from it
+        if let Some(e) = derived_sphere(globals, &locals, &ellps[0])? {
+            ellps[0] = e;
         }
 
         // lat_{n}
@@ -340,7 +434,18 @@ impl ParsedParameters {
         // let mut redundant = BTreeSet::<String>::new();
         // Params specified, but not used
         let given = locals.clone();
-        let ignored: Vec<String> = locals.into_keys().collect();
+        let ignored: Vec<String> = locals
+            .into_keys()
+            .filter(|key| {
+                !keys.contains(key.as_str())
+                    && key != "name"
+                    && !INLINE_ELLIPSOID.contains(&key.as_str())
+                    && !DERIVED_SPHERES.contains(&key.as_str())
+            })
+            .collect();
+        for key in &ignored {
+            warning(format!("Ignoring the unknown parameter {key}"));
+        }
         Ok(ParsedParameters {
             ellps,
             lat,
@@ -360,12 +465,210 @@ impl ParsedParameters {
             fourier_coefficients,
             ignored,
             given,
+            warnings,
+            gamut: keys,
+            prepared: None,
         })
     }
 }
 
 // ----- A N C I L L A R Y   F U N C T I O N S -----------------------------------------
 
+// Aliases valid for any operator having the canonical parameter in its gamut:
+// The PROJ names, and the names used by older versions of Rust Geodesy
+#[rustfmt::skip]
+const COMMON_ALIASES: [OpParameter; 3] = [
+    OpParameter::Alias { key: "k",         canonical: "k_0" },
+    OpParameter::Alias { key: "ellipsoid", canonical: "ellps" },
+    OpParameter::Alias { key: "units",     canonical: "unit" },
+];
+
+// The ellipsoid given by the inline parameters: The semimajor axis `a`, and
+// one of `rf`, `f`, `b`, `es` for its shape (a sphere if none given), or
+// a sphere of radius `R`. None, if neither `a` nor `R` is given.
+fn inline_ellipsoid(
+    globals: &BTreeMap<String, String>,
+    locals: &BTreeMap<String, String>,
+) -> Result<Option<Ellipsoid>, Error> {
+    let real = |key: &str| -> Result<Option<f64>, Error> {
+        let Some(value) = chase(globals, locals, key)? else {
+            return Ok(None);
+        };
+        if let Some(v) = parse_real(key, &value) {
+            return Ok(Some(v));
+        }
+        warn!("Cannot parse {key}:{value} as a real number");
+        Err(Error::BadParam(key.to_string(), value))
+    };
+
+    if let Some(r) = real("R")? {
+        return Ok(Some(Ellipsoid::new(r, 0.)));
+    }
+
+    let keys = ["rf", "f", "b", "es"];
+    let shape = [real("rf")?, real("f")?, real("b")?, real("es")?];
+    let Some(a) = real("a")? else {
+        if let Some(i) = shape.iter().position(|s| s.is_some()) {
+            error!("Ellipsoid shape given without semimajor axis 'a'");
+            let given = chase(globals, locals, keys[i])?.unwrap_or_default();
+            return Err(Error::MissingParam(format!(
+                "a (required by {}={given})",
+                keys[i]
+            )));
+        }
+        return Ok(None);
+    };
+
+    let f = match shape {
+        [Some(rf), ..] if rf != 0. => 1. / rf,
+        [_, Some(f), ..] => f,
+        [_, _, Some(b), _] => (a - b) / a,
+        [_, _, _, Some(es)] => 1. - (1. - es).sqrt(),
+        _ => 0.,
+    };
+    Ok(Some(Ellipsoid::new(a, f)))
+}
+
+// The parameters of the inline ellipsoid definitions, cf. `inline_ellipsoid`
+pub(crate) const INLINE_ELLIPSOID: [&str; 6] = ["a", "rf", "f", "b", "es", "R"];
+
+// The PROJ style spheres derived from an ellipsoid
+pub(crate) const DERIVED_SPHERES: [&str; 7] =
+    ["R_A", "R_V", "R_a", "R_g", "R_h", "R_lat_a", "R_lat_g"];
+
+// The sphere derived from `ellps`, as given by one of the `DERIVED_SPHERES`
+// parameters: `R_A` (same surface area) and `R_V` (same volume), the arithmetic,
+// geometric and harmonic means of the semiaxes, `R_a`, `R_g` and `R_h`, and the
+// arithmetic and geometric means of the principal radii of curvature at a given
+// latitude, `R_lat_a` and `R_lat_g`. None, if none of them are given.
+fn derived_sphere(
+    globals: &BTreeMap<String, String>,
+    locals: &BTreeMap<String, String>,
+    ellps: &Ellipsoid,
+) -> Result<Option<Ellipsoid>, Error> {
+    let Some(key) = DERIVED_SPHERES
+        .into_iter()
+        .find(|key| matches!(chase(globals, locals, key), Ok(Some(_))))
+    else {
+        return Ok(None);
+    };
+    let latitude = || -> Result<f64, Error> {
+        let value = chase(globals, locals, key)?.unwrap_or_default();
+        if let Some(v) = parse_real(key, &value) {
+            return Ok(v.to_radians());
+        }
+        warn!("Cannot parse {key}:{value} as a latitude");
+        Err(Error::BadParam(key.to_string(), value))
+    };
+
+    let a = ellps.semimajor_axis();
+    let b = ellps.semiminor_axis();
+    let radius = match key {
+        "R_A" => return Ok(Some(ellps.authalic_sphere())),
+        "R_V" => (a * a * b).cbrt(),
+        "R_a" => (a + b) / 2.,
+        "R_g" => (a * b).sqrt(),
+        "R_h" => 2. * a * b / (a + b),
+        "R_lat_a" => {
+            let latitude = latitude()?;
+            let m = ellps.meridian_radius_of_curvature(latitude);
+            let n = ellps.prime_vertical_radius_of_curvature(latitude);
+            (m + n) / 2.
+        }
+        _ => return Ok(Some(ellps.conformal_sphere(latitude()?))),
+    };
+    Ok(Some(Ellipsoid::new(radius, 0.)))
+}
+
+// Units accepted as suffixes to numerical parameters, with their size in terms
+// of the base unit of their kind: Metres for lengths, degrees for angles
+#[rustfmt::skip]
+const LENGTH_UNITS: [(&str, f64); 6] = [
+    ("m", 1.), ("km", 1000.), ("cm", 0.01), ("mm", 0.001),
+    ("ft", 0.3048), ("us-ft", 1200. / 3937.),
+];
+#[rustfmt::skip]
+const ANGULAR_UNITS: [(&str, f64); 8] = [
+    ("deg", 1.), ("rad", 180. / std::f64::consts::PI), ("gon", 0.9), ("grad", 0.9),
+    ("min", 1. / 60.), ("sec", 1. / 3600.), ("arcsec", 1. / 3600.), ("mas", 1. / 3.6e6),
+];
+
+// The units accepted for the parameter `key`, and the size of its canonical
+// unit, i.e. the unit expected by the operators. None for parameters without a
+// well defined kind of unit
+fn canonical_unit(key: &str) -> Option<(&'static [(&'static str, f64)], f64)> {
+    match key {
+        // Helmert rotations and their rates are given in arcseconds
+        "rx" | "ry" | "rz" | "drx" | "dry" | "drz" => Some((&ANGULAR_UNITS, 1. / 3600.)),
+        "lat_ts" | "R_lat_a" | "R_lat_g" => Some((&ANGULAR_UNITS, 1.)),
+        "x" | "y" | "z" | "dx" | "dy" | "dz" | "a" | "b" | "da" | "R" => Some((&LENGTH_UNITS, 1.)),
+        _ => {
+            let (name, index) = key.split_once('_')?;
+            index.parse::<usize>().ok()?;
+            match name {
+                "lat" | "lon" => Some((&ANGULAR_UNITS, 1.)),
+                "x" | "y" => Some((&LENGTH_UNITS, 1.)),
+                _ => None,
+            }
+        }
+    }
+}
+
+// Parse the value of the numerical parameter `key`, converting from the unit
+// given as suffix, if any, to the canonical unit of the parameter. So e.g.
+// `x_0=500km` gives 500000, and `rx=1deg` gives 3600. Angles may also be given
+// in sexagesimal notation, as handled by `Coord::parse_angle`, e.g. `57d09'30"N`
+// or `9:58:30`
+pub(crate) fn parse_real(key: &str, value: &str) -> Option<f64> {
+    if let Ok(v) = value.parse::<f64>() {
+        return Some(v);
+    }
+    let (units, canonical) = canonical_unit(key)?;
+    let suffixed = units.iter().find_map(|(unit, size)| {
+        let number = value.strip_suffix(unit)?.trim_end().parse::<f64>().ok()?;
+        Some(number * size / canonical)
+    });
+    if suffixed.is_some() || units != ANGULAR_UNITS.as_slice() {
+        return suffixed;
+    }
+    Coord::parse_angle(value).ok().map(|v| v / canonical)
+}
+
+// The largest number of elements a range in a series may expand to
+const MAX_RANGE: usize = 1 << 16;
+
+// Parse a comma separated series of reals, where each element may also be an
+// inclusive range, `first:last`, or `first:last:step`, so e.g. `0,1:3,5:9:2`
+// gives [0, 1, 2, 3, 5, 7, 9]
+fn parse_series(value: &str) -> Option<Vec<f64>> {
+    let mut elements = Vec::new();
+    for element in value.split(',') {
+        let parts = element
+            .split(':')
+            .map(|part| part.trim().parse::<f64>().ok())
+            .collect::<Option<Vec<f64>>>()?;
+        let (first, last, step) = match parts[..] {
+            [v] => {
+                elements.push(v);
+                continue;
+            }
+            [first, last] => (first, last, 1.),
+            [first, last, step] => (first, last, step),
+            _ => return None,
+        };
+
+        // The step must lead from first to last, in a bounded number of steps
+        let n = (last - first) / step;
+        if !(0. ..=MAX_RANGE as f64).contains(&n) {
+            return None;
+        }
+        // Allow for rounding errors in fractional steps
+        let n = (n + 1e-9).floor() as usize;
+        elements.extend((0..=n).map(|i| first + i as f64 * step));
+    }
+    Some(elements)
+}
+
 pub fn chase(
     globals: &BTreeMap<String, String>,
     locals: &BTreeMap<String, String>,
@@ -384,6 +687,7 @@ pub fn chase(
     let mut default = "";
     let mut needle = key;
     let mut chasing = false;
+    let mut given = "";
     let value;
 
     loop {
@@ -394,13 +698,15 @@ pub fn chase(
             }
             if chasing {
                 return Err(Error::Syntax(format!(
-                    "Incomplete definition for '{}'",
-                    key
+                    "Incomplete definition for '{key}': No value given for '{given}'"
                 )));
             }
             return Ok(None);
         }
         let thevalue = found.unwrap().1.trim();
+        if !chasing {
+            given = thevalue;
+        }
 
         // If the value is a(nother) lookup, we continue the search in the same iterator,
         // now using a *new search key*, as specified by the current value
@@ -444,6 +750,289 @@ mod tests {
         OpParameter::Text    { key: "ellps_0",  default: Some("6400000, 300") },
     ];
 
+    #[test]
+    fn inline_ellipsoid() -> Result<(), Error> {
+        let globals = BTreeMap::<String, String>::new();
+        let gamut = [OpParameter::Text {
+            key: "ellps",
+            default: Some("GRS80"),
+        }];
+        let grs80 = Ellipsoid::named("GRS80")?;
+        let ellps = |definition: &str| -> Result<Ellipsoid, Error> {
+            let raw = RawParameters::new(definition, &globals);
+            Ok(ParsedParameters::new(&raw, &gamut)?.ellps[0])
+        };
+
+        // The "a, rf" form of `ellps`
+        assert_eq!(
+            ellps("cucumber ellps=6378137,298.257")?,
+            Ellipsoid::new(6378137., 1. / 298.257)
+        );
+
+        // Separate keys
+        assert_eq!(ellps("cucumber a=6378137 rf=298.257222100882711")?, grs80);
+        assert_eq!(
+            ellps("cucumber a=6378137 f=0.5")?,
+            Ellipsoid::new(6378137., 0.5)
+        );
+        assert_eq!(ellps("cucumber a=2 b=1")?, Ellipsoid::new(2., 0.5));
+        let e = ellps("cucumber a=6378137 es=0.006694380022903416")?;
+        assert!((e.flattening() - grs80.flattening()).abs() < 1e-15);
+
+        // Spheres, and the inline definitions overriding `ellps`
+        assert_eq!(ellps("cucumber R=3396190")?, Ellipsoid::new(3396190., 0.));
+        assert_eq!(
+            ellps("cucumber ellps=intl a=1737400")?,
+            Ellipsoid::new(1737400., 0.)
+        );
+
+        // Spheres derived from the ellipsoid
+        let (a, b) = (grs80.semimajor_axis(), grs80.semiminor_axis());
+        let radius = |definition: &str| -> Result<f64, Error> {
+            let e = ellps(definition)?;
+            assert_eq!(e.flattening(), 0.);
+            Ok(e.semimajor_axis())
+        };
+        assert_eq!(
+            radius("cucumber R_A")?,
+            grs80.authalic_sphere().semimajor_axis()
+        );
+        assert!((radius("cucumber R_V")? - 6371000.79).abs() < 0.01);
+        assert_eq!(radius("cucumber R_a")?, (a + b) / 2.);
+        assert_eq!(radius("cucumber R_g")?, (a * b).sqrt());
+        assert_eq!(radius("cucumber R_h")?, 2. * a * b / (a + b));
+        let conformal = grs80.conformal_sphere(55_f64.to_radians());
+        assert_eq!(radius("cucumber R_lat_g=55")?, conformal.semimajor_axis());
+        let r = radius("cucumber ellps=intl R_lat_a=55d30'")?;
+        assert!(r > Ellipsoid::named("intl")?.meridian_radius_of_curvature(0.9686));
+        assert_eq!(radius("cucumber a=6378137 R_a")?, 6378137.);
+        assert!(matches!(
+            ellps("cucumber R_lat_a=north"),
+            Err(Error::BadParam(_, _))
+        ));
+
+        // Shape without size
+        assert!(matches!(
+            ellps("cucumber rf=300"),
+            Err(Error::MissingParam(_))
+        ));
+        assert!(matches!(
+            ellps("cucumber a=6378137 rf=flat"),
+            Err(Error::BadParam(_, _))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn aliases() -> Result<(), Error> {
+        let globals = BTreeMap::<String, String>::new();
+        #[rustfmt::skip]
+        let gamut = [
+            OpParameter::Real  { key: "k_0",     default: Some(1.) },
+            OpParameter::Text  { key: "ellps",   default: Some("GRS80") },
+            OpParameter::Real  { key: "real",    default: Some(0.) },
+            OpParameter::Alias { key: "r",       canonical: "real" },
+        ];
+
+        // The common aliases, and the ones given by the gamut
+        let raw = RawParameters::new("cucumber k=0.9996 ellipsoid=intl r=3", &globals);
+        let p = ParsedParameters::new(&raw, &gamut)?;
+        assert_eq!(p.k(0), 0.9996);
+        assert_eq!(p.text("ellps")?, "intl");
+        assert_eq!(p.real("real")?, 3.);
+        assert!(!p.gamut.contains("r"));
+
+        // The canonical key takes precedence
+        let raw = RawParameters::new("cucumber k=2 k_0=0.9996 real=1 r=3", &globals);
+        let p = ParsedParameters::new(&raw, &gamut)?;
+        assert_eq!(p.k(0), 0.9996);
+        assert_eq!(p.real("real")?, 1.);
+
+        // Common aliases only apply to operators having the canonical parameter
+        let raw = RawParameters::new("cucumber units=m", &globals);
+        let p = ParsedParameters::new(&raw, &gamut)?;
+        assert!(p.given.contains_key("units"));
+        Ok(())
+    }
+
+    #[test]
+    fn warnings() -> Result<(), Error> {
+        let globals = BTreeMap::<String, String>::new();
+        #[rustfmt::skip]
+        let gamut = [
+            OpParameter::Real { key: "k_0",   default: Some(1.) },
+            OpParameter::Text { key: "ellps", default: Some("GRS80") },
+        ];
+        let parsed = |definition: &str| -> Result<ParsedParameters, Error> {
+            let raw = RawParameters::new(definition, &globals);
+            ParsedParameters::new(&raw, &gamut)
+        };
+
+        let p = parsed("cucumber k=0.9996 foo=bar")?;
+        assert_eq!(p.ignored, ["foo"]);
+        assert_eq!(p.warnings.len(), 3);
+        assert!(p.warnings[0].contains("alias"));
+        assert!(p.warnings[1].contains("default GRS80"));
+        assert!(p.warnings[2].contains("foo"));
+
+        // Errors name the parameter, and the value, failing
+        let message = |definition: &str| parsed(definition).unwrap_err().to_string();
+        assert!(message("cucumber k_0=one").ends_with("k_0: one"));
+        assert!(message("cucumber ellps=GRS81").ends_with("ellps: GRS81"));
+        assert!(message("cucumber rf=298.25").contains("rf=298.25"));
+        assert!(message("cucumber k_0=$scale").contains("'k_0': No value given for '$scale'"));
+
+        // Neither inline ellipsoids, nor derived spheres, are ignored
+        assert!(parsed("cucumber ellps=intl")?.warnings.is_empty());
+        assert!(parsed("cucumber a=6378137 rf=298.25")?.warnings.is_empty());
+        assert_eq!(parsed("cucumber ellps=intl R_A")?.ignored.len(), 0);
+        Ok(())
+    }
+
+    #[test]
+    fn prepared() -> Result<(), Error> {
+        let globals = BTreeMap::<String, String>::new();
+        let gamut = [OpParameter::Real {
+            key: "x",
+            default: Some(0.),
+        }];
+        let raw = RawParameters::new("cucumber x=3", &globals);
+        let mut p = ParsedParameters::new(&raw, &gamut)?;
+        assert!(p.prepared::<f64>().is_err());
+
+        struct Prepared {
+            x: f64,
+            xx: f64,
+        }
+        let x = p.real("x")?;
+        p.prepare(Prepared { x, xx: x * x });
+        let prepared = p.prepared::<Prepared>()?;
+        assert_eq!((prepared.x, prepared.xx), (3., 9.));
+        assert!(matches!(p.prepared::<f64>(), Err(Error::MissingParam(_))));
+        Ok(())
+    }
+
+    #[test]
+    fn unit_suffixes() -> Result<(), Error> {
+        let globals = BTreeMap::<String, String>::new();
+        #[rustfmt::skip]
+        let gamut = [
+            OpParameter::Real { key: "lat_0", default: Some(0.) },
+            OpParameter::Real { key: "lon_0", default: Some(0.) },
+            OpParameter::Real { key: "x_0",   default: Some(0.) },
+            OpParameter::Real { key: "rx",    default: Some(0.) },
+            OpParameter::Real { key: "real",  default: Some(0.) },
+        ];
+        let raw = RawParameters::new(
+            "cucumber lat_0=55.5deg lon_0=200gon x_0=500km rx=0.05sec a=6378.137km",
+            &globals,
+        );
+        let p = ParsedParameters::new(&raw, &gamut)?;
+        assert_eq!(p.real("lat_0")?, 55.5);
+        assert!((p.real("lon_0")? - 180.).abs() < 1e-12);
+        assert!((p.lon(0) - std::f64::consts::PI).abs() < 1e-12);
+        assert_eq!(p.x(0), 500000.);
+        assert!((p.real("rx")? - 0.05).abs() < 1e-15);
+        assert_eq!(p.ellps(0).semimajor_axis(), 6378137.);
+
+        let raw = RawParameters::new("cucumber rx=1deg x_0=1ft", &globals);
+        let p = ParsedParameters::new(&raw, &gamut)?;
+        assert!((p.real("rx")? - 3600.).abs() < 1e-9);
+        assert_eq!(p.x(0), 0.3048);
+
+        // Sexagesimal angles
+        let raw = RawParameters::new(
+            r#"cucumber lat_0=57d09'30"N lon_0=9:58:30 rx=-0:0:0.5"#,
+            &globals,
+        );
+        let p = ParsedParameters::new(&raw, &gamut)?;
+        assert!((p.real("lat_0")? - (57. + 9.5 / 60.)).abs() < 1e-12);
+        assert!((p.real("lon_0")? - (9. + 58.5 / 60.)).abs() < 1e-12);
+        assert!((p.real("rx")? + 0.5).abs() < 1e-12);
+        let raw = RawParameters::new("cucumber lon_0=9:58:30W", &globals);
+        let p = ParsedParameters::new(&raw, &gamut)?;
+        assert!((p.real("lon_0")? + 9.975).abs() < 1e-12);
+
+        // Units of the wrong kind, and units for parameters without one
+        for definition in [
+            "cucumber lat_0=5km",
+            "cucumber x_0=1deg",
+            "cucumber x_0=1:30",
+            "cucumber real=1m",
+        ] {
+            let raw = RawParameters::new(definition, &globals);
+            assert!(matches!(
+                ParsedParameters::new(&raw, &gamut),
+                Err(Error::BadParam(_, _))
+            ));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn series() -> Result<(), Error> {
+        let globals = BTreeMap::<String, String>::new();
+        #[rustfmt::skip]
+        let gamut = [
+            OpParameter::Series { key: "list",    default: Some("") },
+            OpParameter::Series { key: "rates",   default: Some("0,0,0") },
+            OpParameter::Series { key: "needed",  default: None },
+        ];
+        let series = |definition: &str, key: &str| -> Result<Vec<f64>, Error> {
+            let raw = RawParameters::new(definition, &globals);
+            Ok(ParsedParameters::new(&raw, &gamut)?.series(key)?.to_vec())
+        };
+
+        assert_eq!(series("cucumber needed=1, 2,3", "needed")?, [1., 2., 3.]);
+        assert_eq!(
+            series("cucumber needed=1:5", "needed")?,
+            [1., 2., 3., 4., 5.]
+        );
+        assert_eq!(
+            series("cucumber needed=0,1:3,9:5:-2", "needed")?,
+            [0., 1., 2., 3., 9., 7., 5.]
+        );
+        assert_eq!(series("cucumber needed=0:0.3:0.1", "needed")?.len(), 4);
+        assert_eq!(series("cucumber needed=2:2", "needed")?, [2.]);
+
+        // Defaults, and empty defaults
+        assert_eq!(series("cucumber needed=1", "rates")?, [0., 0., 0.]);
+        assert!(matches!(
+            series("cucumber needed=1", "list"),
+            Err(Error::MissingParam(_))
+        ));
+        assert!(matches!(
+            series("cucumber", "needed"),
+            Err(Error::MissingParam(_))
+        ));
+
+        // Malformed series and ranges
+        for definition in [
+            "cucumber needed=1,,2",
+            "cucumber needed=1:2:3:4",
+            "cucumber needed=5:1",
+            "cucumber needed=1:5:0",
+            "cucumber needed=1:1e9",
+            "cucumber needed=a,b",
+        ] {
+            assert!(matches!(
+                series(definition, "needed"),
+                Err(Error::BadParam(_, _))
+            ));
+        }
+
+        // Fixed size access
+        let raw = RawParameters::new("cucumber needed=1:3", &globals);
+        let p = ParsedParameters::new(&raw, &gamut)?;
+        assert_eq!(p.series_array::<3>("needed")?, [1., 2., 3.]);
+        assert_eq!(p.series_array::<3>("rates")?, [0., 0., 0.]);
+        assert!(matches!(
+            p.series_array::<2>("needed"),
+            Err(Error::BadParam(_, _))
+        ));
+        Ok(())
+    }
+
     #[test]
     fn basic() -> Result<(), Error> {
         let invocation = String::from("cucumber flag ellps_0=123 , 456 natural=$indirection");
@@ -455,12 +1044,12 @@ mod tests {
 
         // Booleans correctly parsed?
         assert!(
-            p.boolean.get("flag").is_some(),
+            p.boolean.contains("flag"),
             "`flag` not in registered booleans: {:#?}",
             p.boolean
         );
         assert!(
-            p.boolean.get("galf").is_none(),
+            !p.boolean.contains("galf"),
             "`galf` not in registered booleans: {:?}",
             p.boolean
         );
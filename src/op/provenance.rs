@@ -0,0 +1,100 @@
+/// The kinds of external resources an operator may depend on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ResourceKind {
+    /// A macro, whether user defined, built in, or from the embedded EPSG subset
+    Macro,
+    /// A grid file
+    Grid,
+    /// A model file, e.g. the coefficients of a spherical harmonic model
+    Model,
+}
+
+/// A resource used in the instantiation of an operator, identified by name,
+/// size, and the CRC-32 checksum of its contents, cf.
+/// [`Op::provenance`](crate::preamble::Op::provenance)
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Provenance {
+    pub kind: ResourceKind,
+    pub name: String,
+    pub size: usize,
+    pub crc32: u32,
+}
+
+impl Provenance {
+    pub fn new(kind: ResourceKind, name: &str, contents: &[u8]) -> Provenance {
+        Provenance {
+            kind,
+            name: name.to_string(),
+            size: contents.len(),
+            crc32: crc32(contents),
+        }
+    }
+}
+
+// e.g. `grid test.datum (1234 bytes, crc32 0x1a2b3c4d)`
+impl std::fmt::Display for Provenance {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let kind = match self.kind {
+            ResourceKind::Macro => "macro",
+            ResourceKind::Grid => "grid",
+            ResourceKind::Model => "model",
+        };
+        write!(
+            f,
+            "{kind} {} ({} bytes, crc32 {:#010x})",
+            self.name, self.size, self.crc32
+        )
+    }
+}
+
+// The CRC-32 checksum used by zip, gzip, PNG etc. (polynomial 0xEDB88320, reflected),
+// so the checksums can be verified by standard tools
+fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = !0_u32;
+    for byte in bytes {
+        crc ^= *byte as u32;
+        for _ in 0..8 {
+            let mask = (crc & 1).wrapping_neg();
+            crc = (crc >> 1) ^ (0xEDB8_8320 & mask);
+        }
+    }
+    !crc
+}
+
+// ----- T E S T S ------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::internal::*;
+
+    #[test]
+    fn provenance() -> Result<(), Error> {
+        // The standard check value
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+
+        let mut ctx = Minimal::new();
+        ctx.register_resource("foo:shift", "gridshift grids=test.datum | addone");
+        let op = ctx.op("geo:in | foo:shift | EPSG:1149")?;
+        let provenance = ctx.provenance(op)?;
+        assert_eq!(provenance.len(), 4);
+        assert_eq!(provenance[0].name, "geo:in");
+        assert_eq!(provenance[1].name, "foo:shift");
+        assert_eq!(provenance[2].kind, ResourceKind::Grid);
+        assert_eq!(provenance[2].name, "test.datum");
+        let grid = ctx.get_blob("test.datum")?;
+        assert_eq!(
+            provenance[2],
+            Provenance::new(ResourceKind::Grid, "test.datum", &grid)
+        );
+        assert_eq!(
+            provenance[3].to_string(),
+            "macro EPSG:1149 (4 bytes, crc32 0x0b56d565)"
+        );
+
+        // Plain operators use no resources
+        let op = ctx.op("cart")?;
+        assert!(ctx.provenance(op)?.is_empty());
+        Ok(())
+    }
+}
@@ -11,6 +11,9 @@ pub struct RawParameters {
     pub invocation: String,
     pub definition: String,
     pub globals: BTreeMap<String, String>,
+    /// The keys of the globals given by macro invocations (or by the defaults
+    /// of their placeholders), rather than inherited from the context globals
+    pub given: BTreeSet<String>,
     recursion_level: usize,
 }
 
@@ -18,6 +21,7 @@ impl RawParameters {
     pub fn new(invocation: &str, globals: &BTreeMap<String, String>) -> RawParameters {
         let recursion_level = 0;
         let globals = globals.clone();
+        let given = BTreeSet::new();
         let invocation = invocation.to_string();
         let definition = invocation.clone();
 
@@ -29,6 +33,7 @@ impl RawParameters {
                 invocation,
                 definition,
                 globals,
+                given,
                 recursion_level,
             };
             return previous.next(&previous.invocation);
@@ -40,6 +45,7 @@ impl RawParameters {
             invocation,
             definition,
             globals,
+            given,
             recursion_level,
         }
     }
@@ -51,10 +57,18 @@ impl RawParameters {
     pub fn next(&self, definition: &str) -> RawParameters {
         let mut recursion_level = self.recursion_level + 1;
         let mut globals = self.globals.clone();
+        let mut given = self.given.clone();
         if super::is_resource_name(definition) {
             globals.remove("name");
-            globals.extend(super::split_into_parameters(definition).into_iter());
-            globals.remove("inv");
+            let arguments = super::split_into_parameters(definition);
+            given.extend(arguments.keys().cloned());
+            globals.extend(arguments);
+            // The inversion and omission flags apply to the macro as a whole,
+            // not to its individual steps
+            for flag in ["inv", "omit_fwd", "omit_inv"] {
+                globals.remove(flag);
+                given.remove(flag);
+            }
             recursion_level += 1;
         }
         let invocation = self.invocation.clone();
@@ -63,6 +77,7 @@ impl RawParameters {
             invocation,
             definition,
             globals,
+            given,
             recursion_level,
         }
     }
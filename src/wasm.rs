@@ -0,0 +1,119 @@
+//! A wasm-bindgen wrapper, for running transformations in the browser.
+//! Enabled by the `wasm` feature, and built by e.g.
+//!
+//! ```sh
+//! wasm-pack build --target web --features wasm
+//! ```
+//!
+//! In JavaScript:
+//!
+//! ```js
+//! const geodesy = new Geodesy();
+//! const utm32 = geodesy.op("geo:in | utm zone=32");
+//! const coordinates = new Float64Array([55, 12, 59, 10]);
+//! geodesy.apply(utm32, false, coordinates, 2);
+//! ```
+//!
+//! Since there is no file system, grids must be handed over by
+//! [`Geodesy::register_blob`], e.g. after fetching them.
+
+use crate::coord::Interleaved;
+use crate::internal::*;
+use wasm_bindgen::prelude::*;
+
+/// A [`Minimal`] context, and the operators instantiated through it,
+/// identified by small integers
+#[wasm_bindgen]
+pub struct Geodesy {
+    ctx: Minimal,
+    operators: Vec<OpHandle>,
+}
+
+// Errors are handed over to JavaScript as their messages
+fn js_error(error: Error) -> JsValue {
+    JsValue::from_str(&error.to_string())
+}
+
+#[wasm_bindgen]
+impl Geodesy {
+    #[wasm_bindgen(constructor)]
+    #[allow(clippy::new_without_default)]
+    pub fn new() -> Geodesy {
+        Geodesy {
+            ctx: Minimal::new(),
+            operators: Vec::new(),
+        }
+    }
+
+    /// Register the macro `name`, given by `definition`
+    pub fn register_resource(&mut self, name: &str, definition: &str) {
+        self.ctx.register_resource(name, definition);
+    }
+
+    /// Make the grid (or other binary resource) `name` available
+    pub fn register_blob(&mut self, name: &str, contents: Vec<u8>) {
+        self.ctx.register_blob(name, contents);
+    }
+
+    /// Instantiate the operator given by `definition`, returning its identifier
+    pub fn op(&mut self, definition: &str) -> Result<usize, JsValue> {
+        let op = self.ctx.op(definition).map_err(js_error)?;
+        self.operators.push(op);
+        Ok(self.operators.len() - 1)
+    }
+
+    /// Apply the operator `op` to the coordinate tuples of `dim` (2, 3, or 4)
+    /// elements each, stored consecutively in `coordinates`. Returns the number
+    /// of successfully transformed coordinates
+    pub fn apply(
+        &self,
+        op: usize,
+        inverse: bool,
+        coordinates: &mut [f64],
+        dim: usize,
+    ) -> Result<usize, JsValue> {
+        let Some(&handle) = self.operators.get(op) else {
+            return Err(js_error(Error::General(format!("Unknown operator {op}"))));
+        };
+        if !(2..=4).contains(&dim) || coordinates.len() % dim != 0 {
+            return Err(js_error(Error::BadParam(
+                "dim".to_string(),
+                dim.to_string(),
+            )));
+        }
+        let direction = if inverse { Inv } else { Fwd };
+        let mut operands = Interleaved {
+            values: coordinates,
+            dim,
+        };
+        self.ctx
+            .apply(handle, direction, &mut operands)
+            .map_err(js_error)
+    }
+}
+
+// ----- T E S T S ------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Only the successful cases can be tested natively, since the errors are
+    // JavaScript values
+    #[test]
+    fn wasm() {
+        let mut geodesy = Geodesy::new();
+        let op = geodesy.op("geo:in | utm zone=32").unwrap();
+        let mut coordinates = [55., 12., 59., 10.];
+        assert_eq!(geodesy.apply(op, false, &mut coordinates, 2).unwrap(), 2);
+        assert!((coordinates[0] - 691875.632).abs() < 1e-3);
+        geodesy.apply(op, true, &mut coordinates, 2).unwrap();
+        assert!((coordinates[3] - 10.).abs() < 1e-12);
+
+        geodesy.register_blob(
+            "in_memory.datum",
+            std::fs::read("geodesy/datum/test.datum").unwrap(),
+        );
+        assert!(geodesy.op("gridshift grids=in_memory.datum").is_ok());
+    }
+}
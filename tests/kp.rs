@@ -0,0 +1,253 @@
+use std::io::Write;
+use std::process::{Command, Output, Stdio};
+
+// ----- T H E   K P   C O M M A N D   L I N E   P R O G R A M ------------------------
+
+/// Run `kp` with `args`, feeding it `input` on stdin
+fn kp(args: &[&str], input: &[u8]) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_kp"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("kp should be runnable");
+    child.stdin.take().unwrap().write_all(input).unwrap();
+    child.wait_with_output().unwrap()
+}
+
+/// The stdout of a successful `kp` run, split into lines
+fn lines(output: &Output) -> Vec<String> {
+    assert!(
+        output.status.success(),
+        "kp failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(String::from)
+        .collect()
+}
+
+/// The whitespace separated numbers of `line`
+fn numbers(line: &str) -> Vec<f64> {
+    line.split_whitespace()
+        .map(|n| n.parse().unwrap())
+        .collect()
+}
+
+const UTM32: &str = "geo:in | utm zone=32";
+
+#[test]
+fn filter() {
+    // stdin to stdout
+    let output = kp(&[UTM32], b"55 12\n");
+    let found = numbers(&lines(&output)[0]);
+    assert!((found[0] - 691875.63214).abs() < 1e-5);
+    assert!((found[1] - 6098907.82501).abs() < 1e-5);
+
+    // Files to the output file, also in batches on multiple threads
+    let dir = std::env::temp_dir().join(format!("kp-filter-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let input = dir.join("input.txt");
+    std::fs::write(&input, "55 12\n56 13\n").unwrap();
+    let result = dir.join("output.txt");
+    for jobs in ["1", "2"] {
+        let args = [
+            "-j",
+            jobs,
+            "-o",
+            result.to_str().unwrap(),
+            UTM32,
+            input.to_str().unwrap(),
+        ];
+        assert!(lines(&kp(&args, b"")).is_empty());
+        let written = std::fs::read_to_string(&result).unwrap();
+        let written: Vec<_> = written.lines().collect();
+        assert_eq!(written.len(), 2);
+        assert_eq!(numbers(written[0]), found);
+    }
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn formatting() {
+    let output = kp(&["-d", "2", "--width", "12", UTM32], b"55 12\n");
+    assert_eq!(
+        lines(&output)[0],
+        "   691875.63   6098907.83         0.00         0.00"
+    );
+
+    // geo:in swaps the axes, and converts to radians
+    let output = kp(&["--degrees", "-d", "1", "geo:in"], b"55 12\n");
+    assert_eq!(lines(&output)[0], "12.0 55.0 0.0 0.0");
+
+    let output = kp(&["--scientific", "-d", "3", UTM32], b"55 12\n");
+    assert!(lines(&output)[0].starts_with("6.919e5 6.099e6"));
+
+    // Sexagesimal input and output
+    let args = ["--dms-in", "--dms-out", "geo:in | geo:out"];
+    let output = kp(&args, b"55 30 36 N 12 45 36 E\n");
+    assert!(lines(&output)[0].starts_with("55°30'36.000\"N 12°45'36.000\"E"));
+}
+
+#[test]
+fn headers_and_definitions() {
+    // Header and comment lines pass through, optionally annotated
+    let args = ["--header", "1", "--annotate", "-d", "1", "geo:in | geo:out"];
+    let output = kp(&args, b"lat lon\n# comment\n55 12\n");
+    let found = lines(&output);
+    assert_eq!(found[0], "lat lon");
+    assert_eq!(found[1], "# kp: geo:in | geo:out");
+    assert_eq!(found[2], "# comment");
+    assert_eq!(found[3], "55.0 12.0 0.0 0.0");
+
+    // The effective definition, with the macros expanded
+    let output = kp(&["--echo-definition", UTM32], b"55 12\n");
+    let found = lines(&output);
+    assert!(found[0].starts_with("#  adapt from=neuf_deg"));
+    assert!(found[1].starts_with("#  | utm"));
+
+    // The intermediate results of each step
+    let output = kp(&["--trace", UTM32], b"55 12\n");
+    let found = lines(&output);
+    assert_eq!(found.len(), 3);
+    assert!(found[0].starts_with("#  adapt from=neuf_deg:"));
+    assert!(found[1].starts_with("#  utm zone=32:"));
+
+    // Named definitions from a file
+    let file = std::env::temp_dir().join(format!("kp-definitions-{}", std::process::id()));
+    std::fs::write(&file, format!("[mine]\n{UTM32}\n")).unwrap();
+    let output = kp(&["-f", file.to_str().unwrap(), "mine"], b"55 12\n");
+    std::fs::remove_file(&file).unwrap();
+    assert_eq!(lines(&output), lines(&kp(&[UTM32], b"55 12\n")));
+
+    // ...with the defaults of their placeholders applied
+    let file = std::env::temp_dir().join(format!("kp-placeholders-{}", std::process::id()));
+    std::fs::write(&file, "[myadd]\n#$ x=1 y\nhelmert x=$x y=$y\n").unwrap();
+    let defs = file.to_str().unwrap();
+    let output = kp(&["-f", defs, "-d", "1", "myadd"], b"1 2\n");
+    assert!(!output.status.success());
+    std::fs::write(&file, "[myadd]\n#$ x=1\nhelmert x=$x\n").unwrap();
+    let output = kp(&["-f", defs, "-d", "1", "myadd"], b"1 2\n");
+    std::fs::remove_file(&file).unwrap();
+    assert_eq!(lines(&output)[0], "2.0 2.0 0.0 0.0");
+
+    // Pairs of EPSG codes
+    let output = kp(&["EPSG:4326 EPSG:25832"], b"55 12\n");
+    assert_eq!(lines(&output), lines(&kp(&[UTM32], b"55 12\n")));
+}
+
+#[test]
+fn roundtrip() {
+    let output = kp(&["-r", "--tolerance", "1mm", UTM32], b"55 12\n");
+    let found = lines(&output);
+    assert_eq!(found[0], "55 12:  d = 0.00 mm");
+    assert!(found[1].contains("0 exceeding 1.00 mm"));
+
+    // The same, inverse, is refused
+    assert!(!kp(&["-r", "-i", UTM32], b"55 12\n").status.success());
+}
+
+#[test]
+fn failing_points() {
+    // Failing points are reported, and give exit code 2
+    let output = kp(&["helmert x=nan"], b"1 2\n1 2\n");
+    assert_eq!(output.status.code(), Some(2));
+    assert_eq!(String::from_utf8_lossy(&output.stdout).lines().count(), 2);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("<stdin>:1: failed"));
+    assert!(stderr.contains("2 points failed to transform"));
+
+    // ...and left out in `skip-bad` mode
+    let output = kp(&["--skip-bad", "helmert x=nan"], b"1 2\n1 2\n");
+    assert_eq!(output.status.code(), Some(2));
+    assert!(output.stdout.is_empty());
+
+    // ...or stop the transformation in `fail-fast` mode
+    let output = kp(&["--fail-fast", "helmert x=nan"], b"1 2\n1 2\n");
+    assert_eq!(output.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("1 points failed"));
+}
+
+#[test]
+fn binary() {
+    let input: Vec<u8> = [55f64, 12.].iter().flat_map(|v| v.to_le_bytes()).collect();
+    let output = kp(&["--binary", "--dimension", "2", UTM32], &input);
+    assert!(output.status.success());
+    assert_eq!(output.stdout.len(), 16);
+    let easting = f64::from_le_bytes(output.stdout[0..8].try_into().unwrap());
+    let northing = f64::from_le_bytes(output.stdout[8..16].try_into().unwrap());
+    assert!((easting - 691875.63214).abs() < 1e-5);
+    assert!((northing - 6098907.82501).abs() < 1e-5);
+}
+
+#[test]
+fn list_doc_and_repl() {
+    let found = lines(&kp(&["list"], b""));
+    assert_eq!(found[0], "Operators:");
+    assert!(found
+        .iter()
+        .any(|line| line.trim_start().starts_with("utm ")));
+
+    let found = lines(&kp(&["doc", "utm"], b""));
+    assert!(found[0].starts_with("utm:"));
+    assert!(found.iter().any(|line| line.contains("zone")));
+
+    let output = kp(
+        &["repl", "geo:in"],
+        b"op geo:in | geo:out\ninv\n55 12\nquit\n",
+    );
+    let found = lines(&output);
+    assert!(found
+        .iter()
+        .any(|line| line.starts_with("55.0000000000 12.0000000000")));
+}
+
+#[test]
+fn gpx() {
+    let gpx = br#"<gpx><wpt lat="55" lon="12"><ele>100</ele></wpt><wpt lat="56" lon="13"/></gpx>"#;
+    let op = "geo:in | cart | helmert z=10 | cart inv | geo:out";
+    let found = lines(&kp(&["gpx", op], gpx));
+    assert_eq!(
+        found[0],
+        r#"<gpx><wpt lat="55.000051523" lon="12.000000000"><ele>108.1915</ele></wpt><wpt lat="56.000050223" lon="13.000000000"/></gpx>"#
+    );
+
+    // Failing points are left unchanged, and give exit code 2
+    let output = kp(&["gpx", "geo:in | helmert x=nan | geo:out"], gpx);
+    assert_eq!(output.status.code(), Some(2));
+    assert_eq!(output.stdout, gpx);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("2 points failed to transform"));
+}
+
+#[test]
+fn wkb() {
+    // A little endian WKB point
+    let mut point = vec![1u8, 1, 0, 0, 0];
+    for v in [12f64, 55.] {
+        point.extend(v.to_le_bytes());
+    }
+    let hex: String = point.iter().map(|b| format!("{b:02x}")).collect();
+    let op = "gis:in | utm zone=32 | utm inv zone=32 | gis:out";
+    let found = lines(&kp(&["wkb", op], format!("# points\n{hex}\n").as_bytes()));
+    assert_eq!(found[0], "# points");
+    assert_eq!(found[1].len(), hex.len());
+    let x = u64::from_str_radix(&found[1][10..26], 16).unwrap();
+    let y = u64::from_str_radix(&found[1][26..42], 16).unwrap();
+    assert!((f64::from_bits(x.swap_bytes()) - 12.).abs() < 1e-9);
+    assert!((f64::from_bits(y.swap_bytes()) - 55.).abs() < 1e-9);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn geojson() {
+    let op = "gis:in | utm zone=32 | utm inv zone=32 | gis:out";
+    let output = kp(
+        &["geojson", op],
+        br#"{"type":"Point","coordinates":[12,55]}"#,
+    );
+    let found = lines(&output).join("\n");
+    assert!(found.contains(r#""type":"Point""#) || found.contains(r#""type": "Point""#));
+}
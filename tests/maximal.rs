@@ -41,10 +41,10 @@ impl Context for Maximal {
         &self,
         op: OpHandle,
         direction: Direction,
-        operands: &mut [Coord],
+        operands: &mut dyn CoordinateSet,
     ) -> Result<usize, Error> {
-        const BAD_ID_MESSAGE: Error = Error::General("Minimal: Unknown operator id");
-        let op = self.operators.get(&op).ok_or(BAD_ID_MESSAGE)?;
+        let bad_id = || Error::General("Minimal: Unknown operator id".to_string());
+        let op = self.operators.get(&op).ok_or_else(bad_id)?;
         op.apply(self, operands, direction)
     }
 
@@ -97,7 +97,7 @@ impl Context for Maximal {
     /// Access grid resources by identifier
     fn get_grid(&self, _name: &str) -> Result<Grid, Error> {
         Err(Error::General(
-            "Grid access by identifier not supported by the Maximal context provider",
+            "Grid access by identifier not supported by the Maximal context provider".to_string(),
         ))
     }
 }